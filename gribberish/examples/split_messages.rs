@@ -59,7 +59,7 @@ pub fn main() {
         let data = &raw_grib_data[*offset..*offset + metadata.message_size];
 
         message_file
-            .write_all(&data)
+            .write_all(data)
             .expect("Failed to write message file");
     });
 }
@@ -10,8 +10,24 @@ pub enum GribberishError {
     JpegError(String),
     #[error("Error reading message: `{0}`")]
     MessageError(String),
+    #[error("Error reading bitmap: `{0}`")]
+    BitmapError(String),
     #[error("Error parsing index: `{0}`")]
     IndexError(String),
+    #[error("Error encoding message: `{0}`")]
+    EncodingError(String),
     #[error("Unknown time unit: `{0}`")]
     TimeUnitError(String),
+    #[error("Error reading file: `{0}`")]
+    IoError(String),
+    #[error("Error reading metadata cache: `{0}`")]
+    CacheError(String),
+    #[error("Error parsing selector: `{0}`")]
+    SelectorError(String),
+}
+
+impl From<std::io::Error> for GribberishError {
+    fn from(value: std::io::Error) -> Self {
+        GribberishError::IoError(value.to_string())
+    }
 }
@@ -0,0 +1,624 @@
+//! GeoTIFF export of decoded fields.
+//!
+//! A field is written as a single band of 32 bit floats, north up, with the
+//! GeoKeys that place it on the earth: the EPSG code of latitude/longitude
+//! grids, or a user-defined projected CRS for Lambert conformal, polar
+//! stereographic and Mercator grids. Points the bitmap masks are written as the
+//! nodata value.
+//!
+//! With internal tiling, overviews and deflate compression, see
+//! [`GeoTiffOptions::cloud_optimized`], the file is laid out as a
+//! Cloud-Optimized GeoTIFF: every image file directory first, then the image
+//! data from the smallest overview to the full resolution image.
+
+use std::io::Write;
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+
+use crate::error::GribberishError;
+use crate::message::Message;
+use crate::message_metadata::MessageMetadata;
+use crate::utils::iter::projection::{GaussianLongitudes, GridProjection, LatLngProjection};
+
+/// Size of the rows of strips written when the image is not tiled
+const STRIP_SIZE: usize = 8192;
+
+/// GeoKey value for a user-defined code
+const USER_DEFINED: u16 = 32767;
+
+/// How a GeoTIFF is laid out
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GeoTiffOptions {
+    /// Width and height of internal tiles, a multiple of 16. The image is
+    /// written in strips of rows when `None`.
+    pub tile_size: Option<u32>,
+    /// Add overviews of tiled images, each half the size of the last, until one
+    /// fits in a single tile
+    pub overviews: bool,
+    /// Deflate compress the image data
+    pub deflate: bool,
+    /// Written in place of missing values, and recorded as the nodata value
+    pub nodata: f32,
+}
+
+impl Default for GeoTiffOptions {
+    fn default() -> Self {
+        GeoTiffOptions {
+            tile_size: None,
+            overviews: false,
+            deflate: false,
+            nodata: f32::NAN,
+        }
+    }
+}
+
+impl GeoTiffOptions {
+    /// 256 × 256 tiles with overviews and deflate compression, a valid
+    /// Cloud-Optimized GeoTIFF
+    pub fn cloud_optimized() -> Self {
+        GeoTiffOptions {
+            tile_size: Some(256),
+            overviews: true,
+            deflate: true,
+            ..Default::default()
+        }
+    }
+}
+
+/// Decode a message and encode it as a GeoTIFF
+pub fn message_to_geotiff(
+    message: &Message,
+    options: &GeoTiffOptions,
+) -> Result<Vec<u8>, GribberishError> {
+    let metadata = MessageMetadata::try_from(message)?;
+    encode_geotiff(&metadata, &message.data()?, options)
+}
+
+/// Encode a decoded field, laid out on the grid `metadata` describes, as a
+/// GeoTIFF
+pub fn encode_geotiff(
+    metadata: &MessageMetadata,
+    data: &[f64],
+    options: &GeoTiffOptions,
+) -> Result<Vec<u8>, GribberishError> {
+    if let Some(tile_size) = options.tile_size {
+        if tile_size == 0 || tile_size % 16 != 0 {
+            return Err(GribberishError::EncodingError(format!(
+                "GeoTIFF tile size must be a multiple of 16, got {tile_size}"
+            )));
+        }
+    }
+
+    let (height, width) = metadata.grid_shape;
+    if data.len() != width * height {
+        return Err(GribberishError::EncodingError(format!(
+            "expected {} values for the grid, got {}",
+            width * height,
+            data.len()
+        )));
+    }
+
+    let georeference = Georeference::new(metadata)?;
+    let mut images = vec![Raster {
+        width,
+        height,
+        pixels: georeference.north_up_pixels(data, width, height, options.nodata),
+    }];
+    if let (Some(tile_size), true) = (options.tile_size, options.overviews) {
+        let tile_size = tile_size as usize;
+        while let Some(last) = images.last() {
+            if last.width <= tile_size && last.height <= tile_size {
+                break;
+            }
+            let overview = last.overview(options.nodata);
+            images.push(overview);
+        }
+    }
+
+    let mut blocks = Vec::with_capacity(images.len());
+    let mut directories = Vec::with_capacity(images.len());
+    for (index, image) in images.iter().enumerate() {
+        let image_blocks = image
+            .blocks(options.tile_size, options.nodata)
+            .into_iter()
+            .map(|block| compress(block, options.deflate))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut directory = image.directory(&image_blocks, options, index > 0);
+        if index == 0 {
+            directory.extend(georeference.tags());
+        }
+        directories.push(directory);
+        blocks.push(image_blocks);
+    }
+
+    write_tiff(directories, blocks)
+}
+
+/// Where the image lies on the earth and how its pixels map to the grid
+struct Georeference {
+    /// Left and top edges of the image in grid coordinates
+    left: f64,
+    top: f64,
+    pixel_width: f64,
+    pixel_height: f64,
+    flip_rows: bool,
+    flip_columns: bool,
+    geo_keys: Vec<(u16, GeoKeyValue)>,
+}
+
+enum GeoKeyValue {
+    Short(u16),
+    Double(f64),
+    Ascii(String),
+}
+
+impl Georeference {
+    fn new(metadata: &MessageMetadata) -> Result<Self, GribberishError> {
+        let projector = &metadata.projector;
+        let (height, width) = metadata.grid_shape;
+
+        let mut geo_keys = vec![(1025, GeoKeyValue::Short(1))];
+        let params = match projector {
+            LatLngProjection::PlateCaree(projection) => &projection.projection_params,
+            LatLngProjection::Gaussian(projection) => {
+                if let GaussianLongitudes::Reduced(_) = projection.longitudes {
+                    return Err(GribberishError::EncodingError(
+                        "reduced Gaussian grids cannot be written as a GeoTIFF".into(),
+                    ));
+                }
+                &projection.projection_params
+            }
+            LatLngProjection::Projected(projection) => &projection.projection_params,
+        };
+        let param = |name: &str| params.get(name).copied().unwrap_or(0.0);
+
+        match projector {
+            LatLngProjection::PlateCaree(_) | LatLngProjection::Gaussian(_) => {
+                geo_keys.push((1024, GeoKeyValue::Short(2)));
+                match metadata
+                    .crs
+                    .strip_prefix("EPSG:")
+                    .and_then(|code| code.parse::<u16>().ok())
+                {
+                    Some(code) => geo_keys.push((2048, GeoKeyValue::Short(code))),
+                    None => geo_keys.extend(user_defined_geographic_keys(params)),
+                }
+            }
+            LatLngProjection::Projected(projection) => {
+                geo_keys.extend([
+                    (1024, GeoKeyValue::Short(1)),
+                    (1026, GeoKeyValue::Ascii(metadata.proj.trim().to_string())),
+                    (3072, GeoKeyValue::Short(USER_DEFINED)),
+                    (3074, GeoKeyValue::Short(USER_DEFINED)),
+                    (3076, GeoKeyValue::Short(9001)),
+                ]);
+                geo_keys.extend(user_defined_geographic_keys(params));
+
+                match projection.projection {
+                    GridProjection::LambertConformalConic(_) => geo_keys.extend([
+                        (3075, GeoKeyValue::Short(8)),
+                        (3078, GeoKeyValue::Double(param("lat_1"))),
+                        (3079, GeoKeyValue::Double(param("lat_2"))),
+                        (3084, GeoKeyValue::Double(param("lon_0"))),
+                        (3085, GeoKeyValue::Double(param("lat_0"))),
+                        (3086, GeoKeyValue::Double(0.0)),
+                        (3087, GeoKeyValue::Double(0.0)),
+                    ]),
+                    // GDAL reads the latitude of natural origin as the latitude
+                    // of true scale of a polar stereographic projection
+                    GridProjection::PolarStereographic(_) => geo_keys.extend([
+                        (3075, GeoKeyValue::Short(15)),
+                        (3081, GeoKeyValue::Double(param("lat_ts"))),
+                        (3082, GeoKeyValue::Double(0.0)),
+                        (3083, GeoKeyValue::Double(0.0)),
+                        (3092, GeoKeyValue::Double(1.0)),
+                        (3095, GeoKeyValue::Double(param("lon_0"))),
+                    ]),
+                    GridProjection::Mercator(_) => geo_keys.extend([
+                        (3075, GeoKeyValue::Short(7)),
+                        (3078, GeoKeyValue::Double(param("lat_ts"))),
+                        (3082, GeoKeyValue::Double(0.0)),
+                        (3083, GeoKeyValue::Double(0.0)),
+                        (3088, GeoKeyValue::Double(param("lon_0"))),
+                    ]),
+                    GridProjection::RotatedPole(_) | GridProjection::Geostationary(_) => {
+                        return Err(GribberishError::EncodingError(format!(
+                            "{} grids cannot be described by GeoTIFF keys",
+                            projection.projection_name
+                        )))
+                    }
+                }
+            }
+        }
+        geo_keys.sort_by_key(|(key, _)| *key);
+
+        // Gaussian latitudes are close to, but not exactly, evenly spaced
+        let (x, y) = projector.grid_point(0, 0);
+        let (next_x, next_y) = projector.grid_point(1, 1);
+        let pixel_width = next_x - x;
+        let pixel_height = if height > 1 {
+            (projector.grid_point(height - 1, 0).1 - y) / (height - 1) as f64
+        } else {
+            next_y - y
+        };
+        if !pixel_width.is_finite() || !pixel_height.is_finite() {
+            return Err(GribberishError::EncodingError(
+                "cannot find the pixel size of the grid".into(),
+            ));
+        }
+
+        let (flip_rows, flip_columns) = (pixel_height > 0.0, pixel_width < 0.0);
+        let (pixel_width, pixel_height) = (pixel_width.abs(), pixel_height.abs());
+        let left = if flip_columns {
+            x - pixel_width * (width - 1) as f64
+        } else {
+            x
+        };
+        let top = if flip_rows {
+            y + pixel_height * (height - 1) as f64
+        } else {
+            y
+        };
+
+        Ok(Georeference {
+            left: left - pixel_width / 2.0,
+            top: top + pixel_height / 2.0,
+            pixel_width,
+            pixel_height,
+            flip_rows,
+            flip_columns,
+            geo_keys,
+        })
+    }
+
+    /// The field as pixels from the top left, missing values replaced by nodata
+    fn north_up_pixels(&self, data: &[f64], width: usize, height: usize, nodata: f32) -> Vec<f32> {
+        (0..height)
+            .flat_map(|row| {
+                let row = if self.flip_rows {
+                    height - 1 - row
+                } else {
+                    row
+                };
+                (0..width).map(move |column| {
+                    let column = if self.flip_columns {
+                        width - 1 - column
+                    } else {
+                        column
+                    };
+                    let value = data[row * width + column];
+                    if value.is_nan() {
+                        nodata
+                    } else {
+                        value as f32
+                    }
+                })
+            })
+            .collect()
+    }
+
+    /// ModelPixelScale, ModelTiepoint and the GeoKey directory with its
+    /// parameters
+    fn tags(&self) -> Vec<(u16, TagValue)> {
+        let mut directory = vec![1, 1, 0, self.geo_keys.len() as u16];
+        let mut doubles = Vec::new();
+        let mut ascii = String::new();
+        for (key, value) in &self.geo_keys {
+            let entry = match value {
+                GeoKeyValue::Short(value) => [*key, 0, 1, *value],
+                GeoKeyValue::Double(value) => {
+                    doubles.push(*value);
+                    [*key, 34736, 1, doubles.len() as u16 - 1]
+                }
+                GeoKeyValue::Ascii(value) => {
+                    let offset = ascii.len() as u16;
+                    ascii.push_str(value);
+                    ascii.push('|');
+                    [*key, 34737, value.len() as u16 + 1, offset]
+                }
+            };
+            directory.extend(entry);
+        }
+
+        let mut tags = vec![
+            (
+                33550,
+                TagValue::Double(vec![self.pixel_width, self.pixel_height, 0.0]),
+            ),
+            (
+                33922,
+                TagValue::Double(vec![0.0, 0.0, 0.0, self.left, self.top, 0.0]),
+            ),
+            (34735, TagValue::Short(directory)),
+        ];
+        if !doubles.is_empty() {
+            tags.push((34736, TagValue::Double(doubles)));
+        }
+        if !ascii.is_empty() {
+            tags.push((34737, TagValue::Ascii(ascii)));
+        }
+        tags
+    }
+}
+
+/// GeoKeys for the geographic CRS of a grid on the ellipsoid with semi-major
+/// and semi-minor axes `a` and `b`, WGS84 when they are not known
+fn user_defined_geographic_keys(
+    params: &std::collections::HashMap<String, f64>,
+) -> Vec<(u16, GeoKeyValue)> {
+    let semi_major_axis = params.get("a").copied().unwrap_or(6_378_137.0);
+    let semi_minor_axis = params.get("b").copied().unwrap_or(6_356_752.314_245);
+    vec![
+        (2048, GeoKeyValue::Short(USER_DEFINED)),
+        (2050, GeoKeyValue::Short(USER_DEFINED)),
+        (2054, GeoKeyValue::Short(9102)),
+        (2056, GeoKeyValue::Short(USER_DEFINED)),
+        (2057, GeoKeyValue::Double(semi_major_axis)),
+        (2058, GeoKeyValue::Double(semi_minor_axis)),
+    ]
+}
+
+struct Raster {
+    width: usize,
+    height: usize,
+    pixels: Vec<f32>,
+}
+
+impl Raster {
+    /// Half the size, each pixel averaging the pixels of a 2 × 2 block that are
+    /// not missing
+    fn overview(&self, nodata: f32) -> Raster {
+        let (width, height) = (self.width.div_ceil(2), self.height.div_ceil(2));
+        let pixels = (0..height)
+            .flat_map(|row| {
+                (0..width).map(move |column| {
+                    let (sum, count) = [(0, 0), (0, 1), (1, 0), (1, 1)]
+                        .iter()
+                        .map(|(dy, dx)| (row * 2 + dy, column * 2 + dx))
+                        .filter(|(y, x)| *y < self.height && *x < self.width)
+                        .map(|(y, x)| self.pixels[y * self.width + x])
+                        .filter(|value| !is_nodata(*value, nodata))
+                        .fold((0.0, 0), |(sum, count), value| (sum + value, count + 1));
+                    if count > 0 {
+                        sum / count as f32
+                    } else {
+                        nodata
+                    }
+                })
+            })
+            .collect();
+
+        Raster {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    fn rows_per_strip(&self) -> usize {
+        (STRIP_SIZE / (self.width * 4).max(1)).clamp(1, self.height.max(1))
+    }
+
+    /// Uncompressed strips of rows, or tiles padded with nodata past the edges
+    fn blocks(&self, tile_size: Option<u32>, nodata: f32) -> Vec<Vec<u8>> {
+        let to_bytes = |values: &[f32]| {
+            values
+                .iter()
+                .flat_map(|value| value.to_le_bytes())
+                .collect::<Vec<_>>()
+        };
+
+        let Some(tile_size) = tile_size.map(|size| size as usize) else {
+            return self
+                .pixels
+                .chunks(self.rows_per_strip() * self.width)
+                .map(to_bytes)
+                .collect();
+        };
+
+        let mut blocks = Vec::new();
+        for tile_row in 0..self.height.div_ceil(tile_size) {
+            for tile_column in 0..self.width.div_ceil(tile_size) {
+                let mut tile = vec![nodata; tile_size * tile_size];
+                let (top, left) = (tile_row * tile_size, tile_column * tile_size);
+                for row in 0..tile_size.min(self.height - top) {
+                    let columns = tile_size.min(self.width - left);
+                    let start = (top + row) * self.width + left;
+                    tile[row * tile_size..row * tile_size + columns]
+                        .copy_from_slice(&self.pixels[start..start + columns]);
+                }
+                blocks.push(to_bytes(&tile));
+            }
+        }
+        blocks
+    }
+
+    /// The image file directory, with placeholder offsets for its blocks
+    fn directory(
+        &self,
+        blocks: &[Vec<u8>],
+        options: &GeoTiffOptions,
+        is_overview: bool,
+    ) -> Vec<(u16, TagValue)> {
+        let byte_counts = TagValue::Long(blocks.iter().map(|b| b.len() as u32).collect());
+        let offsets = TagValue::Long(vec![0; blocks.len()]);
+        let nodata = if options.nodata.is_nan() {
+            "nan".to_string()
+        } else {
+            options.nodata.to_string()
+        };
+
+        let mut directory = vec![
+            (254, TagValue::Long(vec![is_overview as u32])),
+            (256, TagValue::Long(vec![self.width as u32])),
+            (257, TagValue::Long(vec![self.height as u32])),
+            (258, TagValue::Short(vec![32])),
+            (
+                259,
+                TagValue::Short(vec![if options.deflate { 8 } else { 1 }]),
+            ),
+            (262, TagValue::Short(vec![1])),
+            (277, TagValue::Short(vec![1])),
+            (284, TagValue::Short(vec![1])),
+            (339, TagValue::Short(vec![3])),
+            (42113, TagValue::Ascii(nodata)),
+        ];
+        match options.tile_size {
+            Some(tile_size) => directory.extend([
+                (322, TagValue::Long(vec![tile_size])),
+                (323, TagValue::Long(vec![tile_size])),
+                (324, offsets),
+                (325, byte_counts),
+            ]),
+            None => directory.extend([
+                (273, offsets),
+                (278, TagValue::Long(vec![self.rows_per_strip() as u32])),
+                (279, byte_counts),
+            ]),
+        }
+        directory
+    }
+}
+
+fn is_nodata(value: f32, nodata: f32) -> bool {
+    value.is_nan() || value == nodata
+}
+
+fn compress(block: Vec<u8>, deflate: bool) -> Result<Vec<u8>, GribberishError> {
+    if !deflate {
+        return Ok(block);
+    }
+
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&block)?;
+    Ok(encoder.finish()?)
+}
+
+/// The value of a field of an image file directory
+enum TagValue {
+    Short(Vec<u16>),
+    Long(Vec<u32>),
+    Double(Vec<f64>),
+    Ascii(String),
+}
+
+impl TagValue {
+    fn field_type(&self) -> u16 {
+        match self {
+            TagValue::Ascii(_) => 2,
+            TagValue::Short(_) => 3,
+            TagValue::Long(_) => 4,
+            TagValue::Double(_) => 12,
+        }
+    }
+
+    fn count(&self) -> u32 {
+        match self {
+            TagValue::Short(values) => values.len() as u32,
+            TagValue::Long(values) => values.len() as u32,
+            TagValue::Double(values) => values.len() as u32,
+            TagValue::Ascii(value) => value.len() as u32 + 1,
+        }
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        match self {
+            TagValue::Short(values) => values.iter().flat_map(|v| v.to_le_bytes()).collect(),
+            TagValue::Long(values) => values.iter().flat_map(|v| v.to_le_bytes()).collect(),
+            TagValue::Double(values) => values.iter().flat_map(|v| v.to_le_bytes()).collect(),
+            TagValue::Ascii(value) => value.bytes().chain([0]).collect(),
+        }
+    }
+}
+
+/// Octets a directory and the values that do not fit in its entries take up
+fn directory_size(directory: &[(u16, TagValue)]) -> usize {
+    let values: usize = directory
+        .iter()
+        .map(|(_, value)| value.bytes().len())
+        .filter(|len| *len > 4)
+        .map(|len| len + len % 2)
+        .sum();
+    2 + 12 * directory.len() + 4 + values
+}
+
+/// Write a little endian TIFF with every directory ahead of the image data,
+/// which runs from the last image to the first
+fn write_tiff(
+    mut directories: Vec<Vec<(u16, TagValue)>>,
+    blocks: Vec<Vec<Vec<u8>>>,
+) -> Result<Vec<u8>, GribberishError> {
+    let directory_offsets = directories
+        .iter()
+        .scan(8, |offset, directory| {
+            let start = *offset;
+            *offset += directory_size(directory);
+            Some(start)
+        })
+        .collect::<Vec<_>>();
+    let data_start = 8 + directories.iter().map(|d| directory_size(d)).sum::<usize>();
+    let total_length = data_start + blocks.iter().flatten().map(|b| b.len()).sum::<usize>();
+    if total_length > u32::MAX as usize {
+        return Err(GribberishError::EncodingError(
+            "GeoTIFF larger than 4 GB, BigTIFF is not supported".into(),
+        ));
+    }
+
+    let mut offset = data_start;
+    for (directory, image_blocks) in directories.iter_mut().zip(blocks.iter()).rev() {
+        let block_offsets = image_blocks
+            .iter()
+            .map(|block| {
+                let start = offset as u32;
+                offset += block.len();
+                start
+            })
+            .collect();
+        for (tag, value) in directory.iter_mut() {
+            if *tag == 273 || *tag == 324 {
+                *value = TagValue::Long(block_offsets);
+                break;
+            }
+        }
+    }
+
+    let mut tiff = Vec::with_capacity(total_length);
+    tiff.extend_from_slice(b"II");
+    tiff.extend_from_slice(&42u16.to_le_bytes());
+    tiff.extend_from_slice(&8u32.to_le_bytes());
+    for (index, directory) in directories.iter_mut().enumerate() {
+        directory.sort_by_key(|(tag, _)| *tag);
+        let next = directory_offsets.get(index + 1).copied().unwrap_or(0) as u32;
+
+        let mut values_offset = tiff.len() + 2 + 12 * directory.len() + 4;
+        let mut values = Vec::new();
+        tiff.extend_from_slice(&(directory.len() as u16).to_le_bytes());
+        for (tag, value) in directory.iter() {
+            let bytes = value.bytes();
+            tiff.extend_from_slice(&tag.to_le_bytes());
+            tiff.extend_from_slice(&value.field_type().to_le_bytes());
+            tiff.extend_from_slice(&value.count().to_le_bytes());
+            if bytes.len() <= 4 {
+                tiff.extend_from_slice(&bytes);
+                tiff.extend(std::iter::repeat_n(0, 4 - bytes.len()));
+            } else {
+                tiff.extend_from_slice(&(values_offset as u32).to_le_bytes());
+                values_offset += bytes.len() + bytes.len() % 2;
+                values.extend(bytes);
+                if values.len() % 2 == 1 {
+                    values.push(0);
+                }
+            }
+        }
+        tiff.extend_from_slice(&next.to_le_bytes());
+        tiff.extend(values);
+    }
+
+    for block in blocks.iter().rev().flatten() {
+        tiff.extend_from_slice(block);
+    }
+
+    Ok(tiff)
+}
@@ -1,9 +1,12 @@
 /// GRIB1 Binary Data Section (Section 4)
 ///
 /// The BDS contains the actual packed data values.
-/// This implementation handles simple packing (most common).
+/// This implementation handles simple packing (most common) of grid point
+/// data and simple or complex packing of spherical harmonic coefficients.
+use crate::spectral::{expand_complex_packing, triangular_truncation};
 use crate::utils::convert::{
-    read_grib1_sign_magnitude_i16_from_bytes, read_ibm_f32_from_bytes, read_u24_from_bytes,
+    read_grib1_sign_magnitude_i16_from_bytes, read_ibm_f32_from_bytes, read_u16_from_bytes,
+    read_u24_from_bytes,
 };
 
 #[derive(Debug, Clone)]
@@ -68,6 +71,86 @@ impl Grib1BinaryDataSection {
         self.data[10]
     }
 
+    /// Unpack spherical harmonic coefficients, applying the decimal scale
+    /// factor to the packed values only
+    pub fn unpack_spherical_harmonics(
+        &self,
+        num_values: usize,
+        decimal_scale_factor: i16,
+    ) -> Result<Vec<f64>, String> {
+        if !self.is_spherical_harmonics() {
+            return Err("Data are not spherical harmonic coefficients".to_string());
+        }
+
+        let num_bits = self.num_bits() as usize;
+        if num_bits > 32 {
+            return Err(format!("Invalid number of bits: {}", num_bits));
+        }
+
+        let reference = self.reference_value() as f64;
+        let scale_factor = 2.0f64.powi(self.binary_scale_factor() as i32);
+        let unpack = |offset: usize, count: usize| -> Vec<f64> {
+            (0..count)
+                .map(|i| {
+                    reference
+                        + read_bits(&self.data, offset * 8 + i * num_bits, num_bits) as f64
+                            * scale_factor
+                })
+                .collect()
+        };
+
+        if !self.is_complex_packing() {
+            // The real part of the (0, 0) coefficient is stored unpacked in octets 12-15
+            let first = read_ibm_f32_from_bytes(&self.data, 11).ok_or("BDS too short")? as f64;
+            let decimal_scale = 10f64.powi(-(decimal_scale_factor as i32));
+            let mut values = Vec::with_capacity(num_values);
+            values.push(first);
+            values.extend(
+                unpack(15, num_values.saturating_sub(1))
+                    .into_iter()
+                    .map(|v| v * decimal_scale),
+            );
+            return Ok(values);
+        }
+
+        if self.data.len() < 18 {
+            return Err("Complex packed spherical harmonics BDS too short".to_string());
+        }
+
+        let truncation =
+            triangular_truncation(num_values).ok_or("Only triangular truncations are supported")?;
+        // Octets 12-13 point to the packed data, P is stored in thousandths
+        let packed_start = read_u16_from_bytes(&self.data, 11).unwrap_or(0) as usize;
+        let laplacian_operator =
+            read_grib1_sign_magnitude_i16_from_bytes(&self.data, 13).unwrap_or(0) as f64 / 1000.0;
+        let (js, ks, ms) = (self.data[15], self.data[16], self.data[17]);
+        if js != ks || ks != ms {
+            return Err("Only triangular unpacked subsets are supported".to_string());
+        }
+
+        let subset_truncation = js as usize;
+        let subset_count = (subset_truncation + 1) * (subset_truncation + 2);
+        let subset = (0..subset_count)
+            .map(|i| read_ibm_f32_from_bytes(&self.data, 18 + 4 * i).map(|v| v as f64))
+            .collect::<Option<Vec<_>>>()
+            .ok_or("BDS too short for the unpacked subset")?;
+
+        if packed_start == 0 || packed_start > self.data.len() {
+            return Err("Invalid pointer to the packed spherical harmonics".to_string());
+        }
+        let packed = unpack(packed_start - 1, num_values.saturating_sub(subset_count));
+
+        expand_complex_packing(
+            truncation,
+            subset_truncation,
+            &subset,
+            &packed,
+            laplacian_operator,
+            decimal_scale_factor,
+        )
+        .map_err(|e| e.to_string())
+    }
+
     /// Unpack data values
     pub fn unpack_data(
         &self,
@@ -245,6 +328,60 @@ mod tests {
         assert_eq!(bds.binary_scale_factor(), -5);
     }
 
+    #[test]
+    fn test_spherical_harmonics_simple_unpacking() {
+        let mut data = vec![0u8; 20];
+        data[3] = 0x80; // Spherical harmonics, simple packing
+        data[6..10].copy_from_slice(&ieee_to_ibm_bytes(0.0));
+        data[10] = 8;
+        data[11..15].copy_from_slice(&ieee_to_ibm_bytes(250.0));
+        data[15..20].copy_from_slice(&[1, 2, 3, 4, 5]);
+
+        let bds = Grib1BinaryDataSection::from_data(&data).unwrap();
+        assert!(bds.unpack_data(6, None).is_err());
+        let values = bds.unpack_spherical_harmonics(6, 1).unwrap();
+        let expected = [250.0, 0.1, 0.2, 0.3, 0.4, 0.5];
+        for (v, e) in values.iter().zip(expected) {
+            assert!((v - e).abs() < 1e-6, "{v} != {e}");
+        }
+    }
+
+    #[test]
+    fn test_spherical_harmonics_complex_unpacking() {
+        // T2 with a T1 subset stored as IBM floats, then the 6 packed values
+        let mut data = vec![0u8, 0, 48, 0xC0, 0, 0];
+        data.extend_from_slice(&ieee_to_ibm_bytes(0.0));
+        data.push(8);
+        data.extend_from_slice(&43u16.to_be_bytes()); // Packed data start at octet 43
+        data.extend_from_slice(&500u16.to_be_bytes()); // P = 0.5
+        data.extend_from_slice(&[1, 1, 1]);
+        for v in [280.0, 0.0, 1.5, 0.0, -2.0, 0.25] {
+            data.extend_from_slice(&ieee_to_ibm_bytes(v));
+        }
+        data.extend_from_slice(&[12, 1, 6, 8, 4, 2]);
+
+        let bds = Grib1BinaryDataSection::from_data(&data).unwrap();
+        let values = bds.unpack_spherical_harmonics(12, 0).unwrap();
+        let scale = 1.0 / 6f64.sqrt();
+        let expected = [
+            280.0,
+            0.0,
+            1.5,
+            0.0,
+            12.0 * scale,
+            scale,
+            -2.0,
+            0.25,
+            6.0 * scale,
+            8.0 * scale,
+            4.0 * scale,
+            2.0 * scale,
+        ];
+        for (v, e) in values.iter().zip(expected) {
+            assert!((v - e).abs() < 1e-6, "{v} != {e}");
+        }
+    }
+
     #[test]
     fn test_simple_unpacking() {
         let mut data = vec![0u8; 14];
@@ -2,10 +2,16 @@
 ///
 /// The GDS describes the grid geometry and projection.
 /// This implementation focuses on lat/lon grids (type 0) initially.
+use crate::spectral::coefficient_value_count;
 use crate::templates::grid_definition::grid_definition_template::GridDefinitionTemplate;
+use crate::templates::grid_definition::tables::{
+    ResolutionAndComponent, ResolutionAndComponentFlags,
+};
 use crate::utils::convert::read_u16_from_bytes;
+use crate::utils::gaussian::{gaussian_rows, reduced_row_longitudes};
 use crate::utils::iter::projection::{
-    LatLngProjection, PlateCareeProjection, RegularCoordinateIterator,
+    GaussianLongitudes, GaussianProjection, LatLngProjection, PlateCareeProjection,
+    RegularCoordinateIterator,
 };
 use std::collections::HashMap;
 
@@ -13,21 +19,23 @@ use std::collections::HashMap;
 pub enum Grib1Grid {
     LatLon(LatLonGrid),
     Gaussian(GaussianGrid),
+    SphericalHarmonics(SphericalHarmonicsGrid),
     // Other grid types can be added later
     Unsupported { grid_type: u8 },
 }
 
 #[derive(Debug, Clone)]
 pub struct LatLonGrid {
-    pub ni: usize,         // Number of points along x-axis
-    pub nj: usize,         // Number of points along y-axis
-    pub lat1: f64,         // Latitude of first grid point (degrees)
-    pub lon1: f64,         // Longitude of first grid point (degrees)
-    pub lat2: f64,         // Latitude of last grid point (degrees)
-    pub lon2: f64,         // Longitude of last grid point (degrees)
-    pub di: f64,           // i-direction increment (degrees)
-    pub dj: f64,           // j-direction increment (degrees)
-    pub scanning_mode: u8, // Scanning mode flags
+    pub ni: usize,            // Number of points along x-axis
+    pub nj: usize,            // Number of points along y-axis
+    pub lat1: f64,            // Latitude of first grid point (degrees)
+    pub lon1: f64,            // Longitude of first grid point (degrees)
+    pub lat2: f64,            // Latitude of last grid point (degrees)
+    pub lon2: f64,            // Longitude of last grid point (degrees)
+    pub di: f64,              // i-direction increment (degrees)
+    pub dj: f64,              // j-direction increment (degrees)
+    pub resolution_flags: u8, // Resolution and component flags
+    pub scanning_mode: u8,    // Scanning mode flags
 }
 
 #[derive(Debug, Clone)]
@@ -40,7 +48,31 @@ pub struct GaussianGrid {
     pub lon2: f64,
     pub di: f64,
     pub n: u16, // Number of latitude circles between pole and equator
+    pub resolution_flags: u8,
     pub scanning_mode: u8,
+    pub points_per_parallel: Vec<usize>, // Empty unless the grid is quasi-regular (reduced)
+}
+
+#[derive(Debug, Clone)]
+pub struct SphericalHarmonicsGrid {
+    pub j: usize, // Pentagonal resolution parameters
+    pub k: usize,
+    pub m: usize,
+    pub representation_type: u8, // Code table 9
+    pub representation_mode: u8, // Code table 10
+}
+
+impl SphericalHarmonicsGrid {
+    /// Number of real values, two for each complex coefficient
+    pub fn value_count(&self) -> usize {
+        coefficient_value_count(self.j, self.k, self.m)
+    }
+}
+
+impl GaussianGrid {
+    pub fn is_reduced(&self) -> bool {
+        !self.points_per_parallel.is_empty()
+    }
 }
 
 impl Grib1Grid {
@@ -54,6 +86,7 @@ impl Grib1Grid {
         match grid_type {
             0 => Self::parse_latlon(data),
             4 => Self::parse_gaussian(data),
+            50 => Self::parse_spherical_harmonics(data),
             _ => Ok(Grib1Grid::Unsupported { grid_type }),
         }
     }
@@ -70,7 +103,7 @@ impl Grib1Grid {
         let lat1 = read_signed_24(data, 10) as f64 / 1000.0;
         let lon1 = read_signed_24(data, 13) as f64 / 1000.0;
 
-        let _resolution_flags = data[16];
+        let resolution_flags = data[16];
 
         let lat2 = read_signed_24(data, 17) as f64 / 1000.0;
         let lon2 = read_signed_24(data, 20) as f64 / 1000.0;
@@ -89,6 +122,7 @@ impl Grib1Grid {
             lon2,
             di,
             dj,
+            resolution_flags,
             scanning_mode,
         }))
     }
@@ -104,6 +138,8 @@ impl Grib1Grid {
         let lat1 = read_signed_24(data, 10) as f64 / 1000.0;
         let lon1 = read_signed_24(data, 13) as f64 / 1000.0;
 
+        let resolution_flags = data[16];
+
         let lat2 = read_signed_24(data, 17) as f64 / 1000.0;
         let lon2 = read_signed_24(data, 20) as f64 / 1000.0;
 
@@ -112,6 +148,26 @@ impl Grib1Grid {
 
         let scanning_mode = data[27];
 
+        // Quasi-regular grids leave ni missing and list the points on each
+        // parallel after the vertical coordinate parameters
+        let points_per_parallel = if ni == 0xFFFF {
+            let nv = data[3] as usize;
+            let pv_location = data[4] as usize;
+            if pv_location == 0 || pv_location == 255 {
+                return Err("Reduced Gaussian GDS missing list of points per parallel".to_string());
+            }
+            let start = pv_location - 1 + 4 * nv;
+            (0..nj)
+                .map(|i| {
+                    read_u16_from_bytes(data, start + 2 * i)
+                        .map(|points| points as usize)
+                        .ok_or("Failed to read points per parallel")
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        } else {
+            vec![]
+        };
+
         Ok(Grib1Grid::Gaussian(GaussianGrid {
             ni,
             nj,
@@ -121,7 +177,27 @@ impl Grib1Grid {
             lon2,
             di,
             n,
+            resolution_flags,
             scanning_mode,
+            points_per_parallel,
+        }))
+    }
+
+    fn parse_spherical_harmonics(data: &[u8]) -> Result<Self, String> {
+        if data.len() < 14 {
+            return Err("Spherical harmonics GDS too short".to_string());
+        }
+
+        let j = read_u16_from_bytes(data, 6).ok_or("Failed to read J")? as usize;
+        let k = read_u16_from_bytes(data, 8).ok_or("Failed to read K")? as usize;
+        let m = read_u16_from_bytes(data, 10).ok_or("Failed to read M")? as usize;
+
+        Ok(Grib1Grid::SphericalHarmonics(SphericalHarmonicsGrid {
+            j,
+            k,
+            m,
+            representation_type: data[12],
+            representation_mode: data[13],
         }))
     }
 
@@ -129,7 +205,14 @@ impl Grib1Grid {
     pub fn dimensions(&self) -> (usize, usize) {
         match self {
             Grib1Grid::LatLon(grid) => (grid.ni, grid.nj),
+            // Reduced grids have no fixed row length, so their points are laid
+            // out along a single row
+            Grib1Grid::Gaussian(grid) if grid.is_reduced() => {
+                (grid.points_per_parallel.iter().sum(), 1)
+            }
             Grib1Grid::Gaussian(grid) => (grid.ni, grid.nj),
+            // Coefficients are laid out along a single row too
+            Grib1Grid::SphericalHarmonics(grid) => (grid.value_count(), 1),
             Grib1Grid::Unsupported { .. } => (0, 0),
         }
     }
@@ -176,12 +259,8 @@ impl Grib1Grid {
                 }
                 lats
             }
-            Grib1Grid::Gaussian(_) => {
-                // Gaussian grid latitudes require special calculation
-                // For now, return empty - will implement later if needed
-                vec![]
-            }
-            Grib1Grid::Unsupported { .. } => vec![],
+            Grib1Grid::Gaussian(grid) => gaussian_rows(grid.n as usize, grid.lat1, grid.lat2),
+            Grib1Grid::SphericalHarmonics(_) | Grib1Grid::Unsupported { .. } => vec![],
         }
     }
 
@@ -227,7 +306,7 @@ impl Grib1Grid {
                 }
                 lons
             }
-            Grib1Grid::Unsupported { .. } => vec![],
+            Grib1Grid::SphericalHarmonics(_) | Grib1Grid::Unsupported { .. } => vec![],
         }
     }
 }
@@ -263,6 +342,7 @@ impl GridDefinitionTemplate for Grib1Grid {
         match self {
             Grib1Grid::LatLon(_) => "latlon".to_string(),
             Grib1Grid::Gaussian(_) => "latlon".to_string(), // Gaussian grids are also on lat/lon
+            Grib1Grid::SphericalHarmonics(_) => "spectral".to_string(),
             Grib1Grid::Unsupported { .. } => "unknown".to_string(),
         }
     }
@@ -280,6 +360,8 @@ impl GridDefinitionTemplate for Grib1Grid {
             Grib1Grid::LatLon(_) | Grib1Grid::Gaussian(_) => {
                 "+proj=latlon +a=6378137 +b=6356752.314245".to_string()
             }
+            // Coefficients have no location to project
+            Grib1Grid::SphericalHarmonics(_) => "".to_string(),
             Grib1Grid::Unsupported { .. } => "+proj=latlon".to_string(),
         }
     }
@@ -287,7 +369,9 @@ impl GridDefinitionTemplate for Grib1Grid {
     fn crs(&self) -> String {
         match self {
             Grib1Grid::LatLon(_) | Grib1Grid::Gaussian(_) => "EPSG:4326".to_string(),
-            Grib1Grid::Unsupported { .. } => "unknown".to_string(),
+            Grib1Grid::SphericalHarmonics(_) | Grib1Grid::Unsupported { .. } => {
+                "unknown".to_string()
+            }
         }
     }
 
@@ -298,8 +382,8 @@ impl GridDefinitionTemplate for Grib1Grid {
     fn is_regular_grid(&self) -> bool {
         match self {
             Grib1Grid::LatLon(_) => true,
-            Grib1Grid::Gaussian(_) => false, // Gaussian grids are not regular in latitude
-            Grib1Grid::Unsupported { .. } => false,
+            Grib1Grid::Gaussian(grid) => !grid.is_reduced(), // Rows share one longitude axis
+            Grib1Grid::SphericalHarmonics(_) | Grib1Grid::Unsupported { .. } => false,
         }
     }
 
@@ -345,37 +429,45 @@ impl GridDefinitionTemplate for Grib1Grid {
                 })
             }
             Grib1Grid::Gaussian(grid) => {
-                // For Gaussian grids, use a simple regular projection
-                // Note: This is a simplification; proper Gaussian grid support would
-                // require computing actual Gaussian latitudes
-                let lon_start = grid.lon1;
-
                 let lon_increment = if self.scans_positively_i() {
                     grid.di
                 } else {
                     -grid.di
                 };
 
-                // For latitudes, we'll use a simple linear interpolation
-                // between lat1 and lat2 as a fallback
-                let lat_increment = if grid.nj > 1 {
-                    (grid.lat2 - grid.lat1) / (grid.nj - 1) as f64
+                let longitudes = if grid.is_reduced() {
+                    let max_points = grid.points_per_parallel.iter().copied().max().unwrap_or(1);
+                    let mut span = grid.lon2 - grid.lon1;
+                    if span < 0.0 {
+                        span += 360.0;
+                    }
+                    // Longitudes are stored to a millidegree
+                    let is_global = (span + 360.0 / max_points as f64 - 360.0).abs() < 1e-2;
+
+                    GaussianLongitudes::Reduced(
+                        grid.points_per_parallel
+                            .iter()
+                            .map(|points| {
+                                reduced_row_longitudes(*points, grid.lon1, grid.lon2, is_global)
+                            })
+                            .collect(),
+                    )
                 } else {
-                    0.0
+                    GaussianLongitudes::Regular(RegularCoordinateIterator::new(
+                        grid.lon1,
+                        lon_increment,
+                        grid.ni,
+                    ))
                 };
 
-                let lat_iter = RegularCoordinateIterator::new(grid.lat1, lat_increment, grid.nj);
-
-                let lon_iter = RegularCoordinateIterator::new(lon_start, lon_increment, grid.ni);
-
-                LatLngProjection::PlateCaree(PlateCareeProjection {
-                    latitudes: lat_iter,
-                    longitudes: lon_iter,
+                LatLngProjection::Gaussian(GaussianProjection {
+                    latitudes: self.latitudes(),
+                    longitudes,
                     projection_name: self.proj_name(),
                     projection_params: self.proj_params(),
                 })
             }
-            Grib1Grid::Unsupported { .. } => {
+            Grib1Grid::SphericalHarmonics(_) | Grib1Grid::Unsupported { .. } => {
                 // Return an empty projection
                 let lat_iter = RegularCoordinateIterator::new(0.0, 1.0, 0);
                 let lon_iter = RegularCoordinateIterator::new(0.0, 1.0, 0);
@@ -389,6 +481,18 @@ impl GridDefinitionTemplate for Grib1Grid {
             }
         }
     }
+
+    fn resolution_and_component_flags(&self) -> Option<ResolutionAndComponentFlags> {
+        match self {
+            Grib1Grid::LatLon(grid) => Some(ResolutionAndComponent::read_grib1_flags(
+                grid.resolution_flags,
+            )),
+            Grib1Grid::Gaussian(grid) => Some(ResolutionAndComponent::read_grib1_flags(
+                grid.resolution_flags,
+            )),
+            Grib1Grid::SphericalHarmonics(_) | Grib1Grid::Unsupported { .. } => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -455,4 +559,87 @@ mod tests {
             _ => panic!("Expected LatLon grid"),
         }
     }
+
+    #[test]
+    fn test_reduced_gaussian_grid() {
+        // N2 quasi-regular grid with the points per parallel after the GDS
+        let mut data = vec![0u8; 32];
+        data[3] = 0; // No vertical coordinate parameters
+        data[4] = 33; // Points per parallel start at octet 33
+        data[5] = 4; // Grid type 4 (Gaussian)
+        data[6..8].copy_from_slice(&0xFFFFu16.to_be_bytes()); // ni missing
+        data[8..10].copy_from_slice(&4u16.to_be_bytes()); // nj = 4
+
+        let latitudes = crate::utils::gaussian::gaussian_latitudes(2);
+        let millidegrees = |v: f64| {
+            let magnitude = (v.abs() * 1000.0).round() as u32 | if v < 0.0 { 0x800000 } else { 0 };
+            [
+                (magnitude >> 16) as u8,
+                (magnitude >> 8) as u8,
+                magnitude as u8,
+            ]
+        };
+        data[10..13].copy_from_slice(&millidegrees(latitudes[0]));
+        data[17..20].copy_from_slice(&millidegrees(latitudes[3]));
+        data[20..23].copy_from_slice(&millidegrees(315.0));
+        data[25..27].copy_from_slice(&2u16.to_be_bytes()); // n = 2
+        for points in [4u16, 8, 8, 4] {
+            data.extend_from_slice(&points.to_be_bytes());
+        }
+
+        let grid = Grib1Grid::from_data(&data).unwrap();
+        assert_eq!(grid.dimensions(), (24, 1));
+        assert!(!grid.is_regular_grid());
+        assert_eq!(grid.latitudes(), latitudes);
+
+        let (lats, lngs) = grid.projector().lat_lng();
+        assert_eq!(lats.len(), 24);
+        assert_eq!(lats[4], latitudes[1]);
+        assert_eq!(&lngs[0..4], &[0.0, 90.0, 180.0, 270.0]);
+        assert_eq!(lngs[5], 45.0);
+    }
+
+    #[test]
+    fn test_spherical_harmonics_grid() {
+        let mut data = vec![0u8; 32];
+        data[5] = 50;
+        data[6..8].copy_from_slice(&213u16.to_be_bytes());
+        data[8..10].copy_from_slice(&213u16.to_be_bytes());
+        data[10..12].copy_from_slice(&213u16.to_be_bytes());
+        data[12] = 1;
+        data[13] = 1;
+
+        let grid = Grib1Grid::from_data(&data).unwrap();
+        match &grid {
+            Grib1Grid::SphericalHarmonics(g) => {
+                assert_eq!((g.j, g.k, g.m), (213, 213, 213));
+                assert_eq!(g.value_count(), 214 * 215);
+            }
+            _ => panic!("Expected spherical harmonics"),
+        }
+        assert_eq!(grid.dimensions(), (214 * 215, 1));
+        assert!(!grid.is_regular_grid());
+        assert!(grid.latitudes().is_empty());
+    }
+
+    #[test]
+    fn test_regular_gaussian_latitudes() {
+        let mut data = vec![0u8; 32];
+        data[5] = 4;
+        data[6..8].copy_from_slice(&8u16.to_be_bytes());
+        data[8..10].copy_from_slice(&4u16.to_be_bytes());
+        // 59.444 N to 59.444 S, the outer rows of an N2 grid in millidegrees
+        data[10..13].copy_from_slice(&[0x00, 0xE8, 0x34]);
+        data[17..20].copy_from_slice(&[0x80, 0xE8, 0x34]);
+        // 315 E
+        data[20..23].copy_from_slice(&[0x04, 0xCE, 0x78]);
+        data[23..25].copy_from_slice(&45000u16.to_be_bytes());
+        data[25..27].copy_from_slice(&2u16.to_be_bytes());
+
+        let grid = Grib1Grid::from_data(&data).unwrap();
+        assert!(grid.is_regular_grid());
+        let (lats, lngs) = grid.projector().lat_lng();
+        assert_eq!(lats, crate::utils::gaussian::gaussian_latitudes(2));
+        assert_eq!(lngs.len(), 8);
+    }
 }
@@ -198,6 +198,14 @@ impl Grib1Message {
             return Err("Grid dimensions not available".to_string());
         }
 
+        // Spherical harmonic coefficients are never masked by a bitmap and
+        // only part of them is decimally scaled
+        if self.bds.is_spherical_harmonics() {
+            return self
+                .bds
+                .unpack_spherical_harmonics(num_points, self.pds.decimal_scale_factor());
+        }
+
         // Build bitmap if present
         let bitmap_vec: Option<Vec<bool>> = self
             .bitmap
@@ -220,6 +228,11 @@ impl Grib1Message {
         Ok(values)
     }
 
+    /// Whether the data are spherical harmonic coefficients rather than grid point values
+    pub fn is_spherical_harmonics(&self) -> bool {
+        self.bds.is_spherical_harmonics()
+    }
+
     /// Get center ID
     pub fn center_id(&self) -> u8 {
         self.pds.center_id()
@@ -244,6 +257,22 @@ impl Grib1Message {
     pub fn number_of_ensemble_members(&self) -> Option<u8> {
         self.pds.number_of_ensemble_members()
     }
+
+    /// MARS class, type and stream of an ECMWF local extension, each `None`
+    /// when absent or not known.
+    pub fn mars_labelling(
+        &self,
+    ) -> (
+        Option<&'static str>,
+        Option<&'static str>,
+        Option<&'static str>,
+    ) {
+        (
+            self.pds.mars_class(),
+            self.pds.mars_type(),
+            self.pds.mars_stream(),
+        )
+    }
 }
 
 /// Helper to read 24-bit unsigned integer
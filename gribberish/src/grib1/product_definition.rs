@@ -230,6 +230,63 @@ impl Grib1ProductDefinitionSection {
         }
     }
 
+    /// MARS class of an ECMWF local extension (octet 42), such as `od` for
+    /// operational data or `ea` for ERA5. `None` when there is no local
+    /// extension or the code is not known.
+    pub fn mars_class(&self) -> Option<&'static str> {
+        self.local_definition_number()?;
+        match self.data.get(41)? {
+            1 => Some("od"),
+            2 => Some("rd"),
+            3 => Some("er"),
+            4 => Some("cs"),
+            5 => Some("e4"),
+            21 => Some("ei"),
+            23 => Some("ea"),
+            _ => None,
+        }
+    }
+
+    /// MARS type of an ECMWF local extension (octet 43), such as `an` for
+    /// analyses or `pf` for perturbed forecasts. `None` when there is no local
+    /// extension or the code is not known.
+    pub fn mars_type(&self) -> Option<&'static str> {
+        self.local_definition_number()?;
+        match self.data.get(42)? {
+            1 => Some("fg"),
+            2 => Some("an"),
+            3 => Some("ia"),
+            4 => Some("oi"),
+            5 => Some("3v"),
+            6 => Some("4v"),
+            9 => Some("fc"),
+            10 => Some("cf"),
+            11 => Some("pf"),
+            17 => Some("em"),
+            18 => Some("es"),
+            30 => Some("ep"),
+            _ => None,
+        }
+    }
+
+    /// MARS stream of an ECMWF local extension (octets 44 - 45), such as `oper`
+    /// for the atmospheric model or `enda` for the ensemble data assimilation.
+    /// `None` when there is no local extension or the code is not known.
+    pub fn mars_stream(&self) -> Option<&'static str> {
+        self.local_definition_number()?;
+        if self.data.len() < 45 {
+            return None;
+        }
+        match read_u16_from_bytes(&self.data, 43)? {
+            1025 => Some("oper"),
+            1030 => Some("enda"),
+            1035 => Some("enfo"),
+            1045 => Some("waef"),
+            1046 => Some("wave"),
+            _ => None,
+        }
+    }
+
     /// Whether this PDS carries the standard ECMWF MARS ensemble labelling.
     ///
     /// Local definition 1 in particular is also used for plain (non-ensemble)
@@ -312,6 +369,27 @@ mod tests {
         assert_eq!(pds.number_of_ensemble_members(), Some(10));
     }
 
+    #[test]
+    fn test_ecmwf_mars_labelling() {
+        // ERA5 EDA member: class ea, type an, stream enda
+        let mut data = ecmwf_pds_with_local(36, 7, 10);
+        data[41] = 23;
+        data[42] = 2;
+        data[43..45].copy_from_slice(&1030u16.to_be_bytes());
+        let pds = Grib1ProductDefinitionSection::from_data(&data).unwrap();
+        assert_eq!(pds.mars_class(), Some("ea"));
+        assert_eq!(pds.mars_type(), Some("an"));
+        assert_eq!(pds.mars_stream(), Some("enda"));
+
+        // Without a local extension there is nothing to read
+        let mut data = vec![0u8; 28];
+        data[0..3].copy_from_slice(&[0x00, 0x00, 0x1c]);
+        data[4] = 98;
+        let pds = Grib1ProductDefinitionSection::from_data(&data).unwrap();
+        assert_eq!(pds.mars_type(), None);
+        assert_eq!(pds.mars_stream(), None);
+    }
+
     #[test]
     fn test_ecmwf_non_ensemble_mars_labelling() {
         // Local definition 1 with total == 0 is plain MARS data, not an ensemble.
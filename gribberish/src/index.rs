@@ -3,6 +3,12 @@ use std::collections::BTreeMap;
 use chrono::{DateTime, TimeZone, Utc};
 
 use crate::error::GribberishError;
+use crate::message::{read_messages, Message};
+use crate::message_metadata::MessageMetadata;
+use crate::templates::product::tables::{
+    DerivedForecastType, EnsembleForecastType, GeneratingProcess, ProbabilityType, TimeUnit,
+    TypeOfStatisticalProcessing,
+};
 
 /// One line of a GRIB sidecar index file, locating a single message inside a
 /// GRIB file along with whatever identity metadata the index format carries.
@@ -19,6 +25,7 @@ use crate::error::GribberishError;
 /// still require reading the message bytes. (cfgrib's pickled `*.idx` cache
 /// files are an unrelated format and are not supported.)
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IndexEntry {
     /// 1-based message number: explicit in NOAA indexes, the line number in
     /// ECMWF indexes.
@@ -180,6 +187,570 @@ pub fn parse_ecmwf_index(text: &str) -> Result<Vec<IndexEntry>, GribberishError>
         .collect()
 }
 
+/// Write a NOAA wgrib2-style `.idx` inventory of every message in `data`, in
+/// the format of `wgrib2 -s`. Fields of messages holding several are numbered
+/// `message.submessage`. Fields whose metadata cannot be read are left out, the
+/// message numbers of the rest are unchanged.
+pub fn write_noaa_index(data: &[u8]) -> String {
+    let mut index = String::new();
+    let mut message_number = 0;
+    let mut last_offset = None;
+
+    for message in read_messages(data) {
+        if last_offset != Some(message.byte_offset()) {
+            message_number += 1;
+            last_offset = Some(message.byte_offset());
+        }
+
+        let Ok(metadata) = MessageMetadata::try_from(&message) else {
+            continue;
+        };
+
+        let number = match metadata.submessage {
+            Some(submessage) => format!("{message_number}.{submessage}"),
+            None => message_number.to_string(),
+        };
+
+        let mut fields = vec![
+            number,
+            message.byte_offset().to_string(),
+            format!("d={}", metadata.reference_date.format("%Y%m%d%H")),
+            metadata.var.clone(),
+            noaa_level(&message, &metadata),
+            noaa_forecast_time(&metadata),
+        ];
+        fields.extend(noaa_extras(&message, &metadata));
+
+        index.push_str(&fields.join(":"));
+        index.push_str(":\n");
+    }
+
+    index
+}
+
+/// The level of a field the way wgrib2 describes it, e.g. `500 mb` or
+/// `0-0.1 m below ground`
+fn noaa_level(message: &Message, metadata: &MessageMetadata) -> String {
+    let first = metadata.first_fixed_surface_type.clone() as u8;
+    let second = metadata.second_fixed_surface_type.clone() as u8;
+    let first_value =
+        noaa_level_value(message.edition(), first, metadata.first_fixed_surface_value);
+    let second_value = noaa_level_value(
+        message.edition(),
+        second,
+        metadata.second_fixed_surface_value,
+    );
+
+    if let (true, Some(top), Some(bottom)) = (first == second, first_value, second_value) {
+        let range = format!("{}-{}", format_g(top), format_g(bottom));
+        let layer = match first {
+            100 => Some(format!("{range} mb")),
+            102 => Some(format!("{range} m above mean sea level")),
+            103 => Some(format!("{range} m above ground")),
+            104 => Some(format!("{range} sigma layer")),
+            105 => Some(format!("{range} hybrid layer")),
+            106 => Some(format!("{range} m below ground")),
+            108 => Some(format!("{range} mb above ground")),
+            160 => Some(format!("{range} m below sea level")),
+            _ => None,
+        };
+
+        if let Some(layer) = layer {
+            return layer;
+        }
+    }
+
+    let first_level = noaa_surface(
+        first,
+        first_value,
+        &metadata.first_fixed_surface_type.to_string(),
+    );
+    if second == 255 {
+        first_level
+    } else {
+        let second_level = noaa_surface(
+            second,
+            second_value,
+            &metadata.second_fixed_surface_type.to_string(),
+        );
+        format!("{first_level} - {second_level}")
+    }
+}
+
+/// A fixed surface value in the units wgrib2 shows it in. wgrib2 shows pressures
+/// in hPa and depths in m. GRIB1 levels are already in hPa, but its depths are
+/// in cm.
+pub(crate) fn noaa_level_value(edition: u8, code: u8, value: Option<f64>) -> Option<f64> {
+    let factor = match (edition, code) {
+        (1, 106) => 0.01,
+        (2, 100 | 108) => 0.01,
+        _ => 1.0,
+    };
+    value.map(|v| v * factor)
+}
+
+/// A single fixed surface (code table 4.5) the way wgrib2 describes it, with its
+/// value already in wgrib2's units
+pub(crate) fn noaa_surface(code: u8, value: Option<f64>, description: &str) -> String {
+    if let Some(named) = noaa_surface_name(code) {
+        return named.to_string();
+    }
+
+    let Some(value) = value else {
+        return description.to_string();
+    };
+
+    match code {
+        20 => format!("{} K level", format_g(value)),
+        100 => format!("{} mb", format_g(value)),
+        102 => format!("{} m above mean sea level", format_g(value)),
+        103 => format!("{} m above ground", format_g(value)),
+        104 => format!("{} sigma level", format_g(value)),
+        105 => format!("{} hybrid level", format_g(value)),
+        106 => format!("{} m below ground", format_g(value)),
+        107 => format!("{} K isentropic level", format_g(value)),
+        108 => format!("{} mb above ground", format_g(value)),
+        109 => format!("PV={} (Km^2/kg/s) surface", format_g(value)),
+        111 => format!("{} eta level", format_g(value)),
+        160 => format!("{} m below sea level", format_g(value)),
+        161 => format!("{} m below water surface", format_g(value)),
+        241 => format!("{} in sequence", format_g(value)),
+        _ => format!("{} {description}", format_g(value)),
+    }
+}
+
+/// The wgrib2 name of a fixed surface that has no value
+fn noaa_surface_name(code: u8) -> Option<&'static str> {
+    match code {
+        1 => Some("surface"),
+        2 => Some("cloud base"),
+        3 => Some("cloud top"),
+        4 => Some("0C isotherm"),
+        5 => Some("level of adiabatic condensation from sfc"),
+        6 => Some("max wind"),
+        7 => Some("tropopause"),
+        8 => Some("top of atmosphere"),
+        9 => Some("sea bottom"),
+        10 => Some("entire atmosphere"),
+        14 => Some("level of free convection"),
+        101 => Some("mean sea level"),
+        200 => Some("entire atmosphere (considered as a single layer)"),
+        201 => Some("entire ocean (considered as a single layer)"),
+        204 => Some("highest tropospheric freezing level"),
+        211 => Some("boundary layer cloud layer"),
+        212 => Some("low cloud bottom level"),
+        213 => Some("low cloud top level"),
+        214 => Some("low cloud layer"),
+        215 => Some("cloud ceiling"),
+        220 => Some("planetary boundary layer"),
+        222 => Some("middle cloud bottom level"),
+        223 => Some("middle cloud top level"),
+        224 => Some("middle cloud layer"),
+        232 => Some("high cloud bottom level"),
+        233 => Some("high cloud top level"),
+        234 => Some("high cloud layer"),
+        242 => Some("convective cloud bottom level"),
+        243 => Some("convective cloud top level"),
+        244 => Some("convective cloud layer"),
+        247 => Some("equilibrium level"),
+        255 => Some(""),
+        _ => None,
+    }
+}
+
+/// The fixed surface code and wgrib2 level value of a NOAA `.idx` level such as
+/// `500 mb` or `surface`, the inverse of `noaa_surface`. Layers give their
+/// first bound, and of two surfaces `X - Y` the first is used.
+pub(crate) fn parse_noaa_level(level: &str) -> Option<(u8, Option<f64>)> {
+    let level = level.split(" - ").next()?.trim();
+    if level.is_empty() {
+        return None;
+    }
+
+    if let Some(code) = (0..255u8).find(|code| noaa_surface_name(*code) == Some(level)) {
+        return Some((code, None));
+    }
+
+    if let Some(pv) = level.strip_prefix("PV=") {
+        let value = pv.split_whitespace().next()?.parse().ok();
+        return Some((109, value));
+    }
+
+    let (value, surface) = level.split_once(' ')?;
+    // A layer's bounds are joined with a dash, which may also lead a negative value
+    let value = match value.char_indices().skip(1).find(|(_, c)| *c == '-') {
+        Some((dash, _)) => &value[..dash],
+        None => value,
+    };
+    let value = value.parse::<f64>().ok()?;
+    let surface = surface.replace(" layer", " level");
+
+    let code = (0..255u8).find(|code| {
+        noaa_surface(*code, Some(0.0), "")
+            .strip_prefix("0 ")
+            .is_some_and(|named| !named.is_empty() && named == surface)
+    })?;
+    Some((code, Some(value)))
+}
+
+/// The forecast time of a field the way wgrib2 describes it, e.g. `anl`,
+/// `6 hour fcst` or `0-6 hour acc fcst`
+fn noaa_forecast_time(metadata: &MessageMetadata) -> String {
+    let (unit, unit_seconds) = match metadata.time_unit {
+        TimeUnit::Minute => ("min", Some(60)),
+        TimeUnit::Hour => ("hour", Some(3600)),
+        TimeUnit::Day => ("day", Some(86400)),
+        TimeUnit::Month => ("month", None),
+        TimeUnit::Year => ("year", None),
+        TimeUnit::Decade => ("decade", None),
+        TimeUnit::Normal => ("normal", None),
+        TimeUnit::Century => ("century", None),
+        TimeUnit::ThreeHours => ("3 hours", Some(3 * 3600)),
+        TimeUnit::SixHours => ("6 hours", Some(6 * 3600)),
+        TimeUnit::TwelveHours => ("12 hours", Some(12 * 3600)),
+        TimeUnit::Seconds => ("sec", Some(1)),
+    };
+    let start = metadata.time_interval;
+
+    let Some(statistical_process) = metadata.statistical_process.as_ref() else {
+        return if start == 0 {
+            "anl".to_string()
+        } else {
+            format!("{start} {unit} fcst")
+        };
+    };
+
+    let end = match (metadata.forecast_end_date, unit_seconds) {
+        (Some(end_date), Some(unit_seconds)) => {
+            (end_date - metadata.reference_date).num_seconds() / unit_seconds
+        }
+        _ => start as i64,
+    };
+    let process = match statistical_process {
+        TypeOfStatisticalProcessing::Average => "ave".to_string(),
+        other => other.abbv(),
+    };
+
+    format!("{start}-{end} {unit} {process} fcst")
+}
+
+/// Hours from the reference time to the end of a NOAA `.idx` forecast time such
+/// as `anl`, `6 hour fcst` or `0-6 hour acc fcst`
+pub(crate) fn parse_noaa_forecast_hours(forecast_time: &str) -> Option<f64> {
+    if forecast_time == "anl" {
+        return Some(0.0);
+    }
+
+    let mut words = forecast_time.split_whitespace();
+    let end = words.next()?.rsplit('-').next()?.parse::<f64>().ok()?;
+    let hours = match words.next()? {
+        "sec" => 1.0 / 3600.0,
+        "min" => 1.0 / 60.0,
+        "hour" => 1.0,
+        "day" => 24.0,
+        _ => return None,
+    };
+    Some(end * hours)
+}
+
+/// The trailing fields wgrib2 adds for ensemble members, derived ensemble
+/// products, percentiles and probabilities, e.g. `ENS=+5` or `prob >0.254`
+fn noaa_extras(message: &Message, metadata: &MessageMetadata) -> Vec<String> {
+    let mut extras = Vec::new();
+
+    if let Some(number) = metadata.perturbation_number {
+        let ensemble = match message.ensemble_forecast_type().ok().flatten() {
+            // wgrib2 has no name for a control of unstated resolution
+            Some(
+                EnsembleForecastType::UnperturbedHighResolutionControlForecast
+                | EnsembleForecastType::UnperturbedForecast,
+            ) => "ENS=hi-res ctl".to_string(),
+            Some(EnsembleForecastType::UnperturbedLowResolutionControlForecast) => {
+                "ENS=low-res ctl".to_string()
+            }
+            Some(EnsembleForecastType::NegativelyPerturbedForecast) => format!("ENS=-{number}"),
+            Some(EnsembleForecastType::MultiModelForecast) => "ENS=multi-model".to_string(),
+            // GRIB1 ensembles only carry the member number, the control is member 0
+            Some(EnsembleForecastType::Missing) | None if number == 0 => {
+                "ENS=hi-res ctl".to_string()
+            }
+            _ => format!("ENS=+{number}"),
+        };
+        extras.push(ensemble);
+    }
+
+    if let Some(derived) = metadata.derived_forecast_type.as_ref() {
+        let derived = match derived {
+            DerivedForecastType::UnweightedMean => "ens mean".to_string(),
+            DerivedForecastType::WeightedMean => "wt ens mean".to_string(),
+            DerivedForecastType::StandardDeviation => "ens std dev".to_string(),
+            DerivedForecastType::NormalizedStandardDeviation => {
+                "ens std dev normalized".to_string()
+            }
+            DerivedForecastType::Spread => "ens spread".to_string(),
+            DerivedForecastType::LargeAnomaly => "ens large anomaly index".to_string(),
+            DerivedForecastType::UnweightedMeanOfClustered => "ens mean of cluster".to_string(),
+            DerivedForecastType::InterquartileRange => "ens interquartile range".to_string(),
+            DerivedForecastType::Minimum => "ens min".to_string(),
+            DerivedForecastType::Maximum => "ens max".to_string(),
+            other => other.to_string(),
+        };
+        extras.push(derived);
+    }
+
+    if let Some(percentile) = metadata.percentile_value {
+        extras.push(format!("{percentile}% level"));
+    }
+
+    if let Some(probability_type) = metadata.probability_type.as_ref() {
+        let lower = metadata.probability_lower_limit.map(format_g);
+        let upper = metadata.probability_upper_limit.map(format_g);
+        let probability = match (probability_type, lower, upper) {
+            (ProbabilityType::BelowLowerLimit, Some(lower), _) => Some(format!("prob <{lower}")),
+            (ProbabilityType::AboveUpperLimit, _, Some(upper)) => Some(format!("prob >{upper}")),
+            (ProbabilityType::BetweenLimits, Some(lower), Some(upper)) => {
+                Some(format!("prob >={lower} <{upper}"))
+            }
+            (ProbabilityType::AboveLowerLimit, Some(lower), _) => Some(format!("prob >{lower}")),
+            (ProbabilityType::BelowUpperLimit, _, Some(upper)) => Some(format!("prob <{upper}")),
+            (ProbabilityType::EqualToLowerLimit, Some(lower), _) => Some(format!("prob ={lower}")),
+            _ => None,
+        };
+        extras.extend(probability);
+    }
+
+    extras
+}
+
+/// Write an ECMWF open-data style `.index` of every message in `data`: one JSON
+/// object per line with MARS keys derived from the message metadata, plus its
+/// `_offset` and `_length`. The format has no notion of submessages, so each
+/// field of a message holding several gets its own line sharing the message's
+/// byte range. `param` is the MARS short name of the parameter, see
+/// [`ecmwf_param`].
+pub fn write_ecmwf_index(data: &[u8]) -> String {
+    let mut index = String::new();
+
+    for message in read_messages(data) {
+        let Ok(metadata) = MessageMetadata::try_from(&message) else {
+            continue;
+        };
+
+        let levtype = ecmwf_levtype(metadata.first_fixed_surface_type.clone() as u8);
+        let levelist = match levtype {
+            "pl" => metadata.first_fixed_surface_value.map(|v| match message {
+                Message::Grib1 { .. } => v,
+                Message::Grib2 { .. } => v / 100.0,
+            }),
+            "ml" | "pt" => metadata.first_fixed_surface_value,
+            _ => None,
+        };
+
+        let mut keys = vec![
+            ("param", ecmwf_param(&message, &metadata)),
+            ("levtype", levtype.to_string()),
+        ];
+        if let Some(levelist) = levelist {
+            keys.push(("levelist", format_g(levelist)));
+        }
+        keys.push(("step", ecmwf_step(&metadata)));
+        if let Some(number) = metadata.perturbation_number {
+            keys.push(("number", number.to_string()));
+        }
+        keys.push(("date", metadata.reference_date.format("%Y%m%d").to_string()));
+        keys.push(("time", metadata.reference_date.format("%H%M").to_string()));
+        let (class, mars_type, stream) = ecmwf_labelling(&message, &metadata);
+        for (key, value) in [("class", class), ("type", mars_type), ("stream", stream)] {
+            if let Some(value) = value {
+                keys.push((key, value.to_string()));
+            }
+        }
+
+        let fields = keys
+            .iter()
+            .map(|(key, value)| format!("\"{key}\": {}", serde_json::Value::from(value.as_str())))
+            .chain([
+                format!("\"_offset\": {}", message.byte_offset()),
+                format!("\"_length\": {}", metadata.message_size),
+            ])
+            .collect::<Vec<_>>();
+
+        index.push('{');
+        index.push_str(&fields.join(", "));
+        index.push_str("}\n");
+    }
+
+    index
+}
+
+/// The MARS short name of a field's parameter. GRIB1 messages using ECMWF's
+/// local tables already carry it as their abbreviation. GRIB2 parameters are
+/// named by WMO, or NCEP for those gribberish reads with NCEP's abbreviations,
+/// so the parameters ECMWF's open data holds are mapped to their short names.
+/// Any other keeps its gribberish abbreviation, which MARS does not know.
+fn ecmwf_param(message: &Message, metadata: &MessageMetadata) -> String {
+    if let Message::Grib1 { .. } = message {
+        return metadata.var.clone();
+    }
+
+    let surface = metadata.first_fixed_surface_type.clone() as u8;
+    let height = match surface {
+        103 => metadata.first_fixed_surface_value,
+        _ => None,
+    };
+    let param = match (
+        metadata.discipline_value,
+        metadata.category_value,
+        metadata.parameter_value,
+    ) {
+        (0, 0, 0) if height == Some(2.0) => "2t",
+        (0, 0, 0) => "t",
+        (0, 0, 6) if height == Some(2.0) => "2d",
+        (0, 0, 17) => "skt",
+        (0, 1, 0) => "q",
+        (0, 1, 1) => "r",
+        (0, 1, 19) => "ptype",
+        (0, 1, 61) => "rsn",
+        (0, 1, 64) => "tcwv",
+        (0, 1, 254) => "sd",
+        (0, 2, 2) if height == Some(10.0) => "10u",
+        (0, 2, 2) if height == Some(100.0) => "100u",
+        (0, 2, 2) => "u",
+        (0, 2, 3) if height == Some(10.0) => "10v",
+        (0, 2, 3) if height == Some(100.0) => "100v",
+        (0, 2, 3) => "v",
+        (0, 2, 8) => "w",
+        (0, 2, 12) => "vo",
+        (0, 2, 13) => "d",
+        (0, 3, 0) if surface == 101 => "msl",
+        (0, 3, 0) => "sp",
+        (0, 3, 1) => "msl",
+        (0, 3, 4) => "z",
+        (0, 3, 5) => "gh",
+        (0, 6, 192) => "tcc",
+        (0, 19, 192) => "asn",
+        (10, 0, 3) => "swh",
+        _ => return metadata.var.clone(),
+    };
+    param.to_string()
+}
+
+/// The MARS level type of a fixed surface
+pub(crate) fn ecmwf_levtype(code: u8) -> &'static str {
+    match code {
+        100 => "pl",
+        105 => "ml",
+        106 => "sol",
+        107 => "pt",
+        109 => "pv",
+        _ => "sfc",
+    }
+}
+
+/// The MARS step of a field: hours from the reference time to the end of its
+/// statistical processing interval, or to its forecast time
+fn ecmwf_step(metadata: &MessageMetadata) -> String {
+    let valid_date = metadata.forecast_end_date.unwrap_or(metadata.forecast_date);
+    let minutes = (valid_date - metadata.reference_date).num_minutes();
+
+    if minutes % 60 == 0 {
+        (minutes / 60).to_string()
+    } else {
+        format!("{minutes}m")
+    }
+}
+
+/// The MARS class, type and stream of a field. GRIB1 carries them in the ECMWF
+/// local extension of section 1, so they are left out when it has none, as
+/// an analysis ensemble member such as one of the ERA5 EDA can't be told from
+/// a forecast one otherwise. GRIB2 fields have no class, and the type and
+/// stream follow from the product template.
+fn ecmwf_labelling(
+    message: &Message,
+    metadata: &MessageMetadata,
+) -> (
+    Option<&'static str>,
+    Option<&'static str>,
+    Option<&'static str>,
+) {
+    match message {
+        Message::Grib1 { message, .. } => message.mars_labelling(),
+        Message::Grib2 { .. } => {
+            let ensemble = metadata.perturbation_number.is_some()
+                || metadata.derived_forecast_type.is_some()
+                || metadata.probability_type.is_some();
+            (
+                None,
+                Some(ecmwf_type(message, metadata)),
+                Some(if ensemble { "enfo" } else { "oper" }),
+            )
+        }
+    }
+}
+
+/// The MARS type of a GRIB2 field: analysis, forecast, ensemble control or
+/// perturbed forecast, or an ensemble derived product
+fn ecmwf_type(message: &Message, metadata: &MessageMetadata) -> &'static str {
+    if metadata.probability_type.is_some() {
+        return "ep";
+    }
+
+    match metadata.derived_forecast_type {
+        Some(DerivedForecastType::StandardDeviation) => return "es",
+        Some(_) => return "em",
+        None => {}
+    }
+
+    if let Some(number) = metadata.perturbation_number {
+        let control = match message.ensemble_forecast_type().ok().flatten() {
+            Some(EnsembleForecastType::Missing) | None => number == 0,
+            Some(ensemble_type) => matches!(
+                ensemble_type,
+                EnsembleForecastType::UnperturbedHighResolutionControlForecast
+                    | EnsembleForecastType::UnperturbedLowResolutionControlForecast
+                    | EnsembleForecastType::UnperturbedForecast
+            ),
+        };
+        return if control { "cf" } else { "pf" };
+    }
+
+    match metadata.generating_process {
+        GeneratingProcess::Analysis => "an",
+        _ => "fc",
+    }
+}
+
+/// Format a value like C's `%g`: six significant digits without trailing zeros,
+/// switching to an exponent for very large or small values
+fn format_g(value: f64) -> String {
+    if value == 0.0 {
+        return "0".to_string();
+    } else if !value.is_finite() {
+        return value.to_string();
+    }
+
+    // Round to six significant digits first, which can carry into the next power of ten
+    let rounded = format!("{value:.5e}");
+    let (mantissa, exponent) = rounded.split_once('e').unwrap_or((&rounded, "0"));
+    let exponent: i32 = exponent.parse().unwrap_or(0);
+
+    let trim = |s: &str| {
+        if s.contains('.') {
+            s.trim_end_matches('0').trim_end_matches('.').to_string()
+        } else {
+            s.to_string()
+        }
+    };
+
+    if (-4..6).contains(&exponent) {
+        let decimals = (5 - exponent) as usize;
+        trim(&format!("{value:.decimals$}"))
+    } else {
+        let sign = if exponent < 0 { '-' } else { '+' };
+        format!("{}e{sign}{:02}", trim(mantissa), exponent.abs())
+    }
+}
+
 /// `YYYYMMDDHH` with optional trailing minutes (`YYYYMMDDHHMM`).
 fn parse_noaa_date(s: &str) -> Option<DateTime<Utc>> {
     if s.len() < 10 || !s.is_ascii() {
@@ -0,0 +1,306 @@
+//! Reading GRIB messages located by a sidecar index with byte range requests.
+//!
+//! [`GribIndexedReader`] uses the entries from [`crate::index::parse_index`] to
+//! fetch only what it needs from a [`RangeSource`]: the header sections of each
+//! message for its metadata, and whole messages when their data is read. Ranges
+//! that touch are merged into a single request.
+
+use std::future::Future;
+use std::io::{Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::PathBuf;
+
+use crate::error::GribberishError;
+use crate::index::IndexEntry;
+use crate::message::{read_submessage, Message};
+use crate::message_metadata::MessageMetadata;
+use crate::reader::OwnedMessage;
+use crate::sections::bitmap::BITMAP_PRESENT;
+use crate::utils::{read_u24_from_bytes, read_u32_from_bytes, read_u64_from_bytes};
+
+/// Plenty for sections 0 - 5 of a message, which is all the metadata needs. Messages
+/// whose header sections do not fit are fetched whole instead.
+pub const DEFAULT_HEADER_LENGTH: u64 = 4096;
+
+/// A source of bytes that are fetched by range, such as an object store or a HTTP
+/// server supporting range requests
+pub trait RangeSource: Send + Sync {
+    /// Fetch the bytes in `range`. Ranges past the end of the source are cut short.
+    fn fetch(
+        &self,
+        range: Range<u64>,
+    ) -> impl Future<Output = Result<Vec<u8>, GribberishError>> + Send;
+
+    /// Fetch each of `ranges`, in order. Sources that can make requests concurrently
+    /// should override this, the default fetches one range at a time.
+    fn fetch_ranges(
+        &self,
+        ranges: &[Range<u64>],
+    ) -> impl Future<Output = Result<Vec<Vec<u8>>, GribberishError>> + Send {
+        let ranges = ranges.to_vec();
+        async move {
+            let mut fetched = Vec::with_capacity(ranges.len());
+            for range in ranges {
+                fetched.push(self.fetch(range).await?);
+            }
+            Ok(fetched)
+        }
+    }
+}
+
+/// Ranges read from a local file. Reads block, so this is meant for tests and tools
+/// rather than for use on an async runtime's worker threads.
+pub struct FileRangeSource {
+    path: PathBuf,
+}
+
+impl FileRangeSource {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        FileRangeSource { path: path.into() }
+    }
+}
+
+impl RangeSource for FileRangeSource {
+    async fn fetch(&self, range: Range<u64>) -> Result<Vec<u8>, GribberishError> {
+        let mut file = std::fs::File::open(&self.path)?;
+        file.seek(SeekFrom::Start(range.start))?;
+        let mut data = Vec::new();
+        file.take(range.end.saturating_sub(range.start))
+            .read_to_end(&mut data)?;
+        Ok(data)
+    }
+}
+
+/// Merge ranges that overlap or touch, returning the merged ranges in order
+pub fn coalesce_ranges(ranges: &[Range<u64>]) -> Vec<Range<u64>> {
+    let mut sorted = ranges
+        .iter()
+        .filter(|r| !r.is_empty())
+        .cloned()
+        .collect::<Vec<_>>();
+    sorted.sort_by_key(|r| r.start);
+
+    let mut coalesced: Vec<Range<u64>> = Vec::with_capacity(sorted.len());
+    for range in sorted {
+        match coalesced.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => coalesced.push(range),
+        }
+    }
+    coalesced
+}
+
+/// Reads the messages listed in a GRIB index from a [`RangeSource`]
+pub struct GribIndexedReader<S> {
+    source: S,
+    entries: Vec<IndexEntry>,
+    header_length: u64,
+}
+
+impl<S: RangeSource> GribIndexedReader<S> {
+    pub fn new(source: S, entries: Vec<IndexEntry>) -> Self {
+        GribIndexedReader {
+            source,
+            entries,
+            header_length: DEFAULT_HEADER_LENGTH,
+        }
+    }
+
+    /// How many bytes from the start of each message are fetched to read its metadata
+    pub fn with_header_length(mut self, header_length: u64) -> Self {
+        self.header_length = header_length;
+        self
+    }
+
+    pub fn entries(&self) -> &[IndexEntry] {
+        &self.entries
+    }
+
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    /// The metadata of every field in the index, in index order. Only the header
+    /// sections of each message are fetched unless they do not fit in the header
+    /// length, or the message holds several fields and needs reading whole.
+    pub async fn metadata(&self) -> Result<Vec<MessageMetadata>, GribberishError> {
+        let messages = self.messages();
+        let header_ranges = messages
+            .iter()
+            .map(|(entry, whole)| {
+                let length = match (whole, entry.length) {
+                    (false, Some(length)) => length.min(self.header_length),
+                    (true, Some(length)) => length,
+                    (_, None) => self.header_length,
+                };
+                entry.offset..entry.offset + length
+            })
+            .collect::<Vec<_>>();
+        let headers = self.fetch_coalesced(&header_ranges).await?;
+
+        let mut metadata = Vec::new();
+        for ((entry, _), header) in messages.iter().zip(headers) {
+            let fields = match read_metadata(&header) {
+                Some(fields) => fields,
+                None => {
+                    let message = self.fetch_message(entry).await?;
+                    read_metadata(message.bytes()).ok_or_else(|| {
+                        GribberishError::MessageError(format!(
+                            "failed to read message {} at offset {}",
+                            entry.message_number, entry.offset
+                        ))
+                    })?
+                }
+            };
+
+            for mut field in fields {
+                field.byte_offset = entry.offset as usize;
+                metadata.push(field);
+            }
+        }
+
+        Ok(metadata)
+    }
+
+    /// Fetch the whole message `entry` belongs to
+    pub async fn fetch_message(&self, entry: &IndexEntry) -> Result<OwnedMessage, GribberishError> {
+        let length = match entry.length {
+            Some(length) => length,
+            None => self.message_length(entry.offset).await?,
+        };
+        let data = self
+            .source
+            .fetch(entry.offset..entry.offset + length)
+            .await?;
+        Ok(OwnedMessage::new(entry.offset, data))
+    }
+
+    /// Fetch the whole messages of `entries`, coalescing messages that are next to each
+    /// other into single requests. Entries for submessages of the same message share
+    /// one fetched message.
+    pub async fn fetch_messages(
+        &self,
+        entries: &[&IndexEntry],
+    ) -> Result<Vec<OwnedMessage>, GribberishError> {
+        let mut ranges = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let length = match entry.length {
+                Some(length) => length,
+                None => self.message_length(entry.offset).await?,
+            };
+            ranges.push(entry.offset..entry.offset + length);
+        }
+
+        let fetched = self.fetch_coalesced(&ranges).await?;
+        Ok(ranges
+            .into_iter()
+            .zip(fetched)
+            .map(|(range, data)| OwnedMessage::new(range.start, data))
+            .collect())
+    }
+
+    /// Fetch and unpack the data of the field `entry` locates
+    pub async fn data(&self, entry: &IndexEntry) -> Result<Vec<f64>, GribberishError> {
+        let message = self.fetch_message(entry).await?;
+        let field = read_submessage(message.bytes(), 0, entry.submessage.unwrap_or(1)).ok_or_else(
+            || {
+                GribberishError::MessageError(format!(
+                    "failed to read message {} at offset {}",
+                    entry.message_number, entry.offset
+                ))
+            },
+        )?;
+        field.data()
+    }
+
+    /// One entry for each distinct message, with whether it holds several fields
+    fn messages(&self) -> Vec<(&IndexEntry, bool)> {
+        let mut messages: Vec<(&IndexEntry, bool)> = Vec::new();
+        for entry in &self.entries {
+            match messages.last_mut() {
+                Some((last, whole)) if last.offset == entry.offset => *whole = true,
+                _ => messages.push((entry, entry.submessage.is_some_and(|s| s > 1))),
+            }
+        }
+        messages
+    }
+
+    /// Read a message's length from its indicator section
+    async fn message_length(&self, offset: u64) -> Result<u64, GribberishError> {
+        let indicator = self.source.fetch(offset..offset + 16).await?;
+        let length = match indicator.get(7) {
+            Some(1) => read_u24_from_bytes(&indicator, 4).map(|l| l as u64),
+            Some(2) => read_u64_from_bytes(&indicator, 8),
+            _ => None,
+        };
+        length
+            .filter(|_| indicator.starts_with(b"GRIB"))
+            .ok_or_else(|| {
+                GribberishError::MessageError(format!("no GRIB message at offset {offset}"))
+            })
+    }
+
+    /// Fetch `ranges`, merging those that touch into single requests, and return the
+    /// bytes of each of the original ranges
+    async fn fetch_coalesced(
+        &self,
+        ranges: &[Range<u64>],
+    ) -> Result<Vec<Vec<u8>>, GribberishError> {
+        let coalesced = coalesce_ranges(ranges);
+        let fetched = self.source.fetch_ranges(&coalesced).await?;
+
+        Ok(ranges
+            .iter()
+            .map(|range| {
+                if range.is_empty() {
+                    return Vec::new();
+                }
+
+                let index = coalesced
+                    .partition_point(|c| c.end <= range.start)
+                    .min(coalesced.len().saturating_sub(1));
+                let (start, data) = (coalesced[index].start, &fetched[index]);
+                let from = ((range.start - start) as usize).min(data.len());
+                let to = ((range.end - start) as usize).min(data.len());
+                data[from..to].to_vec()
+            })
+            .collect())
+    }
+}
+
+/// The metadata of every field in `data`, or `None` when too little of the message
+/// was fetched to read it
+fn read_metadata(data: &[u8]) -> Option<Vec<MessageMetadata>> {
+    let fields = Message::submessages_from_data(data, 0);
+    if fields.is_empty() {
+        return None;
+    }
+
+    let mut metadata = fields
+        .iter()
+        .map(|field| MessageMetadata::try_from(field).ok())
+        .collect::<Option<Vec<_>>>()?;
+
+    // The bitmap section is usually cut short by the header length, which
+    // hides the bitmap from the parsed message, so read its indicator instead
+    if let ([field], Some(2)) = (metadata.as_mut_slice(), data.get(7)) {
+        field.has_bitmap = bitmap_indicator(data)? == BITMAP_PRESENT;
+    }
+
+    Some(metadata)
+}
+
+/// The bitmap indicator of a GRIB2 message holding a single field, or `None`
+/// when `data` ends before its bitmap section starts
+fn bitmap_indicator(data: &[u8]) -> Option<u8> {
+    // Sections follow the 16 octet indicator section
+    let mut offset = 16;
+    loop {
+        let length = read_u32_from_bytes(data, offset)? as usize;
+        match *data.get(offset + 4)? {
+            6 => return data.get(offset + 5).copied(),
+            number if number > 6 || length == 0 => return None,
+            _ => offset += length,
+        }
+    }
+}
@@ -0,0 +1,29 @@
+//! Sampling decoded fields at arbitrary locations.
+//!
+//! The weights `LatLngProjection::interpolation_weights` gives for a location
+//! depend only on the grid, so they can be computed once and applied with
+//! [`interpolate`] to every message sharing it.
+
+/// How a field is sampled between its grid points
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Interpolation {
+    /// The value of the closest grid point
+    Nearest,
+    /// Linear in both grid directions between the four surrounding grid points
+    Bilinear,
+}
+
+/// Apply interpolation weights to a decoded field. NaN when there are no
+/// weights, as for a location off the grid, or when any weighted point is
+/// missing.
+pub fn interpolate(data: &[f64], weights: &[(usize, f64)]) -> f64 {
+    if weights.is_empty() {
+        return f64::NAN;
+    }
+
+    weights
+        .iter()
+        .map(|(index, weight)| data.get(*index).copied().unwrap_or(f64::NAN) * weight)
+        .sum()
+}
@@ -2,12 +2,29 @@
 mod utils;
 pub mod data_message;
 pub mod error;
+#[cfg(feature = "geotiff")]
+pub mod geotiff;
 pub mod grib1;
 pub mod index;
+pub mod indexed_reader;
+pub mod interpolation;
 pub mod message;
+pub mod message_builder;
 pub mod message_metadata;
+#[cfg(feature = "cache")]
+pub mod metadata_cache;
+pub mod netcdf;
+pub mod reader;
+pub mod regrid;
 pub mod sections;
+pub mod selector;
+pub mod spectral;
+pub mod subset;
 pub mod templates;
+pub mod wind;
 
 pub use utils::iter::projection::adjust_latitude_values;
 pub use utils::iter::projection::adjust_longitude_values;
+pub use utils::iter::projection::{
+    GridWindow, LatLngProjection, PlateCareeProjection, RegularCoordinateIterator,
+};
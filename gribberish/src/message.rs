@@ -1,47 +1,85 @@
 use crate::error::GribberishError;
 use crate::grib1::Grib1Message;
-use crate::sections::{indicator::Discipline, section::Section, section::SectionIterator};
+use crate::interpolation::{interpolate, Interpolation};
+use crate::message_builder::MessageBuilder;
+use crate::message_metadata::product_tokens;
+use crate::sections::indicator::Discipline;
+use crate::sections::section::{
+    scan_section_offsets, submessage_section_offsets, Section, SectionIterator, SectionOffset,
+};
+use crate::spectral::{complex_coefficients, triangular_truncation, SpectralGrid};
+use crate::subset::subset_grid_definition;
+use crate::templates::grid_definition::tables::{
+    ResolutionAndComponent, ResolutionAndComponentFlags,
+};
 use crate::templates::grid_definition::GridDefinitionTemplate;
-use crate::templates::product::product_template::{ProductTemplate, WavePeriodRange};
+use crate::templates::product::product_template::{
+    AerosolInterval, ProductTemplate, SatelliteBand, WavePeriodRange,
+};
 use crate::templates::product::tables::{
-    DerivedForecastType, FixedSurfaceType, GeneratingProcess, ProbabilityType, TimeUnit,
-    TypeOfStatisticalProcessing,
+    DerivedForecastType, EnsembleForecastType, FixedSurfaceType, GeneratingProcess,
+    ProbabilityType, TimeUnit, TypeOfStatisticalProcessing,
 };
 use crate::utils::iter::projection::LatLngProjection;
 use bitvec::view::BitView;
 use chrono::{DateTime, Utc};
 use gribberish_types::Parameter;
-use std::collections::HashMap;
+use num::Complex;
+use std::collections::{HashMap, VecDeque};
 use std::vec::Vec;
 
-pub fn scan_messages(data: &[u8]) -> HashMap<String, (usize, usize)> {
+/// The index, byte offset and field number of every message in `data`, by key.
+/// The field number is `None` for messages holding a single field, the others
+/// are read with [`read_submessage`].
+pub fn scan_messages(data: &[u8]) -> HashMap<String, (usize, usize, Option<usize>)> {
     let message_iter = MessageIterator::from_data(data, 0);
 
     message_iter
         .enumerate()
-        .map(|(index, m)| match m.key() {
-            Ok(var) => (var, (index, m.byte_offset())),
-            Err(_) => ("unknown".into(), (index, m.byte_offset())),
+        .map(|(index, m)| {
+            let submessage = (m.submessage_count() > 1).then_some(m.submessage());
+            match m.key() {
+                Ok(var) => (var, (index, m.byte_offset(), submessage)),
+                Err(_) => ("unknown".into(), (index, m.byte_offset(), submessage)),
+            }
         })
         .collect()
 }
 
 pub fn read_messages<'a>(data: &'a [u8]) -> MessageIterator<'a> {
-    MessageIterator { data, offset: 0 }
+    MessageIterator::from_data(data, 0)
 }
 
 pub fn read_message<'a>(data: &'a [u8], offset: usize) -> Option<Message<'a>> {
     Message::from_data(data, offset)
 }
 
+/// Read field `submessage` (1-based) of the message at `offset`
+pub fn read_submessage<'a>(
+    data: &'a [u8],
+    offset: usize,
+    submessage: usize,
+) -> Option<Message<'a>> {
+    Message::submessages_from_data(data, offset)
+        .into_iter()
+        .nth(submessage.checked_sub(1)?)
+}
+
+/// Iterates the fields in the data, yielding every submessage of a GRIB2 message
+/// that holds more than one field in turn
 pub struct MessageIterator<'a> {
     data: &'a [u8],
     offset: usize,
+    submessages: VecDeque<Message<'a>>,
 }
 
 impl<'a> MessageIterator<'a> {
     pub fn from_data(data: &'a [u8], offset: usize) -> Self {
-        MessageIterator { data, offset }
+        MessageIterator {
+            data,
+            offset,
+            submessages: VecDeque::new(),
+        }
     }
 
     pub fn current_offset(&self) -> usize {
@@ -53,13 +91,21 @@ impl<'a> Iterator for MessageIterator<'a> {
     type Item = Message<'a>;
 
     fn next(&mut self) -> std::option::Option<<Self as std::iter::Iterator>::Item> {
+        // Finish the fields of the last message before moving on
+        if let Some(m) = self.submessages.pop_front() {
+            return Some(m);
+        }
+
         // Scan forward to find the next GRIB magic header
         while self.offset + 4 <= self.data.len() {
             if &self.data[self.offset..self.offset + 4] == b"GRIB" {
                 // Found a potential GRIB message
-                match Message::from_data(self.data, self.offset) {
+                let mut submessages =
+                    VecDeque::from(Message::submessages_from_data(self.data, self.offset));
+                match submessages.pop_front() {
                     Some(m) => {
                         self.offset += m.len();
+                        self.submessages = submessages;
                         return Some(m);
                     }
                     None => {
@@ -84,19 +130,30 @@ pub enum Message<'a> {
     Grib2 {
         data: &'a [u8],
         offset: usize,
+        sections: Vec<SectionOffset>,
+        submessage: usize,
+        submessage_count: usize,
     },
 }
 
 impl<'a> Message<'a> {
+    /// Read the message at `offset`, or its first field when it holds several
     pub fn from_data(data: &'a [u8], offset: usize) -> Option<Message<'a>> {
+        Self::submessages_from_data(data, offset).into_iter().next()
+    }
+
+    /// Read every field of the message at `offset`. A GRIB2 message that repeats its
+    /// sections is read as one message per field, each sharing the sections before it
+    /// that were not repeated.
+    pub fn submessages_from_data(data: &'a [u8], offset: usize) -> Vec<Message<'a>> {
         // Check if there's enough data for GRIB header
         if offset + 8 > data.len() {
-            return None;
+            return Vec::new();
         }
 
         // Check for GRIB magic
         if &data[offset..offset + 4] != b"GRIB" {
-            return None;
+            return Vec::new();
         }
 
         // Edition is at byte 7 (0-indexed)
@@ -106,24 +163,36 @@ impl<'a> Message<'a> {
             1 => {
                 // Parse as GRIB1
                 match Grib1Message::from_data(data, offset) {
-                    Ok(message) => Some(Message::Grib1 {
+                    Ok(message) => vec![Message::Grib1 {
                         data,
                         offset,
                         message,
-                    }),
-                    Err(_) => None,
+                    }],
+                    Err(_) => Vec::new(),
                 }
             }
             2 => {
-                // Parse as GRIB2 (existing logic)
-                let mut sections = SectionIterator { data, offset };
-
-                match sections.next() {
-                    Some(Section::Indicator(_)) => Some(Message::Grib2 { data, offset }),
-                    _ => None,
+                // Parse as GRIB2, recording where each section is so that it is only scanned once
+                let sections = scan_section_offsets(data, offset);
+                if !matches!(sections.first(), Some(indicator) if indicator.number == 0) {
+                    return Vec::new();
                 }
+
+                let fields = submessage_section_offsets(data, &sections);
+                let submessage_count = fields.len();
+                fields
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, sections)| Message::Grib2 {
+                        data,
+                        offset,
+                        sections,
+                        submessage: index + 1,
+                        submessage_count,
+                    })
+                    .collect()
             }
-            _ => None,
+            _ => Vec::new(),
         }
     }
 
@@ -141,19 +210,60 @@ impl<'a> Message<'a> {
         }
     }
 
-    pub fn sections(&self) -> SectionIterator<'_> {
+    pub fn sections(&self) -> SectionIterator<'a> {
+        SectionIterator {
+            data: self.byte_data(),
+            offset: self.byte_offset(),
+        }
+    }
+
+    /// 1-based number of this field within its message, fields after the first are
+    /// GRIB2 submessages
+    pub fn submessage(&self) -> usize {
+        match self {
+            Message::Grib1 { .. } => 1,
+            Message::Grib2 { submessage, .. } => *submessage,
+        }
+    }
+
+    /// The GRIB edition the message is encoded with
+    pub fn edition(&self) -> u8 {
+        match self {
+            Message::Grib1 { .. } => 1,
+            Message::Grib2 { .. } => 2,
+        }
+    }
+
+    /// The number of fields in the message this field was read from
+    pub fn submessage_count(&self) -> usize {
+        match self {
+            Message::Grib1 { .. } => 1,
+            Message::Grib2 {
+                submessage_count, ..
+            } => *submessage_count,
+        }
+    }
+
+    /// Where each section of this field lies in the data, found when the message was
+    /// read. Sections a submessage shares with earlier fields are included, GRIB1
+    /// messages have no GRIB2 sections.
+    pub fn section_offsets(&self) -> &[SectionOffset] {
         match self {
-            Message::Grib1 { data, offset, .. } => SectionIterator {
-                data,
-                offset: *offset,
-            },
-            Message::Grib2 { data, offset } => SectionIterator {
-                data,
-                offset: *offset,
-            },
+            Message::Grib1 { .. } => &[],
+            Message::Grib2 { sections, .. } => sections,
         }
     }
 
+    /// Find a section using the offsets recorded when the message was read, the
+    /// sections borrow the message data rather than copying it
+    fn find_section<T>(&self, f: impl FnMut(Section<'a>) -> Option<T>) -> Option<T> {
+        let data = self.byte_data();
+        self.section_offsets()
+            .iter()
+            .filter_map(|s| s.section(data))
+            .find_map(f)
+    }
+
     pub fn key(&self) -> Result<String, GribberishError> {
         let time = self
             .forecast_date()
@@ -246,8 +356,18 @@ impl<'a> Message<'a> {
             None => "".to_string(),
         };
 
+        let product = product_tokens(
+            self.chemical_constituent_type().unwrap_or(None),
+            self.aerosol_size_range().unwrap_or(None),
+            self.aerosol_wavelength_range().unwrap_or(None),
+            &self.satellite_bands().unwrap_or_default(),
+        )
+        .iter()
+        .map(|token| format!(":{token}"))
+        .collect::<String>();
+
         Ok(format!(
-            "{var}{time}{first_level}{second_level}{perturbation}{percentile}{probability}{wave_period}{anomaly}:{statistical_process}{generating_process}{derived_forecast_type}"
+            "{var}{time}{first_level}{second_level}{product}{perturbation}{percentile}{probability}{wave_period}{anomaly}:{statistical_process}{generating_process}{derived_forecast_type}"
         ))
     }
 
@@ -291,11 +411,12 @@ impl<'a> Message<'a> {
     pub fn len(&self) -> usize {
         match self {
             Message::Grib1 { message, .. } => message.length(),
-            Message::Grib2 { .. } => match self.sections().next() {
-                Some(Section::Indicator(i)) => i.total_length() as usize,
-                Some(_) => 0,
-                None => 0,
-            },
+            Message::Grib2 { .. } => self
+                .find_section(|s| match s {
+                    Section::Indicator(i) => Some(i.total_length() as usize),
+                    _ => None,
+                })
+                .unwrap_or(0),
         }
     }
 
@@ -304,27 +425,31 @@ impl<'a> Message<'a> {
     }
 
     pub fn section_count(&self) -> usize {
-        self.sections().count()
+        match self {
+            Message::Grib1 { .. } => self.sections().count(),
+            Message::Grib2 { sections, .. } => sections.len(),
+        }
     }
 
     pub fn discipline(&self) -> Result<Discipline, GribberishError> {
         match self {
             Message::Grib1 { .. } => Ok(Discipline::Meteorological),
-            Message::Grib2 { .. } => match self.sections().next().unwrap() {
-                Section::Indicator(indicator) => Ok(indicator.discipline()),
-                _ => Err(GribberishError::MessageError(
-                    "Indicator section not found when reading discipline".into(),
-                )),
-            }
-            .clone(),
+            Message::Grib2 { .. } => self
+                .find_section(|s| match s {
+                    Section::Indicator(indicator) => Some(indicator.discipline()),
+                    _ => None,
+                })
+                .ok_or_else(|| {
+                    GribberishError::MessageError(
+                        "Indicator section not found when reading discipline".into(),
+                    )
+                }),
         }
     }
 
     pub fn product_template_id(&self) -> Result<u16, GribberishError> {
-        let mut sections = self.sections();
-
         let product_definition = unwrap_or_return!(
-            sections.find_map(|s| match s {
+            self.find_section(|s| match s {
                 Section::ProductDefinition(product_definition) => Some(product_definition),
                 _ => None,
             }),
@@ -336,11 +461,9 @@ impl<'a> Message<'a> {
         Ok(product_definition.product_definition_template_number())
     }
 
-    pub fn product_template(&self) -> Result<Box<dyn ProductTemplate>, GribberishError> {
-        let mut sections = self.sections();
-
+    pub fn product_template(&self) -> Result<Box<dyn ProductTemplate + 'a>, GribberishError> {
         let discipline = unwrap_or_return!(
-            sections.find_map(|s| match s {
+            self.find_section(|s| match s {
                 Section::Indicator(indicator) => Some(indicator.discipline_value()),
                 _ => None,
             }),
@@ -350,7 +473,7 @@ impl<'a> Message<'a> {
         );
 
         let product_definition = unwrap_or_return!(
-            sections.find_map(|s| match s {
+            self.find_section(|s| match s {
                 Section::ProductDefinition(product_definition) => Some(product_definition),
                 _ => None,
             }),
@@ -369,7 +492,7 @@ impl<'a> Message<'a> {
         Ok(product_template)
     }
 
-    pub fn grid_template(&self) -> Result<Box<dyn GridDefinitionTemplate>, GribberishError> {
+    pub fn grid_template(&self) -> Result<Box<dyn GridDefinitionTemplate + 'a>, GribberishError> {
         match self {
             Message::Grib1 { message, .. } => {
                 // For GRIB1, get the grid from the message
@@ -383,7 +506,7 @@ impl<'a> Message<'a> {
             Message::Grib2 { .. } => {
                 // For GRIB2, use the existing section-based approach
                 let grid_definition = unwrap_or_return!(
-                    self.sections().find_map(|s| match s {
+                    self.find_section(|s| match s {
                         Section::GridDefinition(grid_definition) => Some(grid_definition),
                         _ => None,
                     }),
@@ -515,7 +638,7 @@ impl<'a> Message<'a> {
                 .map_err(GribberishError::MessageError),
             Message::Grib2 { .. } => {
                 let reference_date = unwrap_or_return!(
-                    self.sections().find_map(|s| match s {
+                    self.find_section(|s| match s {
                         Section::Identification(identification) =>
                             Some(identification.reference_date()),
                         _ => None,
@@ -559,6 +682,16 @@ impl<'a> Message<'a> {
         }
     }
 
+    pub fn ensemble_forecast_type(&self) -> Result<Option<EnsembleForecastType>, GribberishError> {
+        match self {
+            Message::Grib1 { .. } => Ok(None),
+            Message::Grib2 { .. } => {
+                let product_template = self.product_template()?;
+                Ok(product_template.ensemble_forecast_type())
+            }
+        }
+    }
+
     pub fn perturbation_number(&self) -> Result<Option<u8>, GribberishError> {
         match self {
             Message::Grib1 { message, .. } => Ok(message.ensemble_number()),
@@ -614,6 +747,55 @@ impl<'a> Message<'a> {
         }
     }
 
+    /// Returns the atmospheric chemical constituent type (code table 4.230),
+    /// or the aerosol type for the aerosol templates, for messages using the
+    /// chemistry and aerosol templates 4.40 to 4.48.
+    pub fn chemical_constituent_type(&self) -> Result<Option<u16>, GribberishError> {
+        match self {
+            Message::Grib1 { .. } => Ok(None),
+            Message::Grib2 { .. } => {
+                let product_template = self.product_template()?;
+                Ok(product_template.chemical_constituent_type())
+            }
+        }
+    }
+
+    /// Returns the aerosol particle size interval `(first, second)` in metres
+    /// for the aerosol templates 4.44, 4.46 and 4.48.
+    pub fn aerosol_size_range(&self) -> Result<Option<AerosolInterval>, GribberishError> {
+        match self {
+            Message::Grib1 { .. } => Ok(None),
+            Message::Grib2 { .. } => {
+                let product_template = self.product_template()?;
+                Ok(product_template.aerosol_size_range())
+            }
+        }
+    }
+
+    /// Returns the wavelength interval `(first, second)` in metres of aerosol
+    /// optical properties (template 4.48).
+    pub fn aerosol_wavelength_range(&self) -> Result<Option<AerosolInterval>, GribberishError> {
+        match self {
+            Message::Grib1 { .. } => Ok(None),
+            Message::Grib2 { .. } => {
+                let product_template = self.product_template()?;
+                Ok(product_template.aerosol_wavelength_range())
+            }
+        }
+    }
+
+    /// Returns the contributing spectral bands of satellite products
+    /// (templates 4.31 and 4.32), empty for any other product.
+    pub fn satellite_bands(&self) -> Result<Vec<SatelliteBand>, GribberishError> {
+        match self {
+            Message::Grib1 { .. } => Ok(Vec::new()),
+            Message::Grib2 { .. } => {
+                let product_template = self.product_template()?;
+                Ok(product_template.satellite_bands())
+            }
+        }
+    }
+
     pub fn probability_type(&self) -> Result<Option<ProbabilityType>, GribberishError> {
         match self {
             Message::Grib1 { .. } => Ok(None),
@@ -783,7 +965,7 @@ impl<'a> Message<'a> {
 
     pub fn grid_template_id(&self) -> Result<u16, GribberishError> {
         let grid_definition = unwrap_or_return!(
-            self.sections().find_map(|s| match s {
+            self.find_section(|s| match s {
                 Section::GridDefinition(grid_definition) => Some(grid_definition),
                 _ => None,
             }),
@@ -818,6 +1000,22 @@ impl<'a> Message<'a> {
         }
     }
 
+    pub fn resolution_and_component_flags(
+        &self,
+    ) -> Result<Option<ResolutionAndComponentFlags>, GribberishError> {
+        let grid_template = self.grid_template()?;
+        Ok(grid_template.resolution_and_component_flags())
+    }
+
+    /// Whether vector components are resolved along the grid's x and y axes
+    /// rather than towards east and north
+    pub fn has_grid_relative_vectors(&self) -> Result<bool, GribberishError> {
+        Ok(matches!(
+            self.resolution_and_component_flags()?,
+            Some([_, _, ResolutionAndComponent::GridRelative])
+        ))
+    }
+
     pub fn latlng_projector(&self) -> Result<LatLngProjection, GribberishError> {
         let grid_template = self.grid_template()?;
         Ok(grid_template.projector())
@@ -825,7 +1023,7 @@ impl<'a> Message<'a> {
 
     pub fn data_template_number(&self) -> Result<u16, GribberishError> {
         let data_representation = unwrap_or_return!(
-            self.sections().find_map(|s| match s {
+            self.find_section(|s| match s {
                 Section::DataRepresentation(data_representation) => Some(data_representation),
                 _ => None,
             }),
@@ -839,7 +1037,7 @@ impl<'a> Message<'a> {
 
     pub fn data_compression_type(&self) -> Result<String, GribberishError> {
         let data_representation = unwrap_or_return!(
-            self.sections().find_map(|s| match s {
+            self.find_section(|s| match s {
                 Section::DataRepresentation(data_representation) => Some(data_representation),
                 _ => None,
             }),
@@ -860,7 +1058,7 @@ impl<'a> Message<'a> {
 
     pub fn data_point_count(&self) -> Result<usize, GribberishError> {
         let data_representation = unwrap_or_return!(
-            self.sections().find_map(|s| match s {
+            self.find_section(|s| match s {
                 Section::DataRepresentation(data_representation) => Some(data_representation),
                 _ => None,
             }),
@@ -873,7 +1071,7 @@ impl<'a> Message<'a> {
     }
 
     pub fn has_bitmap(&self) -> bool {
-        let bitmap_section = self.sections().find_map(|s| match s {
+        let bitmap_section = self.find_section(|s| match s {
             Section::Bitmap(bitmap_section) => Some(bitmap_section),
             _ => None,
         });
@@ -889,7 +1087,7 @@ impl<'a> Message<'a> {
             Message::Grib1 { message, .. } => message.data().map_err(GribberishError::MessageError),
             Message::Grib2 { .. } => {
                 let data_section = unwrap_or_return!(
-                    self.sections().find_map(|s| match s {
+                    self.find_section(|s| match s {
                         Section::Data(data_section) => Some(data_section),
                         _ => None,
                     }),
@@ -901,7 +1099,7 @@ impl<'a> Message<'a> {
                 let raw_packed_data = data_section.raw_data_array().view_bits();
 
                 let data_representation_section = unwrap_or_return!(
-                    self.sections().find_map(|s| match s {
+                    self.find_section(|s| match s {
                         Section::DataRepresentation(data_representation_section) =>
                             Some(data_representation_section),
                         _ => None,
@@ -921,7 +1119,7 @@ impl<'a> Message<'a> {
                 let scaled_unpacked_data = data_representation_template.unpack(raw_packed_data)?;
 
                 let bitmap_section = unwrap_or_return!(
-                    self.sections().find_map(|s| match s {
+                    self.find_section(|s| match s {
                         Section::Bitmap(bitmap_section) => Some(bitmap_section),
                         _ => None,
                     }),
@@ -930,14 +1128,8 @@ impl<'a> Message<'a> {
                     )
                 );
 
-                let mut data = if bitmap_section.has_bitmap() {
-                    bitmap_section.map_data(scaled_unpacked_data)
-                } else {
-                    scaled_unpacked_data
-                };
-
                 let count = unwrap_or_return!(
-                    self.sections().find_map(|s| match s {
+                    self.find_section(|s| match s {
                         Section::GridDefinition(grid_definition) => {
                             Some(grid_definition.data_point_count())
                         }
@@ -947,9 +1139,111 @@ impl<'a> Message<'a> {
                         "Grid definition section not found when reading message data".into()
                     )
                 );
+
+                let mut data = bitmap_section.apply(scaled_unpacked_data, count)?;
                 data.resize(count, 0.0);
                 Ok(data)
             }
         }
     }
+
+    /// The value of the field at a location, NaN when it is off the grid or
+    /// missing
+    pub fn value_at(
+        &self,
+        lat: f64,
+        lng: f64,
+        method: Interpolation,
+    ) -> Result<f64, GribberishError> {
+        Ok(self.values_at(&[(lat, lng)], method)?[0])
+    }
+
+    /// The values of the field at many `(lat, lng)` locations, decoding it once
+    pub fn values_at(
+        &self,
+        locations: &[(f64, f64)],
+        method: Interpolation,
+    ) -> Result<Vec<f64>, GribberishError> {
+        let projector = self.latlng_projector()?;
+        let data = self.data()?;
+
+        Ok(locations
+            .iter()
+            .map(|(lat, lng)| {
+                interpolate(&data, &projector.interpolation_weights(*lat, *lng, method))
+            })
+            .collect())
+    }
+
+    /// Crop the field to the grid points inside a `(min_lng, min_lat, max_lng,
+    /// max_lat)` bounding box and encode it as a new GRIB2 message. The crop is
+    /// the smallest window of whole rows and columns of the grid, in its native
+    /// projection, holding every point inside the box, see
+    /// [`LatLngProjection::subset_window`]. The new message keeps every other
+    /// section and is packed like this one where that can be written, see
+    /// [`MessageBuilder::with_packing_of`].
+    pub fn subset(&self, bbox: (f64, f64, f64, f64)) -> Result<Vec<u8>, GribberishError> {
+        let projector = self.latlng_projector()?;
+        let window = unwrap_or_return!(
+            projector.subset_window(bbox),
+            GribberishError::MessageError(format!("No grid points fall inside {bbox:?}"))
+        );
+
+        let data = self.byte_data();
+        let grid_definition = unwrap_or_return!(
+            self.section_offsets()
+                .iter()
+                .find(|section| section.number == 3)
+                .map(|section| &data[section.offset..section.offset + section.len]),
+            GribberishError::MessageError("Grid definition section not found".into())
+        );
+        let grid_definition = subset_grid_definition(grid_definition, &projector, &window)?;
+
+        let (_, nx) = self.grid_dimensions()?;
+        let values = self.data()?;
+        let values = window.indices(nx).map(|i| values[i]).collect::<Vec<_>>();
+
+        MessageBuilder::from_message(self)?
+            .with_grid_definition(&grid_definition)
+            .with_packing_of(self)?
+            .build(&values)
+    }
+
+    /// Whether the field is stored as spherical harmonic coefficients rather
+    /// than values on a grid
+    pub fn is_spectral(&self) -> bool {
+        match self {
+            Message::Grib1 { message, .. } => message.is_spherical_harmonics(),
+            Message::Grib2 { .. } => matches!(self.grid_template_id(), Ok(50)),
+        }
+    }
+
+    /// The spherical harmonic coefficients of a spectral field, in the order
+    /// they are stored with `n` increasing from `m` for each `m` in turn
+    pub fn spectral_coefficients(&self) -> Result<Vec<Complex<f64>>, GribberishError> {
+        if !self.is_spectral() {
+            return Err(GribberishError::MessageError(
+                "message does not contain spherical harmonic coefficients".into(),
+            ));
+        }
+
+        Ok(complex_coefficients(&self.data()?))
+    }
+
+    /// Synthesise a triangularly truncated spectral field onto `grid`, returning the
+    /// grid's projection and its values. This is an inverse Legendre transform on every
+    /// row, so it is only computed on request.
+    pub fn synthesize_spectral(
+        &self,
+        grid: SpectralGrid,
+    ) -> Result<(LatLngProjection, Vec<f64>), GribberishError> {
+        let coefficients = self.spectral_coefficients()?;
+        let truncation = unwrap_or_return!(
+            triangular_truncation(2 * coefficients.len()),
+            GribberishError::MessageError(
+                "only triangularly truncated spectral fields can be synthesised".into()
+            )
+        );
+        grid.synthesize(&coefficients, truncation)
+    }
 }
@@ -0,0 +1,756 @@
+use chrono::{DateTime, Datelike, Timelike, Utc};
+
+use crate::error::GribberishError;
+use crate::message::Message;
+use crate::sections::grid_definition::GridDefinitionSection;
+use crate::sections::identification::{GribDataType, ProductionStatus, ReferenceDataSignificance};
+use crate::templates::data_representation::tables::{
+    FloatingPointPrecision, MissingValueManagement,
+};
+use crate::templates::data_representation::{
+    CCSDSDataRepresentationTemplate, CCSDSPackingOptions,
+    ComplexSpatialPackingDataRepresentationTemplate, ComplexSpatialPackingOptions,
+    IEEEFloatDataRepresentationTemplate, SimplePackingDataRepresentationTemplate,
+};
+use crate::templates::template::Template;
+use crate::utils::{pack_bits, read_u16_from_bytes, read_u32_from_bytes};
+
+/// The data representation used to pack the values of a built message
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum DataPacking {
+    /// Template 5.0
+    #[default]
+    Simple,
+    /// Template 5.3
+    ComplexSpatial(ComplexSpatialPackingOptions),
+    /// Template 5.4, values are written unscaled so the scale factors are ignored
+    IEEE(FloatingPointPrecision),
+    /// Template 5.42
+    CCSDS(CCSDSPackingOptions),
+}
+
+impl DataPacking {
+    /// Whether missing values are packed into the data section rather than the bitmap
+    fn manages_missing_values(&self) -> bool {
+        match self {
+            DataPacking::ComplexSpatial(options) => {
+                options.missing_value_management != MissingValueManagement::NoMissingValues
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Assembles a GRIB2 message (sections 0 through 8) from a field of values and the
+/// identification, grid definition and product definition sections that describe it.
+///
+/// Sections 1 through 4 are passed through as raw bytes, the same layout the templates
+/// read from, so they can be copied from an existing message with
+/// [`MessageBuilder::from_message`] or written by hand. The data representation, bitmap
+/// and data sections are generated from the values when the message is built, with any
+/// NaN values marked as missing in the bitmap.
+#[derive(Clone, Debug)]
+pub struct MessageBuilder {
+    discipline: u8,
+    identification: Vec<u8>,
+    local_use: Option<Vec<u8>>,
+    grid_definition: Option<Vec<u8>>,
+    product_definition: Option<Vec<u8>>,
+    packing: DataPacking,
+    decimal_scale_factor: i16,
+    binary_scale_factor: i16,
+}
+
+impl MessageBuilder {
+    /// Create a builder with a default identification section for the given reference date.
+    /// The originating center, subcenter and local table versions are marked as missing.
+    pub fn new(discipline: u8, reference_date: DateTime<Utc>) -> Self {
+        MessageBuilder {
+            discipline,
+            identification: default_identification_section(reference_date),
+            local_use: None,
+            grid_definition: None,
+            product_definition: None,
+            packing: DataPacking::Simple,
+            decimal_scale_factor: 0,
+            binary_scale_factor: 0,
+        }
+    }
+
+    /// Create a builder that reuses the discipline and sections 1 through 4 of an existing
+    /// GRIB2 message, so a processed field can be written back with the same metadata.
+    pub fn from_message(message: &Message) -> Result<Self, GribberishError> {
+        let Message::Grib2 { .. } = message else {
+            return Err(GribberishError::EncodingError(
+                "only GRIB2 messages can be used as a template for encoding".into(),
+            ));
+        };
+
+        let mut builder = MessageBuilder::new(message.discipline_value()?, Utc::now());
+        let data = message.byte_data();
+        for section in message.section_offsets() {
+            let raw = data[section.offset..section.offset + section.len].to_vec();
+
+            match section.number {
+                1 => builder.identification = raw,
+                2 => builder.local_use = Some(raw),
+                3 => builder.grid_definition = Some(raw),
+                4 => builder.product_definition = Some(raw),
+                5 => break,
+                _ => {}
+            }
+        }
+
+        Ok(builder)
+    }
+
+    /// Replace the identification section (section 1)
+    pub fn with_identification(mut self, section: &[u8]) -> Self {
+        self.identification = section.to_vec();
+        self
+    }
+
+    /// Set the local use section (section 2)
+    pub fn with_local_use(mut self, section: &[u8]) -> Self {
+        self.local_use = Some(section.to_vec());
+        self
+    }
+
+    /// Set the grid definition section (section 3), for example the `data()` of a grid template
+    pub fn with_grid_definition(mut self, section: &[u8]) -> Self {
+        self.grid_definition = Some(section.to_vec());
+        self
+    }
+
+    /// Set the product definition section (section 4), for example the `data()` of a product template
+    pub fn with_product_definition(mut self, section: &[u8]) -> Self {
+        self.product_definition = Some(section.to_vec());
+        self
+    }
+
+    /// Choose how the values are packed, simple packing is used by default
+    pub fn with_packing(mut self, packing: DataPacking) -> Self {
+        self.packing = packing;
+        self
+    }
+
+    /// Pack the values the way `message` packs its own, with the same data
+    /// representation template, options and scale factors. Templates that cannot
+    /// be written fall back within their family: JPEG 2000 and PNG to simple
+    /// packing, complex packing without spatial differencing to complex packing
+    /// with it. Any other template is written as 32 bit floats.
+    pub fn with_packing_of(mut self, message: &Message) -> Result<Self, GribberishError> {
+        let data = message.byte_data();
+        let section = unwrap_or_return!(
+            message
+                .section_offsets()
+                .iter()
+                .find(|section| section.number == 5)
+                .map(|section| &data[section.offset..section.offset + section.len]),
+            GribberishError::EncodingError("data representation section not found".into())
+        );
+
+        let scale_factors = SimplePackingDataRepresentationTemplate::new(section);
+        self.decimal_scale_factor = scale_factors.decimal_scale_factor();
+        self.binary_scale_factor = scale_factors.binary_scale_factor();
+        self.packing = match read_u16_from_bytes(section, 9).unwrap_or(0) {
+            0 | 40 | 41 => DataPacking::Simple,
+            template_number @ (2 | 3) => {
+                let template = ComplexSpatialPackingDataRepresentationTemplate::new(section);
+                let defaults = ComplexSpatialPackingOptions::default();
+                DataPacking::ComplexSpatial(ComplexSpatialPackingOptions {
+                    spatial_differencing_order: if template_number == 3 {
+                        template.spatial_differencing_order()
+                    } else {
+                        defaults.spatial_differencing_order
+                    },
+                    missing_value_management: template.missing_value_management(),
+                    primary_missing_value_substitute: template.primary_missing_value_substitute(),
+                    secondary_missing_value_substitute: template
+                        .secondary_missing_value_substitute(),
+                })
+            }
+            4 => DataPacking::IEEE(IEEEFloatDataRepresentationTemplate::new(section).precision()),
+            42 => {
+                let template = CCSDSDataRepresentationTemplate::new(section);
+                DataPacking::CCSDS(CCSDSPackingOptions {
+                    block_size: template.block_size(),
+                    reference_sample_interval: template.reference_sample_interval(),
+                    compression_options_mask: template.ccsds_compression_options_mask(),
+                })
+            }
+            _ => {
+                self.decimal_scale_factor = 0;
+                self.binary_scale_factor = 0;
+                DataPacking::IEEE(FloatingPointPrecision::IEEE32Bit)
+            }
+        };
+
+        Ok(self)
+    }
+
+    /// Values are multiplied by `10^decimal_scale_factor` before packing
+    pub fn with_decimal_scale_factor(mut self, decimal_scale_factor: i16) -> Self {
+        self.decimal_scale_factor = decimal_scale_factor;
+        self
+    }
+
+    /// Packed integers are in units of `2^binary_scale_factor`
+    pub fn with_binary_scale_factor(mut self, binary_scale_factor: i16) -> Self {
+        self.binary_scale_factor = binary_scale_factor;
+        self
+    }
+
+    /// Encode `values`, in the scanning order of the grid definition, into a complete GRIB2 message
+    pub fn build(&self, values: &[f64]) -> Result<Vec<u8>, GribberishError> {
+        let identification = checked_section(&self.identification, 1)?;
+        let local_use = self
+            .local_use
+            .as_deref()
+            .map(|s| checked_section(s, 2))
+            .transpose()?;
+        let grid_definition = checked_section(
+            unwrap_or_return!(
+                self.grid_definition.as_deref(),
+                GribberishError::EncodingError("grid definition section is required".into())
+            ),
+            3,
+        )?;
+        let product_definition = checked_section(
+            unwrap_or_return!(
+                self.product_definition.as_deref(),
+                GribberishError::EncodingError("product definition section is required".into())
+            ),
+            4,
+        )?;
+
+        let grid_point_count = GridDefinitionSection::from_data(grid_definition).data_point_count();
+        if values.len() != grid_point_count {
+            return Err(GribberishError::EncodingError(format!(
+                "expected {grid_point_count} values for the grid definition, got {}",
+                values.len()
+            )));
+        }
+
+        let has_missing =
+            !self.packing.manages_missing_values() && values.iter().any(|v| v.is_nan());
+        let bitmap = if has_missing {
+            let mut bitmap = vec![0u8, 0, 0, 0, 6, 0];
+            bitmap.extend(pack_bits(values.iter().map(|v| !v.is_nan() as u32), 1));
+            bitmap
+        } else {
+            vec![0u8, 0, 0, 0, 6, 255]
+        };
+        let bitmap = with_section_length(bitmap);
+
+        let present_values = if has_missing {
+            values
+                .iter()
+                .copied()
+                .filter(|v| !v.is_nan())
+                .collect::<Vec<_>>()
+        } else {
+            values.to_vec()
+        };
+        let (data_representation, packed) = match self.packing {
+            DataPacking::Simple => {
+                let (template, packed) = SimplePackingDataRepresentationTemplate::pack(
+                    &present_values,
+                    self.decimal_scale_factor,
+                    self.binary_scale_factor,
+                )?;
+                (template.data().to_vec(), packed)
+            }
+            DataPacking::ComplexSpatial(options) => {
+                let (template, packed) = ComplexSpatialPackingDataRepresentationTemplate::pack(
+                    &present_values,
+                    self.decimal_scale_factor,
+                    self.binary_scale_factor,
+                    options,
+                )?;
+                (template.data().to_vec(), packed)
+            }
+            DataPacking::IEEE(precision) => {
+                let (template, packed) =
+                    IEEEFloatDataRepresentationTemplate::pack(&present_values, precision)?;
+                (template.data().to_vec(), packed)
+            }
+            DataPacking::CCSDS(options) => {
+                let (template, packed) = CCSDSDataRepresentationTemplate::pack(
+                    &present_values,
+                    self.decimal_scale_factor,
+                    self.binary_scale_factor,
+                    options,
+                )?;
+                (template.data().to_vec(), packed)
+            }
+        };
+
+        let mut data = vec![0u8, 0, 0, 0, 7];
+        data.extend(packed);
+        let data = with_section_length(data);
+
+        let sections = [
+            Some(identification),
+            local_use,
+            Some(grid_definition),
+            Some(product_definition),
+            Some(data_representation.as_slice()),
+            Some(bitmap.as_slice()),
+            Some(data.as_slice()),
+        ];
+        let total_length = 16 + sections.iter().flatten().map(|s| s.len()).sum::<usize>() + 4;
+
+        let mut message = Vec::with_capacity(total_length);
+        message.extend_from_slice(b"GRIB");
+        message.extend_from_slice(&[0, 0, self.discipline, 2]);
+        message.extend_from_slice(&(total_length as u64).to_be_bytes());
+        sections
+            .iter()
+            .flatten()
+            .for_each(|s| message.extend_from_slice(s));
+        message.extend_from_slice(b"7777");
+
+        Ok(message)
+    }
+}
+
+fn checked_section(section: &[u8], number: u8) -> Result<&[u8], GribberishError> {
+    if section.len() < 5 || section[4] != number {
+        return Err(GribberishError::EncodingError(format!(
+            "expected section {number}"
+        )));
+    }
+
+    let length = read_u32_from_bytes(section, 0).unwrap_or(0) as usize;
+    if length != section.len() {
+        return Err(GribberishError::EncodingError(format!(
+            "section {number} declares {length} octets but {} were given",
+            section.len()
+        )));
+    }
+
+    Ok(section)
+}
+
+fn with_section_length(mut section: Vec<u8>) -> Vec<u8> {
+    let length = section.len() as u32;
+    section[0..4].copy_from_slice(&length.to_be_bytes());
+    section
+}
+
+fn default_identification_section(reference_date: DateTime<Utc>) -> Vec<u8> {
+    let mut section = vec![0u8, 0, 0, 0, 1];
+    // Originating center and subcenter are missing
+    section.extend_from_slice(&u16::MAX.to_be_bytes());
+    section.extend_from_slice(&u16::MAX.to_be_bytes());
+    // Master tables version 2, no local tables
+    section.extend_from_slice(&[2, 0]);
+    section.push(ReferenceDataSignificance::StartOfForecast as u8);
+    section.extend_from_slice(&(reference_date.year() as u16).to_be_bytes());
+    section.extend_from_slice(&[
+        reference_date.month() as u8,
+        reference_date.day() as u8,
+        reference_date.hour() as u8,
+        reference_date.minute() as u8,
+        reference_date.second() as u8,
+    ]);
+    section.push(ProductionStatus::Missing as u8);
+    section.push(GribDataType::Missing as u8);
+    with_section_length(section)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message_metadata::MessageMetadata;
+    use crate::templates::data_representation::tables::SpatialDifferencingOrder;
+    use crate::templates::product::tables::FixedSurfaceType;
+    use chrono::TimeZone;
+
+    /// Template 3.0 for a global grid starting at 90N 0E and scanning south
+    fn latlng_grid_definition(ni: u32, nj: u32, step: f64) -> Vec<u8> {
+        let micro = |v: f64| (v * 1e6).round() as u32;
+        let mut section = vec![0u8, 0, 0, 0, 3, 0];
+        section.extend_from_slice(&(ni * nj).to_be_bytes());
+        section.extend_from_slice(&[0, 0, 0, 0, 6, 0, 0, 0, 0, 0]);
+        section.extend_from_slice(&[0; 10]);
+        section.extend_from_slice(&ni.to_be_bytes());
+        section.extend_from_slice(&nj.to_be_bytes());
+        section.extend_from_slice(&0u32.to_be_bytes());
+        section.extend_from_slice(&u32::MAX.to_be_bytes());
+        section.extend_from_slice(&micro(90.0).to_be_bytes());
+        section.extend_from_slice(&0u32.to_be_bytes());
+        section.push(48);
+        let end_latitude = 90.0 - step * (nj - 1) as f64;
+        let sign = if end_latitude < 0.0 { 0x8000_0000 } else { 0 };
+        section.extend_from_slice(&(micro(end_latitude.abs()) | sign).to_be_bytes());
+        section.extend_from_slice(&micro(step * (ni - 1) as f64).to_be_bytes());
+        section.extend_from_slice(&micro(step).to_be_bytes());
+        section.extend_from_slice(&micro(step).to_be_bytes());
+        section.push(0);
+        with_section_length(section)
+    }
+
+    /// Template 4.0 for 2 m temperature at a 6 hour forecast
+    fn temperature_product_definition() -> Vec<u8> {
+        let mut section = vec![0u8, 0, 0, 0, 4, 0, 0, 0, 0];
+        section.extend_from_slice(&[0, 0, 2, 0, 96, 0, 0, 0, 1]);
+        section.extend_from_slice(&6u32.to_be_bytes());
+        section.extend_from_slice(&[103, 0]);
+        section.extend_from_slice(&2u32.to_be_bytes());
+        section.extend_from_slice(&[255, 0, 0, 0, 0, 0]);
+        with_section_length(section)
+    }
+
+    fn builder() -> MessageBuilder {
+        MessageBuilder::new(0, Utc.with_ymd_and_hms(2024, 3, 15, 12, 0, 0).unwrap())
+            .with_grid_definition(&latlng_grid_definition(8, 5, 45.0))
+            .with_product_definition(&temperature_product_definition())
+    }
+
+    #[test]
+    fn test_simple_packing_roundtrip() {
+        let values = (0..40)
+            .map(|i| 250.0 + (i as f64 * 0.37).sin() * 30.0)
+            .collect::<Vec<_>>();
+
+        let encoded = builder()
+            .with_decimal_scale_factor(2)
+            .build(&values)
+            .unwrap();
+        let message = Message::from_data(&encoded, 0).unwrap();
+        assert_eq!(message.len(), encoded.len());
+        assert!(!message.has_bitmap());
+
+        let decoded = message.data().unwrap();
+        assert_eq!(decoded.len(), values.len());
+        for (decoded, value) in decoded.iter().zip(values.iter()) {
+            assert!((decoded - value).abs() <= 0.005 + 1e-9);
+        }
+
+        let metadata = MessageMetadata::try_from(&message).unwrap();
+        assert_eq!(metadata.var, "TMP");
+        assert_eq!(metadata.grid_shape, (5, 8));
+        assert_eq!(
+            metadata.first_fixed_surface_type,
+            FixedSurfaceType::SpecifiedHeightLevelAboveGround
+        );
+        assert_eq!(metadata.first_fixed_surface_value, Some(2.0));
+        assert_eq!(
+            metadata.reference_date,
+            Utc.with_ymd_and_hms(2024, 3, 15, 12, 0, 0).unwrap()
+        );
+        assert_eq!(
+            metadata.forecast_date,
+            Utc.with_ymd_and_hms(2024, 3, 15, 18, 0, 0).unwrap()
+        );
+
+        let (lat, lng) = metadata.latlng();
+        assert!((lat[0] - 90.0).abs() < 1e-9);
+        assert!((lat[4] + 90.0).abs() < 1e-9);
+        assert!((lng[7] - 315.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_simple_packing_bitmap_roundtrip() {
+        let values = (0..40)
+            .map(|i| if i % 3 == 0 { f64::NAN } else { i as f64 * 0.5 })
+            .collect::<Vec<_>>();
+
+        let encoded = builder()
+            .with_decimal_scale_factor(1)
+            .build(&values)
+            .unwrap();
+        let message = Message::from_data(&encoded, 0).unwrap();
+        assert!(message.has_bitmap());
+        assert_eq!(message.data_point_count().unwrap(), 26);
+
+        let decoded = message.data().unwrap();
+        assert_eq!(decoded.len(), values.len());
+        for (decoded, value) in decoded.iter().zip(values.iter()) {
+            if value.is_nan() {
+                assert!(decoded.is_nan());
+            } else {
+                assert!((decoded - value).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_simple_packing_constant_field() {
+        let values = vec![273.15; 40];
+        let encoded = builder()
+            .with_decimal_scale_factor(2)
+            .build(&values)
+            .unwrap();
+        let message = Message::from_data(&encoded, 0).unwrap();
+        let decoded = message.data().unwrap();
+        assert!(decoded.iter().all(|v| (v - 273.15).abs() < 1e-4));
+    }
+
+    #[test]
+    fn test_binary_scale_factor() {
+        let values = (0..40).map(|i| i as f64 * 100.0).collect::<Vec<_>>();
+        let encoded = builder()
+            .with_binary_scale_factor(2)
+            .build(&values)
+            .unwrap();
+        let message = Message::from_data(&encoded, 0).unwrap();
+        let decoded = message.data().unwrap();
+        for (decoded, value) in decoded.iter().zip(values.iter()) {
+            assert!((decoded - value).abs() <= 2.0);
+        }
+    }
+
+    fn assert_decodes_to(encoded: &[u8], values: &[f64], tolerance: f64) {
+        let message = Message::from_data(encoded, 0).unwrap();
+        let decoded = message.data().unwrap();
+        assert_eq!(decoded.len(), values.len());
+        for (decoded, value) in decoded.iter().zip(values.iter()) {
+            if value.is_nan() {
+                assert!(decoded.is_nan());
+            } else {
+                assert!((decoded - value).abs() <= tolerance, "{decoded} != {value}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_complex_spatial_packing_roundtrip() {
+        let values = (0..40)
+            .map(|i| 250.0 + (i as f64 * 0.37).sin() * 30.0 + if i > 30 { 80.0 } else { 0.0 })
+            .collect::<Vec<_>>();
+
+        for order in [
+            SpatialDifferencingOrder::First,
+            SpatialDifferencingOrder::Second,
+        ] {
+            let encoded = builder()
+                .with_decimal_scale_factor(2)
+                .with_packing(DataPacking::ComplexSpatial(ComplexSpatialPackingOptions {
+                    spatial_differencing_order: order,
+                    ..Default::default()
+                }))
+                .build(&values)
+                .unwrap();
+
+            let message = Message::from_data(&encoded, 0).unwrap();
+            assert_eq!(message.data_template_number().unwrap(), 3);
+            assert_decodes_to(&encoded, &values, 0.005 + 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_complex_spatial_packing_negative_and_constant_values() {
+        let values = (0..40)
+            .map(|i| if i < 20 { -5.5 } else { -(i as f64) * 3.25 })
+            .collect::<Vec<_>>();
+        let encoded = builder()
+            .with_decimal_scale_factor(2)
+            .with_packing(DataPacking::ComplexSpatial(Default::default()))
+            .build(&values)
+            .unwrap();
+        assert_decodes_to(&encoded, &values, 1e-9);
+    }
+
+    #[test]
+    fn test_complex_spatial_packing_bitmap_roundtrip() {
+        let values = (0..40)
+            .map(|i| if i % 4 == 1 { f64::NAN } else { i as f64 * 1.5 })
+            .collect::<Vec<_>>();
+        let encoded = builder()
+            .with_decimal_scale_factor(1)
+            .with_packing(DataPacking::ComplexSpatial(Default::default()))
+            .build(&values)
+            .unwrap();
+
+        let message = Message::from_data(&encoded, 0).unwrap();
+        assert!(message.has_bitmap());
+        assert_decodes_to(&encoded, &values, 1e-9);
+    }
+
+    #[test]
+    fn test_complex_spatial_packing_primary_missing_values() {
+        let values = (0..40)
+            .map(|i| {
+                if (10..22).contains(&i) || i == 3 {
+                    f64::NAN
+                } else {
+                    100.0 + (i as f64 * 0.9).cos() * 12.0
+                }
+            })
+            .collect::<Vec<_>>();
+
+        for order in [
+            SpatialDifferencingOrder::First,
+            SpatialDifferencingOrder::Second,
+        ] {
+            let encoded = builder()
+                .with_decimal_scale_factor(1)
+                .with_packing(DataPacking::ComplexSpatial(ComplexSpatialPackingOptions {
+                    spatial_differencing_order: order,
+                    missing_value_management: MissingValueManagement::IncludesMissingPrimary,
+                    ..Default::default()
+                }))
+                .build(&values)
+                .unwrap();
+
+            let message = Message::from_data(&encoded, 0).unwrap();
+            assert!(!message.has_bitmap());
+            assert_eq!(message.data_point_count().unwrap(), 40);
+            assert_decodes_to(&encoded, &values, 0.05 + 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_complex_spatial_packing_primary_and_secondary_missing_values() {
+        let secondary = -999.0;
+        let values = (0..40)
+            .map(|i| match i {
+                0..=7 => secondary,
+                8..=15 => f64::NAN,
+                _ if i % 5 == 0 => secondary,
+                _ if i % 7 == 0 => f64::NAN,
+                _ => i as f64,
+            })
+            .collect::<Vec<_>>();
+
+        let encoded = builder()
+            .with_packing(DataPacking::ComplexSpatial(ComplexSpatialPackingOptions {
+                missing_value_management: MissingValueManagement::IncludesMissingPrimarySecondary,
+                secondary_missing_value_substitute: secondary as f32,
+                ..Default::default()
+            }))
+            .build(&values)
+            .unwrap();
+
+        let expected = values
+            .iter()
+            .map(|v| if *v == secondary { f64::NAN } else { *v })
+            .collect::<Vec<_>>();
+        assert_decodes_to(&encoded, &expected, 1e-9);
+    }
+
+    #[test]
+    fn test_complex_spatial_packing_all_missing() {
+        let values = vec![f64::NAN; 40];
+        let encoded = builder()
+            .with_packing(DataPacking::ComplexSpatial(ComplexSpatialPackingOptions {
+                missing_value_management: MissingValueManagement::IncludesMissingPrimary,
+                ..Default::default()
+            }))
+            .build(&values)
+            .unwrap();
+        assert_decodes_to(&encoded, &values, 0.0);
+
+        let encoded = builder()
+            .with_packing(DataPacking::ComplexSpatial(Default::default()))
+            .build(&values)
+            .unwrap();
+        assert_decodes_to(&encoded, &values, 0.0);
+    }
+
+    #[test]
+    fn test_ccsds_packing_roundtrip() {
+        let values = (0..40)
+            .map(|i| 1013.0 + (i as f64 * 0.21).sin() * 25.0)
+            .collect::<Vec<_>>();
+
+        for options in [
+            CCSDSPackingOptions::default(),
+            CCSDSPackingOptions {
+                block_size: 8,
+                reference_sample_interval: 2,
+                compression_options_mask: 8,
+            },
+        ] {
+            let encoded = builder()
+                .with_decimal_scale_factor(2)
+                .with_packing(DataPacking::CCSDS(options))
+                .build(&values)
+                .unwrap();
+
+            let message = Message::from_data(&encoded, 0).unwrap();
+            assert_eq!(message.data_template_number().unwrap(), 42);
+            assert_decodes_to(&encoded, &values, 0.005 + 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_ccsds_packing_bitmap_and_constant_field() {
+        let values = (0..40)
+            .map(|i| {
+                if i % 6 == 2 {
+                    f64::NAN
+                } else {
+                    -(i as f64) * 0.25
+                }
+            })
+            .collect::<Vec<_>>();
+        let encoded = builder()
+            .with_decimal_scale_factor(2)
+            .with_packing(DataPacking::CCSDS(Default::default()))
+            .build(&values)
+            .unwrap();
+        assert!(Message::from_data(&encoded, 0).unwrap().has_bitmap());
+        assert_decodes_to(&encoded, &values, 1e-9);
+
+        let values = vec![3.5; 40];
+        let encoded = builder()
+            .with_packing(DataPacking::CCSDS(Default::default()))
+            .build(&values)
+            .unwrap();
+        assert_decodes_to(&encoded, &values, 1e-9);
+    }
+
+    #[test]
+    fn test_ccsds_packing_rejects_invalid_options() {
+        let values = (0..40).map(|i| i as f64).collect::<Vec<_>>();
+        assert!(builder()
+            .with_packing(DataPacking::CCSDS(CCSDSPackingOptions {
+                block_size: 12,
+                ..Default::default()
+            }))
+            .build(&values)
+            .is_err());
+    }
+
+    #[test]
+    fn test_ieee_packing_roundtrip() {
+        let values = (0..40)
+            .map(|i| {
+                if i % 9 == 4 {
+                    f64::NAN
+                } else {
+                    101325.0 + (i as f64 * 0.13).cos() * 1234.5
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let encoded = builder()
+            .with_packing(DataPacking::IEEE(FloatingPointPrecision::IEEE64Bit))
+            .build(&values)
+            .unwrap();
+        let message = Message::from_data(&encoded, 0).unwrap();
+        assert_eq!(message.data_template_number().unwrap(), 4);
+        assert!(message.has_bitmap());
+        assert_decodes_to(&encoded, &values, 0.0);
+
+        let encoded = builder()
+            .with_packing(DataPacking::IEEE(FloatingPointPrecision::IEEE32Bit))
+            .build(&values)
+            .unwrap();
+        assert_decodes_to(&encoded, &values, 0.01);
+    }
+
+    #[test]
+    fn test_build_validates_input() {
+        assert!(builder().build(&[1.0, 2.0]).is_err());
+        assert!(MessageBuilder::new(0, Utc::now()).build(&[]).is_err());
+        assert!(builder()
+            .with_decimal_scale_factor(12)
+            .build(&vec![1.0e6; 39].into_iter().chain([0.0]).collect::<Vec<_>>())
+            .is_err());
+    }
+}
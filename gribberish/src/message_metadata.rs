@@ -5,8 +5,9 @@ use chrono::{DateTime, Utc};
 use crate::{
     error::GribberishError,
     message::{Message, MessageIterator},
+    templates::grid_definition::tables::{ResolutionAndComponent, ResolutionAndComponentFlags},
     templates::product::{
-        product_template::WavePeriodRange,
+        product_template::{AerosolInterval, SatelliteBand, WavePeriodRange},
         tables::{
             DerivedForecastType, FixedSurfaceType, GeneratingProcess, ProbabilityType, TimeUnit,
             TypeOfStatisticalProcessing,
@@ -15,11 +16,17 @@ use crate::{
     utils::iter::projection::LatLngProjection,
 };
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MessageMetadata {
     pub key: String,
     pub byte_offset: usize,
     pub message_size: usize,
+    /// 1-based field number within a GRIB2 message holding several fields, `None`
+    /// when the message holds a single field
+    pub submessage: Option<usize>,
+    /// GRIB edition of the message, 1 or 2
+    pub edition: u8,
     pub var: String,
     pub name: String,
     pub units: String,
@@ -48,6 +55,13 @@ pub struct MessageMetadata {
     pub is_regular_grid: bool,
     pub grid_shape: (usize, usize),
     pub projector: LatLngProjection,
+    /// Resolution and component flags of the grid, `None` for spherical
+    /// harmonics
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::templates::grid_definition::tables::resolution_and_component_octet")
+    )]
+    pub resolution_and_component_flags: Option<ResolutionAndComponentFlags>,
     pub perturbation_number: Option<u8>,
     pub number_of_ensemble_members: Option<u8>,
     pub derived_forecast_type: Option<DerivedForecastType>,
@@ -58,9 +72,136 @@ pub struct MessageMetadata {
     pub probability_upper_limit: Option<f64>,
     pub is_anomaly: bool,
     pub wave_period_range: Option<WavePeriodRange>,
+    pub chemical_constituent_type: Option<u16>,
+    pub aerosol_size_range: Option<AerosolInterval>,
+    pub aerosol_wavelength_range: Option<AerosolInterval>,
+    pub satellite_bands: Vec<SatelliteBand>,
+}
+
+/// Tokens naming what sets apart fields that share a parameter number and only
+/// differ in their product template: the chemical constituent and aerosol size
+/// and wavelength bins of templates 4.40 - 4.48, where MASSDEN covers ozone,
+/// smoke and dust alike, and the satellite bands of templates 4.31 and 4.32.
+/// For example `chem0`, `size0-2500nm` and `sat257wn2000`.
+pub fn product_tokens(
+    chemical_constituent_type: Option<u16>,
+    aerosol_size_range: Option<AerosolInterval>,
+    aerosol_wavelength_range: Option<AerosolInterval>,
+    satellite_bands: &[SatelliteBand],
+) -> Vec<String> {
+    let mut tokens = Vec::new();
+    if let Some(constituent) = chemical_constituent_type {
+        tokens.push(format!("chem{constituent}"));
+    }
+    for (prefix, interval) in [
+        ("size", aerosol_size_range),
+        ("wvl", aerosol_wavelength_range),
+    ] {
+        if let Some((first, second)) = interval {
+            let first = first.map_or("".to_string(), |v| format!("{:.0}", v * 1e9));
+            let second = second.map_or("".to_string(), |v| format!("{:.0}", v * 1e9));
+            tokens.push(format!("{prefix}{first}-{second}nm"));
+        }
+    }
+    for band in satellite_bands {
+        let wave_number = band
+            .central_wave_number
+            .map_or("".to_string(), |v| format!("{v:.0}"));
+        tokens.push(format!("sat{}wn{wave_number}", band.satellite_number));
+    }
+    tokens
 }
 
 impl MessageMetadata {
+    /// The [`product_tokens`] of the field
+    pub fn product_tokens(&self) -> Vec<String> {
+        product_tokens(
+            self.chemical_constituent_type,
+            self.aerosol_size_range,
+            self.aerosol_wavelength_range,
+            &self.satellite_bands,
+        )
+    }
+
+    /// Compute the "kind" discriminator for a message: the statistical process,
+    /// ensemble product, probability descriptor, anomaly flag, wave period band
+    /// and [`product_tokens`] combined into a single readable token. Plain
+    /// instantaneous forecasts collapse to "instant". This is what (potentially)
+    /// separates two hypercubes of the same variable at the same level type into
+    /// different groups. Single probability thresholds and percentile values are
+    /// left out, as those stack along a dimension of one hypercube.
+    pub fn kind(&self) -> String {
+        let mut tokens: Vec<String> = Vec::new();
+
+        if let Some(stat) = self.statistical_process.as_ref() {
+            let mut token = stat.abbv();
+            // Distinguish accumulation/averaging windows (e.g. 1h vs 6h precip).
+            if let Some(end_date) = self.forecast_end_date {
+                let hours = end_date
+                    .signed_duration_since(self.forecast_date)
+                    .num_hours();
+                if hours > 0 {
+                    token = format!("{token}{hours}h");
+                }
+            }
+            tokens.push(token);
+        }
+
+        if let Some(derived) = self.derived_forecast_type.as_ref() {
+            tokens.push(derived.abbv());
+        }
+
+        if let Some(prob) = self.probability_type.as_ref() {
+            let mut token = prob.abbv();
+            if matches!(
+                prob,
+                ProbabilityType::BetweenLimits | ProbabilityType::BetweenLimitsInclusive
+            ) {
+                if let (Some(lower), Some(upper)) =
+                    (self.probability_lower_limit, self.probability_upper_limit)
+                {
+                    token = format!("{token}_{lower:.0}_{upper:.0}");
+                }
+            }
+            tokens.push(token);
+        } else if self.percentile_value.is_some() {
+            // Percentile products share the "pctl" kind so they never collapse into
+            // the same array as raw members; the value itself stays a dimension.
+            tokens.push("pctl".to_string());
+        }
+
+        if self.is_anomaly {
+            tokens.push("anom".to_string());
+        }
+
+        // Period-banded wave fields (template 4.103) share a parameter number with
+        // plain significant wave height, so the period band is the only thing that
+        // separates them. Fold it into the kind - like between-limit probability
+        // thresholds - so each band becomes its own hypercube instead of colliding.
+        if let Some((lower, upper)) = self.wave_period_range {
+            let lower = lower.map_or("".to_string(), |v| format!("{v:.0}"));
+            let upper = upper.map_or("".to_string(), |v| format!("{v:.0}"));
+            tokens.push(format!("per{lower}-{upper}s"));
+        }
+
+        // Chemical species, aerosol bins and satellite bands share parameter
+        // numbers, so those split them the same way
+        tokens.extend(self.product_tokens());
+
+        // Drop any empty tokens defensively: an empty token would join into a
+        // malformed kind (e.g. "acc__mean") or, on its own, an empty kind string,
+        // which becomes an unnamed group and corrupts the datatree hierarchy. The
+        // contributing abbreviations should already be non-empty, but a missing /
+        // unrecognized code can render blank.
+        tokens.retain(|t| !t.is_empty());
+
+        if tokens.is_empty() {
+            "instant".to_string()
+        } else {
+            tokens.join("_")
+        }
+    }
+
     pub fn data_point_count(&self) -> usize {
         self.grid_shape.0 * self.grid_shape.1
     }
@@ -82,6 +223,15 @@ impl MessageMetadata {
             .lat_lng_adjusted(adjust_longitude_range, north_up)
     }
 
+    /// Whether vector components are resolved along the grid's x and y axes
+    /// rather than towards east and north
+    pub fn has_grid_relative_vectors(&self) -> bool {
+        matches!(
+            self.resolution_and_component_flags,
+            Some([_, _, ResolutionAndComponent::GridRelative])
+        )
+    }
+
     pub fn xy(&self) -> (Vec<f64>, Vec<f64>) {
         (self.projector.x(), self.projector.y())
     }
@@ -151,6 +301,8 @@ impl<'a> TryFrom<&Message<'a>> for MessageMetadata {
             key: message.key()?,
             byte_offset: message.byte_offset(),
             message_size: message.len(),
+            submessage: (message.submessage_count() > 1).then_some(message.submessage()),
+            edition: message.edition(),
             var: message.variable_abbrev()?,
             name: message.variable_name()?,
             units: message.unit()?,
@@ -185,6 +337,7 @@ impl<'a> TryFrom<&Message<'a>> for MessageMetadata {
             is_regular_grid: message.is_regular_grid()?,
             grid_shape: message.grid_dimensions()?,
             projector: message.latlng_projector()?,
+            resolution_and_component_flags: message.resolution_and_component_flags()?,
             perturbation_number: message.perturbation_number()?,
             number_of_ensemble_members: message.number_of_ensemble_members()?,
             derived_forecast_type: message.derived_forecast_type()?,
@@ -195,6 +348,10 @@ impl<'a> TryFrom<&Message<'a>> for MessageMetadata {
             probability_upper_limit: message.probability_upper_limit()?,
             is_anomaly: message.is_anomaly()?,
             wave_period_range: message.wave_period_range()?,
+            chemical_constituent_type: message.chemical_constituent_type()?,
+            aerosol_size_range: message.aerosol_size_range()?,
+            aerosol_wavelength_range: message.aerosol_wavelength_range()?,
+            satellite_bands: message.satellite_bands()?,
         })
     }
 }
@@ -0,0 +1,247 @@
+//! A persistent cache of the metadata [`scan_message_metadata`] reads from a GRIB
+//! file, so large archives only need scanning once.
+//!
+//! A cache file holds a magic number, the cache format version, the
+//! [`CacheKey`] of the GRIB file it was built from and the scanned metadata,
+//! encoded with bincode. Caches written by a different format version, or for a
+//! file whose key no longer matches, are ignored and rebuilt.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::GribberishError;
+use crate::message_metadata::{scan_message_metadata, MessageMetadata};
+#[cfg(not(feature = "mmap"))]
+use crate::reader::GribReader;
+#[cfg(feature = "mmap")]
+use crate::reader::MappedGribFile;
+
+/// Bumped whenever the encoding of the cached metadata changes
+pub const CACHE_VERSION: u32 = 1;
+
+const CACHE_MAGIC: &[u8; 8] = b"GRIBMETA";
+
+/// Caches written so far by this process, to give each its own partial file
+static PARTIAL_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Extension of cache files written next to the GRIB file they describe
+pub const CACHE_EXTENSION: &str = "gribberish-cache";
+
+/// The metadata of every message in a file, as [`scan_message_metadata`] returns it
+pub type ScannedMetadata = HashMap<String, (usize, usize, MessageMetadata)>;
+
+/// What a cache is checked against to tell whether its GRIB file has changed
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CacheKey {
+    /// The file's size and modification time in nanoseconds since the unix epoch.
+    /// Cheap to read, but trusts the file system to update the modification time.
+    FileStat { size: u64, modified: u128 },
+    /// The size and FNV-1a hash of the file's contents. Reads the whole file.
+    ContentHash { size: u64, hash: u64 },
+}
+
+impl CacheKey {
+    /// Key the file at `path` by its size and modification time
+    pub fn from_file_stat<P: AsRef<Path>>(path: P) -> Result<Self, GribberishError> {
+        let metadata = fs::metadata(path)?;
+        let modified = metadata
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+
+        Ok(CacheKey::FileStat {
+            size: metadata.len(),
+            modified,
+        })
+    }
+
+    /// Key GRIB data by a hash of its contents
+    pub fn from_content(data: &[u8]) -> Self {
+        CacheKey::ContentHash {
+            size: data.len() as u64,
+            hash: fnv1a(data),
+        }
+    }
+}
+
+/// The scanned metadata of a GRIB file together with the key of the file it
+/// was scanned from
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MetadataCache {
+    pub key: CacheKey,
+    pub metadata: ScannedMetadata,
+}
+
+impl MetadataCache {
+    /// Scan `data` and key the result with `key`
+    pub fn build(data: &[u8], key: CacheKey) -> Self {
+        MetadataCache {
+            key,
+            metadata: scan_message_metadata(data),
+        }
+    }
+
+    pub fn is_valid_for(&self, key: &CacheKey) -> bool {
+        &self.key == key
+    }
+
+    pub fn into_metadata(self) -> ScannedMetadata {
+        self.metadata
+    }
+
+    pub fn write_to<W: Write>(&self, writer: W) -> Result<(), GribberishError> {
+        let mut writer = BufWriter::new(writer);
+        writer.write_all(CACHE_MAGIC)?;
+        writer.write_all(&CACHE_VERSION.to_le_bytes())?;
+        bincode::serialize_into(&mut writer, self).map_err(cache_error)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn read_from<R: Read>(reader: R) -> Result<Self, GribberishError> {
+        let mut reader = BufReader::new(reader);
+
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != CACHE_MAGIC {
+            return Err(GribberishError::CacheError(
+                "not a gribberish metadata cache".into(),
+            ));
+        }
+
+        let mut version = [0u8; 4];
+        reader.read_exact(&mut version)?;
+        let version = u32::from_le_bytes(version);
+        if version != CACHE_VERSION {
+            return Err(GribberishError::CacheError(format!(
+                "cache version {version} does not match {CACHE_VERSION}"
+            )));
+        }
+
+        bincode::deserialize_from(reader).map_err(cache_error)
+    }
+
+    /// Write the cache to `path`, replacing it atomically so readers never see a
+    /// partially written cache. Each write goes through its own partial file, so
+    /// processes and threads writing the same cache at once do not clobber it.
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), GribberishError> {
+        let path = path.as_ref();
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(format!(
+            ".{}.{}.partial",
+            std::process::id(),
+            PARTIAL_COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let written = fs::File::create(&partial)
+            .map_err(GribberishError::from)
+            .and_then(|file| self.write_to(file))
+            .and_then(|_| fs::rename(&partial, path).map_err(GribberishError::from));
+        if written.is_err() {
+            let _ = fs::remove_file(&partial);
+        }
+        written
+    }
+
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, GribberishError> {
+        Self::read_from(fs::File::open(path)?)
+    }
+}
+
+/// The cache file for `grib_path` when it is kept next to the GRIB file
+pub fn cache_path_beside<P: AsRef<Path>>(grib_path: P) -> PathBuf {
+    let mut path = grib_path.as_ref().as_os_str().to_owned();
+    path.push(".");
+    path.push(CACHE_EXTENSION);
+    PathBuf::from(path)
+}
+
+/// The cache file for `grib_path` when caches are kept together in `cache_dir`.
+/// The name includes a hash of the GRIB file's full path so files with the same
+/// name in different directories do not share a cache.
+pub fn cache_path_in<P: AsRef<Path>, D: AsRef<Path>>(
+    cache_dir: D,
+    grib_path: P,
+) -> Result<PathBuf, GribberishError> {
+    let grib_path = fs::canonicalize(grib_path)?;
+    let name = grib_path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let hash = fnv1a(grib_path.as_os_str().as_encoded_bytes());
+
+    Ok(cache_dir
+        .as_ref()
+        .join(format!("{name}.{hash:016x}.{CACHE_EXTENSION}")))
+}
+
+/// The metadata of the GRIB file at `grib_path`, read from the cache at
+/// `cache_path` when it is still valid. Otherwise the file is scanned and the
+/// cache written for next time. The cache is keyed by the file's size and
+/// modification time. Scanning reads one message at a time, or maps the file
+/// with the `mmap` feature, rather than reading the whole file into memory.
+pub fn load_or_scan<P: AsRef<Path>, C: AsRef<Path>>(
+    grib_path: P,
+    cache_path: C,
+) -> Result<ScannedMetadata, GribberishError> {
+    let key = CacheKey::from_file_stat(&grib_path)?;
+
+    if let Ok(cache) = MetadataCache::read(&cache_path) {
+        if cache.is_valid_for(&key) {
+            return Ok(cache.into_metadata());
+        }
+    }
+
+    let cache = MetadataCache {
+        key,
+        metadata: scan_file(grib_path.as_ref())?,
+    };
+    cache.write(&cache_path)?;
+    Ok(cache.into_metadata())
+}
+
+/// [`scan_message_metadata`] of the file at `path`
+#[cfg(feature = "mmap")]
+fn scan_file(path: &Path) -> Result<ScannedMetadata, GribberishError> {
+    let file = MappedGribFile::open(path)?;
+    Ok(scan_message_metadata(file.data()))
+}
+
+/// [`scan_message_metadata`] of the file at `path`, read a message at a time
+#[cfg(not(feature = "mmap"))]
+fn scan_file(path: &Path) -> Result<ScannedMetadata, GribberishError> {
+    let mut scanned = ScannedMetadata::new();
+    let mut index = 0;
+    for message in GribReader::new(fs::File::open(path)?) {
+        let message = message?;
+        for field in message.submessages() {
+            if let Ok(mut metadata) = MessageMetadata::try_from(&field) {
+                // Fields report offsets relative to the message they were read in
+                metadata.byte_offset += message.offset() as usize;
+                let offset = metadata.byte_offset;
+                scanned.insert(metadata.key.clone(), (index, offset, metadata));
+            }
+            index += 1;
+        }
+    }
+    Ok(scanned)
+}
+
+fn cache_error(error: bincode::Error) -> GribberishError {
+    GribberishError::CacheError(error.to_string())
+}
+
+/// 64 bit FNV-1a, which unlike the standard library's hasher is stable across
+/// releases, so keys written by one build match those of another
+fn fnv1a(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
@@ -0,0 +1,839 @@
+//! NetCDF export of message collections.
+//!
+//! [`Dataset::from_messages`] groups messages into CF-1.8 variables, one per
+//! parameter, level type and kind of product, each stacked along time, level
+//! and ensemble member dimensions ahead of the grid's own. The dataset is
+//! written as a NetCDF-3 64-bit offset file, so no HDF5 is needed:
+//!
+//! ```ignore
+//! let messages = read_messages(&data).collect::<Vec<_>>();
+//! std::fs::write("out.nc", messages_to_netcdf(&messages)?)?;
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{DateTime, Utc};
+
+use crate::error::GribberishError;
+use crate::message::Message;
+use crate::message_metadata::MessageMetadata;
+use crate::templates::product::tables::ProbabilityType;
+use crate::utils::iter::projection::LatLngProjection;
+
+/// Name of the scalar variable holding the grid mapping
+const GRID_MAPPING_NAME: &str = "spatial_ref";
+
+const TIME_UNITS: &str = "seconds since 1970-01-01 00:00:00";
+
+const NC_DIMENSION: u32 = 0x0A;
+const NC_VARIABLE: u32 = 0x0B;
+const NC_ATTRIBUTE: u32 = 0x0C;
+
+/// The value of an attribute, stored with the NetCDF type of the same name
+#[derive(Clone, Debug, PartialEq)]
+pub enum AttributeValue {
+    Text(String),
+    Int(Vec<i32>),
+    Float(Vec<f32>),
+    Double(Vec<f64>),
+}
+
+impl AttributeValue {
+    fn nc_type(&self) -> u32 {
+        match self {
+            AttributeValue::Text(_) => 2,
+            AttributeValue::Int(_) => 4,
+            AttributeValue::Float(_) => 5,
+            AttributeValue::Double(_) => 6,
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            AttributeValue::Text(value) => value.len(),
+            AttributeValue::Int(values) => values.len(),
+            AttributeValue::Float(values) => values.len(),
+            AttributeValue::Double(values) => values.len(),
+        }
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        match self {
+            AttributeValue::Text(value) => value.as_bytes().to_vec(),
+            AttributeValue::Int(values) => values.iter().flat_map(|v| v.to_be_bytes()).collect(),
+            AttributeValue::Float(values) => values.iter().flat_map(|v| v.to_be_bytes()).collect(),
+            AttributeValue::Double(values) => values.iter().flat_map(|v| v.to_be_bytes()).collect(),
+        }
+    }
+}
+
+/// The values of a variable, row-major over its dimensions
+#[derive(Clone, Debug, PartialEq)]
+pub enum VariableData {
+    Int(Vec<i32>),
+    Float(Vec<f32>),
+    Double(Vec<f64>),
+}
+
+impl VariableData {
+    fn nc_type(&self) -> u32 {
+        match self {
+            VariableData::Int(_) => 4,
+            VariableData::Float(_) => 5,
+            VariableData::Double(_) => 6,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            VariableData::Int(values) => values.len(),
+            VariableData::Float(values) => values.len(),
+            VariableData::Double(values) => values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        match self {
+            VariableData::Int(values) => values.iter().flat_map(|v| v.to_be_bytes()).collect(),
+            VariableData::Float(values) => values.iter().flat_map(|v| v.to_be_bytes()).collect(),
+            VariableData::Double(values) => values.iter().flat_map(|v| v.to_be_bytes()).collect(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Variable {
+    pub name: String,
+    /// Names of the dimensions, slowest varying first. Empty for a scalar.
+    pub dimensions: Vec<String>,
+    pub attributes: Vec<(String, AttributeValue)>,
+    pub data: VariableData,
+}
+
+impl Variable {
+    pub fn attribute(&self, name: &str) -> Option<&AttributeValue> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, value)| value)
+    }
+}
+
+/// The dimensions, global attributes and variables of a NetCDF file
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Dataset {
+    pub dimensions: Vec<(String, usize)>,
+    pub attributes: Vec<(String, AttributeValue)>,
+    pub variables: Vec<Variable>,
+}
+
+impl Dataset {
+    /// Group messages on a single grid into CF-1.8 variables. Points no message
+    /// covers, and points the bitmaps mask, are NaN, the fill value. Ensemble
+    /// members of a parameter that also has fields of no member get their own
+    /// variable, suffixed `_ens`. Messages sharing a time, level and member
+    /// within a variable are an error rather than overwriting each other.
+    pub fn from_messages(messages: &[Message]) -> Result<Self, GribberishError> {
+        let metadata = messages
+            .iter()
+            .map(MessageMetadata::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        let Some(first) = metadata.first() else {
+            return Err(GribberishError::MessageError(
+                "No messages to write to NetCDF".into(),
+            ));
+        };
+        if let Some(other) = metadata
+            .iter()
+            .find(|m| m.grid_shape != first.grid_shape || m.proj != first.proj)
+        {
+            return Err(GribberishError::MessageError(format!(
+                "{} and {} are on different grids and cannot share a NetCDF file",
+                first.var, other.var
+            )));
+        }
+
+        let mut dataset = Dataset {
+            attributes: vec![
+                ("Conventions".into(), AttributeValue::Text("CF-1.8".into())),
+                (
+                    "source".into(),
+                    AttributeValue::Text("Converted from GRIB with gribberish".into()),
+                ),
+            ],
+            ..Default::default()
+        };
+        let grid_dimensions = dataset.add_grid(first);
+        let grid_mapping = cf_grid_mapping(&first.projector);
+        let has_grid_mapping = grid_mapping.is_some();
+        if let Some(mut attributes) = grid_mapping {
+            // Kept for tools that prefer it over the CF attributes
+            attributes.push(("proj4".into(), AttributeValue::Text(first.proj.clone())));
+            dataset.variables.push(Variable {
+                name: GRID_MAPPING_NAME.into(),
+                dimensions: Vec::new(),
+                attributes,
+                data: VariableData::Int(vec![0]),
+            });
+        }
+
+        if metadata
+            .iter()
+            .all(|m| m.reference_date == first.reference_date)
+        {
+            dataset.variables.push(Variable {
+                name: "reference_time".into(),
+                dimensions: Vec::new(),
+                attributes: vec![
+                    (
+                        "standard_name".into(),
+                        AttributeValue::Text("forecast_reference_time".into()),
+                    ),
+                    ("units".into(), AttributeValue::Text(TIME_UNITS.into())),
+                ],
+                data: VariableData::Double(vec![first.reference_date.timestamp() as f64]),
+            });
+        }
+
+        // Parameters split into one variable per level type and kind, and
+        // ensemble members apart from fields of no member, named after
+        // whichever of those tell them apart
+        let mut groups: BTreeMap<(String, String, String, bool), Vec<usize>> = BTreeMap::new();
+        for (index, m) in metadata.iter().enumerate() {
+            let key = (
+                m.var.to_lowercase(),
+                m.first_fixed_surface_type.coordinate_name().to_string(),
+                message_kind(m),
+                m.perturbation_number.is_some(),
+            );
+            groups.entry(key).or_default().push(index);
+        }
+        let mut splits: BTreeMap<&str, (BTreeSet<_>, BTreeSet<_>, BTreeSet<_>)> = BTreeMap::new();
+        for (var, level, kind, members) in groups.keys() {
+            let (levels, kinds, ensembles) = splits.entry(var).or_default();
+            levels.insert(level);
+            kinds.insert(kind);
+            ensembles.insert(*members);
+        }
+
+        for ((var, level, kind, members), indices) in &groups {
+            let (levels, kinds, ensembles) = &splits[var.as_str()];
+            let mut name = var.clone();
+            if levels.len() > 1 && !level.is_empty() {
+                name = format!("{name}_{level}");
+            }
+            if kinds.len() > 1 {
+                name = format!("{name}_{kind}");
+            }
+            if ensembles.len() > 1 && *members {
+                name = format!("{name}_ens");
+            }
+
+            let group = indices.iter().map(|i| &metadata[*i]).collect::<Vec<_>>();
+            let group_messages = indices.iter().map(|i| &messages[*i]).collect::<Vec<_>>();
+            let variable = dataset.add_variable(
+                netcdf_name(&name),
+                &group,
+                &group_messages,
+                &grid_dimensions,
+                has_grid_mapping,
+            )?;
+            dataset.variables.push(variable);
+        }
+
+        Ok(dataset)
+    }
+
+    pub fn dimension_len(&self, name: &str) -> Option<usize> {
+        self.dimensions
+            .iter()
+            .find(|(dimension, _)| dimension == name)
+            .map(|(_, len)| *len)
+    }
+
+    pub fn variable(&self, name: &str) -> Option<&Variable> {
+        self.variables.iter().find(|variable| variable.name == name)
+    }
+
+    /// Encode the dataset as a NetCDF-3 64-bit offset file
+    pub fn to_bytes(&self) -> Result<Vec<u8>, GribberishError> {
+        let mut dimension_ids = Vec::with_capacity(self.variables.len());
+        for variable in &self.variables {
+            let mut ids = Vec::with_capacity(variable.dimensions.len());
+            let mut expected_len = 1;
+            for name in &variable.dimensions {
+                let id = self
+                    .dimensions
+                    .iter()
+                    .position(|(dimension, _)| dimension == name)
+                    .ok_or_else(|| {
+                        GribberishError::EncodingError(format!(
+                            "{} uses the undefined dimension {name}",
+                            variable.name
+                        ))
+                    })?;
+                expected_len *= self.dimensions[id].1;
+                ids.push(id as u32);
+            }
+            if variable.data.len() != expected_len {
+                return Err(GribberishError::EncodingError(format!(
+                    "{} has {} values for its {expected_len} points",
+                    variable.name,
+                    variable.data.len()
+                )));
+            }
+            dimension_ids.push(ids);
+        }
+        if let Some((name, _)) = self.dimensions.iter().find(|(_, len)| *len == 0) {
+            return Err(GribberishError::EncodingError(format!(
+                "dimension {name} is empty, NetCDF reserves that for the record dimension"
+            )));
+        }
+
+        let data = self
+            .variables
+            .iter()
+            .map(|variable| {
+                let mut bytes = variable.data.bytes();
+                pad(&mut bytes);
+                bytes
+            })
+            .collect::<Vec<_>>();
+
+        // The header size does not depend on where the data begins
+        let header_len = self.header(&dimension_ids, &data, 0).len() as u64;
+        let mut netcdf = self.header(&dimension_ids, &data, header_len);
+        for bytes in data {
+            netcdf.extend(bytes);
+        }
+        Ok(netcdf)
+    }
+
+    fn header(&self, dimension_ids: &[Vec<u32>], data: &[Vec<u8>], data_start: u64) -> Vec<u8> {
+        let mut header = b"CDF\x02".to_vec();
+        header.extend(0u32.to_be_bytes());
+
+        write_list_tag(&mut header, NC_DIMENSION, self.dimensions.len());
+        for (name, len) in &self.dimensions {
+            write_name(&mut header, name);
+            header.extend((*len as u32).to_be_bytes());
+        }
+
+        write_attributes(&mut header, &self.attributes);
+
+        write_list_tag(&mut header, NC_VARIABLE, self.variables.len());
+        let mut begin = data_start;
+        for ((variable, ids), bytes) in self.variables.iter().zip(dimension_ids).zip(data) {
+            write_name(&mut header, &variable.name);
+            header.extend((ids.len() as u32).to_be_bytes());
+            for id in ids {
+                header.extend(id.to_be_bytes());
+            }
+            write_attributes(&mut header, &variable.attributes);
+            header.extend(variable.data.nc_type().to_be_bytes());
+            header.extend(u32::try_from(bytes.len()).unwrap_or(u32::MAX).to_be_bytes());
+            header.extend(begin.to_be_bytes());
+            begin += bytes.len() as u64;
+        }
+
+        header
+    }
+
+    /// Add the grid's dimensions and coordinates, returning the names of its
+    /// dimensions
+    fn add_grid(&mut self, metadata: &MessageMetadata) -> Vec<String> {
+        let (ny, nx) = metadata.grid_shape;
+        let (lats, lngs) = metadata.latlng();
+        let coordinate =
+            |name: &str, dimensions: &[&str], units: &str, axis: Option<&str>, values: Vec<f64>| {
+                Variable {
+                    name: name.into(),
+                    dimensions: dimensions.iter().map(|d| d.to_string()).collect(),
+                    attributes: [
+                        ("standard_name", name),
+                        ("long_name", name),
+                        ("units", units),
+                    ]
+                    .into_iter()
+                    .chain(axis.map(|axis| ("axis", axis)))
+                    .map(|(key, value)| (key.to_string(), AttributeValue::Text(value.into())))
+                    .collect(),
+                    data: VariableData::Double(values),
+                }
+            };
+
+        if metadata.is_regular_grid {
+            self.dimensions.push(("latitude".into(), ny));
+            self.dimensions.push(("longitude".into(), nx));
+            self.variables.extend([
+                coordinate("latitude", &["latitude"], "degrees_north", Some("Y"), lats),
+                coordinate("longitude", &["longitude"], "degrees_east", Some("X"), lngs),
+            ]);
+            return vec!["latitude".into(), "longitude".into()];
+        }
+
+        // Rotated pole grids are regular in rotated degrees rather than
+        // projected metres
+        let (x_name, y_name, units) = if metadata.projector.proj_name() == "ob_tran" {
+            ("grid_longitude", "grid_latitude", "degrees")
+        } else {
+            ("projection_x_coordinate", "projection_y_coordinate", "m")
+        };
+        let mut y = coordinate(y_name, &["y"], units, Some("Y"), metadata.projector.y());
+        let mut x = coordinate(x_name, &["x"], units, Some("X"), metadata.projector.x());
+        y.name = "y".into();
+        x.name = "x".into();
+
+        self.dimensions.push(("y".into(), ny));
+        self.dimensions.push(("x".into(), nx));
+        self.variables.extend([
+            y,
+            x,
+            coordinate("latitude", &["y", "x"], "degrees_north", None, lats),
+            coordinate("longitude", &["y", "x"], "degrees_east", None, lngs),
+        ]);
+        vec!["y".into(), "x".into()]
+    }
+
+    /// Add a dimension and its coordinate, unless one of the same name holds
+    /// the same values, returning the name of the dimension
+    fn add_dimension(
+        &mut self,
+        name: &str,
+        attributes: Vec<(String, AttributeValue)>,
+        values: VariableData,
+    ) -> String {
+        let mut suffix = 0;
+        loop {
+            let candidate = if suffix == 0 {
+                name.to_string()
+            } else {
+                format!("{name}_{suffix}")
+            };
+            match self.variable(&candidate) {
+                Some(existing) if existing.data == values => return candidate,
+                Some(_) => suffix += 1,
+                None => {
+                    self.dimensions.push((candidate.clone(), values.len()));
+                    self.variables.push(Variable {
+                        name: candidate.clone(),
+                        dimensions: vec![candidate.clone()],
+                        attributes,
+                        data: values,
+                    });
+                    return candidate;
+                }
+            }
+        }
+    }
+
+    fn add_variable(
+        &mut self,
+        name: String,
+        metadata: &[&MessageMetadata],
+        messages: &[&Message],
+        grid_dimensions: &[String],
+        has_grid_mapping: bool,
+    ) -> Result<Variable, GribberishError> {
+        let first = metadata[0];
+        let valid_time = |m: &MessageMetadata| m.forecast_end_date.unwrap_or(m.forecast_date);
+        let times = metadata
+            .iter()
+            .map(|m| valid_time(m))
+            .collect::<BTreeSet<DateTime<Utc>>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        let levels = sorted_unique(metadata.iter().filter_map(|m| m.first_fixed_surface_value));
+        let members = metadata
+            .iter()
+            .filter_map(|m| m.perturbation_number)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        let text = |key: &str, value: String| (key.to_string(), AttributeValue::Text(value));
+        let mut dimensions = vec![self.add_dimension(
+            "time",
+            vec![
+                text("standard_name", "time".into()),
+                text("long_name", "time".into()),
+                text("units", TIME_UNITS.into()),
+                text("calendar", "proleptic_gregorian".into()),
+                text("axis", "T".into()),
+            ],
+            VariableData::Double(times.iter().map(|t| t.timestamp() as f64).collect()),
+        )];
+        let mut shape = vec![times.len()];
+
+        let surface_type = &first.first_fixed_surface_type;
+        let level_name = match surface_type.coordinate_name() {
+            "" => "level",
+            name => name,
+        };
+        if levels.len() > 1 {
+            let mut attributes = vec![text("long_name", surface_type.to_string())];
+            if surface_type.is_vertical_level() {
+                attributes.push(text("axis", "Z".into()));
+            }
+            dimensions.push(self.add_dimension(
+                level_name,
+                attributes,
+                VariableData::Double(levels.clone()),
+            ));
+            shape.push(levels.len());
+        }
+
+        if !members.is_empty() {
+            dimensions.push(self.add_dimension(
+                "number",
+                vec![
+                    text("standard_name", "realization".into()),
+                    text("long_name", "ensemble member".into()),
+                ],
+                VariableData::Int(members.iter().map(|m| *m as i32).collect()),
+            ));
+            shape.push(members.len());
+        }
+
+        let point_count = first.data_point_count();
+        let slot_count = shape.iter().product::<usize>();
+        let mut values = vec![f32::NAN; slot_count * point_count];
+        let mut filled = vec![false; slot_count];
+        for (m, message) in metadata.iter().zip(messages) {
+            let mut slot = times.binary_search(&valid_time(m)).unwrap_or_default();
+            if levels.len() > 1 {
+                let level = m.first_fixed_surface_value.unwrap_or(f64::NAN);
+                let index = levels.iter().position(|l| *l == level).unwrap_or_default();
+                slot = slot * levels.len() + index;
+            }
+            if let Some(member) = m.perturbation_number {
+                let index = members.binary_search(&member).unwrap_or_default();
+                slot = slot * members.len() + index;
+            }
+            if std::mem::replace(&mut filled[slot], true) {
+                return Err(GribberishError::MessageError(format!(
+                    "{name} has more than one {} field valid at {} on level {:?} for member {:?}",
+                    m.var,
+                    valid_time(m),
+                    m.first_fixed_surface_value,
+                    m.perturbation_number
+                )));
+            }
+
+            let data = message.data()?;
+            for (value, point) in values[slot * point_count..(slot + 1) * point_count]
+                .iter_mut()
+                .zip(data)
+            {
+                *value = point as f32;
+            }
+        }
+        dimensions.extend(grid_dimensions.iter().cloned());
+
+        let mut attributes = vec![
+            text("long_name", first.name.clone()),
+            text("units", first.units.clone()),
+            ("_FillValue".into(), AttributeValue::Float(vec![f32::NAN])),
+            text("GRIB_shortName", first.var.clone()),
+            (
+                "GRIB_discipline".into(),
+                AttributeValue::Int(vec![first.discipline_value as i32]),
+            ),
+            (
+                "GRIB_parameterCategory".into(),
+                AttributeValue::Int(vec![first.category_value as i32]),
+            ),
+            (
+                "GRIB_parameterNumber".into(),
+                AttributeValue::Int(vec![first.parameter_value as i32]),
+            ),
+            text("GRIB_typeOfLevel", surface_type.to_string()),
+        ];
+        if let ([level], false) = (levels.as_slice(), surface_type.coordinate_name().is_empty()) {
+            attributes.push((
+                format!("GRIB_{level_name}"),
+                AttributeValue::Double(vec![*level]),
+            ));
+        }
+        if grid_dimensions[0] == "y" {
+            attributes.push(text("coordinates", "latitude longitude".into()));
+        }
+        if has_grid_mapping {
+            attributes.push(text("grid_mapping", GRID_MAPPING_NAME.into()));
+        }
+
+        Ok(Variable {
+            name,
+            dimensions,
+            attributes,
+            data: VariableData::Float(values),
+        })
+    }
+}
+
+/// Group messages on a single grid into CF-1.8 variables and encode them as a
+/// NetCDF-3 64-bit offset file
+pub fn messages_to_netcdf(messages: &[Message]) -> Result<Vec<u8>, GribberishError> {
+    Dataset::from_messages(messages)?.to_bytes()
+}
+
+/// The CF grid mapping attributes of a grid, from which tools such as pyproj,
+/// cartopy and rioxarray rebuild its CRS. `None` for projections without a CF
+/// grid mapping name.
+pub fn cf_grid_mapping(projector: &LatLngProjection) -> Option<Vec<(String, AttributeValue)>> {
+    let params = projector.proj_params();
+    let param = |name: &str| params.get(name).copied();
+    let mut attributes = Vec::new();
+    let mut push = |name: &str, value: AttributeValue| attributes.push((name.to_string(), value));
+    let double = |value: f64| AttributeValue::Double(vec![value]);
+
+    match projector.proj_name().as_str() {
+        "latlon" => {
+            push(
+                "grid_mapping_name",
+                AttributeValue::Text("latitude_longitude".into()),
+            );
+        }
+        "lcc" => {
+            push(
+                "grid_mapping_name",
+                AttributeValue::Text("lambert_conformal_conic".into()),
+            );
+            if let (Some(lat_1), Some(lat_2)) = (param("lat_1"), param("lat_2")) {
+                push(
+                    "standard_parallel",
+                    AttributeValue::Double(vec![lat_1, lat_2]),
+                );
+            }
+            if let Some(lon_0) = param("lon_0") {
+                push("longitude_of_central_meridian", double(lon_0));
+            }
+            if let Some(lat_0) = param("lat_0") {
+                push("latitude_of_projection_origin", double(lat_0));
+            }
+            push("false_easting", double(0.0));
+            push("false_northing", double(0.0));
+        }
+        "stere" => {
+            push(
+                "grid_mapping_name",
+                AttributeValue::Text("polar_stereographic".into()),
+            );
+            if let Some(lon_0) = param("lon_0") {
+                push("straight_vertical_longitude_from_pole", double(lon_0));
+            }
+            if let Some(lat_ts) = param("lat_ts") {
+                push("standard_parallel", double(lat_ts));
+            }
+            if let Some(lat_0) = param("lat_0") {
+                push("latitude_of_projection_origin", double(lat_0));
+            }
+            push("false_easting", double(0.0));
+            push("false_northing", double(0.0));
+        }
+        "ob_tran" => {
+            push(
+                "grid_mapping_name",
+                AttributeValue::Text("rotated_latitude_longitude".into()),
+            );
+            if let Some(o_lat_p) = param("o_lat_p") {
+                push("grid_north_pole_latitude", double(o_lat_p));
+            }
+            // proj's lon_0 is the meridian of the rotated south pole
+            if let Some(lon_0) = param("lon_0") {
+                let north_pole_longitude = if lon_0 > 0.0 {
+                    lon_0 - 180.0
+                } else {
+                    lon_0 + 180.0
+                };
+                push("grid_north_pole_longitude", double(north_pole_longitude));
+            }
+            if let Some(o_lon_p) = param("o_lon_p") {
+                push("north_pole_grid_longitude", double(o_lon_p));
+            }
+        }
+        "merc" => {
+            push("grid_mapping_name", AttributeValue::Text("mercator".into()));
+            if let Some(lat_ts) = param("lat_ts") {
+                push("standard_parallel", double(lat_ts));
+            }
+            // Template 3.10 has no central-meridian field; the projection
+            // origin is always the prime meridian.
+            push("longitude_of_projection_origin", double(0.0));
+            push("false_easting", double(0.0));
+            push("false_northing", double(0.0));
+        }
+        "geos" => {
+            push(
+                "grid_mapping_name",
+                AttributeValue::Text("geostationary".into()),
+            );
+            if let Some(h) = param("h") {
+                push("perspective_point_height", double(h));
+            }
+            if let Some(lon_0) = param("lon_0") {
+                push("longitude_of_projection_origin", double(lon_0));
+            }
+            push("latitude_of_projection_origin", double(0.0));
+            push("sweep_angle_axis", AttributeValue::Text("y".into()));
+            push("false_easting", double(0.0));
+            push("false_northing", double(0.0));
+        }
+        _ => return None,
+    }
+
+    // Earth shape: spherical -> earth_radius, otherwise the semi-axes.
+    match (param("a"), param("b")) {
+        (Some(a), Some(b)) if (a - b).abs() < 1e-3 => push("earth_radius", double(a)),
+        (Some(a), Some(b)) => {
+            push("semi_major_axis", double(a));
+            push("semi_minor_axis", double(b));
+        }
+        _ => {}
+    }
+
+    Some(attributes)
+}
+
+/// The [`MessageMetadata::kind`] of a message, with the probability limits and
+/// percentile value it leaves to a dimension appended, as variables here have
+/// no threshold or percentile dimension to stack those along
+fn message_kind(metadata: &MessageMetadata) -> String {
+    let mut kind = metadata.kind();
+    match metadata.probability_type {
+        // Between-limit probabilities already carry both limits in the kind
+        Some(ProbabilityType::BetweenLimits | ProbabilityType::BetweenLimitsInclusive) => {}
+        Some(_) => {
+            for limit in [
+                metadata.probability_lower_limit,
+                metadata.probability_upper_limit,
+            ]
+            .iter()
+            .flatten()
+            {
+                kind = format!("{kind}_{limit}");
+            }
+        }
+        None => {
+            if let Some(percentile) = metadata.percentile_value {
+                kind = format!("{kind}{percentile}");
+            }
+        }
+    }
+    kind
+}
+
+fn sorted_unique(values: impl Iterator<Item = f64>) -> Vec<f64> {
+    let mut values = values.collect::<Vec<_>>();
+    values.sort_by(f64::total_cmp);
+    values.dedup();
+    values
+}
+
+/// A name NetCDF tools accept: letters, digits and underscores, not starting
+/// with a digit
+fn netcdf_name(name: &str) -> String {
+    let name = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    match name.chars().next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => name,
+        _ => format!("_{name}"),
+    }
+}
+
+fn pad(bytes: &mut Vec<u8>) {
+    bytes.resize(bytes.len().next_multiple_of(4), 0);
+}
+
+fn write_name(header: &mut Vec<u8>, name: &str) {
+    header.extend((name.len() as u32).to_be_bytes());
+    header.extend(name.as_bytes());
+    pad(header);
+}
+
+/// The tag and element count opening a list, or the zeros of an absent one
+fn write_list_tag(header: &mut Vec<u8>, tag: u32, len: usize) {
+    let tag = if len == 0 { 0 } else { tag };
+    header.extend(tag.to_be_bytes());
+    header.extend((len as u32).to_be_bytes());
+}
+
+fn write_attributes(header: &mut Vec<u8>, attributes: &[(String, AttributeValue)]) {
+    write_list_tag(header, NC_ATTRIBUTE, attributes.len());
+    for (name, value) in attributes {
+        write_name(header, name);
+        header.extend(value.nc_type().to_be_bytes());
+        header.extend((value.len() as u32).to_be_bytes());
+        header.extend(value.bytes());
+        pad(header);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(bytes: &mut Vec<u8>, words: &[u32]) {
+        for word in words {
+            bytes.extend(word.to_be_bytes());
+        }
+    }
+
+    #[test]
+    fn test_netcdf_layout() {
+        let dataset = Dataset {
+            dimensions: vec![("x".into(), 3)],
+            attributes: vec![("title".into(), AttributeValue::Text("t".into()))],
+            variables: vec![
+                Variable {
+                    name: "x".into(),
+                    dimensions: vec!["x".into()],
+                    attributes: Vec::new(),
+                    data: VariableData::Int(vec![1, 2, 3]),
+                },
+                Variable {
+                    name: "v".into(),
+                    dimensions: Vec::new(),
+                    attributes: vec![("scale".into(), AttributeValue::Float(vec![0.5]))],
+                    data: VariableData::Double(vec![2.0]),
+                },
+            ],
+        };
+
+        let bytes = dataset.to_bytes().unwrap();
+        let mut expected = b"CDF\x02".to_vec();
+        // numrecs, then the dimension and global attribute lists
+        words(&mut expected, &[0, NC_DIMENSION, 1, 1]);
+        expected.extend(b"x\0\0\0");
+        words(&mut expected, &[3, NC_ATTRIBUTE, 1, 5]);
+        expected.extend(b"title\0\0\0");
+        words(&mut expected, &[2, 1]);
+        expected.extend(b"t\0\0\0");
+        // x: one dimension, no attributes, 3 ints
+        words(&mut expected, &[NC_VARIABLE, 2, 1]);
+        expected.extend(b"x\0\0\0");
+        words(&mut expected, &[1, 0, 0, 0, 4, 12, 0, 168]);
+        // v: a scalar double with one float attribute
+        words(&mut expected, &[1]);
+        expected.extend(b"v\0\0\0");
+        words(&mut expected, &[0, NC_ATTRIBUTE, 1, 5]);
+        expected.extend(b"scale\0\0\0");
+        words(&mut expected, &[5, 1, 0.5f32.to_bits(), 6, 8, 0, 180]);
+        assert_eq!(expected.len(), 168);
+        words(&mut expected, &[1, 2, 3]);
+        expected.extend(2.0f64.to_be_bytes());
+        assert_eq!(bytes, expected);
+
+        let mut invalid = dataset.clone();
+        invalid.variables[0].data = VariableData::Int(vec![1, 2]);
+        assert!(invalid.to_bytes().is_err());
+        invalid.variables[0].dimensions = vec!["y".into()];
+        assert!(invalid.to_bytes().is_err());
+    }
+}
@@ -0,0 +1,218 @@
+//! Reading GRIB messages from files too large to hold in memory.
+//!
+//! [`GribReader`] streams over any `Read + Seek` source, reading only the bytes
+//! each message declares in its indicator section. With the `mmap` feature,
+//! [`MappedGribFile`] maps a file instead so messages borrow from the mapping
+//! without copying.
+
+use std::io::{ErrorKind, Read, Seek, SeekFrom};
+
+use crate::error::GribberishError;
+use crate::message::Message;
+use crate::utils::{read_u24_from_bytes, read_u64_from_bytes};
+
+/// How much is read at a time while scanning for the start of the next message
+const SCAN_CHUNK_SIZE: usize = 64 * 1024;
+
+/// The bytes of a single GRIB message read from a larger source
+pub struct OwnedMessage {
+    offset: u64,
+    data: Vec<u8>,
+}
+
+impl OwnedMessage {
+    pub(crate) fn new(offset: u64, data: Vec<u8>) -> Self {
+        OwnedMessage { offset, data }
+    }
+
+    /// Byte offset of the message in the source it was read from
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.data
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// The message, or its first field when it holds several. Offsets reported by
+    /// the message are relative to the start of this message, see [`Self::offset`].
+    pub fn message(&self) -> Message<'_> {
+        Message::from_data(&self.data, 0).expect("messages are validated when read")
+    }
+
+    /// Every field of the message, see [`Message::submessages_from_data`]
+    pub fn submessages(&self) -> Vec<Message<'_>> {
+        Message::submessages_from_data(&self.data, 0)
+    }
+}
+
+/// Reads GRIB messages one at a time from the start of a `Read + Seek` source,
+/// skipping any bytes between messages the way [`crate::message::MessageIterator`] does
+pub struct GribReader<R> {
+    reader: R,
+    offset: u64,
+    source_length: Option<u64>,
+    failed: bool,
+}
+
+impl<R: Read + Seek> GribReader<R> {
+    pub fn new(reader: R) -> Self {
+        GribReader {
+            reader,
+            offset: 0,
+            source_length: None,
+            failed: false,
+        }
+    }
+
+    /// Byte offset the next message will be searched for from
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Read the next message, or `None` once there are no more in the source
+    pub fn read_message(&mut self) -> Result<Option<OwnedMessage>, GribberishError> {
+        let source_length = match self.source_length {
+            Some(length) => length,
+            None => {
+                let length = self.reader.seek(SeekFrom::End(0))?;
+                self.source_length = Some(length);
+                length
+            }
+        };
+
+        loop {
+            let Some(offset) = self.find_magic()? else {
+                self.offset = source_length;
+                return Ok(None);
+            };
+            self.offset = offset;
+
+            let mut header = [0u8; 16];
+            self.reader.seek(SeekFrom::Start(offset))?;
+            let header_length = read_full(&mut self.reader, &mut header)?;
+            let message_length = match header[7] {
+                1 if header_length >= 8 => read_u24_from_bytes(&header, 4).unwrap_or(0) as u64,
+                2 if header_length >= 16 => read_u64_from_bytes(&header, 8).unwrap_or(0),
+                _ => 0,
+            };
+
+            // Not the start of a readable message, keep scanning after the magic
+            if message_length < header_length as u64
+                || offset.saturating_add(message_length) > source_length
+            {
+                self.offset += 1;
+                continue;
+            }
+
+            let mut data = vec![0u8; message_length as usize];
+            self.reader.seek(SeekFrom::Start(offset))?;
+            self.reader.read_exact(&mut data)?;
+
+            if Message::from_data(&data, 0).is_none() {
+                self.offset += 1;
+                continue;
+            }
+
+            self.offset += message_length;
+            return Ok(Some(OwnedMessage::new(offset, data)));
+        }
+    }
+
+    /// Find the offset of the next `GRIB` magic at or after the current offset
+    fn find_magic(&mut self) -> Result<Option<u64>, GribberishError> {
+        let mut buffer = vec![0u8; SCAN_CHUNK_SIZE];
+        let mut start = self.offset;
+
+        loop {
+            self.reader.seek(SeekFrom::Start(start))?;
+            let read = read_full(&mut self.reader, &mut buffer)?;
+            if let Some(position) = buffer[..read].windows(4).position(|w| w == b"GRIB") {
+                return Ok(Some(start + position as u64));
+            }
+
+            if read < buffer.len() {
+                return Ok(None);
+            }
+
+            // Keep the tail in case the magic straddles two chunks
+            start += (read - 3) as u64;
+        }
+    }
+}
+
+impl<R: Read + Seek> Iterator for GribReader<R> {
+    type Item = Result<OwnedMessage, GribberishError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+
+        let message = self.read_message().transpose();
+        self.failed = matches!(message, Some(Err(_)));
+        message
+    }
+}
+
+/// Fill as much of `buffer` as the reader has left, returning how much was read
+fn read_full<R: Read>(reader: &mut R, buffer: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// A GRIB file mapped into memory. Messages read from it borrow from the mapping,
+/// so only the pages that are read are loaded.
+#[cfg(feature = "mmap")]
+pub struct MappedGribFile {
+    map: memmap2::Mmap,
+}
+
+#[cfg(feature = "mmap")]
+impl MappedGribFile {
+    /// Map the file at `path`. The file must not be modified or truncated while it
+    /// is mapped, the messages read from it would change underneath them.
+    pub fn open<P: AsRef<std::path::Path>>(path: P) -> Result<Self, GribberishError> {
+        let file = std::fs::File::open(path)?;
+        // SAFETY: the mapping is read only, and callers are told not to modify the
+        // file while it is open
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        Ok(MappedGribFile { map })
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.map
+    }
+
+    pub fn messages(&self) -> crate::message::MessageIterator<'_> {
+        crate::message::read_messages(&self.map)
+    }
+
+    pub fn message(&self, offset: usize) -> Option<Message<'_>> {
+        Message::from_data(&self.map, offset)
+    }
+}
@@ -0,0 +1,321 @@
+//! Regridding decoded fields onto a regular lat/lon grid.
+//!
+//! [`RegridWeights`] hold, for every point of the target grid, the source grid
+//! points that make it up and their weights. They depend only on the two grids,
+//! so every message on the same source grid is regridded with one computation:
+//!
+//! ```ignore
+//! let weights = RegridWeights::new(&metadata.projector, &target, RegridMethod::Bilinear);
+//! for message in read_messages(&data) {
+//!     let regridded = weights.apply(&message.data()?)?;
+//! }
+//! ```
+
+use crate::error::GribberishError;
+use crate::interpolation::{interpolate, Interpolation};
+use crate::utils::iter::projection::{GaussianLongitudes, LatLngProjection, PlateCareeProjection};
+
+/// How source values are combined into each target grid point
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RegridMethod {
+    /// The value of the closest source point
+    Nearest,
+    /// Bilinear interpolation between the four surrounding source points
+    Bilinear,
+    /// The average of the source points within each target cell, weighted by
+    /// the area of their cells. Each source cell counts wholly toward the target
+    /// cell holding its point, with no overlap computed, so this is not
+    /// conservative regridding. A target cell holding no source points takes
+    /// the source point nearest its centre.
+    AreaAverage,
+}
+
+/// Sparse weights mapping a source grid onto a target grid, stored row by row
+/// for the target points
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RegridWeights {
+    method: RegridMethod,
+    source_point_count: usize,
+    target_shape: (usize, usize),
+    /// Start of each target point's weights, plus the end of the last
+    offsets: Vec<usize>,
+    indices: Vec<usize>,
+    weights: Vec<f64>,
+}
+
+impl RegridWeights {
+    pub fn new(
+        source: &LatLngProjection,
+        target: &PlateCareeProjection,
+        method: RegridMethod,
+    ) -> Self {
+        let target_lats = target.latitudes.clone().collect::<Vec<_>>();
+        let target_lngs = target.longitudes.clone().collect::<Vec<_>>();
+        let (ny, nx) = source.dims();
+        let source_point_count = ny * nx;
+
+        let target_weights = match method {
+            RegridMethod::Nearest | RegridMethod::Bilinear => {
+                let interpolation = if method == RegridMethod::Nearest {
+                    Interpolation::Nearest
+                } else {
+                    Interpolation::Bilinear
+                };
+                target_lats
+                    .iter()
+                    .flat_map(|lat| {
+                        target_lngs
+                            .iter()
+                            .map(move |lng| source.interpolation_weights(*lat, *lng, interpolation))
+                    })
+                    .collect::<Vec<_>>()
+            }
+            RegridMethod::AreaAverage => {
+                area_average_weights(source, target, &target_lats, &target_lngs)
+            }
+        };
+
+        let mut offsets = Vec::with_capacity(target_weights.len() + 1);
+        let mut indices = Vec::new();
+        let mut weights = Vec::new();
+        offsets.push(0);
+        for point_weights in target_weights {
+            for (index, weight) in point_weights {
+                indices.push(index);
+                weights.push(weight);
+            }
+            offsets.push(indices.len());
+        }
+
+        RegridWeights {
+            method,
+            source_point_count,
+            target_shape: (target_lats.len(), target_lngs.len()),
+            offsets,
+            indices,
+            weights,
+        }
+    }
+
+    pub fn method(&self) -> RegridMethod {
+        self.method
+    }
+
+    /// Number of points of the source grid the weights were computed for
+    pub fn source_point_count(&self) -> usize {
+        self.source_point_count
+    }
+
+    /// Target grid dimensions as `(ny, nx)`
+    pub fn target_shape(&self) -> (usize, usize) {
+        self.target_shape
+    }
+
+    /// The source points making up a target point, and their weights
+    pub fn point_weights(&self, target_index: usize) -> Vec<(usize, f64)> {
+        let range = self.offsets[target_index]..self.offsets[target_index + 1];
+        self.indices[range.clone()]
+            .iter()
+            .copied()
+            .zip(self.weights[range].iter().copied())
+            .collect()
+    }
+
+    /// Regrid a decoded field on the source grid, row-major on the target grid.
+    /// Target points off the source grid are NaN. With nearest and bilinear
+    /// regridding so are points next to missing source values, area averages
+    /// leave the missing source values out.
+    pub fn apply(&self, data: &[f64]) -> Result<Vec<f64>, GribberishError> {
+        if data.len() != self.source_point_count {
+            return Err(GribberishError::MessageError(format!(
+                "Cannot regrid {} values with weights for a grid of {} points",
+                data.len(),
+                self.source_point_count
+            )));
+        }
+
+        Ok((0..self.offsets.len() - 1)
+            .map(|target_index| {
+                let weights = self.point_weights(target_index);
+                match self.method {
+                    RegridMethod::Nearest | RegridMethod::Bilinear => interpolate(data, &weights),
+                    RegridMethod::AreaAverage => {
+                        let (sum, total_weight) = weights
+                            .iter()
+                            .filter(|(index, _)| !data[*index].is_nan())
+                            .fold((0.0, 0.0), |(sum, total), (index, weight)| {
+                                (sum + data[*index] * weight, total + weight)
+                            });
+                        if total_weight > 0.0 {
+                            sum / total_weight
+                        } else {
+                            f64::NAN
+                        }
+                    }
+                }
+            })
+            .collect())
+    }
+}
+
+/// Regrid a decoded field from its grid onto a regular lat/lon grid. Compute
+/// [`RegridWeights`] once instead when regridding many fields on the same grid.
+pub fn regrid(
+    data: &[f64],
+    source: &LatLngProjection,
+    target: &PlateCareeProjection,
+    method: RegridMethod,
+) -> Result<Vec<f64>, GribberishError> {
+    RegridWeights::new(source, target, method).apply(data)
+}
+
+/// The location of every source grid point, in data order, with the relative
+/// area of the cell around it
+fn source_points(source: &LatLngProjection) -> Vec<(f64, f64, f64)> {
+    let (lats, lngs) = source.lat_lng();
+    match source {
+        LatLngProjection::PlateCaree(_) => lats
+            .iter()
+            .flat_map(|lat| {
+                let area = lat.to_radians().cos();
+                lngs.iter().map(move |lng| (*lat, *lng, area))
+            })
+            .collect(),
+        LatLngProjection::Gaussian(projection) => match &projection.longitudes {
+            GaussianLongitudes::Regular(_) => lats
+                .iter()
+                .flat_map(|lat| {
+                    let area = lat.to_radians().cos();
+                    lngs.iter().map(move |lng| (*lat, *lng, area))
+                })
+                .collect(),
+            // Points of a reduced row share its circle of latitude
+            GaussianLongitudes::Reduced(rows) => {
+                let areas = projection
+                    .latitudes
+                    .iter()
+                    .zip(rows)
+                    .flat_map(|(lat, row)| {
+                        let count = row.clone().count();
+                        let area = lat.to_radians().cos() / count.max(1) as f64;
+                        std::iter::repeat_n(area, count)
+                    });
+                lats.iter()
+                    .zip(lngs.iter())
+                    .zip(areas)
+                    .map(|((lat, lng), area)| (*lat, *lng, area))
+                    .collect()
+            }
+        },
+        // Projected cells are equal in projected units, which is close enough
+        // to equal in area over the extent of a regional grid
+        LatLngProjection::Projected(_) => lats
+            .iter()
+            .zip(lngs.iter())
+            .map(|(lat, lng)| (*lat, *lng, 1.0))
+            .collect(),
+    }
+}
+
+fn area_average_weights(
+    source: &LatLngProjection,
+    target: &PlateCareeProjection,
+    target_lats: &[f64],
+    target_lngs: &[f64],
+) -> Vec<Vec<(usize, f64)>> {
+    let mut target_weights = vec![Vec::new(); target_lats.len() * target_lngs.len()];
+
+    for (source_index, (lat, lng, area)) in source_points(source).into_iter().enumerate() {
+        if let Some(target_index) = target.cell_index(lat, lng) {
+            target_weights[target_index].push((source_index, area));
+        }
+    }
+
+    for (target_index, weights) in target_weights.iter_mut().enumerate() {
+        if weights.is_empty() {
+            let lat = target_lats[target_index / target_lngs.len()];
+            let lng = target_lngs[target_index % target_lngs.len()];
+            *weights = source.interpolation_weights(lat, lng, Interpolation::Nearest);
+            continue;
+        }
+
+        let total: f64 = weights.iter().map(|(_, weight)| weight).sum();
+        for (_, weight) in weights.iter_mut() {
+            *weight /= total;
+        }
+    }
+
+    target_weights
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::iter::projection::RegularCoordinateIterator;
+
+    fn grid(
+        lat_start: f64,
+        lat_step: f64,
+        ny: usize,
+        lng_start: f64,
+        lng_step: f64,
+        nx: usize,
+    ) -> PlateCareeProjection {
+        PlateCareeProjection::new(
+            RegularCoordinateIterator::new(lat_start, lat_step, ny),
+            RegularCoordinateIterator::new(lng_start, lng_step, nx),
+        )
+    }
+
+    #[test]
+    fn test_regrid_to_coarser_grid() {
+        // 1° source at the equator, 2° target cells each holding 2 × 2 of its points
+        let source = LatLngProjection::PlateCaree(grid(1.5, -1.0, 4, 10.5, 1.0, 4));
+        let target = grid(1.0, -2.0, 2, 11.0, 2.0, 2);
+        let data = (0..16).map(|v| v as f64).collect::<Vec<_>>();
+
+        let average = regrid(&data, &source, &target, RegridMethod::AreaAverage).unwrap();
+        let expected = [2.5, 4.5, 10.5, 12.5];
+        for (value, expected) in average.iter().zip(expected) {
+            assert!((value - expected).abs() < 1e-3, "{value} != {expected}");
+        }
+
+        // Target points lie between four source points
+        let bilinear = regrid(&data, &source, &target, RegridMethod::Bilinear).unwrap();
+        assert_eq!(bilinear, vec![2.5, 4.5, 10.5, 12.5]);
+
+        let nearest = regrid(&data, &source, &target, RegridMethod::Nearest).unwrap();
+        assert_eq!(nearest, vec![5.0, 7.0, 13.0, 15.0]);
+
+        // Missing source values are left out of the average
+        let mut missing = data.clone();
+        missing[0] = f64::NAN;
+        let weights = RegridWeights::new(&source, &target, RegridMethod::AreaAverage);
+        assert!((weights.apply(&missing).unwrap()[0] - 3.33).abs() < 1e-2);
+        let weights = RegridWeights::new(&source, &target, RegridMethod::Bilinear);
+        assert!(weights.apply(&missing).unwrap()[0].is_nan());
+    }
+
+    #[test]
+    fn test_regrid_to_finer_grid() {
+        let source = LatLngProjection::PlateCaree(grid(1.0, -2.0, 2, 0.0, 2.0, 2));
+        let target = grid(1.0, -0.75, 2, 0.0, 0.75, 3);
+        let data = vec![1.0, 2.0, 3.0, 4.0];
+
+        // Target cells smaller than the source cells take the one they lie in
+        let average = regrid(&data, &source, &target, RegridMethod::AreaAverage).unwrap();
+        assert_eq!(average, vec![1.0, 1.0, 2.0, 1.0, 1.0, 2.0]);
+
+        // Off the source grid
+        let target = grid(1.0, 1.0, 2, 0.0, 1.0, 2);
+        let bilinear = regrid(&data, &source, &target, RegridMethod::Bilinear).unwrap();
+        assert!(bilinear[2].is_nan());
+        assert_eq!(bilinear[1], 1.5);
+
+        assert!(RegridWeights::new(&source, &target, RegridMethod::Nearest)
+            .apply(&data[..3])
+            .is_err());
+    }
+}
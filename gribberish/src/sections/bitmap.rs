@@ -1,10 +1,18 @@
 use bitvec::prelude::*;
 
 use super::grib_section::GribSection;
+use crate::error::GribberishError;
 use crate::utils::read_u32_from_bytes;
 use std::iter::Iterator;
 use std::vec::Vec;
 
+/// Bitmap indicator for a bitmap that follows in the section
+pub const BITMAP_PRESENT: u8 = 0;
+/// Bitmap indicator for reusing the bitmap previously defined in the same message
+pub const PREVIOUSLY_DEFINED_BITMAP: u8 = 254;
+/// Bitmap indicator for a field without a bitmap
+pub const NO_BITMAP: u8 = 255;
+
 pub struct BitmapSection<'a> {
     data: &'a [u8],
 }
@@ -14,35 +22,74 @@ impl<'a> BitmapSection<'a> {
         BitmapSection { data }
     }
 
+    /// 0 when the bitmap follows, 1 - 253 for a bitmap predefined by the originating
+    /// centre, 254 to reuse the bitmap previously defined in the message and 255 when
+    /// there is no bitmap (code table 6.0)
+    pub fn bitmap_indicator(&self) -> u8 {
+        self.data[5]
+    }
+
     pub fn has_bitmap(&self) -> bool {
-        self.data[5] == 0
+        self.bitmap_indicator() == BITMAP_PRESENT
+    }
+
+    /// Mask `unmapped_data` with the bitmap, if the field has one, for a grid of
+    /// `data_point_count` points. Predefined bitmaps and indicator 254 without a
+    /// previously defined bitmap cannot be applied.
+    pub fn apply(
+        &self,
+        unmapped_data: Vec<f64>,
+        data_point_count: usize,
+    ) -> Result<Vec<f64>, GribberishError> {
+        match self.bitmap_indicator() {
+            BITMAP_PRESENT => self.map_data(unmapped_data, data_point_count),
+            NO_BITMAP => Ok(unmapped_data),
+            PREVIOUSLY_DEFINED_BITMAP => Err(GribberishError::BitmapError(
+                "bitmap indicator 254 refers to a previously defined bitmap, but none was defined earlier in the message".into(),
+            )),
+            predefined => Err(GribberishError::BitmapError(format!(
+                "predefined bitmap {predefined} is not known"
+            ))),
+        }
     }
 
     pub fn raw_bitmap_data(&self) -> &[u8] {
         &self.data[6..]
     }
 
-    pub fn map_data(&self, unmapped_data: Vec<f64>) -> Vec<f64> {
-        let mut nan_count: usize = 0;
-
+    /// Spread `unmapped_data` over the points of the grid the bitmap marks as
+    /// present, leaving the rest NaN. Bits past `data_point_count` only pad the
+    /// bitmap to a whole octet and are ignored.
+    pub fn map_data(
+        &self,
+        unmapped_data: Vec<f64>,
+        data_point_count: usize,
+    ) -> Result<Vec<f64>, GribberishError> {
         let bitmask = self.raw_bitmap_data().view_bits::<Msb0>();
-        let mut data = Vec::new();
-        data.resize(bitmask.len(), 0.0);
-        if unmapped_data.is_empty() {
-            return data;
+        if bitmask.len() < data_point_count {
+            return Err(GribberishError::BitmapError(format!(
+                "bitmap holds {} bits for {data_point_count} data points",
+                bitmask.len()
+            )));
         }
 
-        for (i, mask) in bitmask.iter().enumerate() {
-            data[i] = match *mask {
-                true => unmapped_data[i - nan_count],
-                _ => {
-                    nan_count += 1;
-                    f64::NAN
-                }
-            };
+        let bitmask = &bitmask[..data_point_count];
+        let present_count = bitmask.count_ones();
+        if present_count != unmapped_data.len() {
+            return Err(GribberishError::BitmapError(format!(
+                "bitmap marks {present_count} data points present, but {} values were unpacked",
+                unmapped_data.len()
+            )));
         }
 
-        data
+        let mut values = unmapped_data.into_iter();
+        Ok(bitmask
+            .iter()
+            .map(|mask| match *mask {
+                true => values.next().unwrap_or(f64::NAN),
+                false => f64::NAN,
+            })
+            .collect())
     }
 
     pub fn data_index(&self, index: usize) -> Option<usize> {
@@ -98,3 +145,29 @@ impl<'a> GribSection for BitmapSection<'a> {
         self.data[4]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn map_bitmap_data() {
+        use super::BitmapSection;
+        use crate::error::GribberishError;
+
+        // Three data points, the rest of the octet only pads the bitmap
+        let raw = [0x00u8, 0x00, 0x00, 0x07, 0x06, 0x00, 0b1011_1111];
+        let bitmap = BitmapSection::from_data(&raw);
+        let data = bitmap.apply(vec![1.0, 2.0], 3).unwrap();
+        assert_eq!(data[0], 1.0);
+        assert!(data[1].is_nan());
+        assert_eq!(data[2], 2.0);
+
+        assert!(matches!(
+            bitmap.apply(vec![1.0, 2.0, 3.0], 3),
+            Err(GribberishError::BitmapError(_))
+        ));
+        assert!(matches!(
+            bitmap.apply(vec![1.0; 9], 9),
+            Err(GribberishError::BitmapError(_))
+        ));
+    }
+}
@@ -1,7 +1,8 @@
 use crate::templates::data_representation::{
     CCSDSDataRepresentationTemplate, ComplexPackingDataRepresentationTemplate,
     ComplexSpatialPackingDataRepresentationTemplate, DataRepresentationTemplate,
-    SimplePackingDataRepresentationTemplate,
+    IEEEFloatDataRepresentationTemplate, SimplePackingDataRepresentationTemplate,
+    SpectralComplexDataRepresentationTemplate, SpectralSimpleDataRepresentationTemplate,
 };
 use crate::utils::{read_u16_from_bytes, read_u32_from_bytes};
 
@@ -29,28 +30,33 @@ impl<'a> DataRepresentationSection<'a> {
         read_u16_from_bytes(self.data, 9).unwrap_or(0)
     }
 
-    pub fn data_representation_template(&self) -> Option<Box<dyn DataRepresentationTemplate<f64>>> {
+    pub fn data_representation_template(
+        &self,
+    ) -> Option<Box<dyn DataRepresentationTemplate<f64> + 'a>> {
         let template_number = self.data_representation_template_number();
         match template_number {
             0 => Some(Box::new(SimplePackingDataRepresentationTemplate::new(
-                self.data.to_vec(),
+                self.data,
             ))),
             2 => Some(Box::new(ComplexPackingDataRepresentationTemplate::new(
-                self.data.to_vec(),
+                self.data,
             ))),
             3 => Some(Box::new(
-                ComplexSpatialPackingDataRepresentationTemplate::new(self.data.to_vec()),
+                ComplexSpatialPackingDataRepresentationTemplate::new(self.data),
             )),
-            #[cfg(feature = "jpeg")]
-            40 => Some(Box::new(JPEGDataRepresentationTemplate::new(
-                self.data.to_vec(),
+            4 => Some(Box::new(IEEEFloatDataRepresentationTemplate::new(
+                self.data,
             ))),
+            #[cfg(feature = "jpeg")]
+            40 => Some(Box::new(JPEGDataRepresentationTemplate::new(self.data))),
             #[cfg(feature = "png")]
-            41 => Some(Box::new(PNGDataRepresentationTemplate::new(
-                self.data.to_vec(),
+            41 => Some(Box::new(PNGDataRepresentationTemplate::new(self.data))),
+            42 => Some(Box::new(CCSDSDataRepresentationTemplate::new(self.data))),
+            50 => Some(Box::new(SpectralSimpleDataRepresentationTemplate::new(
+                self.data,
             ))),
-            42 => Some(Box::new(CCSDSDataRepresentationTemplate::new(
-                self.data.to_vec(),
+            51 => Some(Box::new(SpectralComplexDataRepresentationTemplate::new(
+                self.data,
             ))),
             _ => None,
         }
@@ -1,8 +1,9 @@
 use super::grib_section::GribSection;
 use crate::{
     templates::grid_definition::{
-        GridDefinitionTemplate, LambertConformalTemplate, LatLngTemplate, MercatorTemplate,
-        PolarStereographicTemplate,
+        GaussianTemplate, GridDefinitionTemplate, LambertConformalTemplate, LatLngTemplate,
+        MercatorTemplate, PolarStereographicTemplate, RotatedLatLngTemplate, SpaceViewTemplate,
+        SphericalHarmonicsTemplate,
     },
     utils::{read_u16_from_bytes, read_u32_from_bytes},
 };
@@ -10,6 +11,7 @@ use gribberish_macros::{DisplayDescription, FromValue};
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum GridSource {
     #[description = "specified in code"]
     Code = 0,
@@ -20,6 +22,7 @@ pub enum GridSource {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum NumberListInterpretation {
     #[description = "no appended list"]
     None = 0,
@@ -61,15 +64,17 @@ impl<'a> GridDefinitionSection<'a> {
         read_u16_from_bytes(self.data, 12).unwrap_or(0)
     }
 
-    pub fn grid_definition_template(&self) -> Option<Box<dyn GridDefinitionTemplate>> {
+    pub fn grid_definition_template(&self) -> Option<Box<dyn GridDefinitionTemplate + 'a>> {
         let template_number = self.grid_definition_template_number();
         match template_number {
-            0 => Some(Box::new(LatLngTemplate::new(self.data.to_vec()))),
-            10 => Some(Box::new(MercatorTemplate::new(self.data.to_vec()))),
-            20 => Some(Box::new(PolarStereographicTemplate::new(
-                self.data.to_vec(),
-            ))),
-            30 => Some(Box::new(LambertConformalTemplate::new(self.data.to_vec()))),
+            0 => Some(Box::new(LatLngTemplate::new(self.data))),
+            1 => Some(Box::new(RotatedLatLngTemplate::new(self.data))),
+            10 => Some(Box::new(MercatorTemplate::new(self.data))),
+            20 => Some(Box::new(PolarStereographicTemplate::new(self.data))),
+            30 => Some(Box::new(LambertConformalTemplate::new(self.data))),
+            40 => Some(Box::new(GaussianTemplate::new(self.data))),
+            50 => Some(Box::new(SphericalHarmonicsTemplate::new(self.data))),
+            90 => Some(Box::new(SpaceViewTemplate::new(self.data))),
             _ => None,
         }
     }
@@ -5,6 +5,7 @@ use gribberish_macros::{DisplayDescription, FromValue};
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum ReferenceDataSignificance {
     Analysis = 0,
     #[description = "start of forecast"]
@@ -18,6 +19,7 @@ pub enum ReferenceDataSignificance {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum ProductionStatus {
     Operational = 0,
     #[description = "operational test"]
@@ -41,6 +43,7 @@ pub enum ProductionStatus {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum GribDataType {
     Analysis = 0,
     Forecast = 1,
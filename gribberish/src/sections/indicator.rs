@@ -12,6 +12,7 @@ fn validate_indicator_section(data: &[u8]) -> bool {
 
 #[repr(u8)]
 #[derive(Eq, Clone, PartialEq, Debug, DisplayDescription, FromValue)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum Discipline {
     Meteorological = 0,
     Hydrological = 1,
@@ -52,7 +53,7 @@ impl<'a> IndicatorSection<'a> {
     }
 
     pub fn total_length(&self) -> u64 {
-        read_u64_from_bytes(self.data, 8).unwrap_or(0) as u64
+        read_u64_from_bytes(self.data, 8).unwrap_or(0)
     }
 }
 
@@ -3,12 +3,15 @@ use crate::{
     templates::product::{
         derived_ensemble_forecast_time_interval_reference_template::DerivedEnsembleForecastTimeIntervalReferenceTemplate,
         derived_ensemble_horizontal_forecast_time_interval_template::DerivedEnsembleHorizontalForecastTimeIntervalTemplate,
-        product_template::ProductTemplate,
+        product_template::ProductTemplate, AerosolHorizontalForecastTemplate,
+        AerosolOpticalPropertiesHorizontalForecastTemplate, AerosolTimeIntervalTemplate,
         AverageAccumulationExtremeHorizontalAnalysisForecastTemplate,
+        ChemicalConstituentEnsembleForecastTemplate, ChemicalConstituentHorizontalForecastTemplate,
         DerivedEnsembleHorizontalAnalysisForecastTemplate, EnsembleForecastTimeIntervalTemplate,
         HorizontalAnalysisForecastTemplate, HorizontalEnsembleForecastTemplate,
         PercentileHorizontalTemplate, PercentileHorizontalTimeIntervalTemplate,
         ProbabilityHorizontalForecastTemplate, ProbabilityHorizontalTimeIntervalTemplate,
+        SatelliteProductTemplate, SimulatedSatelliteTemplate,
         WavePeriodRangeHorizontalForecastTemplate,
     },
     utils::{read_u16_from_bytes, read_u32_from_bytes},
@@ -31,63 +34,69 @@ impl<'a> ProductDefinitionSection<'a> {
         read_u16_from_bytes(self.data, 7).unwrap_or(0)
     }
 
-    pub fn product_definition_template(&self, discipline: u8) -> Option<Box<dyn ProductTemplate>> {
+    pub fn product_definition_template(
+        &self,
+        discipline: u8,
+    ) -> Option<Box<dyn ProductTemplate + 'a>> {
         match self.product_definition_template_number() {
             0 => Some(Box::new(HorizontalAnalysisForecastTemplate::new(
-                self.data.to_vec(),
-                discipline,
+                self.data, discipline,
             ))),
             1 => Some(Box::new(HorizontalEnsembleForecastTemplate::new(
-                self.data.to_vec(),
-                discipline,
+                self.data, discipline,
             ))),
             2 => Some(Box::new(
-                DerivedEnsembleHorizontalAnalysisForecastTemplate::new(
-                    self.data.to_vec(),
-                    discipline,
-                ),
+                DerivedEnsembleHorizontalAnalysisForecastTemplate::new(self.data, discipline),
             )),
             8 => Some(Box::new(
                 AverageAccumulationExtremeHorizontalAnalysisForecastTemplate::new(
-                    self.data.to_vec(),
-                    discipline,
+                    self.data, discipline,
                 ),
             )),
             5 => Some(Box::new(ProbabilityHorizontalForecastTemplate::new(
-                self.data.to_vec(),
-                discipline,
+                self.data, discipline,
             ))),
             6 => Some(Box::new(PercentileHorizontalTemplate::new(
-                self.data.to_vec(),
-                discipline,
+                self.data, discipline,
             ))),
             9 => Some(Box::new(ProbabilityHorizontalTimeIntervalTemplate::new(
-                self.data.to_vec(),
-                discipline,
+                self.data, discipline,
             ))),
             10 => Some(Box::new(PercentileHorizontalTimeIntervalTemplate::new(
-                self.data.to_vec(),
-                discipline,
+                self.data, discipline,
             ))),
             11 => Some(Box::new(EnsembleForecastTimeIntervalTemplate::new(
-                self.data.to_vec(),
-                discipline,
+                self.data, discipline,
             ))),
             12 => Some(Box::new(
-                DerivedEnsembleHorizontalForecastTimeIntervalTemplate::new(
-                    self.data.to_vec(),
-                    discipline,
-                ),
+                DerivedEnsembleHorizontalForecastTimeIntervalTemplate::new(self.data, discipline),
+            )),
+            31 => Some(Box::new(SatelliteProductTemplate::new(
+                self.data, discipline,
+            ))),
+            32 => Some(Box::new(SimulatedSatelliteTemplate::new(
+                self.data, discipline,
+            ))),
+            40 => Some(Box::new(
+                ChemicalConstituentHorizontalForecastTemplate::new(self.data, discipline),
+            )),
+            41 => Some(Box::new(ChemicalConstituentEnsembleForecastTemplate::new(
+                self.data, discipline,
+            ))),
+            44 => Some(Box::new(AerosolHorizontalForecastTemplate::new(
+                self.data, discipline,
+            ))),
+            46 => Some(Box::new(AerosolTimeIntervalTemplate::new(
+                self.data, discipline,
+            ))),
+            48 => Some(Box::new(
+                AerosolOpticalPropertiesHorizontalForecastTemplate::new(self.data, discipline),
             )),
             103 => Some(Box::new(WavePeriodRangeHorizontalForecastTemplate::new(
-                self.data.to_vec(),
-                discipline,
+                self.data, discipline,
             ))),
             107 => Some(Box::new(
-                DerivedEnsembleForecastTimeIntervalReferenceTemplate::new(
-                    self.data.to_vec(),
-                    discipline,
-                ),
+                DerivedEnsembleForecastTimeIntervalReferenceTemplate::new(self.data, discipline),
             )),
             _ => None,
         }
@@ -1,4 +1,4 @@
-use super::bitmap::BitmapSection;
+use super::bitmap::{BitmapSection, BITMAP_PRESENT, PREVIOUSLY_DEFINED_BITMAP};
 use super::data::DataSection;
 use super::data_representation::DataRepresentationSection;
 use super::end::EndSection;
@@ -89,7 +89,7 @@ impl<'a> Section<'a> {
 // TODO: IMPL TRY FROMS FOR INNER TYPES HERE
 
 fn section_length(data: &[u8], offset: usize) -> Option<usize> {
-    if data.len() <= offset + 4 {
+    if data.len() < offset + 4 {
         None
     } else if IndicatorSection::is_indicator_section(data, offset) {
         Some(16)
@@ -101,7 +101,7 @@ fn section_length(data: &[u8], offset: usize) -> Option<usize> {
 }
 
 fn section_number(data: &[u8], offset: usize) -> Option<u8> {
-    if data.len() <= offset + 4 {
+    if data.len() < offset + 4 {
         None
     } else if IndicatorSection::is_indicator_section(data, offset) {
         Some(0)
@@ -112,6 +112,102 @@ fn section_number(data: &[u8], offset: usize) -> Option<u8> {
     }
 }
 
+/// Where a section of a GRIB2 message sits within the underlying data
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SectionOffset {
+    pub number: u8,
+    pub offset: usize,
+    pub len: usize,
+}
+
+impl SectionOffset {
+    pub fn section<'a>(&self, data: &'a [u8]) -> Option<Section<'a>> {
+        Section::from_data(data, self.offset)
+    }
+}
+
+/// Walk the sections of the GRIB2 message starting at `offset` once, stopping after
+/// the end section, so the sections can be read later without scanning for them again
+pub fn scan_section_offsets(data: &[u8], offset: usize) -> Vec<SectionOffset> {
+    let mut sections = Vec::new();
+    let mut offset = offset;
+
+    while let (Some(len), Some(number)) =
+        (section_length(data, offset), section_number(data, offset))
+    {
+        if len == 0 || number > 8 || offset + len > data.len() {
+            break;
+        }
+
+        sections.push(SectionOffset {
+            number,
+            offset,
+            len,
+        });
+        offset += len;
+
+        if number == 8 {
+            break;
+        }
+    }
+
+    sections
+}
+
+/// Split the sections of a GRIB2 message into the sections of each field it holds.
+///
+/// A message may repeat sections 2 to 7, 3 to 7 or 4 to 7 to carry several fields
+/// (submessages). Every data section completes a field, which uses the most recent of
+/// each section before it so sections that were not repeated are shared. A message
+/// read without its data section is a single field.
+///
+/// A bitmap section with indicator 254 is replaced by the bitmap last defined in the
+/// message, which is what the field uses.
+pub fn submessage_section_offsets(
+    data: &[u8],
+    sections: &[SectionOffset],
+) -> Vec<Vec<SectionOffset>> {
+    let mut latest: [Option<SectionOffset>; 7] = [None; 7];
+    let mut defined_bitmap: Option<SectionOffset> = None;
+    let mut fields = Vec::new();
+
+    for section in sections {
+        match section.number {
+            6 => {
+                let bitmap = match section.section(data) {
+                    Some(Section::Bitmap(bitmap)) => Some(bitmap.bitmap_indicator()),
+                    _ => None,
+                };
+                latest[6] = match bitmap {
+                    Some(BITMAP_PRESENT) => {
+                        defined_bitmap = Some(*section);
+                        Some(*section)
+                    }
+                    Some(PREVIOUSLY_DEFINED_BITMAP) => defined_bitmap.or(Some(*section)),
+                    _ => Some(*section),
+                };
+            }
+            0..=5 => latest[section.number as usize] = Some(*section),
+            7 => {
+                let mut field = latest.iter().flatten().copied().collect::<Vec<_>>();
+                field.push(*section);
+                fields.push(field);
+            }
+            _ => {
+                for field in fields.iter_mut() {
+                    field.push(*section);
+                }
+            }
+        }
+    }
+
+    if fields.is_empty() {
+        fields.push(sections.to_vec());
+    }
+
+    fields
+}
+
 pub struct SectionIterator<'a> {
     pub data: &'a [u8],
     pub offset: usize,
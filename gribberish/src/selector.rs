@@ -0,0 +1,534 @@
+//! Selecting messages by their metadata, like `grib_ls -w`.
+//!
+//! A [`Selector`] is a list of clauses such as `var=TMP,level_type=isobaric,level=500,step>=6`
+//! that must all hold for a message to be selected. It is evaluated against
+//! anything [`Selectable`], which includes [`MessageMetadata`] and the
+//! [`IndexEntry`] of a sidecar index, so messages can be chosen without decoding
+//! their data.
+//!
+//! Clauses are written `key=value`, `key!=value`, `key<value`, `key<=value`,
+//! `key>value` or `key>=value`. Equality clauses take a `/` separated list of
+//! values and hold when any of them matches, `!=` when none of them do. Ordered
+//! comparisons take a single number. Text is compared ignoring case, numbers by
+//! value. A clause on a key a message does not have never holds.
+//!
+//! The keys understood are
+//!
+//! - `var`: the variable abbreviation, e.g. `TMP` or `2t`
+//! - `name` and `units`: the variable name and units. Not in indexes.
+//! - `level_type`: the first fixed surface, by its code (`100`), its coordinate
+//!   name (`isobar`), its ecCodes `typeOfLevel` (`isobaricInhPa`, also
+//!   `isobaric`), its MARS `levtype` (`pl`) or its description
+//! - `level`: the first fixed surface value, with pressures in hPa and depths in m
+//! - `step`: hours from the reference time to the end of the forecast
+//! - `date` and `time`: the reference time as `YYYYMMDD` and `HHMM`
+//! - `number`: the ensemble member, 0 for control forecasts
+//! - `discipline`, `category` and `parameter`: the parameter codes, or the
+//!   discipline and category names. Not in indexes.
+//! - `edition`: the GRIB edition. Not in indexes.
+//!
+//! ECMWF index entries also match any of their other MARS keys, e.g. `stream=enfo`.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::GribberishError;
+use crate::index::{
+    ecmwf_levtype, noaa_level_value, parse_noaa_forecast_hours, parse_noaa_level, IndexEntry,
+};
+use crate::message_metadata::MessageMetadata;
+use crate::templates::product::tables::FixedSurfaceType;
+
+/// How a clause compares a message's values to its own
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Comparison::Equal => "=",
+            Comparison::NotEqual => "!=",
+            Comparison::Less => "<",
+            Comparison::LessOrEqual => "<=",
+            Comparison::Greater => ">",
+            Comparison::GreaterOrEqual => ">=",
+        }
+    }
+
+    fn is_ordered(&self) -> bool {
+        !matches!(self, Comparison::Equal | Comparison::NotEqual)
+    }
+}
+
+/// A single `key<comparison>values` condition of a [`Selector`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Clause {
+    /// Lowercase key the clause is on
+    pub key: String,
+    pub comparison: Comparison,
+    pub values: Vec<String>,
+}
+
+impl Clause {
+    /// Whether the clause holds for the values a message has for its key
+    pub fn matches(&self, values: &[SelectorValue]) -> bool {
+        if values.is_empty() {
+            return false;
+        }
+
+        match self.comparison {
+            Comparison::Equal => values
+                .iter()
+                .any(|value| self.values.iter().any(|expected| value.equals(expected))),
+            Comparison::NotEqual => !values
+                .iter()
+                .any(|value| self.values.iter().any(|expected| value.equals(expected))),
+            comparison => values.iter().any(|value| {
+                let Some(ordering) = self
+                    .values
+                    .first()
+                    .and_then(|expected| value.compare(expected))
+                else {
+                    return false;
+                };
+                match comparison {
+                    Comparison::Less => ordering == Ordering::Less,
+                    Comparison::LessOrEqual => ordering != Ordering::Greater,
+                    Comparison::Greater => ordering == Ordering::Greater,
+                    _ => ordering != Ordering::Less,
+                }
+            }),
+        }
+    }
+}
+
+impl fmt::Display for Clause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}{}",
+            self.key,
+            self.comparison.symbol(),
+            self.values.join("/")
+        )
+    }
+}
+
+/// Clauses that must all hold for a message to be selected. An empty selector
+/// selects everything.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Selector {
+    clauses: Vec<Clause>,
+}
+
+impl Selector {
+    pub fn new() -> Self {
+        Selector::default()
+    }
+
+    /// Add a clause to the selector
+    pub fn with<I, S>(mut self, key: &str, comparison: Comparison, values: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.clauses.push(Clause {
+            key: key.trim().to_lowercase(),
+            comparison,
+            values: values.into_iter().map(Into::into).collect(),
+        });
+        self
+    }
+
+    pub fn clauses(&self) -> &[Clause] {
+        &self.clauses
+    }
+
+    pub fn matches<T: Selectable + ?Sized>(&self, item: &T) -> bool {
+        self.clauses
+            .iter()
+            .all(|clause| clause.matches(&item.selector_values(&clause.key)))
+    }
+}
+
+impl FromStr for Selector {
+    type Err = GribberishError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().is_empty() {
+            return Ok(Selector::default());
+        }
+
+        let mut selector = Selector::default();
+        for clause in s.split(',') {
+            let invalid =
+                |reason: &str| GribberishError::SelectorError(format!("{reason} in `{clause}`"));
+
+            let Some(start) = clause.find(['=', '!', '<', '>']) else {
+                return Err(invalid("missing comparison"));
+            };
+            let (key, rest) = clause.split_at(start);
+            let (comparison, values) = if let Some(values) = rest.strip_prefix("!=") {
+                (Comparison::NotEqual, values)
+            } else if let Some(values) = rest.strip_prefix("<=") {
+                (Comparison::LessOrEqual, values)
+            } else if let Some(values) = rest.strip_prefix(">=") {
+                (Comparison::GreaterOrEqual, values)
+            } else if let Some(values) = rest.strip_prefix('=') {
+                (Comparison::Equal, values)
+            } else if let Some(values) = rest.strip_prefix('<') {
+                (Comparison::Less, values)
+            } else if let Some(values) = rest.strip_prefix('>') {
+                (Comparison::Greater, values)
+            } else {
+                return Err(invalid("invalid comparison"));
+            };
+
+            if key.trim().is_empty() {
+                return Err(invalid("missing key"));
+            }
+            let values = values.split('/').map(str::trim).collect::<Vec<_>>();
+            if values.iter().any(|value| value.is_empty()) {
+                return Err(invalid("missing value"));
+            }
+            if comparison.is_ordered() {
+                if values.len() > 1 {
+                    return Err(invalid(
+                        "a list of values can only be compared with = or !=",
+                    ));
+                }
+                if values[0].parse::<f64>().is_err() {
+                    return Err(invalid("only numbers can be ordered"));
+                }
+            }
+
+            selector = selector.with(key, comparison, values);
+        }
+
+        Ok(selector)
+    }
+}
+
+impl fmt::Display for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let clauses = self
+            .clauses
+            .iter()
+            .map(|clause| clause.to_string())
+            .collect::<Vec<_>>();
+        write!(f, "{}", clauses.join(","))
+    }
+}
+
+/// A value a message has for a selector key
+#[derive(Clone, Debug, PartialEq)]
+pub enum SelectorValue {
+    Number(f64),
+    Text(String),
+}
+
+impl SelectorValue {
+    /// A number when the text is one, so index keys compare by value
+    fn parse(text: &str) -> Self {
+        match text.parse() {
+            Ok(number) => SelectorValue::Number(number),
+            Err(_) => SelectorValue::Text(text.to_string()),
+        }
+    }
+
+    fn equals(&self, expected: &str) -> bool {
+        match self {
+            SelectorValue::Text(text) => text.eq_ignore_ascii_case(expected),
+            SelectorValue::Number(_) => self.compare(expected) == Some(Ordering::Equal),
+        }
+    }
+
+    fn compare(&self, expected: &str) -> Option<Ordering> {
+        let SelectorValue::Number(number) = self else {
+            return None;
+        };
+        let expected = expected.parse::<f64>().ok()?;
+        // Levels are often scaled from integers, so allow for rounding
+        if (number - expected).abs() <= 1e-9 * number.abs().max(1.0) {
+            Some(Ordering::Equal)
+        } else {
+            number.partial_cmp(&expected)
+        }
+    }
+}
+
+/// Something a [`Selector`] can be evaluated against
+pub trait Selectable {
+    /// The values this has for a lowercase selector key, empty when it does not
+    /// have the key
+    fn selector_values(&self, key: &str) -> Vec<SelectorValue>;
+}
+
+impl Selectable for MessageMetadata {
+    fn selector_values(&self, key: &str) -> Vec<SelectorValue> {
+        let surface = self.first_fixed_surface_type.clone() as u8;
+        match key {
+            "var" => vec![SelectorValue::Text(self.var.clone())],
+            "name" => vec![SelectorValue::Text(self.name.clone())],
+            "units" => vec![SelectorValue::Text(self.units.clone())],
+            "level_type" => level_type_values(surface),
+            "level" => noaa_level_value(self.edition, surface, self.first_fixed_surface_value)
+                .map(SelectorValue::Number)
+                .into_iter()
+                .collect(),
+            "step" => {
+                let valid_date = self.forecast_end_date.unwrap_or(self.forecast_date);
+                let minutes = (valid_date - self.reference_date).num_minutes();
+                vec![SelectorValue::Number(minutes as f64 / 60.0)]
+            }
+            "date" => vec![SelectorValue::parse(
+                &self.reference_date.format("%Y%m%d").to_string(),
+            )],
+            "time" => vec![SelectorValue::parse(
+                &self.reference_date.format("%H%M").to_string(),
+            )],
+            "number" => self
+                .perturbation_number
+                .map(|number| SelectorValue::Number(number as f64))
+                .into_iter()
+                .collect(),
+            "discipline" => vec![
+                SelectorValue::Number(self.discipline_value as f64),
+                SelectorValue::Text(self.discipline.clone()),
+            ],
+            "category" => vec![
+                SelectorValue::Number(self.category_value as f64),
+                SelectorValue::Text(self.category.clone()),
+            ],
+            "parameter" => vec![SelectorValue::Number(self.parameter_value as f64)],
+            "edition" => vec![SelectorValue::Number(self.edition as f64)],
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl Selectable for IndexEntry {
+    fn selector_values(&self, key: &str) -> Vec<SelectorValue> {
+        // ECMWF entries carry their MARS keys, NOAA entries only descriptions
+        let ecmwf = !self.keys.is_empty();
+        let noaa_level = self.level.as_deref().and_then(parse_noaa_level);
+
+        match key {
+            "var" => self.var.iter().cloned().map(SelectorValue::Text).collect(),
+            "level_type" if ecmwf => {
+                let Some(levtype) = self.keys.get("levtype") else {
+                    return Vec::new();
+                };
+                match ecmwf_levtype_surface(levtype) {
+                    Some(surface) => level_type_values(surface),
+                    None => vec![SelectorValue::Text(levtype.clone())],
+                }
+            }
+            "level_type" => noaa_level
+                .map(|(surface, _)| level_type_values(surface))
+                .unwrap_or_default(),
+            "level" if ecmwf => self.level.iter().map(|l| SelectorValue::parse(l)).collect(),
+            "level" => noaa_level
+                .and_then(|(_, value)| value)
+                .map(SelectorValue::Number)
+                .into_iter()
+                .collect(),
+            "step" => {
+                let hours = match self.forecast_time.as_deref() {
+                    Some(step) if ecmwf => parse_ecmwf_step_hours(step),
+                    Some(forecast_time) => parse_noaa_forecast_hours(forecast_time),
+                    None => None,
+                };
+                hours.map(SelectorValue::Number).into_iter().collect()
+            }
+            "date" => self
+                .reference_date
+                .map(|date| SelectorValue::parse(&date.format("%Y%m%d").to_string()))
+                .into_iter()
+                .collect(),
+            "time" => self
+                .reference_date
+                .map(|date| SelectorValue::parse(&date.format("%H%M").to_string()))
+                .into_iter()
+                .collect(),
+            "number" if !ecmwf => self
+                .extra
+                .iter()
+                .find_map(|extra| {
+                    let ens = extra.strip_prefix("ENS=")?;
+                    if ens.ends_with("ctl") {
+                        Some(0.0)
+                    } else {
+                        ens.trim_start_matches(['+', '-']).parse().ok()
+                    }
+                })
+                .map(SelectorValue::Number)
+                .into_iter()
+                .collect(),
+            _ => self
+                .keys
+                .get(key)
+                .map(|value| SelectorValue::parse(value))
+                .into_iter()
+                .collect(),
+        }
+    }
+}
+
+/// The names a fixed surface (code table 4.5) can be selected by
+fn level_type_values(surface: u8) -> Vec<SelectorValue> {
+    let fixed_surface_type = FixedSurfaceType::from(surface);
+    let mut values = vec![
+        SelectorValue::Number(surface as f64),
+        SelectorValue::Text(ecmwf_levtype(surface).to_string()),
+    ];
+
+    if fixed_surface_type != FixedSurfaceType::Missing {
+        values.push(SelectorValue::Text(
+            fixed_surface_type.coordinate_name().to_string(),
+        ));
+        values.push(SelectorValue::Text(fixed_surface_type.to_string()));
+    }
+
+    let type_of_level: &[&str] = match surface {
+        1 => &["surface"],
+        2 => &["cloudBase"],
+        3 => &["cloudTop"],
+        4 => &["isothermZero"],
+        5 => &["adiabaticCondensation"],
+        6 => &["maxWind"],
+        7 => &["tropopause"],
+        8 => &["nominalTop"],
+        9 => &["seaBottom"],
+        10 => &["entireAtmosphere"],
+        20 => &["isothermal"],
+        100 => &["isobaric", "isobaricInhPa", "isobaricInPa"],
+        101 => &["meanSea"],
+        102 => &["heightAboveSea"],
+        103 => &["heightAboveGround"],
+        104 => &["sigma"],
+        105 => &["hybrid"],
+        106 => &["depthBelowLand"],
+        107 => &["theta"],
+        108 => &["pressureFromGroundLayer"],
+        109 => &["potentialVorticity"],
+        111 => &["eta"],
+        114 => &["snow"],
+        160 => &["depthBelowSea"],
+        _ => &[],
+    };
+    values.extend(
+        type_of_level
+            .iter()
+            .map(|name| SelectorValue::Text(name.to_string())),
+    );
+
+    values
+}
+
+/// The fixed surface of a MARS `levtype` that has a single one
+fn ecmwf_levtype_surface(levtype: &str) -> Option<u8> {
+    match levtype {
+        "pl" => Some(100),
+        "ml" => Some(105),
+        "sol" => Some(106),
+        "pt" => Some(107),
+        "pv" => Some(109),
+        _ => None,
+    }
+}
+
+/// Hours of a MARS step such as `6`, `30m` or the range `0-6`
+fn parse_ecmwf_step_hours(step: &str) -> Option<f64> {
+    let end = step.rsplit('-').next()?;
+    match end.strip_suffix('m') {
+        Some(minutes) => minutes.parse::<f64>().ok().map(|minutes| minutes / 60.0),
+        None => end.strip_suffix('h').unwrap_or(end).parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_selector() {
+        let selector: Selector = "var=TMP/UGRD, level_type=isobaric,level>=500,step!=0"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            selector.clauses(),
+            &[
+                Clause {
+                    key: "var".to_string(),
+                    comparison: Comparison::Equal,
+                    values: vec!["TMP".to_string(), "UGRD".to_string()],
+                },
+                Clause {
+                    key: "level_type".to_string(),
+                    comparison: Comparison::Equal,
+                    values: vec!["isobaric".to_string()],
+                },
+                Clause {
+                    key: "level".to_string(),
+                    comparison: Comparison::GreaterOrEqual,
+                    values: vec!["500".to_string()],
+                },
+                Clause {
+                    key: "step".to_string(),
+                    comparison: Comparison::NotEqual,
+                    values: vec!["0".to_string()],
+                },
+            ]
+        );
+        assert_eq!(
+            selector.to_string(),
+            "var=TMP/UGRD,level_type=isobaric,level>=500,step!=0"
+        );
+
+        assert!("".parse::<Selector>().unwrap().clauses().is_empty());
+        for invalid in [
+            "var",
+            "=TMP",
+            "var=",
+            "var=TMP,",
+            "level>=a",
+            "level<1/2",
+            "var!TMP",
+        ] {
+            assert!(
+                matches!(
+                    invalid.parse::<Selector>(),
+                    Err(GribberishError::SelectorError(_))
+                ),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_clause_matches() {
+        let clause = |s: &str| s.parse::<Selector>().unwrap().clauses()[0].clone();
+        let level = [SelectorValue::Number(500.0)];
+        let var = [SelectorValue::Text("TMP".to_string())];
+
+        assert!(clause("level=500").matches(&level));
+        assert!(clause("level=850/500.0").matches(&level));
+        assert!(!clause("level!=850/500").matches(&level));
+        assert!(clause("level<=500").matches(&level));
+        assert!(!clause("level<500").matches(&level));
+        assert!(clause("level>250").matches(&level));
+        assert!(clause("var=tmp").matches(&var));
+        assert!(!clause("var>1").matches(&var));
+        // A message without the key never matches
+        assert!(!clause("level!=850").matches(&[]));
+    }
+}
@@ -0,0 +1,506 @@
+//! Spherical harmonic fields, shared by GRIB2 template 3.50 with packing 5.50
+//! or 5.51 and GRIB1 spherical harmonic data.
+//!
+//! Coefficients are stored as pairs of real numbers `Re(F_n^m), Im(F_n^m)`
+//! with `n` increasing from `m` to the truncation, first for `m = 0` and then
+//! for increasing `m` (code table 3.7). The associated Legendre functions are
+//! normalised so that the `(0, 0)` coefficient is the global mean, following
+//! the ECMWF IFS convention, and the field is
+//!
+//! `f(lat, lon) = sum_n F_n^0 P_n^0 + 2 Re(sum_m sum_n F_n^m P_n^m e^(i m lon))`
+
+use std::collections::HashMap;
+
+use num::Complex;
+
+use crate::error::GribberishError;
+use crate::utils::gaussian::gaussian_latitudes;
+use crate::utils::iter::projection::{
+    GaussianLongitudes, GaussianProjection, LatLngProjection, PlateCareeProjection,
+    RegularCoordinateIterator,
+};
+
+/// The grid a spherical harmonic field is synthesised onto. Both grids are
+/// global, with rows ordered north to south and columns starting at the prime
+/// meridian.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SpectralGrid {
+    /// A regular grid with rows from pole to pole inclusive
+    Regular {
+        latitude_count: usize,
+        longitude_count: usize,
+    },
+    /// A regular Gaussian grid with `n` parallels between a pole and the
+    /// equator and `4n` points on each parallel
+    Gaussian(usize),
+}
+
+impl SpectralGrid {
+    /// Evaluate the coefficients of a field triangularly truncated at
+    /// `truncation` on every point of the grid, returning the grid's
+    /// projection alongside the row-major values
+    pub fn synthesize(
+        &self,
+        coefficients: &[Complex<f64>],
+        truncation: usize,
+    ) -> Result<(LatLngProjection, Vec<f64>), GribberishError> {
+        let (latitudes, longitudes, projection): (Vec<f64>, Vec<f64>, _) = match *self {
+            SpectralGrid::Regular {
+                latitude_count,
+                longitude_count,
+            } => {
+                if latitude_count < 2 || longitude_count == 0 {
+                    return Err(GribberishError::MessageError(
+                        "a regular grid needs at least two rows and one column".into(),
+                    ));
+                }
+
+                let latitudes = RegularCoordinateIterator::new(
+                    90.0,
+                    -180.0 / (latitude_count - 1) as f64,
+                    latitude_count,
+                );
+                let longitudes = RegularCoordinateIterator::new(
+                    0.0,
+                    360.0 / longitude_count as f64,
+                    longitude_count,
+                );
+                let projection = LatLngProjection::PlateCaree(PlateCareeProjection {
+                    latitudes: latitudes.clone(),
+                    longitudes: longitudes.clone(),
+                    projection_name: "latlon".to_string(),
+                    projection_params: HashMap::new(),
+                });
+                (latitudes.collect(), longitudes.collect(), projection)
+            }
+            SpectralGrid::Gaussian(n) => {
+                if n == 0 {
+                    return Err(GribberishError::MessageError(
+                        "a Gaussian grid needs at least one parallel per hemisphere".into(),
+                    ));
+                }
+
+                let latitudes = gaussian_latitudes(n);
+                let longitudes = RegularCoordinateIterator::new(0.0, 90.0 / n as f64, 4 * n);
+                let projection = LatLngProjection::Gaussian(GaussianProjection {
+                    latitudes: latitudes.clone(),
+                    longitudes: GaussianLongitudes::Regular(longitudes.clone()),
+                    projection_name: "latlon".to_string(),
+                    projection_params: HashMap::new(),
+                });
+                (latitudes, longitudes.collect(), projection)
+            }
+        };
+
+        let values = synthesize(coefficients, truncation, &latitudes, &longitudes)?;
+        Ok((projection, values))
+    }
+}
+
+/// The number of real values, two per complex coefficient, of a field with
+/// pentagonal resolution parameters `j`, `k` and `m`
+pub fn coefficient_value_count(j: usize, k: usize, m: usize) -> usize {
+    (0..=m)
+        .map(|order| 2 * ((j + order).min(k) + 1).saturating_sub(order))
+        .sum()
+}
+
+/// The truncation of a triangularly truncated field with `value_count` real
+/// values, if there is one
+pub fn triangular_truncation(value_count: usize) -> Option<usize> {
+    // (T + 1) * (T + 2) = value_count
+    let truncation = (((1.0 + 4.0 * value_count as f64).sqrt() - 3.0) / 2.0).round();
+    if truncation < 0.0 {
+        return None;
+    }
+    let truncation = truncation as usize;
+    ((truncation + 1) * (truncation + 2) == value_count).then_some(truncation)
+}
+
+/// Pair up the real and imaginary parts of unpacked spectral values
+pub fn complex_coefficients(values: &[f64]) -> Vec<Complex<f64>> {
+    values
+        .chunks_exact(2)
+        .map(|pair| Complex::new(pair[0], pair[1]))
+        .collect()
+}
+
+/// Evaluate a field triangularly truncated at `truncation` at every
+/// combination of `latitudes` and `longitudes` in degrees, row by row
+pub fn synthesize(
+    coefficients: &[Complex<f64>],
+    truncation: usize,
+    latitudes: &[f64],
+    longitudes: &[f64],
+) -> Result<Vec<f64>, GribberishError> {
+    let expected = (truncation + 1) * (truncation + 2) / 2;
+    if coefficients.len() != expected {
+        return Err(GribberishError::MessageError(format!(
+            "expected {expected} coefficients for truncation T{truncation}, found {}",
+            coefficients.len()
+        )));
+    }
+
+    // e^(i m lon) for every column, reused on every row
+    let waves = longitudes
+        .iter()
+        .map(|lon| {
+            let step = Complex::new(lon.to_radians().cos(), lon.to_radians().sin());
+            std::iter::successors(Some(Complex::new(1.0, 0.0)), |w| Some(w * step))
+                .take(truncation + 1)
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut values = Vec::with_capacity(latitudes.len() * longitudes.len());
+    for latitude in latitudes {
+        let legendre = legendre_functions(truncation, latitude.to_radians().sin());
+
+        // Fourier coefficients of the row, one per order
+        let mut offset = 0;
+        let fourier = (0..=truncation)
+            .map(|order| {
+                let count = truncation - order + 1;
+                let sum = coefficients[offset..offset + count]
+                    .iter()
+                    .zip(&legendre[offset..offset + count])
+                    .map(|(c, p)| c * p)
+                    .sum::<Complex<f64>>();
+                offset += count;
+                sum
+            })
+            .collect::<Vec<_>>();
+
+        values.extend(waves.iter().map(|wave| {
+            fourier[0].re
+                + 2.0
+                    * fourier[1..]
+                        .iter()
+                        .zip(&wave[1..])
+                        .map(|(f, w)| (f * w).re)
+                        .sum::<f64>()
+        }));
+    }
+
+    Ok(values)
+}
+
+/// Rebuild the values of a complex packed field. Coefficients of the
+/// triangular subset up to `subset_truncation` are stored unpacked, the rest
+/// were packed after being multiplied by `(n (n + 1))^laplacian_operator` to
+/// flatten their spectrum. `packed` holds the rest as `R + X * 2^E`.
+pub(crate) fn expand_complex_packing(
+    truncation: usize,
+    subset_truncation: usize,
+    subset: &[f64],
+    packed: &[f64],
+    laplacian_operator: f64,
+    decimal_scale_factor: i16,
+) -> Result<Vec<f64>, GribberishError> {
+    let value_count = (truncation + 1) * (truncation + 2);
+    let subset_count = (subset_truncation + 1) * (subset_truncation + 2);
+    if subset_truncation > truncation
+        || subset.len() != subset_count
+        || packed.len() != value_count - subset_count
+    {
+        return Err(GribberishError::MessageError(format!(
+            "complex packed T{truncation} field with a T{subset_truncation} subset expects {subset_count} unpacked and {} packed values, found {} and {}",
+            value_count - subset_count,
+            subset.len(),
+            packed.len()
+        )));
+    }
+
+    let decimal_scale = 10f64.powi(-(decimal_scale_factor as i32));
+    let scales = (0..=truncation)
+        .map(|n| {
+            let operator = ((n * (n + 1)) as f64).powf(laplacian_operator);
+            if n == 0 || operator == 0.0 {
+                0.0
+            } else {
+                decimal_scale / operator
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut subset = subset.iter();
+    let mut packed = packed.iter();
+    let mut values = Vec::with_capacity(value_count);
+    for order in 0..=truncation {
+        for (n, scale) in scales.iter().enumerate().skip(order) {
+            if n <= subset_truncation {
+                values.extend(subset.by_ref().take(2));
+            } else {
+                values.extend(packed.by_ref().take(2).map(|v| v * scale));
+            }
+        }
+    }
+
+    Ok(values)
+}
+
+/// The normalised associated Legendre functions `P_n^m(mu)` for every
+/// `m <= n <= truncation`, in the order the coefficients are stored
+fn legendre_functions(truncation: usize, mu: f64) -> Vec<f64> {
+    let cos_lat = (1.0 - mu * mu).max(0.0).sqrt();
+    let mut functions = Vec::with_capacity((truncation + 1) * (truncation + 2) / 2);
+
+    let mut sectoral = 1.0;
+    for m in 0..=truncation {
+        if m > 0 {
+            let m = m as f64;
+            sectoral *= ((2.0 * m + 1.0) / (2.0 * m)).sqrt() * cos_lat;
+        }
+
+        let mut p2 = 0.0;
+        let mut p1 = sectoral;
+        functions.push(p1);
+        for n in m + 1..=truncation {
+            let (nf, mf) = (n as f64, m as f64);
+            let a = ((4.0 * nf * nf - 1.0) / (nf * nf - mf * mf)).sqrt();
+            let b = (((nf - 1.0) * (nf - 1.0) - mf * mf) / (4.0 * (nf - 1.0) * (nf - 1.0) - 1.0))
+                .sqrt();
+            let p = a * (mu * p1 - b * p2);
+            functions.push(p);
+            p2 = p1;
+            p1 = p;
+        }
+    }
+
+    functions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(truncation: usize, n: usize, m: usize) -> usize {
+        (0..m).map(|order| truncation - order + 1).sum::<usize>() + n - m
+    }
+
+    #[test]
+    fn test_value_counts() {
+        assert_eq!(coefficient_value_count(639, 639, 639), 640 * 641);
+        // Rhomboidal truncation has J + 1 coefficients for every order
+        assert_eq!(coefficient_value_count(3, 5, 2), 2 * 3 * 4);
+        assert_eq!(triangular_truncation(640 * 641), Some(639));
+        assert_eq!(triangular_truncation(2), Some(0));
+        assert_eq!(triangular_truncation(7), None);
+    }
+
+    #[test]
+    fn test_legendre_functions_match_closed_forms() {
+        let mu: f64 = 0.3;
+        let cos = (1.0 - mu * mu).sqrt();
+        let p = legendre_functions(2, mu);
+        let expected = [
+            1.0,
+            3f64.sqrt() * mu,
+            5f64.sqrt() * (3.0 * mu * mu - 1.0) / 2.0,
+            1.5f64.sqrt() * cos,
+            (15f64 / 2.0).sqrt() * mu * cos,
+            (15f64 / 8.0).sqrt() * cos * cos,
+        ];
+        for (p, e) in p.iter().zip(expected) {
+            assert!((p - e).abs() < 1e-12, "{p} != {e}");
+        }
+    }
+
+    #[test]
+    fn test_synthesize_closed_forms() {
+        let truncation = 3;
+        let mut coefficients = vec![Complex::new(0.0, 0.0); 10];
+        coefficients[index(truncation, 0, 0)] = Complex::new(288.0, 0.0);
+        coefficients[index(truncation, 1, 1)] = Complex::new(1.0, -2.0);
+
+        let latitudes = [60.0, 0.0, -30.0];
+        let longitudes = [0.0, 45.0, 200.0];
+        let values = synthesize(&coefficients, truncation, &latitudes, &longitudes).unwrap();
+        for (i, lat) in latitudes.iter().enumerate() {
+            for (j, lon) in longitudes.iter().map(|l| l.to_radians()).enumerate() {
+                let p11 = 1.5f64.sqrt() * lat.to_radians().cos();
+                let expected = 288.0 + 2.0 * p11 * (lon.cos() + 2.0 * lon.sin());
+                let value = values[i * longitudes.len() + j];
+                assert!((value - expected).abs() < 1e-9, "{value} != {expected}");
+            }
+        }
+    }
+
+    /// Gaussian quadrature of the synthesised field gives back the coefficients
+    #[test]
+    fn test_synthesis_is_orthonormal() {
+        let truncation = 5;
+        let coefficients = (0..21)
+            .map(|i| {
+                let order_zero = i <= truncation;
+                Complex::new(
+                    (i as f64 * 0.7).sin(),
+                    if order_zero { 0.0 } else { (i as f64).cos() },
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let n = 8;
+        let (projection, values) = SpectralGrid::Gaussian(n)
+            .synthesize(&coefficients, truncation)
+            .unwrap();
+        let (latitudes, longitudes) = projection.lat_lng();
+        assert_eq!(values.len(), 2 * n * 4 * n);
+
+        // Gauss-Legendre weights, which sum to 2
+        let weights = latitudes
+            .iter()
+            .map(|lat| {
+                let mu = lat.to_radians().sin();
+                let dp = legendre_derivative(2 * n, mu);
+                2.0 / ((1.0 - mu * mu) * dp * dp)
+            })
+            .collect::<Vec<_>>();
+
+        for m in 0..=truncation {
+            for degree in m..=truncation {
+                let mut sum = Complex::new(0.0, 0.0);
+                for (row, lat) in latitudes.iter().enumerate() {
+                    let p = legendre_functions(truncation, lat.to_radians().sin())
+                        [index(truncation, degree, m)];
+                    for (column, lon) in longitudes.iter().enumerate() {
+                        let value = values[row * longitudes.len() + column];
+                        let angle = -(m as f64) * lon.to_radians();
+                        sum += Complex::new(angle.cos(), angle.sin()) * value * p * weights[row];
+                    }
+                }
+                let coefficient = sum / (2.0 * longitudes.len() as f64);
+                let expected = coefficients[index(truncation, degree, m)];
+                assert!(
+                    (coefficient - expected).norm_sqr() < 1e-18,
+                    "{coefficient} != {expected}"
+                );
+            }
+        }
+    }
+
+    fn legendre_derivative(n: usize, x: f64) -> f64 {
+        let mut p0 = 1.0;
+        let mut p1 = x;
+        for k in 2..=n {
+            let k = k as f64;
+            let p2 = ((2.0 * k - 1.0) * x * p1 - (k - 1.0) * p0) / k;
+            p0 = p1;
+            p1 = p2;
+        }
+        n as f64 * (x * p1 - p0) / (x * x - 1.0)
+    }
+
+    /// Swap the packing of a built message for complex packed spectral data
+    fn with_complex_packing(message: Vec<u8>, section: Vec<u8>, payload: Vec<u8>) -> Vec<u8> {
+        let mut offset = 16;
+        while message[offset + 4] != 5 {
+            offset += u32::from_be_bytes(message[offset..offset + 4].try_into().unwrap()) as usize;
+        }
+
+        let mut encoded = message[..offset].to_vec();
+        encoded.extend(section);
+        encoded.extend([0, 0, 0, 6, 6, 255]);
+        encoded.extend((payload.len() as u32 + 5).to_be_bytes());
+        encoded.push(7);
+        encoded.extend(payload);
+        encoded.extend(b"7777");
+        let length = encoded.len() as u64;
+        encoded[8..16].copy_from_slice(&length.to_be_bytes());
+        encoded
+    }
+
+    #[test]
+    fn test_synthesize_complex_packed_message() {
+        use crate::message::Message;
+        use crate::message_builder::MessageBuilder;
+        use chrono::{TimeZone, Utc};
+
+        let mut grid = vec![0u8, 0, 0, 28, 3, 0];
+        grid.extend_from_slice(&12u32.to_be_bytes());
+        grid.extend_from_slice(&[0, 0, 0, 50]);
+        for _ in 0..3 {
+            grid.extend_from_slice(&2u32.to_be_bytes());
+        }
+        grid.extend_from_slice(&[1, 1]);
+
+        let mut product = vec![0u8, 0, 0, 34, 4, 0, 0, 0, 0];
+        product.extend_from_slice(&[0, 0, 2, 0, 96, 0, 0, 0, 1]);
+        product.extend_from_slice(&0u32.to_be_bytes());
+        product.extend_from_slice(&[100, 0]);
+        product.extend_from_slice(&50000u32.to_be_bytes());
+        product.extend_from_slice(&[255, 0, 0, 0, 0, 0]);
+
+        let built = MessageBuilder::new(0, Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap())
+            .with_grid_definition(&grid)
+            .with_product_definition(&product)
+            .build(&[0.0; 12])
+            .unwrap();
+
+        // T2 with a T1 subset, every packed coefficient is zero
+        let mut section = vec![0u8, 0, 0, 35, 5, 0, 0, 0, 12, 0, 51];
+        section.extend_from_slice(&[0; 9]);
+        section.extend_from_slice(&500_000u32.to_be_bytes());
+        section.extend_from_slice(&[0, 1, 0, 1, 0, 1, 0, 0, 0, 6, 1]);
+        let payload = [288.0f32, 0.0, 0.0, 0.0, 1.0, -2.0]
+            .iter()
+            .flat_map(|v| v.to_be_bytes())
+            .collect();
+        let encoded = with_complex_packing(built, section, payload);
+
+        let message = Message::from_data(&encoded, 0).unwrap();
+        assert!(message.is_spectral());
+        assert_eq!(message.data_template_number().unwrap(), 51);
+        let coefficients = message.spectral_coefficients().unwrap();
+        assert_eq!(coefficients.len(), 6);
+        assert_eq!(coefficients[0], Complex::new(288.0, 0.0));
+        assert_eq!(coefficients[3], Complex::new(1.0, -2.0));
+
+        let (projection, values) = message
+            .synthesize_spectral(SpectralGrid::Regular {
+                latitude_count: 5,
+                longitude_count: 8,
+            })
+            .unwrap();
+        let (latitudes, longitudes) = projection.lat_lng();
+        assert_eq!(latitudes, vec![90.0, 45.0, 0.0, -45.0, -90.0]);
+        assert_eq!(values.len(), 40);
+        for (i, lat) in latitudes.iter().enumerate() {
+            for (j, lon) in longitudes.iter().enumerate() {
+                let p11 = 1.5f64.sqrt() * lat.to_radians().cos();
+                let lon = lon.to_radians();
+                let expected = 288.0 + 2.0 * p11 * (lon.cos() + 2.0 * lon.sin());
+                let value = values[i * 8 + j];
+                assert!((value - expected).abs() < 1e-9, "{value} != {expected}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_expand_complex_packing() {
+        // T2 with a T1 subset, three coefficients are packed
+        let subset = [10.0, 0.0, 1.0, 0.0, 2.0, 3.0];
+        let packed = [4.0, 0.0, 5.0, 6.0, 7.0, 8.0];
+        let values = expand_complex_packing(2, 1, &subset, &packed, 0.5, 1).unwrap();
+        let scale = |n: f64| 0.1 / (n * (n + 1.0)).sqrt();
+        let expected = [
+            10.0,
+            0.0,
+            1.0,
+            0.0,
+            4.0 * scale(2.0),
+            0.0,
+            2.0,
+            3.0,
+            5.0 * scale(2.0),
+            6.0 * scale(2.0),
+            7.0 * scale(2.0),
+            8.0 * scale(2.0),
+        ];
+        for (v, e) in values.iter().zip(expected) {
+            assert!((v - e).abs() < 1e-12, "{v} != {e}");
+        }
+
+        assert!(expand_complex_packing(2, 1, &subset, &packed[1..], 0.5, 0).is_err());
+    }
+}
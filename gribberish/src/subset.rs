@@ -0,0 +1,89 @@
+//! Cropping GRIB2 messages to a bounding box.
+//!
+//! The crop is a window of whole rows and columns of the grid in its native
+//! projection, so a Lambert conformal grid stays a Lambert conformal grid with
+//! the same spacing and only its first point and dimensions change. See
+//! [`Message::subset`](crate::message::Message::subset).
+
+use crate::error::GribberishError;
+use crate::templates::grid_definition::tables::ScanningMode;
+use crate::utils::iter::projection::{GridWindow, LatLngProjection};
+use crate::utils::{read_u16_from_bytes, sign_magnitude_u32};
+
+/// Rewrite a grid definition section (section 3) to describe a window of its
+/// grid: the number of points, the grid dimensions and the first and last grid
+/// points. Supported for the latitude/longitude, rotated latitude/longitude,
+/// Mercator, polar stereographic, Lambert conformal and regular Gaussian
+/// templates scanned row by row.
+pub fn subset_grid_definition(
+    section: &[u8],
+    projector: &LatLngProjection,
+    window: &GridWindow,
+) -> Result<Vec<u8>, GribberishError> {
+    let template_number = read_u16_from_bytes(section, 12).unwrap_or(u16::MAX);
+    // Octets of the first and last grid points, and of the scanning mode
+    let (first_point, last_point, scanning_mode) = match template_number {
+        0 | 1 | 40 => (46, Some(55), 71),
+        10 => (38, Some(51), 59),
+        20 | 30 => (38, None, 64),
+        _ => {
+            return Err(GribberishError::GridTemplateError(format!(
+                "Cannot subset grid definition template {template_number}"
+            )))
+        }
+    };
+
+    let scanning_mode = ScanningMode::read_flags(section[scanning_mode]);
+    if scanning_mode[2] != ScanningMode::ConsecutiveI
+        || scanning_mode[3] != ScanningMode::SameDirection
+    {
+        return Err(GribberishError::GridTemplateError(
+            "Only grids scanned row by row can be subset".into(),
+        ));
+    }
+
+    // Lat/lon templates give their points in their own coordinates, rotated
+    // ones in rotated latitude and longitude, the rest in geographic ones
+    let location = |row: usize, column: usize| {
+        let (x, y) = projector.grid_point(row, column);
+        match template_number {
+            10 | 20 | 30 => projector.project_xy(x, y),
+            _ => (y, x),
+        }
+    };
+
+    let mut section = section.to_vec();
+    write_u32(&mut section, 6, window.point_count() as u32);
+    write_u32(&mut section, 30, window.column_count as u32);
+    write_u32(&mut section, 34, window.row_count as u32);
+    write_location(
+        &mut section,
+        first_point,
+        location(window.row_start, window.column_start),
+    );
+    if let Some(last_point) = last_point {
+        write_location(
+            &mut section,
+            last_point,
+            location(
+                window.row_start + window.row_count - 1,
+                window.column_start + window.column_count - 1,
+            ),
+        );
+    }
+
+    Ok(section)
+}
+
+fn write_u32(section: &mut [u8], offset: usize, value: u32) {
+    section[offset..offset + 4].copy_from_slice(&value.to_be_bytes());
+}
+
+/// Write a `(lat, lng)` as the latitude and longitude, in microdegrees, of a
+/// grid point. Longitudes are written in `[0, 360)`.
+fn write_location(section: &mut [u8], offset: usize, (lat, lng): (f64, f64)) {
+    let lat = (lat * 1e6).round() as i32;
+    let lng = (lng.rem_euclid(360.0) * 1e6).round() as u32 % 360_000_000;
+    write_u32(section, offset, sign_magnitude_u32(lat));
+    write_u32(section, offset + 4, lng);
+}
@@ -1,7 +1,10 @@
 use bitvec::prelude::*;
+use std::borrow::Cow;
 
 use super::data_representation_template::DataRepresentationTemplate;
+use super::simple_packing_template::{quantize_values, simple_packing_section_header};
 use super::tables::OriginalFieldValue;
+use crate::utils::ccsds_encoder::encode_ccsds_data;
 use crate::utils::read_f32_from_bytes;
 use crate::{
     error::GribberishError,
@@ -15,13 +18,35 @@ use crate::utils::ccsds_libaec::extract_ccsds_data;
 #[cfg(not(feature = "libaec"))]
 use crate::utils::ccsds::extract_ccsds_data;
 
-pub struct CCSDSDataRepresentationTemplate {
-    data: Vec<u8>,
+/// Options for [`CCSDSDataRepresentationTemplate::pack`]. The defaults match the
+/// preprocessed, 32 sample block, 128 block interval streams written by ecCodes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CCSDSPackingOptions {
+    /// Samples per block: 8, 16, 32 or 64
+    pub block_size: u8,
+    /// Blocks per reference sample interval, at most 4096
+    pub reference_sample_interval: u16,
+    /// The libaec flags stored in octet 22
+    pub compression_options_mask: u8,
 }
 
-impl Template for CCSDSDataRepresentationTemplate {
+impl Default for CCSDSPackingOptions {
+    fn default() -> Self {
+        CCSDSPackingOptions {
+            block_size: 32,
+            reference_sample_interval: 128,
+            compression_options_mask: 14,
+        }
+    }
+}
+
+pub struct CCSDSDataRepresentationTemplate<'a> {
+    data: Cow<'a, [u8]>,
+}
+
+impl Template for CCSDSDataRepresentationTemplate<'_> {
     fn data(&self) -> &[u8] {
-        self.data.as_slice()
+        &self.data
     }
 
     fn template_number(&self) -> u16 {
@@ -37,33 +62,63 @@ impl Template for CCSDSDataRepresentationTemplate {
     }
 }
 
-impl CCSDSDataRepresentationTemplate {
-    pub fn new(data: Vec<u8>) -> CCSDSDataRepresentationTemplate {
-        CCSDSDataRepresentationTemplate { data }
+impl<'a> CCSDSDataRepresentationTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>) -> Self {
+        CCSDSDataRepresentationTemplate { data: data.into() }
+    }
+
+    /// Pack `values` with the scale factors, returning the template and the compressed
+    /// data section payload
+    pub fn pack(
+        values: &[f64],
+        decimal_scale_factor: i16,
+        binary_scale_factor: i16,
+        options: CCSDSPackingOptions,
+    ) -> Result<(Self, Vec<u8>), GribberishError> {
+        let (reference_value, bit_count, integers) =
+            quantize_values(values, decimal_scale_factor, binary_scale_factor)?;
+        let packed = if bit_count == 0 {
+            vec![]
+        } else {
+            encode_ccsds_data(
+                &integers,
+                bit_count as usize,
+                options.block_size,
+                options.compression_options_mask,
+                options.reference_sample_interval,
+            )?
+        };
+
+        let mut data = simple_packing_section_header(
+            25,
+            42,
+            values.len(),
+            reference_value,
+            binary_scale_factor,
+            decimal_scale_factor,
+            bit_count,
+        );
+        data.push(options.compression_options_mask);
+        data.push(options.block_size);
+        data.extend(options.reference_sample_interval.to_be_bytes());
+
+        Ok((Self::new(data), packed))
     }
 
     pub fn data_point_count(&self) -> usize {
-        read_u32_from_bytes(self.data.as_slice(), 5).unwrap_or(0) as usize
+        read_u32_from_bytes(&self.data, 5).unwrap_or(0) as usize
     }
 
     pub fn reference_value(&self) -> f32 {
-        read_f32_from_bytes(self.data.as_slice(), 11).unwrap_or(0.0)
+        read_f32_from_bytes(&self.data, 11).unwrap_or(0.0)
     }
 
     pub fn binary_scale_factor(&self) -> i16 {
-        as_signed!(
-            read_u16_from_bytes(self.data.as_slice(), 15).unwrap_or(0),
-            16,
-            i16
-        )
+        as_signed!(read_u16_from_bytes(&self.data, 15).unwrap_or(0), 16, i16)
     }
 
     pub fn decimal_scale_factor(&self) -> i16 {
-        as_signed!(
-            read_u16_from_bytes(self.data.as_slice(), 17).unwrap_or(0),
-            16,
-            i16
-        )
+        as_signed!(read_u16_from_bytes(&self.data, 17).unwrap_or(0), 16, i16)
     }
 
     // Nbits
@@ -85,11 +140,11 @@ impl CCSDSDataRepresentationTemplate {
 
     // restart interval
     pub fn reference_sample_interval(&self) -> u16 {
-        read_u16_from_bytes(self.data.as_slice(), 23).unwrap_or(0)
+        read_u16_from_bytes(&self.data, 23).unwrap_or(0)
     }
 }
 
-impl DataRepresentationTemplate<f64> for CCSDSDataRepresentationTemplate {
+impl DataRepresentationTemplate<f64> for CCSDSDataRepresentationTemplate<'_> {
     fn compression_type(&self) -> String {
         "CCSDS".into()
     }
@@ -101,7 +156,16 @@ impl DataRepresentationTemplate<f64> for CCSDSDataRepresentationTemplate {
     fn unpack(&self, bits: &BitSlice<u8, Msb0>) -> Result<Vec<f64>, GribberishError> {
         let bits_per_val: usize = self.bit_count().into();
         if bits_per_val == 0 {
-            return Ok(vec![]);
+            // A constant field carries no packed data, every point is the reference value
+            let value = std::iter::once(0u32)
+                .scale_value_by(
+                    self.binary_scale_factor(),
+                    self.decimal_scale_factor(),
+                    self.reference_value(),
+                )
+                .next()
+                .unwrap_or(0.0);
+            return Ok(vec![value; self.data_point_count()]);
         }
 
         let bytes: Vec<u8> = bits.to_bitvec().into();
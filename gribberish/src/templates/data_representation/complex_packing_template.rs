@@ -1,4 +1,5 @@
 use bitvec::prelude::*;
+use std::borrow::Cow;
 
 use crate::{error::GribberishError, utils::iter::ScaleGribValueIterator};
 use itertools::izip;
@@ -13,13 +14,13 @@ use super::{
     DataRepresentationTemplate,
 };
 
-pub struct ComplexPackingDataRepresentationTemplate {
-    data: Vec<u8>,
+pub struct ComplexPackingDataRepresentationTemplate<'a> {
+    data: Cow<'a, [u8]>,
 }
 
-impl Template for ComplexPackingDataRepresentationTemplate {
+impl Template for ComplexPackingDataRepresentationTemplate<'_> {
     fn data(&self) -> &[u8] {
-        self.data.as_slice()
+        &self.data
     }
 
     fn template_number(&self) -> u16 {
@@ -35,29 +36,21 @@ impl Template for ComplexPackingDataRepresentationTemplate {
     }
 }
 
-impl ComplexPackingDataRepresentationTemplate {
-    pub fn new(data: Vec<u8>) -> ComplexPackingDataRepresentationTemplate {
-        ComplexPackingDataRepresentationTemplate { data }
+impl<'a> ComplexPackingDataRepresentationTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>) -> Self {
+        ComplexPackingDataRepresentationTemplate { data: data.into() }
     }
 
     pub fn reference_value(&self) -> f32 {
-        read_f32_from_bytes(self.data.as_slice(), 11).unwrap_or(0.0)
+        read_f32_from_bytes(&self.data, 11).unwrap_or(0.0)
     }
 
     pub fn binary_scale_factor(&self) -> i16 {
-        as_signed!(
-            read_u16_from_bytes(self.data.as_slice(), 15).unwrap_or(0),
-            16,
-            i16
-        )
+        as_signed!(read_u16_from_bytes(&self.data, 15).unwrap_or(0), 16, i16)
     }
 
     pub fn decimal_scale_factor(&self) -> i16 {
-        as_signed!(
-            read_u16_from_bytes(self.data.as_slice(), 17).unwrap_or(0),
-            16,
-            i16
-        )
+        as_signed!(read_u16_from_bytes(&self.data, 17).unwrap_or(0), 16, i16)
     }
 
     pub fn bit_count(&self) -> u8 {
@@ -77,15 +70,15 @@ impl ComplexPackingDataRepresentationTemplate {
     }
 
     pub fn primary_missing_value_substitute(&self) -> f32 {
-        read_f32_from_bytes(self.data.as_slice(), 23).unwrap_or(0.0)
+        read_f32_from_bytes(&self.data, 23).unwrap_or(0.0)
     }
 
     pub fn secondary_missing_value_substitute(&self) -> f32 {
-        read_f32_from_bytes(self.data.as_slice(), 27).unwrap_or(0.0)
+        read_f32_from_bytes(&self.data, 27).unwrap_or(0.0)
     }
 
     pub fn number_of_groups(&self) -> u32 {
-        read_u32_from_bytes(self.data.as_slice(), 31).unwrap()
+        read_u32_from_bytes(&self.data, 31).unwrap()
     }
 
     pub fn group_width_reference(&self) -> u8 {
@@ -97,7 +90,7 @@ impl ComplexPackingDataRepresentationTemplate {
     }
 
     pub fn group_length_reference(&self) -> u32 {
-        read_u32_from_bytes(self.data.as_slice(), 37).unwrap()
+        read_u32_from_bytes(&self.data, 37).unwrap()
     }
 
     pub fn group_length_increment(&self) -> u8 {
@@ -105,7 +98,7 @@ impl ComplexPackingDataRepresentationTemplate {
     }
 
     pub fn group_last_length(&self) -> u32 {
-        read_u32_from_bytes(self.data.as_slice(), 42).unwrap()
+        read_u32_from_bytes(&self.data, 42).unwrap()
     }
 
     pub fn group_length_bits(&self) -> u8 {
@@ -113,7 +106,7 @@ impl ComplexPackingDataRepresentationTemplate {
     }
 }
 
-impl DataRepresentationTemplate<f64> for ComplexPackingDataRepresentationTemplate {
+impl DataRepresentationTemplate<f64> for ComplexPackingDataRepresentationTemplate<'_> {
     fn compression_type(&self) -> String {
         "Complex Grid Packing".into()
     }
@@ -139,7 +132,7 @@ impl DataRepresentationTemplate<f64> for ComplexPackingDataRepresentationTemplat
         let n_width_bits = self.group_width_bits() as usize;
         let group_widths = (0..ng).map(|ig| {
             if n_width_bits == 0 {
-                0
+                self.group_width_reference() as u32
             } else {
                 let start = group_widths_start + ig * n_width_bits;
                 bits[start..start + n_width_bits].load::<u32>()
@@ -152,7 +145,7 @@ impl DataRepresentationTemplate<f64> for ComplexPackingDataRepresentationTemplat
         let n_length_bits = self.group_length_bits() as usize;
         let group_lengths = (0..ng).map(|ig| {
             if n_length_bits == 0 {
-                0
+                self.group_length_reference()
             } else {
                 let start = group_lengths_start + ig * n_length_bits;
                 bits[start..start + n_length_bits].load::<u32>()
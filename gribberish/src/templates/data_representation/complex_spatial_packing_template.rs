@@ -1,4 +1,5 @@
 use bitvec::prelude::*;
+use std::borrow::Cow;
 
 use std::iter;
 
@@ -7,27 +8,163 @@ use itertools::izip;
 use crate::{
     error::GribberishError,
     templates::template::{Template, TemplateType},
-    utils::{read_f32_from_bytes, read_u16_from_bytes, read_u32_from_bytes},
+    utils::{pack_bits, read_f32_from_bytes, read_u16_from_bytes, read_u32_from_bytes},
 };
 
 use super::{
+    simple_packing_template::{reference_value_at_or_below, simple_packing_section_header},
     tables::{
         GroupSplittingMethod, MissingValueManagement, OriginalFieldValue, SpatialDifferencingOrder,
     },
     DataRepresentationTemplate,
 };
 
-pub struct ComplexSpatialPackingDataRepresentationTemplate {
-    data: Vec<u8>,
+/// Values are split into runs of this many points before neighbouring runs are merged
+const GROUP_CHUNK_LENGTH: usize = 8;
+
+/// Rough cost of starting a new group: its reference, width and length descriptors
+const GROUP_OVERHEAD_BITS: u64 = 24;
+
+/// The widest group the unpacking path can read, the all-ones missing value pattern
+/// must still fit in a u32
+const MAX_GROUP_WIDTH: u32 = 31;
+
+/// Options for [`ComplexSpatialPackingDataRepresentationTemplate::pack`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ComplexSpatialPackingOptions {
+    pub spatial_differencing_order: SpatialDifferencingOrder,
+    /// When missing values are managed in the data, NaN values and values equal to the
+    /// primary substitute are packed as primary missing values, and values equal to the
+    /// secondary substitute as secondary missing values. Otherwise missing values must be
+    /// carried by the bitmap.
+    pub missing_value_management: MissingValueManagement,
+    pub primary_missing_value_substitute: f32,
+    pub secondary_missing_value_substitute: f32,
 }
 
-impl Template for ComplexSpatialPackingDataRepresentationTemplate {
+impl Default for ComplexSpatialPackingOptions {
+    fn default() -> Self {
+        ComplexSpatialPackingOptions {
+            spatial_differencing_order: SpatialDifferencingOrder::Second,
+            missing_value_management: MissingValueManagement::NoMissingValues,
+            primary_missing_value_substitute: 9.999e20,
+            secondary_missing_value_substitute: 9.999e20,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PackedPoint {
+    Value(u32),
+    PrimaryMissing,
+    SecondaryMissing,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct PackingGroup {
+    start: usize,
+    length: usize,
+    min: u32,
+    max: u32,
+    has_value: bool,
+    has_primary: bool,
+    has_secondary: bool,
+}
+
+impl PackingGroup {
+    fn new(start: usize, points: &[PackedPoint]) -> Self {
+        let mut group = PackingGroup {
+            start,
+            length: points.len(),
+            min: u32::MAX,
+            max: 0,
+            has_value: false,
+            has_primary: false,
+            has_secondary: false,
+        };
+
+        for point in points {
+            match point {
+                PackedPoint::Value(v) => {
+                    group.has_value = true;
+                    group.min = group.min.min(*v);
+                    group.max = group.max.max(*v);
+                }
+                PackedPoint::PrimaryMissing => group.has_primary = true,
+                PackedPoint::SecondaryMissing => group.has_secondary = true,
+            }
+        }
+
+        group
+    }
+
+    fn merge(&self, other: &PackingGroup) -> PackingGroup {
+        PackingGroup {
+            start: self.start,
+            length: self.length + other.length,
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+            has_value: self.has_value || other.has_value,
+            has_primary: self.has_primary || other.has_primary,
+            has_secondary: self.has_secondary || other.has_secondary,
+        }
+    }
+
+    /// The reference for groups holding at least one value, missing only groups get
+    /// their reference once the reference bit count is known
+    fn reference(&self) -> u32 {
+        if self.has_value {
+            self.min
+        } else {
+            0
+        }
+    }
+
+    /// `reserved` is the number of all-ones patterns set aside for missing values
+    fn width(&self, reserved: u32) -> u32 {
+        let has_missing = self.has_primary || self.has_secondary;
+        if !self.has_value {
+            if self.has_primary && self.has_secondary {
+                bit_width(2)
+            } else {
+                0
+            }
+        } else if self.min == self.max && !has_missing {
+            0
+        } else {
+            bit_width((self.max - self.min) as u64 + reserved as u64)
+        }
+    }
+
+    fn cost(&self, reserved: u32) -> u64 {
+        self.width(reserved) as u64 * self.length as u64
+    }
+}
+
+fn bit_width(value: u64) -> u32 {
+    u64::BITS - value.leading_zeros()
+}
+
+fn write_bits(bits: &mut BitVec<u8, Msb0>, value: u32, width: usize) {
+    if width == 0 {
+        return;
+    }
+    let start = bits.len();
+    bits.resize(start + width, false);
+    bits[start..].store_be(value);
+}
+
+pub struct ComplexSpatialPackingDataRepresentationTemplate<'a> {
+    data: Cow<'a, [u8]>,
+}
+
+impl Template for ComplexSpatialPackingDataRepresentationTemplate<'_> {
     fn data(&self) -> &[u8] {
-        self.data.as_slice()
+        &self.data
     }
 
     fn template_number(&self) -> u16 {
-        2
+        3
     }
 
     fn template_type(&self) -> TemplateType {
@@ -39,29 +176,276 @@ impl Template for ComplexSpatialPackingDataRepresentationTemplate {
     }
 }
 
-impl ComplexSpatialPackingDataRepresentationTemplate {
-    pub fn new(data: Vec<u8>) -> ComplexSpatialPackingDataRepresentationTemplate {
-        ComplexSpatialPackingDataRepresentationTemplate { data }
+impl<'a> ComplexSpatialPackingDataRepresentationTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>) -> Self {
+        ComplexSpatialPackingDataRepresentationTemplate { data: data.into() }
+    }
+
+    /// Pack `values` with complex packing and spatial differencing, returning the full
+    /// data representation section alongside the packed data section payload.
+    ///
+    /// The scaled integers are differenced, then split into groups by merging short runs
+    /// of points whenever a shared reference and width is cheaper than separate groups.
+    pub fn pack(
+        values: &[f64],
+        decimal_scale_factor: i16,
+        binary_scale_factor: i16,
+        options: ComplexSpatialPackingOptions,
+    ) -> Result<(Self, Vec<u8>), GribberishError> {
+        let mvm = options.missing_value_management;
+        let is_primary =
+            |v: f64| v.is_nan() || v as f32 == options.primary_missing_value_substitute;
+        let is_secondary = |v: f64| v as f32 == options.secondary_missing_value_substitute;
+        let is_missing = |v: f64| match mvm {
+            MissingValueManagement::NoMissingValues => false,
+            MissingValueManagement::IncludesMissingPrimary => is_primary(v),
+            MissingValueManagement::IncludesMissingPrimarySecondary => {
+                is_primary(v) || is_secondary(v)
+            }
+        };
+
+        if values.iter().any(|v| !is_missing(*v) && !v.is_finite()) {
+            return Err(GribberishError::EncodingError(
+                "complex packing requires finite values, enable missing value management or use a bitmap".into(),
+            ));
+        }
+
+        let decimal_scale = 10_f64.powi(decimal_scale_factor as i32);
+        let binary_scale = 2_f64.powi(-(binary_scale_factor as i32));
+        let scaled = values
+            .iter()
+            .filter(|v| !is_missing(**v))
+            .map(|v| v * decimal_scale)
+            .collect::<Vec<_>>();
+        let min = scaled.iter().copied().fold(f64::INFINITY, f64::min);
+        let reference_value = if scaled.is_empty() {
+            0.0
+        } else {
+            reference_value_at_or_below(min)
+        };
+        let integers = scaled
+            .iter()
+            .map(|v| ((v - reference_value as f64) * binary_scale).round() as i64)
+            .collect::<Vec<_>>();
+
+        // Difference the defined values, the leading values are carried in the
+        // extra descriptors instead of the groups
+        let order = options.spatial_differencing_order;
+        let leading = match order {
+            SpatialDifferencingOrder::First => 1,
+            SpatialDifferencingOrder::Second => 2,
+        };
+        let differences = integers
+            .iter()
+            .enumerate()
+            .map(|(i, x)| match order {
+                _ if i < leading => None,
+                SpatialDifferencingOrder::First => Some(x - integers[i - 1]),
+                SpatialDifferencingOrder::Second => Some(x - 2 * integers[i - 1] + integers[i - 2]),
+            })
+            .collect::<Vec<_>>();
+        let minimum_difference = differences.iter().flatten().copied().min().unwrap_or(0);
+        let first_value = integers.first().copied().unwrap_or(0);
+        let second_value = if order == SpatialDifferencingOrder::Second {
+            integers.get(1).copied().unwrap_or(0)
+        } else {
+            0
+        };
+
+        let mut differences = differences.into_iter();
+        let points = values
+            .iter()
+            .map(|v| {
+                if is_missing(*v) {
+                    if mvm == MissingValueManagement::IncludesMissingPrimarySecondary
+                        && !is_primary(*v)
+                    {
+                        return Ok(PackedPoint::SecondaryMissing);
+                    }
+                    return Ok(PackedPoint::PrimaryMissing);
+                }
+
+                let difference = differences.next().flatten().unwrap_or(minimum_difference);
+                let value = difference - minimum_difference;
+                if bit_width(value as u64) > MAX_GROUP_WIDTH - 1 {
+                    return Err(GribberishError::EncodingError(
+                        "scaled differences are too large to pack, increase the binary scale factor or reduce the decimal scale factor".into(),
+                    ));
+                }
+                Ok(PackedPoint::Value(value as u32))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let reserved = match mvm {
+            MissingValueManagement::NoMissingValues => 0,
+            MissingValueManagement::IncludesMissingPrimary => 1,
+            MissingValueManagement::IncludesMissingPrimarySecondary => 2,
+        };
+
+        let mut groups: Vec<PackingGroup> = Vec::new();
+        for (i, chunk) in points.chunks(GROUP_CHUNK_LENGTH).enumerate() {
+            let chunk = PackingGroup::new(i * GROUP_CHUNK_LENGTH, chunk);
+            match groups.last_mut() {
+                Some(current) => {
+                    let merged = current.merge(&chunk);
+                    if merged.cost(reserved)
+                        <= current.cost(reserved) + chunk.cost(reserved) + GROUP_OVERHEAD_BITS
+                    {
+                        *current = merged;
+                    } else {
+                        groups.push(chunk);
+                    }
+                }
+                None => groups.push(chunk),
+            }
+        }
+        if groups.is_empty() {
+            groups.push(PackingGroup::new(0, &[]));
+        }
+
+        // The all-ones reference marks a group where every point is missing, so valid
+        // references must stay below it
+        let max_reference = groups.iter().map(|g| g.reference()).max().unwrap_or(0);
+        let reference_bits = bit_width(max_reference as u64 + reserved as u64);
+        let reference_all_ones = if reference_bits == 0 {
+            0
+        } else {
+            ((1u64 << reference_bits) - 1) as u32
+        };
+        let references = groups
+            .iter()
+            .map(|g| match g {
+                g if g.has_value || (g.has_primary && g.has_secondary) => g.reference(),
+                g if g.has_secondary => reference_all_ones - 1,
+                _ => reference_all_ones,
+            })
+            .collect::<Vec<_>>();
+
+        let widths = groups.iter().map(|g| g.width(reserved)).collect::<Vec<_>>();
+        if widths.iter().any(|w| *w > MAX_GROUP_WIDTH) {
+            return Err(GribberishError::EncodingError(
+                "group width exceeds 31 bits".into(),
+            ));
+        }
+        let width_reference = widths.iter().copied().min().unwrap_or(0);
+        let width_bits = bit_width(
+            widths
+                .iter()
+                .map(|w| w - width_reference)
+                .max()
+                .unwrap_or(0) as u64,
+        );
+
+        let lengths = groups.iter().map(|g| g.length as u32).collect::<Vec<_>>();
+        let length_reference = lengths.iter().copied().min().unwrap_or(0);
+        let length_bits = bit_width(
+            lengths
+                .iter()
+                .map(|l| l - length_reference)
+                .max()
+                .unwrap_or(0) as u64,
+        );
+
+        let max_descriptor = [first_value, second_value, minimum_difference]
+            .iter()
+            .map(|v| v.unsigned_abs())
+            .max()
+            .unwrap_or(0);
+        let descriptor_octets = (bit_width(max_descriptor) as usize + 1).div_ceil(8).max(1);
+        if descriptor_octets > 4 {
+            return Err(GribberishError::EncodingError(
+                "spatial differencing descriptors exceed 4 octets".into(),
+            ));
+        }
+
+        let mut packed: BitVec<u8, Msb0> = BitVec::new();
+        let descriptor_bits = descriptor_octets * 8;
+        let descriptors = match order {
+            SpatialDifferencingOrder::First => vec![first_value, minimum_difference],
+            SpatialDifferencingOrder::Second => {
+                vec![first_value, second_value, minimum_difference]
+            }
+        };
+        for descriptor in descriptors {
+            let sign = if descriptor < 0 {
+                1u32 << (descriptor_bits - 1)
+            } else {
+                0
+            };
+            write_bits(
+                &mut packed,
+                descriptor.unsigned_abs() as u32 | sign,
+                descriptor_bits,
+            );
+        }
+
+        let mut packed = packed.into_vec();
+        packed.extend(pack_bits(
+            references.iter().copied(),
+            reference_bits as usize,
+        ));
+        packed.extend(pack_bits(
+            widths.iter().map(|w| w - width_reference),
+            width_bits as usize,
+        ));
+        packed.extend(pack_bits(
+            lengths.iter().map(|l| l - length_reference),
+            length_bits as usize,
+        ));
+
+        let mut group_values: BitVec<u8, Msb0> = BitVec::new();
+        for ((group, reference), width) in groups.iter().zip(references.iter()).zip(widths.iter()) {
+            if *width == 0 {
+                continue;
+            }
+            let all_ones = ((1u64 << width) - 1) as u32;
+            for point in &points[group.start..group.start + group.length] {
+                let value = match point {
+                    PackedPoint::Value(v) => v - reference,
+                    PackedPoint::PrimaryMissing => all_ones,
+                    PackedPoint::SecondaryMissing => all_ones - 1,
+                };
+                write_bits(&mut group_values, value, *width as usize);
+            }
+        }
+        packed.extend(group_values.into_vec());
+
+        let mut data = simple_packing_section_header(
+            49,
+            3,
+            values.len(),
+            reference_value,
+            binary_scale_factor,
+            decimal_scale_factor,
+            reference_bits as u8,
+        );
+        data.push(GroupSplittingMethod::GeneralGroup as u8);
+        data.push(mvm as u8);
+        data.extend_from_slice(&options.primary_missing_value_substitute.to_be_bytes());
+        data.extend_from_slice(&options.secondary_missing_value_substitute.to_be_bytes());
+        data.extend_from_slice(&(groups.len() as u32).to_be_bytes());
+        data.push(width_reference as u8);
+        data.push(width_bits as u8);
+        data.extend_from_slice(&length_reference.to_be_bytes());
+        data.push(1);
+        data.extend_from_slice(&lengths.last().copied().unwrap_or(0).to_be_bytes());
+        data.push(length_bits as u8);
+        data.push(order as u8);
+        data.push(descriptor_octets as u8);
+
+        Ok((Self::new(data), packed))
     }
 
     pub fn reference_value(&self) -> f32 {
-        read_f32_from_bytes(self.data.as_slice(), 11).unwrap_or(0.0)
+        read_f32_from_bytes(&self.data, 11).unwrap_or(0.0)
     }
 
     pub fn binary_scale_factor(&self) -> i16 {
-        as_signed!(
-            read_u16_from_bytes(self.data.as_slice(), 15).unwrap_or(0),
-            16,
-            i16
-        )
+        as_signed!(read_u16_from_bytes(&self.data, 15).unwrap_or(0), 16, i16)
     }
 
     pub fn decimal_scale_factor(&self) -> i16 {
-        as_signed!(
-            read_u16_from_bytes(self.data.as_slice(), 17).unwrap_or(0),
-            16,
-            i16
-        )
+        as_signed!(read_u16_from_bytes(&self.data, 17).unwrap_or(0), 16, i16)
     }
 
     pub fn bit_count(&self) -> u8 {
@@ -81,15 +465,15 @@ impl ComplexSpatialPackingDataRepresentationTemplate {
     }
 
     pub fn primary_missing_value_substitute(&self) -> f32 {
-        read_f32_from_bytes(self.data.as_slice(), 23).unwrap_or(0.0)
+        read_f32_from_bytes(&self.data, 23).unwrap_or(0.0)
     }
 
     pub fn secondary_missing_value_substitute(&self) -> f32 {
-        read_f32_from_bytes(self.data.as_slice(), 27).unwrap_or(0.0)
+        read_f32_from_bytes(&self.data, 27).unwrap_or(0.0)
     }
 
     pub fn number_of_groups(&self) -> u32 {
-        read_u32_from_bytes(self.data.as_slice(), 31).unwrap()
+        read_u32_from_bytes(&self.data, 31).unwrap()
     }
 
     pub fn group_width_reference(&self) -> u8 {
@@ -101,7 +485,7 @@ impl ComplexSpatialPackingDataRepresentationTemplate {
     }
 
     pub fn group_length_reference(&self) -> u32 {
-        read_u32_from_bytes(self.data.as_slice(), 37).unwrap()
+        read_u32_from_bytes(&self.data, 37).unwrap()
     }
 
     pub fn group_length_increment(&self) -> u8 {
@@ -109,7 +493,7 @@ impl ComplexSpatialPackingDataRepresentationTemplate {
     }
 
     pub fn group_last_length(&self) -> u32 {
-        read_u32_from_bytes(self.data.as_slice(), 42).unwrap()
+        read_u32_from_bytes(&self.data, 42).unwrap()
     }
 
     pub fn group_length_bits(&self) -> u8 {
@@ -125,7 +509,7 @@ impl ComplexSpatialPackingDataRepresentationTemplate {
     }
 }
 
-impl DataRepresentationTemplate<f64> for ComplexSpatialPackingDataRepresentationTemplate {
+impl DataRepresentationTemplate<f64> for ComplexSpatialPackingDataRepresentationTemplate<'_> {
     fn compression_type(&self) -> String {
         "Complex Grid Packing with Spatial Differencing".into()
     }
@@ -172,7 +556,7 @@ impl DataRepresentationTemplate<f64> for ComplexSpatialPackingDataRepresentation
 
         let group_widths = (0..ng).map(|ig| {
             if n_width_bits == 0 {
-                0
+                self.group_width_reference() as u32
             } else {
                 let start = group_widths_start + ig * n_width_bits;
                 let value = bits[start..start + n_width_bits].load_be::<u32>();
@@ -186,7 +570,7 @@ impl DataRepresentationTemplate<f64> for ComplexSpatialPackingDataRepresentation
         let group_lengths = (0..ng - 1)
             .map(|ig| {
                 if n_length_bits == 0 {
-                    0
+                    self.group_length_reference()
                 } else {
                     let start = group_lengths_start + ig * n_length_bits;
                     let value = bits[start..start + n_length_bits].load_be::<u32>();
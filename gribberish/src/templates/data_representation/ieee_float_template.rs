@@ -0,0 +1,187 @@
+use bitvec::prelude::*;
+use std::borrow::Cow;
+
+use super::data_representation_template::DataRepresentationTemplate;
+use super::tables::FloatingPointPrecision;
+use crate::error::GribberishError;
+use crate::templates::template::{Template, TemplateType};
+use crate::utils::read_u32_from_bytes;
+
+/// Template 5.4. Values are stored unpacked as big-endian IEEE floating point
+/// numbers, so there is no reference value or scaling to apply.
+pub struct IEEEFloatDataRepresentationTemplate<'a> {
+    data: Cow<'a, [u8]>,
+}
+
+impl Template for IEEEFloatDataRepresentationTemplate<'_> {
+    fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn template_number(&self) -> u16 {
+        4
+    }
+
+    fn template_type(&self) -> TemplateType {
+        TemplateType::DataRepresentation
+    }
+
+    fn template_name(&self) -> &str {
+        "grid point data - IEEE floating point data"
+    }
+}
+
+impl<'a> IEEEFloatDataRepresentationTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>) -> Self {
+        IEEEFloatDataRepresentationTemplate { data: data.into() }
+    }
+
+    /// Write `values` as IEEE floating point numbers of the given precision, returning the
+    /// full data representation section alongside the data section payload.
+    ///
+    /// 64 bit precision is lossless, 32 bit precision rounds each value to the nearest `f32`.
+    /// `values` must not contain missing values, those are expected to be carried by the bitmap.
+    pub fn pack(
+        values: &[f64],
+        precision: FloatingPointPrecision,
+    ) -> Result<(Self, Vec<u8>), GribberishError> {
+        if values.iter().any(|v| v.is_nan()) {
+            return Err(GribberishError::EncodingError(
+                "packing requires values that are not NaN, missing values belong in the bitmap"
+                    .into(),
+            ));
+        }
+
+        let packed = match precision {
+            FloatingPointPrecision::IEEE32Bit => values
+                .iter()
+                .flat_map(|v| (*v as f32).to_be_bytes())
+                .collect(),
+            FloatingPointPrecision::IEEE64Bit => {
+                values.iter().flat_map(|v| v.to_be_bytes()).collect()
+            }
+            FloatingPointPrecision::IEEE128Bit => {
+                return Err(GribberishError::EncodingError(
+                    "IEEE 128 bit precision is not supported".into(),
+                ))
+            }
+        };
+
+        let mut data = Vec::with_capacity(12);
+        data.extend_from_slice(&12u32.to_be_bytes());
+        data.push(5);
+        data.extend_from_slice(&(values.len() as u32).to_be_bytes());
+        data.extend_from_slice(&4u16.to_be_bytes());
+        data.push(precision as u8);
+
+        Ok((Self::new(data), packed))
+    }
+
+    pub fn data_point_count(&self) -> usize {
+        read_u32_from_bytes(&self.data, 5).unwrap_or(0) as usize
+    }
+
+    pub fn precision(&self) -> FloatingPointPrecision {
+        self.data[11].into()
+    }
+}
+
+impl DataRepresentationTemplate<f64> for IEEEFloatDataRepresentationTemplate<'_> {
+    fn compression_type(&self) -> String {
+        "IEEE Floating Point".into()
+    }
+
+    fn bit_count_per_datapoint(&self) -> usize {
+        match self.precision() {
+            FloatingPointPrecision::IEEE32Bit => 32,
+            FloatingPointPrecision::IEEE64Bit => 64,
+            FloatingPointPrecision::IEEE128Bit => 128,
+        }
+    }
+
+    fn unpack(&self, bits: &BitSlice<u8, Msb0>) -> Result<Vec<f64>, GribberishError> {
+        let count = self.data_point_count();
+
+        let values = match self.precision() {
+            FloatingPointPrecision::IEEE32Bit => bits
+                .chunks_exact(32)
+                .take(count)
+                .map(|b| f32::from_bits(b.load_be::<u32>()) as f64)
+                .collect::<Vec<_>>(),
+            FloatingPointPrecision::IEEE64Bit => bits
+                .chunks_exact(64)
+                .take(count)
+                .map(|b| f64::from_bits(b.load_be::<u64>()))
+                .collect::<Vec<_>>(),
+            FloatingPointPrecision::IEEE128Bit => {
+                return Err(GribberishError::MessageError(
+                    "IEEE 128 bit floating point data is not supported".into(),
+                ))
+            }
+        };
+
+        if values.len() < count {
+            return Err(GribberishError::MessageError(format!(
+                "expected {count} IEEE floating point values, found {}",
+                values.len()
+            )));
+        }
+
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ieee32_roundtrip() {
+        let values = [1.5, -273.15, 0.0, 1.0e-7, f64::INFINITY];
+        let (template, packed) =
+            IEEEFloatDataRepresentationTemplate::pack(&values, FloatingPointPrecision::IEEE32Bit)
+                .unwrap();
+        assert_eq!(template.data().len(), 12);
+        assert_eq!(template.data_point_count(), 5);
+        assert_eq!(template.precision(), FloatingPointPrecision::IEEE32Bit);
+        assert_eq!(template.bit_count_per_datapoint(), 32);
+        assert_eq!(packed.len(), 20);
+        assert_eq!(&packed[0..4], &[0x3F, 0xC0, 0x00, 0x00]);
+
+        let decoded = template.unpack(packed.view_bits()).unwrap();
+        let expected = values.iter().map(|v| *v as f32 as f64).collect::<Vec<_>>();
+        assert_eq!(decoded, expected);
+    }
+
+    #[test]
+    fn test_ieee64_is_lossless() {
+        let values = [std::f64::consts::PI, -1.0e300, 5.0e-324, 101325.0];
+        let (template, packed) =
+            IEEEFloatDataRepresentationTemplate::pack(&values, FloatingPointPrecision::IEEE64Bit)
+                .unwrap();
+        assert_eq!(template.bit_count_per_datapoint(), 64);
+        assert_eq!(packed.len(), 32);
+        assert_eq!(template.unpack(packed.view_bits()).unwrap(), values);
+    }
+
+    #[test]
+    fn test_rejects_unsupported_data() {
+        assert!(IEEEFloatDataRepresentationTemplate::pack(
+            &[1.0],
+            FloatingPointPrecision::IEEE128Bit
+        )
+        .is_err());
+        assert!(IEEEFloatDataRepresentationTemplate::pack(
+            &[f64::NAN],
+            FloatingPointPrecision::IEEE32Bit
+        )
+        .is_err());
+
+        let (template, packed) = IEEEFloatDataRepresentationTemplate::pack(
+            &[1.0, 2.0],
+            FloatingPointPrecision::IEEE32Bit,
+        )
+        .unwrap();
+        assert!(template.unpack(packed[..6].view_bits()).is_err());
+    }
+}
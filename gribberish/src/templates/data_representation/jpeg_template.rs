@@ -1,4 +1,5 @@
 use bitvec::prelude::*;
+use std::borrow::Cow;
 
 use super::data_representation_template::DataRepresentationTemplate;
 use super::tables::{CompressionType, OriginalFieldValue};
@@ -9,13 +10,13 @@ use crate::{
     utils::{extract_jpeg_data, iter::ScaleGribValueIterator, read_u16_from_bytes},
 };
 
-pub struct JPEGDataRepresentationTemplate {
-    data: Vec<u8>,
+pub struct JPEGDataRepresentationTemplate<'a> {
+    data: Cow<'a, [u8]>,
 }
 
-impl Template for JPEGDataRepresentationTemplate {
+impl Template for JPEGDataRepresentationTemplate<'_> {
     fn data(&self) -> &[u8] {
-        self.data.as_slice()
+        &self.data
     }
 
     fn template_number(&self) -> u16 {
@@ -31,29 +32,21 @@ impl Template for JPEGDataRepresentationTemplate {
     }
 }
 
-impl JPEGDataRepresentationTemplate {
-    pub fn new(data: Vec<u8>) -> JPEGDataRepresentationTemplate {
-        JPEGDataRepresentationTemplate { data }
+impl<'a> JPEGDataRepresentationTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>) -> Self {
+        JPEGDataRepresentationTemplate { data: data.into() }
     }
 
     pub fn reference_value(&self) -> f32 {
-        read_f32_from_bytes(self.data.as_slice(), 11).unwrap_or(0.0)
+        read_f32_from_bytes(&self.data, 11).unwrap_or(0.0)
     }
 
     pub fn binary_scale_factor(&self) -> i16 {
-        as_signed!(
-            read_u16_from_bytes(self.data.as_slice(), 15).unwrap_or(0),
-            16,
-            i16
-        )
+        as_signed!(read_u16_from_bytes(&self.data, 15).unwrap_or(0), 16, i16)
     }
 
     pub fn decimal_scale_factor(&self) -> i16 {
-        as_signed!(
-            read_u16_from_bytes(self.data.as_slice(), 17).unwrap_or(0),
-            16,
-            i16
-        )
+        as_signed!(read_u16_from_bytes(&self.data, 17).unwrap_or(0), 16, i16)
     }
 
     pub fn bit_count(&self) -> u8 {
@@ -73,7 +66,7 @@ impl JPEGDataRepresentationTemplate {
     }
 }
 
-impl DataRepresentationTemplate<f64> for JPEGDataRepresentationTemplate {
+impl DataRepresentationTemplate<f64> for JPEGDataRepresentationTemplate<'_> {
     fn compression_type(&self) -> String {
         "JPEG2000".into()
     }
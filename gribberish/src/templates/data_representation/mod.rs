@@ -2,18 +2,26 @@ pub mod ccsds_template;
 pub mod complex_packing_template;
 pub mod complex_spatial_packing_template;
 pub mod data_representation_template;
+pub mod ieee_float_template;
 #[cfg(feature = "jpeg")]
 pub mod jpeg_template;
 pub mod simple_packing_template;
+pub mod spectral_complex_template;
+pub mod spectral_simple_template;
 pub mod tables;
 
 #[cfg(feature = "png")]
 pub mod png_template;
 
 pub use complex_packing_template::ComplexPackingDataRepresentationTemplate;
-pub use complex_spatial_packing_template::ComplexSpatialPackingDataRepresentationTemplate;
+pub use complex_spatial_packing_template::{
+    ComplexSpatialPackingDataRepresentationTemplate, ComplexSpatialPackingOptions,
+};
 pub use data_representation_template::DataRepresentationTemplate;
+pub use ieee_float_template::IEEEFloatDataRepresentationTemplate;
 pub use simple_packing_template::SimplePackingDataRepresentationTemplate;
+pub use spectral_complex_template::SpectralComplexDataRepresentationTemplate;
+pub use spectral_simple_template::SpectralSimpleDataRepresentationTemplate;
 
 #[cfg(feature = "jpeg")]
 pub use jpeg_template::JPEGDataRepresentationTemplate;
@@ -21,4 +29,4 @@ pub use jpeg_template::JPEGDataRepresentationTemplate;
 #[cfg(feature = "png")]
 pub use png_template::PNGDataRepresentationTemplate;
 
-pub use ccsds_template::CCSDSDataRepresentationTemplate;
+pub use ccsds_template::{CCSDSDataRepresentationTemplate, CCSDSPackingOptions};
@@ -1,4 +1,5 @@
 use bitvec::prelude::*;
+use std::borrow::Cow;
 
 use super::{tables::OriginalFieldValue, DataRepresentationTemplate};
 use crate::{
@@ -8,13 +9,13 @@ use crate::{
 };
 use png::Decoder;
 
-pub struct PNGDataRepresentationTemplate {
-    data: Vec<u8>,
+pub struct PNGDataRepresentationTemplate<'a> {
+    data: Cow<'a, [u8]>,
 }
 
-impl Template for PNGDataRepresentationTemplate {
+impl Template for PNGDataRepresentationTemplate<'_> {
     fn data(&self) -> &[u8] {
-        self.data.as_slice()
+        &self.data
     }
 
     fn template_number(&self) -> u16 {
@@ -30,29 +31,21 @@ impl Template for PNGDataRepresentationTemplate {
     }
 }
 
-impl PNGDataRepresentationTemplate {
-    pub fn new(data: Vec<u8>) -> PNGDataRepresentationTemplate {
-        PNGDataRepresentationTemplate { data }
+impl<'a> PNGDataRepresentationTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>) -> Self {
+        PNGDataRepresentationTemplate { data: data.into() }
     }
 
     pub fn reference_value(&self) -> f32 {
-        read_f32_from_bytes(self.data.as_slice(), 11).unwrap_or(0.0)
+        read_f32_from_bytes(&self.data, 11).unwrap_or(0.0)
     }
 
     pub fn binary_scale_factor(&self) -> i16 {
-        as_signed!(
-            read_u16_from_bytes(self.data.as_slice(), 15).unwrap_or(0),
-            16,
-            i16
-        )
+        as_signed!(read_u16_from_bytes(&self.data, 15).unwrap_or(0), 16, i16)
     }
 
     pub fn decimal_scale_factor(&self) -> i16 {
-        as_signed!(
-            read_u16_from_bytes(self.data.as_slice(), 17).unwrap_or(0),
-            16,
-            i16
-        )
+        as_signed!(read_u16_from_bytes(&self.data, 17).unwrap_or(0), 16, i16)
     }
 
     pub fn bit_count(&self) -> u8 {
@@ -64,7 +57,7 @@ impl PNGDataRepresentationTemplate {
     }
 }
 
-impl DataRepresentationTemplate<f64> for PNGDataRepresentationTemplate {
+impl DataRepresentationTemplate<f64> for PNGDataRepresentationTemplate<'_> {
     fn compression_type(&self) -> String {
         "PNG".into()
     }
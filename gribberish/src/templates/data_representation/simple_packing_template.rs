@@ -1,21 +1,25 @@
 use bitvec::prelude::*;
+use std::borrow::Cow;
 
 use super::data_representation_template::DataRepresentationTemplate;
 use super::tables::OriginalFieldValue;
 use crate::error::GribberishError;
-use crate::utils::read_f32_from_bytes;
+use crate::utils::{pack_bits, read_f32_from_bytes, read_u32_from_bytes, sign_magnitude_u16};
 use crate::{
     templates::template::{Template, TemplateType},
     utils::{iter::ScaleGribValueIterator, read_u16_from_bytes},
 };
 
-pub struct SimplePackingDataRepresentationTemplate {
-    data: Vec<u8>,
+/// The largest bit width the unpacking path can load into a single value
+const MAX_BITS_PER_VALUE: u32 = 32;
+
+pub struct SimplePackingDataRepresentationTemplate<'a> {
+    data: Cow<'a, [u8]>,
 }
 
-impl Template for SimplePackingDataRepresentationTemplate {
+impl Template for SimplePackingDataRepresentationTemplate<'_> {
     fn data(&self) -> &[u8] {
-        self.data.as_slice()
+        &self.data
     }
 
     fn template_number(&self) -> u16 {
@@ -31,29 +35,53 @@ impl Template for SimplePackingDataRepresentationTemplate {
     }
 }
 
-impl SimplePackingDataRepresentationTemplate {
-    pub fn new(data: Vec<u8>) -> SimplePackingDataRepresentationTemplate {
-        SimplePackingDataRepresentationTemplate { data }
+impl<'a> SimplePackingDataRepresentationTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>) -> Self {
+        SimplePackingDataRepresentationTemplate { data: data.into() }
+    }
+
+    /// Pack `values` with simple packing so that `Y * 10^D = R + X * 2^E`, returning the
+    /// full data representation section alongside the packed data section payload.
+    ///
+    /// `values` must not contain missing values, those are expected to be carried
+    /// by the bitmap. The number of bits per value is the smallest that can hold the
+    /// scaled range of the field, a constant field is packed with zero bits.
+    pub fn pack(
+        values: &[f64],
+        decimal_scale_factor: i16,
+        binary_scale_factor: i16,
+    ) -> Result<(Self, Vec<u8>), GribberishError> {
+        let (reference_value, bit_count, integers) =
+            quantize_values(values, decimal_scale_factor, binary_scale_factor)?;
+        let packed = pack_bits(integers, bit_count as usize);
+
+        let data = simple_packing_section_header(
+            21,
+            0,
+            values.len(),
+            reference_value,
+            binary_scale_factor,
+            decimal_scale_factor,
+            bit_count,
+        );
+
+        Ok((Self::new(data), packed))
+    }
+
+    pub fn data_point_count(&self) -> usize {
+        read_u32_from_bytes(&self.data, 5).unwrap_or(0) as usize
     }
 
     pub fn reference_value(&self) -> f32 {
-        read_f32_from_bytes(self.data.as_slice(), 11).unwrap_or(0.0)
+        read_f32_from_bytes(&self.data, 11).unwrap_or(0.0)
     }
 
     pub fn binary_scale_factor(&self) -> i16 {
-        as_signed!(
-            read_u16_from_bytes(self.data.as_slice(), 15).unwrap_or(0),
-            16,
-            i16
-        )
+        as_signed!(read_u16_from_bytes(&self.data, 15).unwrap_or(0), 16, i16)
     }
 
     pub fn decimal_scale_factor(&self) -> i16 {
-        as_signed!(
-            read_u16_from_bytes(self.data.as_slice(), 17).unwrap_or(0),
-            16,
-            i16
-        )
+        as_signed!(read_u16_from_bytes(&self.data, 17).unwrap_or(0), 16, i16)
     }
 
     pub fn bit_count(&self) -> u8 {
@@ -65,7 +93,7 @@ impl SimplePackingDataRepresentationTemplate {
     }
 }
 
-impl DataRepresentationTemplate<f64> for SimplePackingDataRepresentationTemplate {
+impl DataRepresentationTemplate<f64> for SimplePackingDataRepresentationTemplate<'_> {
     fn compression_type(&self) -> String {
         "Simple Grid Packing".into()
     }
@@ -77,11 +105,23 @@ impl DataRepresentationTemplate<f64> for SimplePackingDataRepresentationTemplate
     fn unpack(&self, bits: &BitSlice<u8, Msb0>) -> Result<Vec<f64>, GribberishError> {
         let bits_per_val: usize = self.bit_count().into();
         if bits_per_val == 0 {
-            return Ok(vec![]);
+            // A constant field carries no packed data, every point is the reference value
+            let value = std::iter::once(0u32)
+                .scale_value_by(
+                    self.binary_scale_factor(),
+                    self.decimal_scale_factor(),
+                    self.reference_value(),
+                )
+                .next()
+                .unwrap_or(0.0);
+            return Ok(vec![value; self.data_point_count()]);
         }
 
+        // Trailing bits only pad the section to a whole octet, so stop at the
+        // number of packed values
         let values = (0..bits.len())
             .step_by(bits_per_val)
+            .take(self.data_point_count())
             .filter_map(|i| {
                 let mut i_end_index = i + bits_per_val;
                 if i_end_index > bits.len() {
@@ -105,3 +145,89 @@ impl DataRepresentationTemplate<f64> for SimplePackingDataRepresentationTemplate
         Ok(values)
     }
 }
+
+/// Scale `values` onto the non-negative integers `X` of `Y * 10^D = R + X * 2^E`, returning
+/// the reference value `R`, the number of bits needed to hold every `X` and the integers.
+/// This is shared by the templates that build on simple packing.
+pub(crate) fn quantize_values(
+    values: &[f64],
+    decimal_scale_factor: i16,
+    binary_scale_factor: i16,
+) -> Result<(f32, u8, Vec<u32>), GribberishError> {
+    if values.iter().any(|v| !v.is_finite()) {
+        return Err(GribberishError::EncodingError(
+            "packing requires finite values, missing values belong in the bitmap".into(),
+        ));
+    }
+
+    let decimal_scale = 10_f64.powi(decimal_scale_factor as i32);
+    let binary_scale = 2_f64.powi(-(binary_scale_factor as i32));
+    let scaled = values.iter().map(|v| v * decimal_scale).collect::<Vec<_>>();
+
+    let min = scaled.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = scaled.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let reference_value = if scaled.is_empty() {
+        0.0
+    } else {
+        reference_value_at_or_below(min)
+    };
+
+    let range = if scaled.is_empty() {
+        0.0
+    } else {
+        ((max - reference_value as f64) * binary_scale).round()
+    };
+    let bit_count = if range < 1.0 {
+        0
+    } else {
+        u64::BITS - (range as u64).leading_zeros()
+    };
+    if bit_count > MAX_BITS_PER_VALUE {
+        return Err(GribberishError::EncodingError(format!(
+            "packing requires {bit_count} bits per value, increase the binary scale factor or reduce the decimal scale factor"
+        )));
+    }
+
+    let max_packed = range as u32;
+    let integers = scaled
+        .iter()
+        .map(|v| (((v - reference_value as f64) * binary_scale).round() as u32).min(max_packed))
+        .collect();
+
+    Ok((reference_value, bit_count as u8, integers))
+}
+
+/// Write octets 1-21 of a data representation section, which every template derived
+/// from simple packing shares. `length` is the length of the whole section.
+pub(crate) fn simple_packing_section_header(
+    length: u32,
+    template_number: u16,
+    data_point_count: usize,
+    reference_value: f32,
+    binary_scale_factor: i16,
+    decimal_scale_factor: i16,
+    bit_count: u8,
+) -> Vec<u8> {
+    let mut data = Vec::with_capacity(length as usize);
+    data.extend_from_slice(&length.to_be_bytes());
+    data.push(5);
+    data.extend_from_slice(&(data_point_count as u32).to_be_bytes());
+    data.extend_from_slice(&template_number.to_be_bytes());
+    data.extend_from_slice(&reference_value.to_be_bytes());
+    data.extend_from_slice(&sign_magnitude_u16(binary_scale_factor).to_be_bytes());
+    data.extend_from_slice(&sign_magnitude_u16(decimal_scale_factor).to_be_bytes());
+    data.push(bit_count);
+    data.push(OriginalFieldValue::FloatingPoint as u8);
+    data
+}
+
+/// The reference value is stored as an f32 and must not exceed the field minimum,
+/// otherwise the smallest value would pack to a negative integer.
+pub(crate) fn reference_value_at_or_below(value: f64) -> f32 {
+    let reference = value as f32;
+    if reference as f64 > value {
+        reference.next_down()
+    } else {
+        reference
+    }
+}
@@ -0,0 +1,238 @@
+use bitvec::prelude::*;
+use std::borrow::Cow;
+
+use super::data_representation_template::DataRepresentationTemplate;
+use super::tables::FloatingPointPrecision;
+use crate::error::GribberishError;
+use crate::spectral::{expand_complex_packing, triangular_truncation};
+use crate::templates::template::{Template, TemplateType};
+use crate::utils::iter::ScaleGribValueIterator;
+use crate::utils::{read_f32_from_bytes, read_u16_from_bytes, read_u32_from_bytes};
+
+/// Template 5.51. The coefficients of a triangular subset of the lowest
+/// wavenumbers are stored unpacked as IEEE floating point numbers, the rest
+/// are scaled by a power of the Laplacian operator to flatten their spectrum
+/// before simple packing.
+pub struct SpectralComplexDataRepresentationTemplate<'a> {
+    data: Cow<'a, [u8]>,
+}
+
+impl Template for SpectralComplexDataRepresentationTemplate<'_> {
+    fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn template_number(&self) -> u16 {
+        51
+    }
+
+    fn template_type(&self) -> TemplateType {
+        TemplateType::DataRepresentation
+    }
+
+    fn template_name(&self) -> &str {
+        "spherical harmonics data - complex packing"
+    }
+}
+
+impl<'a> SpectralComplexDataRepresentationTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>) -> Self {
+        SpectralComplexDataRepresentationTemplate { data: data.into() }
+    }
+
+    pub fn data_point_count(&self) -> usize {
+        read_u32_from_bytes(&self.data, 5).unwrap_or(0) as usize
+    }
+
+    pub fn reference_value(&self) -> f32 {
+        read_f32_from_bytes(&self.data, 11).unwrap_or(0.0)
+    }
+
+    pub fn binary_scale_factor(&self) -> i16 {
+        as_signed!(read_u16_from_bytes(&self.data, 15).unwrap_or(0), 16, i16)
+    }
+
+    pub fn decimal_scale_factor(&self) -> i16 {
+        as_signed!(read_u16_from_bytes(&self.data, 17).unwrap_or(0), 16, i16)
+    }
+
+    pub fn bit_count(&self) -> u8 {
+        self.data[19]
+    }
+
+    /// P, the power of the Laplacian operator the packed coefficients were scaled by
+    pub fn laplacian_scaling_factor(&self) -> f64 {
+        let raw_value = read_u32_from_bytes(&self.data, 20).unwrap_or(0);
+        as_signed!(raw_value, 32, i32) as f64 * 1e-6
+    }
+
+    /// JS, the pentagonal resolution parameter of the unpacked subset
+    pub fn subset_j(&self) -> u16 {
+        read_u16_from_bytes(&self.data, 24).unwrap_or(0)
+    }
+
+    /// KS, the pentagonal resolution parameter of the unpacked subset
+    pub fn subset_k(&self) -> u16 {
+        read_u16_from_bytes(&self.data, 26).unwrap_or(0)
+    }
+
+    /// MS, the pentagonal resolution parameter of the unpacked subset
+    pub fn subset_m(&self) -> u16 {
+        read_u16_from_bytes(&self.data, 28).unwrap_or(0)
+    }
+
+    /// TS, the number of values in the unpacked subset
+    pub fn subset_value_count(&self) -> usize {
+        read_u32_from_bytes(&self.data, 30).unwrap_or(0) as usize
+    }
+
+    pub fn subset_precision(&self) -> FloatingPointPrecision {
+        self.data[34].into()
+    }
+}
+
+impl DataRepresentationTemplate<f64> for SpectralComplexDataRepresentationTemplate<'_> {
+    fn compression_type(&self) -> String {
+        "Spectral Complex Packing".into()
+    }
+
+    fn bit_count_per_datapoint(&self) -> usize {
+        self.bit_count() as usize
+    }
+
+    fn unpack(&self, bits: &BitSlice<u8, Msb0>) -> Result<Vec<f64>, GribberishError> {
+        let count = self.data_point_count();
+        let truncation = unwrap_or_return!(
+            triangular_truncation(count),
+            GribberishError::MessageError(format!(
+                "{count} spectral values do not form a triangular truncation"
+            ))
+        );
+        let subset_truncation = self.subset_j();
+        if self.subset_k() != subset_truncation || self.subset_m() != subset_truncation {
+            return Err(GribberishError::MessageError(
+                "only triangular unpacked subsets are supported".into(),
+            ));
+        }
+
+        let subset_count = self.subset_value_count().min(count);
+        let subset_bits = match self.subset_precision() {
+            FloatingPointPrecision::IEEE32Bit => 32,
+            FloatingPointPrecision::IEEE64Bit => 64,
+            FloatingPointPrecision::IEEE128Bit => {
+                return Err(GribberishError::MessageError(
+                    "IEEE 128 bit unpacked subsets are not supported".into(),
+                ))
+            }
+        };
+        let subset_end = (subset_count * subset_bits).min(bits.len());
+        let subset = bits[..subset_end]
+            .chunks_exact(subset_bits)
+            .map(|chunk| match subset_bits {
+                32 => f32::from_bits(chunk.load_be::<u32>()) as f64,
+                _ => f64::from_bits(chunk.load_be::<u64>()),
+            })
+            .collect::<Vec<_>>();
+
+        let packed_count = count - subset_count;
+        let bits_per_val = self.bit_count() as usize;
+        let integers = if bits_per_val == 0 {
+            vec![0u32; packed_count]
+        } else {
+            bits[subset_end..]
+                .chunks_exact(bits_per_val)
+                .take(packed_count)
+                .map(|chunk| chunk.load_be::<u32>())
+                .collect()
+        };
+        // The decimal scale factor is only applied to the packed values
+        let packed = integers
+            .into_iter()
+            .scale_value_by(self.binary_scale_factor(), 0, self.reference_value())
+            .collect::<Vec<_>>();
+
+        expand_complex_packing(
+            truncation,
+            subset_truncation as usize,
+            &subset,
+            &packed,
+            self.laplacian_scaling_factor(),
+            self.decimal_scale_factor(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::templates::data_representation::simple_packing_template::simple_packing_section_header;
+    use crate::utils::pack_bits;
+
+    /// A T2 field with a T1 subset, P = 0.5
+    fn t2_section() -> Vec<u8> {
+        let mut data = simple_packing_section_header(35, 51, 12, 1.0, 0, 0, 8);
+        data.pop();
+        data.extend_from_slice(&500_000u32.to_be_bytes());
+        for _ in 0..3 {
+            data.extend_from_slice(&1u16.to_be_bytes());
+        }
+        data.extend_from_slice(&6u32.to_be_bytes());
+        data.push(1);
+        data
+    }
+
+    #[test]
+    fn test_unpack_spectral_complex() {
+        let template = SpectralComplexDataRepresentationTemplate::new(t2_section());
+        assert_eq!(template.laplacian_scaling_factor(), 0.5);
+        assert_eq!(
+            (
+                template.subset_j(),
+                template.subset_k(),
+                template.subset_m()
+            ),
+            (1, 1, 1)
+        );
+        assert_eq!(template.subset_value_count(), 6);
+        assert_eq!(
+            template.subset_precision(),
+            FloatingPointPrecision::IEEE32Bit
+        );
+
+        let mut data = [280.0f32, 0.0, 1.5, 0.0, -2.0, 0.25]
+            .iter()
+            .flat_map(|v| v.to_be_bytes())
+            .collect::<Vec<_>>();
+        data.extend(pack_bits([11u32, 0, 5, 7, 3, 1], 8));
+
+        let values = template.unpack(data.view_bits()).unwrap();
+        // Packed coefficients all have n = 2, so are divided by sqrt(6)
+        let scale = 1.0 / 6f64.sqrt();
+        let expected = [
+            280.0,
+            0.0,
+            1.5,
+            0.0,
+            12.0 * scale,
+            1.0 * scale,
+            -2.0,
+            0.25,
+            6.0 * scale,
+            8.0 * scale,
+            4.0 * scale,
+            2.0 * scale,
+        ];
+        assert_eq!(values.len(), expected.len());
+        for (v, e) in values.iter().zip(expected) {
+            assert!((v - e).abs() < 1e-12, "{v} != {e}");
+        }
+    }
+
+    #[test]
+    fn test_rejects_non_triangular_fields() {
+        let mut section = t2_section();
+        section[5..9].copy_from_slice(&11u32.to_be_bytes());
+        let template = SpectralComplexDataRepresentationTemplate::new(section);
+        assert!(template.unpack([0u8; 40].view_bits()).is_err());
+    }
+}
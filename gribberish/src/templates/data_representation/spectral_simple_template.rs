@@ -0,0 +1,130 @@
+use bitvec::prelude::*;
+use std::borrow::Cow;
+
+use super::data_representation_template::DataRepresentationTemplate;
+use crate::error::GribberishError;
+use crate::templates::template::{Template, TemplateType};
+use crate::utils::iter::ScaleGribValueIterator;
+use crate::utils::{read_f32_from_bytes, read_u16_from_bytes, read_u32_from_bytes};
+
+/// Template 5.50. Spherical harmonic coefficients packed with simple packing,
+/// except for the real part of the `(0, 0)` coefficient which is stored in
+/// the section itself as it is usually far larger than the rest.
+pub struct SpectralSimpleDataRepresentationTemplate<'a> {
+    data: Cow<'a, [u8]>,
+}
+
+impl Template for SpectralSimpleDataRepresentationTemplate<'_> {
+    fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn template_number(&self) -> u16 {
+        50
+    }
+
+    fn template_type(&self) -> TemplateType {
+        TemplateType::DataRepresentation
+    }
+
+    fn template_name(&self) -> &str {
+        "spectral data - simple packing"
+    }
+}
+
+impl<'a> SpectralSimpleDataRepresentationTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>) -> Self {
+        SpectralSimpleDataRepresentationTemplate { data: data.into() }
+    }
+
+    pub fn data_point_count(&self) -> usize {
+        read_u32_from_bytes(&self.data, 5).unwrap_or(0) as usize
+    }
+
+    pub fn reference_value(&self) -> f32 {
+        read_f32_from_bytes(&self.data, 11).unwrap_or(0.0)
+    }
+
+    pub fn binary_scale_factor(&self) -> i16 {
+        as_signed!(read_u16_from_bytes(&self.data, 15).unwrap_or(0), 16, i16)
+    }
+
+    pub fn decimal_scale_factor(&self) -> i16 {
+        as_signed!(read_u16_from_bytes(&self.data, 17).unwrap_or(0), 16, i16)
+    }
+
+    pub fn bit_count(&self) -> u8 {
+        self.data[19]
+    }
+
+    /// The real part of the `(0, 0)` coefficient, the global mean of the field
+    pub fn real_part_of_first_coefficient(&self) -> f32 {
+        read_f32_from_bytes(&self.data, 20).unwrap_or(0.0)
+    }
+}
+
+impl DataRepresentationTemplate<f64> for SpectralSimpleDataRepresentationTemplate<'_> {
+    fn compression_type(&self) -> String {
+        "Spectral Simple Packing".into()
+    }
+
+    fn bit_count_per_datapoint(&self) -> usize {
+        self.bit_count() as usize
+    }
+
+    fn unpack(&self, bits: &BitSlice<u8, Msb0>) -> Result<Vec<f64>, GribberishError> {
+        let count = self.data_point_count().saturating_sub(1);
+        let bits_per_val = self.bit_count() as usize;
+        let integers = if bits_per_val == 0 {
+            vec![0u32; count]
+        } else {
+            bits.chunks_exact(bits_per_val)
+                .take(count)
+                .map(|chunk| chunk.load_be::<u32>())
+                .collect::<Vec<_>>()
+        };
+
+        if integers.len() < count {
+            return Err(GribberishError::MessageError(format!(
+                "expected {count} packed spectral coefficients, found {}",
+                integers.len()
+            )));
+        }
+
+        let mut values = Vec::with_capacity(count + 1);
+        values.push(self.real_part_of_first_coefficient() as f64);
+        values.extend(integers.into_iter().scale_value_by(
+            self.binary_scale_factor(),
+            self.decimal_scale_factor(),
+            self.reference_value(),
+        ));
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::templates::data_representation::simple_packing_template::simple_packing_section_header;
+    use crate::utils::pack_bits;
+
+    #[test]
+    fn test_unpack_spectral_simple() {
+        // T1, the (0, 0) real part is stored in the section and the other 5 values are packed
+        let mut data = simple_packing_section_header(24, 50, 6, -2.0, -1, 1, 4);
+        data.pop();
+        data.extend_from_slice(&287.5f32.to_be_bytes());
+        let template = SpectralSimpleDataRepresentationTemplate::new(data);
+        assert_eq!(template.real_part_of_first_coefficient(), 287.5);
+
+        let packed = pack_bits([0u32, 4, 8, 15, 1], 4);
+        let values = template.unpack(packed.view_bits()).unwrap();
+        let expected = [287.5, -0.2, 0.0, 0.2, 0.55, -0.15];
+        assert_eq!(values.len(), expected.len());
+        for (v, e) in values.iter().zip(expected) {
+            assert!((v - e).abs() < 1e-12, "{v} != {e}");
+        }
+
+        assert!(template.unpack(packed[..1].view_bits()).is_err());
+    }
+}
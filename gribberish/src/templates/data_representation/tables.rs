@@ -2,6 +2,7 @@ use gribberish_macros::{DisplayDescription, FromValue};
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum OriginalFieldValue {
     FloatingPoint = 0,
     Integer = 1,
@@ -9,6 +10,7 @@ pub enum OriginalFieldValue {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum MatrixCoordinateValueFunctions {
     ExplicitCoordinateValueSet = 0,
     LinearCoordinates = 1,
@@ -17,6 +19,7 @@ pub enum MatrixCoordinateValueFunctions {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum MatrixCoordinateParameters {
     DirectionDegreesTrue = 1,
     Frequency = 2,
@@ -24,14 +27,16 @@ pub enum MatrixCoordinateParameters {
 }
 
 #[repr(u8)]
-#[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, DisplayDescription, FromValue)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum GroupSplittingMethod {
     RowByRow = 0,
     GeneralGroup = 1,
 }
 
 #[repr(u8)]
-#[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, DisplayDescription, FromValue)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum MissingValueManagement {
     #[description = "no explicit missing values included with the data values"]
     NoMissingValues = 0,
@@ -42,7 +47,8 @@ pub enum MissingValueManagement {
 }
 
 #[repr(u8)]
-#[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, DisplayDescription, FromValue)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum SpatialDifferencingOrder {
     #[description = "first order spatial differencing"]
     First = 1,
@@ -51,7 +57,8 @@ pub enum SpatialDifferencingOrder {
 }
 
 #[repr(u8)]
-#[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, DisplayDescription, FromValue)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum FloatingPointPrecision {
     #[description = "IEEE 32 bit"]
     IEEE32Bit = 1,
@@ -63,6 +70,7 @@ pub enum FloatingPointPrecision {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum CompressionType {
     Lossless = 0,
     Lossy = 1,
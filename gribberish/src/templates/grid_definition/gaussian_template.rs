@@ -0,0 +1,359 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use super::earth_shape::EarthShapeDefinition;
+use super::grid_definition_template::GridDefinitionTemplate;
+use super::tables::{
+    EarthShape, ResolutionAndComponent, ResolutionAndComponentFlags, ScanningMode,
+    ScanningModeFlags,
+};
+use crate::sections::grid_definition::NumberListInterpretation;
+use crate::templates::template::{Template, TemplateType};
+use crate::utils::gaussian::{gaussian_rows, reduced_row_longitudes};
+use crate::utils::iter::projection::{
+    GaussianLongitudes, GaussianProjection, LatLngProjection, RegularCoordinateIterator,
+};
+use crate::utils::{read_u16_from_bytes, read_u32_from_bytes};
+
+/// Octet index where the optional list of points per parallel starts
+const POINT_LIST_START: usize = 72;
+
+/// Template 3.40. A regular grid has the same number of points on every
+/// parallel, a reduced grid leaves `Ni` missing and appends the number of
+/// points on each parallel to the section.
+pub struct GaussianTemplate<'a> {
+    data: Cow<'a, [u8]>,
+}
+
+impl Template for GaussianTemplate<'_> {
+    fn template_type(&self) -> TemplateType {
+        TemplateType::Grid
+    }
+
+    fn template_number(&self) -> u16 {
+        40
+    }
+
+    fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn template_name(&self) -> &str {
+        "Gaussian Latitude Longitude"
+    }
+}
+
+impl<'a> GaussianTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>) -> Self {
+        GaussianTemplate { data: data.into() }
+    }
+
+    fn earth(&self) -> EarthShapeDefinition<'_> {
+        EarthShapeDefinition::new(&self.data)
+    }
+
+    pub fn earth_shape(&self) -> EarthShape {
+        self.earth().shape()
+    }
+
+    pub fn parallel_point_count(&self) -> u32 {
+        read_u32_from_bytes(&self.data, 30).unwrap_or(0)
+    }
+
+    pub fn meridian_point_count(&self) -> u32 {
+        read_u32_from_bytes(&self.data, 34).unwrap_or(0)
+    }
+
+    pub fn start_latitude(&self) -> f64 {
+        let raw_value = read_u32_from_bytes(&self.data, 46).unwrap_or(0);
+        as_signed!(raw_value, 32, i32) as f64 * 1e-6
+    }
+
+    pub fn start_longitude(&self) -> f64 {
+        read_u32_from_bytes(&self.data, 50).unwrap_or(0) as f64 * 1e-6
+    }
+
+    pub fn end_latitude(&self) -> f64 {
+        let raw_value = read_u32_from_bytes(&self.data, 55).unwrap_or(0);
+        as_signed!(raw_value, 32, i32) as f64 * 1e-6
+    }
+
+    pub fn end_longitude(&self) -> f64 {
+        read_u32_from_bytes(&self.data, 59).unwrap_or(0) as f64 * 1e-6
+    }
+
+    pub fn i_direction_increment(&self) -> f64 {
+        let value = read_u32_from_bytes(&self.data, 63).unwrap_or(0);
+        // Reduced grids, and some regular ones, leave the increment missing
+        let value = if value == u32::MAX || value == 0 {
+            let span = self.end_longitude() - self.start_longitude();
+            let span = if span < 0.0 { span + 360.0 } else { span };
+            span / (self.x_count().max(2) - 1) as f64
+        } else {
+            value as f64 * 1e-6
+        };
+
+        if self.scanning_mode_flags()[0] == ScanningMode::MinusI {
+            -value
+        } else {
+            value
+        }
+    }
+
+    /// Number of parallels between a pole and the equator
+    pub fn parallel_count(&self) -> u32 {
+        read_u32_from_bytes(&self.data, 67).unwrap_or(0)
+    }
+
+    pub fn resolution_component_flags(&self) -> ResolutionAndComponentFlags {
+        ResolutionAndComponent::read_flags(self.data[54])
+    }
+
+    pub fn scanning_mode_flags(&self) -> ScanningModeFlags {
+        ScanningMode::read_flags(self.data[71])
+    }
+
+    pub fn is_reduced(&self) -> bool {
+        self.parallel_point_count() == u32::MAX
+            && self.data[11] == NumberListInterpretation::Parallels as u8
+    }
+
+    /// The number of points on each parallel of a reduced grid, in scanning order
+    pub fn points_per_parallel(&self) -> Vec<usize> {
+        let octets = self.data[10] as usize;
+        if !self.is_reduced() || octets == 0 {
+            return vec![];
+        }
+
+        (0..self.meridian_point_count() as usize)
+            .map(|i| {
+                let offset = POINT_LIST_START + i * octets;
+                match octets {
+                    1 => self.data.get(offset).copied().unwrap_or(0) as usize,
+                    2 => read_u16_from_bytes(&self.data, offset).unwrap_or(0) as usize,
+                    _ => read_u32_from_bytes(&self.data, offset).unwrap_or(0) as usize,
+                }
+            })
+            .collect()
+    }
+
+    pub fn latitudes(&self) -> Vec<f64> {
+        gaussian_rows(
+            self.parallel_count() as usize,
+            self.start_latitude(),
+            self.end_latitude(),
+        )
+    }
+}
+
+impl GridDefinitionTemplate for GaussianTemplate<'_> {
+    fn proj_name(&self) -> String {
+        "latlon".to_string()
+    }
+
+    fn proj_params(&self) -> HashMap<String, f64> {
+        let mut params = HashMap::new();
+        let earth_params = self.earth().proj_params().unwrap_or_default();
+        for (k, v) in earth_params {
+            params.insert(k, v);
+        }
+        params
+    }
+
+    fn proj_string(&self) -> String {
+        let earth_shape = self.earth().proj_string().unwrap_or("".to_string());
+        format!("+proj=latlon{earth_shape}")
+    }
+
+    fn crs(&self) -> String {
+        "EPSG:4326".to_string()
+    }
+
+    fn grid_point_count(&self) -> usize {
+        if self.is_reduced() {
+            self.points_per_parallel().iter().sum()
+        } else {
+            (self.parallel_point_count() * self.meridian_point_count()) as usize
+        }
+    }
+
+    fn is_regular_grid(&self) -> bool {
+        !self.is_reduced()
+    }
+
+    /// Reduced grids have no fixed row length, so their points are laid out
+    /// along a single row
+    fn y_count(&self) -> usize {
+        if self.is_reduced() {
+            1
+        } else {
+            self.meridian_point_count() as usize
+        }
+    }
+
+    fn x_count(&self) -> usize {
+        if self.is_reduced() {
+            self.grid_point_count()
+        } else {
+            self.parallel_point_count() as usize
+        }
+    }
+
+    fn projector(&self) -> LatLngProjection {
+        let longitudes = if self.is_reduced() {
+            let points = self.points_per_parallel();
+            let max_points = points.iter().copied().max().unwrap_or(1).max(1);
+            let mut span = self.end_longitude() - self.start_longitude();
+            if span < 0.0 {
+                span += 360.0;
+            }
+            let is_global = (span + 360.0 / max_points as f64 - 360.0).abs() < 1e-3;
+
+            GaussianLongitudes::Reduced(
+                points
+                    .into_iter()
+                    .map(|count| {
+                        reduced_row_longitudes(
+                            count,
+                            self.start_longitude(),
+                            self.end_longitude(),
+                            is_global,
+                        )
+                    })
+                    .collect(),
+            )
+        } else {
+            GaussianLongitudes::Regular(RegularCoordinateIterator::new(
+                self.start_longitude(),
+                self.i_direction_increment(),
+                self.x_count(),
+            ))
+        };
+
+        LatLngProjection::Gaussian(GaussianProjection {
+            latitudes: self.latitudes(),
+            longitudes,
+            projection_name: self.proj_name(),
+            projection_params: self.proj_params(),
+        })
+    }
+
+    fn resolution_and_component_flags(&self) -> Option<ResolutionAndComponentFlags> {
+        Some(self.resolution_component_flags())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::Message;
+    use crate::message_builder::MessageBuilder;
+    use crate::message_metadata::MessageMetadata;
+    use crate::utils::gaussian::gaussian_latitudes;
+    use chrono::{TimeZone, Utc};
+
+    /// An N2 global grid, regular with 8 points per row or reduced with the
+    /// given points per row
+    fn n2_section(points_per_parallel: Option<&[u8]>) -> Vec<u8> {
+        let micro = |v: f64| (v.abs() * 1e6).round() as u32 | if v < 0.0 { 0x8000_0000 } else { 0 };
+        let latitudes = gaussian_latitudes(2);
+        let (ni, list_octets, data_points) = match points_per_parallel {
+            Some(points) => (u32::MAX, 1, points.iter().map(|p| *p as u32).sum::<u32>()),
+            None => (8, 0, 32),
+        };
+
+        let mut section = vec![0u8, 0, 0, 0, 3, 0];
+        section.extend_from_slice(&data_points.to_be_bytes());
+        section.extend_from_slice(&[list_octets, list_octets, 0, 40, 6]);
+        section.extend_from_slice(&[0; 15]);
+        section.extend_from_slice(&ni.to_be_bytes());
+        section.extend_from_slice(&4u32.to_be_bytes());
+        section.extend_from_slice(&0u32.to_be_bytes());
+        section.extend_from_slice(&u32::MAX.to_be_bytes());
+        section.extend_from_slice(&micro(latitudes[0]).to_be_bytes());
+        section.extend_from_slice(&0u32.to_be_bytes());
+        section.push(48);
+        section.extend_from_slice(&micro(latitudes[3]).to_be_bytes());
+        section.extend_from_slice(&micro(315.0).to_be_bytes());
+        let di = if points_per_parallel.is_some() {
+            u32::MAX
+        } else {
+            micro(45.0)
+        };
+        section.extend_from_slice(&di.to_be_bytes());
+        section.extend_from_slice(&2u32.to_be_bytes());
+        section.push(0);
+        if let Some(points) = points_per_parallel {
+            section.extend_from_slice(points);
+        }
+        let length = section.len() as u32;
+        section[0..4].copy_from_slice(&length.to_be_bytes());
+        section
+    }
+
+    #[test]
+    fn test_regular_gaussian_grid() {
+        let template = GaussianTemplate::new(n2_section(None));
+        assert!(!template.is_reduced());
+        assert!(template.is_regular_grid());
+        assert_eq!(template.parallel_count(), 2);
+        assert_eq!((template.y_count(), template.x_count()), (4, 8));
+
+        let projector = template.projector();
+        assert!(projector.is_regular_latlng_grid());
+        let (lats, lngs) = projector.lat_lng();
+        assert_eq!(lats, gaussian_latitudes(2));
+        assert_eq!(lngs.len(), 8);
+        assert!((lngs[7] - 315.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_reduced_gaussian_grid() {
+        let template = GaussianTemplate::new(n2_section(Some(&[4, 8, 8, 4])));
+        assert!(template.is_reduced());
+        assert!(!template.is_regular_grid());
+        assert_eq!(template.points_per_parallel(), vec![4, 8, 8, 4]);
+        assert_eq!(template.grid_point_count(), 24);
+        assert_eq!((template.y_count(), template.x_count()), (1, 24));
+
+        let projector = template.projector();
+        assert!(!projector.is_regular_latlng_grid());
+        let (lats, lngs) = projector.lat_lng();
+        let rows = gaussian_latitudes(2);
+        assert_eq!(lats.len(), 24);
+        assert_eq!(lngs.len(), 24);
+        assert_eq!(lats[3], rows[0]);
+        assert_eq!(lats[4], rows[1]);
+        assert_eq!(lats[23], rows[3]);
+        assert_eq!(&lngs[0..4], &[0.0, 90.0, 180.0, 270.0]);
+        assert_eq!(lngs[5], 45.0);
+        assert_eq!(projector.x().len(), 24);
+        assert_eq!(projector.y().len(), 1);
+    }
+
+    #[test]
+    fn test_reduced_gaussian_message() {
+        let values = (0..24).map(|i| i as f64).collect::<Vec<_>>();
+        let product = {
+            let mut section = vec![0u8, 0, 0, 34, 4, 0, 0, 0, 0];
+            section.extend_from_slice(&[0, 0, 2, 0, 96, 0, 0, 0, 1]);
+            section.extend_from_slice(&0u32.to_be_bytes());
+            section.extend_from_slice(&[103, 0]);
+            section.extend_from_slice(&2u32.to_be_bytes());
+            section.extend_from_slice(&[255, 0, 0, 0, 0, 0]);
+            section
+        };
+        let encoded = MessageBuilder::new(0, Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap())
+            .with_grid_definition(&n2_section(Some(&[4, 8, 8, 4])))
+            .with_product_definition(&product)
+            .build(&values)
+            .unwrap();
+
+        let message = Message::from_data(&encoded, 0).unwrap();
+        let metadata = MessageMetadata::try_from(&message).unwrap();
+        assert_eq!(metadata.grid_shape, (1, 24));
+        assert!(!metadata.is_regular_grid);
+        assert_eq!(metadata.latlng().0.len(), 24);
+        assert_eq!(message.data().unwrap(), values);
+    }
+}
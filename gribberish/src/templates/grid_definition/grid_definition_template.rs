@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use super::tables::ResolutionAndComponentFlags;
 use crate::utils::iter::projection::LatLngProjection;
 
 pub trait GridDefinitionTemplate {
@@ -12,4 +13,7 @@ pub trait GridDefinitionTemplate {
     fn y_count(&self) -> usize;
     fn x_count(&self) -> usize;
     fn projector(&self) -> LatLngProjection;
+    /// Resolution and component flags, `None` for grids without them such as
+    /// spherical harmonics
+    fn resolution_and_component_flags(&self) -> Option<ResolutionAndComponentFlags>;
 }
@@ -1,6 +1,6 @@
-use bitvec::prelude::*;
+use std::borrow::Cow;
 
-use mappers::{projections::LambertConformalConic, Ellipsoid, Projection};
+use mappers::{Ellipsoid, Projection};
 
 use crate::{
     error::GribberishError,
@@ -9,6 +9,7 @@ use crate::{
         iter::projection::{
             GridProjection, LatLngProjection, ProjectedGrid, RegularCoordinateIterator,
         },
+        projections::lambert_conformal::LambertConformal,
         read_u32_from_bytes,
     },
 };
@@ -16,16 +17,17 @@ use crate::{
 use super::{
     earth_shape::EarthShapeDefinition,
     tables::{
-        EarthShape, ProjectionCenter, ProjectionCenterFlags, ScanningMode, ScanningModeFlags,
+        EarthShape, ProjectionCenter, ProjectionCenterFlags, ResolutionAndComponent,
+        ResolutionAndComponentFlags, ScanningMode, ScanningModeFlags,
     },
     GridDefinitionTemplate,
 };
 
-pub struct LambertConformalTemplate {
-    data: Vec<u8>,
+pub struct LambertConformalTemplate<'a> {
+    data: Cow<'a, [u8]>,
 }
 
-impl Template for LambertConformalTemplate {
+impl Template for LambertConformalTemplate<'_> {
     fn template_type(&self) -> TemplateType {
         TemplateType::Grid
     }
@@ -43,9 +45,9 @@ impl Template for LambertConformalTemplate {
     }
 }
 
-impl LambertConformalTemplate {
-    pub fn new(data: Vec<u8>) -> Self {
-        LambertConformalTemplate { data }
+impl<'a> LambertConformalTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>) -> Self {
+        LambertConformalTemplate { data: data.into() }
     }
 
     fn earth(&self) -> EarthShapeDefinition<'_> {
@@ -124,8 +126,8 @@ impl LambertConformalTemplate {
         value * (10f64.powf(-6.0))
     }
 
-    pub fn resolution_component_flags(&self) -> &BitSlice<u8, Msb0> {
-        self.data[46..47].view_bits()
+    pub fn resolution_component_flags(&self) -> ResolutionAndComponentFlags {
+        ResolutionAndComponent::read_flags(self.data[46])
     }
 
     pub fn latitude_of_dx_dy(&self) -> f64 {
@@ -200,13 +202,13 @@ impl LambertConformalTemplate {
         }
     }
 
-    pub fn projection(&self) -> Result<LambertConformalConic, GribberishError> {
+    pub fn projection(&self) -> Result<LambertConformal, GribberishError> {
         let mut lng = self.longitude_of_paralell_meridian_to_latitude_increase();
         lng = if lng > 180.0 { lng - 360.0 } else { lng };
 
         let earth_shape = self.earth_ellipsoid()?;
 
-        LambertConformalConic::new(
+        LambertConformal::new(
             lng,
             self.latitude_of_dx_dy(),
             self.latin_1(),
@@ -220,9 +222,7 @@ impl LambertConformalTemplate {
         })
     }
 
-    pub fn project_axes(
-        &self,
-    ) -> Result<(LambertConformalConic, Vec<f64>, Vec<f64>), GribberishError> {
+    pub fn project_axes(&self) -> Result<(LambertConformal, Vec<f64>, Vec<f64>), GribberishError> {
         let mut start_lng = self.longitude_of_first_grid_point();
         start_lng = if start_lng > 180.0 {
             start_lng - 360.0
@@ -262,7 +262,7 @@ impl LambertConformalTemplate {
     }
 }
 
-impl GridDefinitionTemplate for LambertConformalTemplate {
+impl GridDefinitionTemplate for LambertConformalTemplate<'_> {
     fn proj_name(&self) -> String {
         "lcc".to_string()
     }
@@ -355,4 +355,8 @@ impl GridDefinitionTemplate for LambertConformalTemplate {
             projection_params: self.proj_params(),
         })
     }
+
+    fn resolution_and_component_flags(&self) -> Option<ResolutionAndComponentFlags> {
+        Some(self.resolution_component_flags())
+    }
 }
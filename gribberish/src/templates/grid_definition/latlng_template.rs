@@ -1,8 +1,11 @@
-use bitvec::prelude::*;
+use std::borrow::Cow;
 
 use super::earth_shape::EarthShapeDefinition;
 use super::grid_definition_template::GridDefinitionTemplate;
-use super::tables::{EarthShape, ScanningMode, ScanningModeFlags};
+use super::tables::{
+    EarthShape, ResolutionAndComponent, ResolutionAndComponentFlags, ScanningMode,
+    ScanningModeFlags,
+};
 use crate::templates::template::{Template, TemplateType};
 use crate::utils::iter::projection::{
     LatLngProjection, PlateCareeProjection, RegularCoordinateIterator,
@@ -12,11 +15,11 @@ use crate::utils::read_u32_from_bytes;
 use std::iter::Iterator;
 use std::vec::Vec;
 
-pub struct LatLngTemplate {
-    data: Vec<u8>,
+pub struct LatLngTemplate<'a> {
+    data: Cow<'a, [u8]>,
 }
 
-impl Template for LatLngTemplate {
+impl Template for LatLngTemplate<'_> {
     fn template_type(&self) -> TemplateType {
         TemplateType::Grid
     }
@@ -34,9 +37,9 @@ impl Template for LatLngTemplate {
     }
 }
 
-impl LatLngTemplate {
-    pub fn new(data: Vec<u8>) -> Self {
-        LatLngTemplate { data }
+impl<'a> LatLngTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>) -> Self {
+        LatLngTemplate { data: data.into() }
     }
 
     fn earth(&self) -> EarthShapeDefinition<'_> {
@@ -98,8 +101,8 @@ impl LatLngTemplate {
         value * (10f64.powf(-6.0))
     }
 
-    pub fn resolution_component_flags(&self) -> &BitSlice<u8, Msb0> {
-        self.data[54..55].view_bits()
+    pub fn resolution_component_flags(&self) -> ResolutionAndComponentFlags {
+        ResolutionAndComponent::read_flags(self.data[54])
     }
 
     pub fn end_latitude(&self) -> f64 {
@@ -173,7 +176,7 @@ impl LatLngTemplate {
     }
 }
 
-impl GridDefinitionTemplate for LatLngTemplate {
+impl GridDefinitionTemplate for LatLngTemplate<'_> {
     fn proj_name(&self) -> String {
         "latlon".to_string()
     }
@@ -229,4 +232,8 @@ impl GridDefinitionTemplate for LatLngTemplate {
             projection_params: self.proj_params(),
         })
     }
+
+    fn resolution_and_component_flags(&self) -> Option<ResolutionAndComponentFlags> {
+        Some(self.resolution_component_flags())
+    }
 }
@@ -1,4 +1,5 @@
 use mappers::Projection;
+use std::borrow::Cow;
 
 use crate::{
     error::GribberishError,
@@ -14,15 +15,17 @@ use crate::{
 
 use super::{
     earth_shape::EarthShapeDefinition,
-    tables::{ScanningMode, ScanningModeFlags},
+    tables::{
+        ResolutionAndComponent, ResolutionAndComponentFlags, ScanningMode, ScanningModeFlags,
+    },
     GridDefinitionTemplate,
 };
 
-pub struct MercatorTemplate {
-    data: Vec<u8>,
+pub struct MercatorTemplate<'a> {
+    data: Cow<'a, [u8]>,
 }
 
-impl Template for MercatorTemplate {
+impl Template for MercatorTemplate<'_> {
     fn template_type(&self) -> TemplateType {
         TemplateType::Grid
     }
@@ -40,9 +43,9 @@ impl Template for MercatorTemplate {
     }
 }
 
-impl MercatorTemplate {
-    pub fn new(data: Vec<u8>) -> Self {
-        MercatorTemplate { data }
+impl<'a> MercatorTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>) -> Self {
+        MercatorTemplate { data: data.into() }
     }
 
     fn earth(&self) -> EarthShapeDefinition<'_> {
@@ -87,6 +90,10 @@ impl MercatorTemplate {
         as_signed!(raw, 32, i32) as f64 * 1e-6
     }
 
+    pub fn resolution_component_flags(&self) -> ResolutionAndComponentFlags {
+        ResolutionAndComponent::read_flags(self.data[46])
+    }
+
     pub fn scanning_mode_flags(&self) -> ScanningModeFlags {
         ScanningMode::read_flags(self.data[59])
     }
@@ -135,7 +142,7 @@ impl MercatorTemplate {
     }
 }
 
-impl GridDefinitionTemplate for MercatorTemplate {
+impl GridDefinitionTemplate for MercatorTemplate<'_> {
     fn proj_name(&self) -> String {
         "merc".to_string()
     }
@@ -198,6 +205,10 @@ impl GridDefinitionTemplate for MercatorTemplate {
             projection_params: self.proj_params(),
         })
     }
+
+    fn resolution_and_component_flags(&self) -> Option<ResolutionAndComponentFlags> {
+        Some(self.resolution_component_flags())
+    }
 }
 
 fn wrap_longitude(lng: f64) -> f64 {
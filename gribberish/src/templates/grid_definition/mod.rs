@@ -1,14 +1,22 @@
 pub mod earth_shape;
+pub mod gaussian_template;
 pub mod grid_definition_template;
 pub mod lambert_conformal_template;
 pub mod latlng_template;
 pub mod mercator_template;
 pub mod polar_stereographic_template;
+pub mod rotated_latlng_template;
+pub mod space_view_template;
+pub mod spherical_harmonics_template;
 pub mod tables;
 
 pub use earth_shape::EarthShapeDefinition;
+pub use gaussian_template::GaussianTemplate;
 pub use grid_definition_template::GridDefinitionTemplate;
 pub use lambert_conformal_template::LambertConformalTemplate;
 pub use latlng_template::LatLngTemplate;
 pub use mercator_template::MercatorTemplate;
 pub use polar_stereographic_template::PolarStereographicTemplate;
+pub use rotated_latlng_template::RotatedLatLngTemplate;
+pub use space_view_template::SpaceViewTemplate;
+pub use spherical_harmonics_template::SphericalHarmonicsTemplate;
@@ -1,4 +1,5 @@
 use mappers::Projection;
+use std::borrow::Cow;
 
 use crate::{
     error::GribberishError,
@@ -14,15 +15,18 @@ use crate::{
 
 use super::{
     earth_shape::EarthShapeDefinition,
-    tables::{ProjectionCenter, ProjectionCenterFlags, ScanningMode, ScanningModeFlags},
+    tables::{
+        ProjectionCenter, ProjectionCenterFlags, ResolutionAndComponent,
+        ResolutionAndComponentFlags, ScanningMode, ScanningModeFlags,
+    },
     GridDefinitionTemplate,
 };
 
-pub struct PolarStereographicTemplate {
-    data: Vec<u8>,
+pub struct PolarStereographicTemplate<'a> {
+    data: Cow<'a, [u8]>,
 }
 
-impl Template for PolarStereographicTemplate {
+impl Template for PolarStereographicTemplate<'_> {
     fn template_type(&self) -> TemplateType {
         TemplateType::Grid
     }
@@ -40,9 +44,9 @@ impl Template for PolarStereographicTemplate {
     }
 }
 
-impl PolarStereographicTemplate {
-    pub fn new(data: Vec<u8>) -> Self {
-        PolarStereographicTemplate { data }
+impl<'a> PolarStereographicTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>) -> Self {
+        PolarStereographicTemplate { data: data.into() }
     }
 
     fn earth(&self) -> EarthShapeDefinition<'_> {
@@ -91,6 +95,10 @@ impl PolarStereographicTemplate {
         ProjectionCenter::read_flags(self.data[63])
     }
 
+    pub fn resolution_component_flags(&self) -> ResolutionAndComponentFlags {
+        ResolutionAndComponent::read_flags(self.data[46])
+    }
+
     pub fn scanning_mode_flags(&self) -> ScanningModeFlags {
         ScanningMode::read_flags(self.data[64])
     }
@@ -127,7 +135,7 @@ impl PolarStereographicTemplate {
     }
 }
 
-impl GridDefinitionTemplate for PolarStereographicTemplate {
+impl GridDefinitionTemplate for PolarStereographicTemplate<'_> {
     fn proj_name(&self) -> String {
         "stere".to_string()
     }
@@ -196,6 +204,10 @@ impl GridDefinitionTemplate for PolarStereographicTemplate {
             projection_params: self.proj_params(),
         })
     }
+
+    fn resolution_and_component_flags(&self) -> Option<ResolutionAndComponentFlags> {
+        Some(self.resolution_component_flags())
+    }
 }
 
 fn wrap_longitude(lng: f64) -> f64 {
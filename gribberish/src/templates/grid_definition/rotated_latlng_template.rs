@@ -0,0 +1,227 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use super::earth_shape::EarthShapeDefinition;
+use super::grid_definition_template::GridDefinitionTemplate;
+use super::latlng_template::LatLngTemplate;
+use super::tables::ResolutionAndComponentFlags;
+use crate::templates::template::{Template, TemplateType};
+use crate::utils::iter::projection::{
+    GridProjection, LatLngProjection, ProjectedGrid, RegularCoordinateIterator,
+};
+use crate::utils::projections::rotated_pole::RotatedPole;
+use crate::utils::{read_f32_from_bytes, read_u32_from_bytes};
+
+/// Template 3.1. Octets 15-72 are laid out exactly like template 3.0 but
+/// describe the grid in the rotated coordinate system, followed by the
+/// location of the rotated system's southern pole and its angle of rotation.
+pub struct RotatedLatLngTemplate<'a> {
+    grid: LatLngTemplate<'a>,
+}
+
+impl Template for RotatedLatLngTemplate<'_> {
+    fn template_type(&self) -> TemplateType {
+        TemplateType::Grid
+    }
+
+    fn template_number(&self) -> u16 {
+        1
+    }
+
+    fn data(&self) -> &[u8] {
+        self.grid.data()
+    }
+
+    fn template_name(&self) -> &str {
+        "Rotated Latitude Longitude"
+    }
+}
+
+impl<'a> RotatedLatLngTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>) -> Self {
+        RotatedLatLngTemplate {
+            grid: LatLngTemplate::new(data),
+        }
+    }
+
+    fn earth(&self) -> EarthShapeDefinition<'_> {
+        EarthShapeDefinition::new(self.data())
+    }
+
+    /// The grid in rotated coordinates. Its latitudes and longitudes are
+    /// rotated latitudes and longitudes, not geographic ones.
+    pub fn rotated_grid(&self) -> &LatLngTemplate<'a> {
+        &self.grid
+    }
+
+    pub fn south_pole_latitude(&self) -> f64 {
+        let raw_value = read_u32_from_bytes(self.data(), 72).unwrap_or(0);
+        as_signed!(raw_value, 32, i32) as f64 * 1e-6
+    }
+
+    pub fn south_pole_longitude(&self) -> f64 {
+        read_u32_from_bytes(self.data(), 76).unwrap_or(0) as f64 * 1e-6
+    }
+
+    pub fn angle_of_rotation(&self) -> f64 {
+        read_f32_from_bytes(self.data(), 80).unwrap_or(0.0) as f64
+    }
+
+    pub fn projection(&self) -> RotatedPole {
+        RotatedPole::new(
+            self.south_pole_latitude(),
+            wrap_longitude(self.south_pole_longitude()),
+            self.angle_of_rotation(),
+        )
+    }
+}
+
+impl GridDefinitionTemplate for RotatedLatLngTemplate<'_> {
+    fn proj_name(&self) -> String {
+        "ob_tran".to_string()
+    }
+
+    fn proj_params(&self) -> HashMap<String, f64> {
+        let mut params = HashMap::new();
+        params.insert("o_lat_p".to_string(), -self.south_pole_latitude());
+        params.insert("o_lon_p".to_string(), self.angle_of_rotation());
+        params.insert(
+            "lon_0".to_string(),
+            wrap_longitude(self.south_pole_longitude()),
+        );
+
+        let earth_params = self.earth().proj_params().unwrap_or_default();
+        for (k, v) in earth_params {
+            params.insert(k, v);
+        }
+        params
+    }
+
+    fn proj_string(&self) -> String {
+        let earth_shape = self.earth().proj_string().unwrap_or("".to_string());
+        format!(
+            "+proj=ob_tran +o_proj=longlat +o_lat_p={} +o_lon_p={} +lon_0={}{earth_shape}",
+            -self.south_pole_latitude(),
+            self.angle_of_rotation(),
+            wrap_longitude(self.south_pole_longitude()),
+        )
+    }
+
+    fn crs(&self) -> String {
+        // A rotated pole has no EPSG code
+        "unknown".to_string()
+    }
+
+    fn grid_point_count(&self) -> usize {
+        self.grid.grid_point_count()
+    }
+
+    fn is_regular_grid(&self) -> bool {
+        false
+    }
+
+    fn y_count(&self) -> usize {
+        self.grid.y_count()
+    }
+
+    fn x_count(&self) -> usize {
+        self.grid.x_count()
+    }
+
+    fn projector(&self) -> LatLngProjection {
+        LatLngProjection::Projected(ProjectedGrid {
+            x: RegularCoordinateIterator::new(
+                wrap_longitude(self.grid.start_longitude()),
+                self.grid.i_direction_increment(),
+                self.x_count(),
+            ),
+            y: RegularCoordinateIterator::new(
+                self.grid.start_latitude(),
+                self.grid.j_direction_increment(),
+                self.y_count(),
+            ),
+            projection: GridProjection::RotatedPole(self.projection()),
+            projection_name: self.proj_name(),
+            projection_params: self.proj_params(),
+        })
+    }
+
+    fn resolution_and_component_flags(&self) -> Option<ResolutionAndComponentFlags> {
+        Some(self.grid.resolution_component_flags())
+    }
+}
+
+/// Rotated grids commonly straddle the rotated prime meridian, which GRIB
+/// encodes as longitudes just under 360
+fn wrap_longitude(lng: f64) -> f64 {
+    if lng > 180.0 {
+        lng - 360.0
+    } else {
+        lng
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 4 x 3 grid at 0.5 degree spacing starting at rotated (-5, -5), with
+    /// the north pole at 40N 170W
+    fn cosmo_section() -> Vec<u8> {
+        let micro = |v: f64| (v * 1e6).round() as u32;
+        let signed_micro = |v: f64| micro(v.abs()) | if v < 0.0 { 0x8000_0000 } else { 0 };
+
+        let mut section = vec![0u8, 0, 0, 84, 3, 0];
+        section.extend_from_slice(&12u32.to_be_bytes());
+        section.extend_from_slice(&[0, 0, 0, 1, 6, 0, 0, 0, 0, 0]);
+        section.extend_from_slice(&[0; 10]);
+        section.extend_from_slice(&4u32.to_be_bytes());
+        section.extend_from_slice(&3u32.to_be_bytes());
+        section.extend_from_slice(&0u32.to_be_bytes());
+        section.extend_from_slice(&u32::MAX.to_be_bytes());
+        section.extend_from_slice(&signed_micro(-5.0).to_be_bytes());
+        section.extend_from_slice(&micro(355.0).to_be_bytes());
+        section.push(48);
+        section.extend_from_slice(&signed_micro(-4.0).to_be_bytes());
+        section.extend_from_slice(&micro(356.5).to_be_bytes());
+        section.extend_from_slice(&micro(0.5).to_be_bytes());
+        section.extend_from_slice(&micro(0.5).to_be_bytes());
+        section.push(64);
+        section.extend_from_slice(&signed_micro(-40.0).to_be_bytes());
+        section.extend_from_slice(&micro(10.0).to_be_bytes());
+        section.extend_from_slice(&0f32.to_be_bytes());
+        section
+    }
+
+    #[test]
+    fn test_rotated_pole_parameters() {
+        let template = RotatedLatLngTemplate::new(cosmo_section());
+        assert_eq!(template.template_number(), 1);
+        assert!((template.south_pole_latitude() + 40.0).abs() < 1e-9);
+        assert!((template.south_pole_longitude() - 10.0).abs() < 1e-9);
+        assert_eq!(template.angle_of_rotation(), 0.0);
+        assert_eq!(template.x_count(), 4);
+        assert_eq!(template.y_count(), 3);
+        assert!(!template.is_regular_grid());
+        assert_eq!(
+            template.proj_string(),
+            "+proj=ob_tran +o_proj=longlat +o_lat_p=40 +o_lon_p=0 +lon_0=10 +a=6371229 +b=6371229"
+        );
+    }
+
+    #[test]
+    fn test_rotated_grid_coordinates() {
+        let projector = RotatedLatLngTemplate::new(cosmo_section()).projector();
+        assert!(!projector.is_regular_latlng_grid());
+        assert_eq!(projector.x(), vec![-5.0, -4.5, -4.0, -3.5]);
+        assert_eq!(projector.y(), vec![-5.0, -4.5, -4.0]);
+
+        // Geographic coordinates, one per grid point, row-major
+        let (lats, lngs) = projector.lat_lng();
+        assert_eq!(lats.len(), 12);
+        assert!((lats[0] - 44.765_178_904_2).abs() < 1e-9, "lat {}", lats[0]);
+        assert!((lngs[0] - 2.975_852_266_7).abs() < 1e-9, "lng {}", lngs[0]);
+        assert!(lats[4] > lats[0], "rows step north");
+        assert!(lngs[1] > lngs[0], "columns step east");
+    }
+}
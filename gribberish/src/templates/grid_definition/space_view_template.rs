@@ -0,0 +1,332 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use crate::{
+    error::GribberishError,
+    templates::template::{Template, TemplateType},
+    utils::{
+        iter::projection::{
+            GridProjection, LatLngProjection, ProjectedGrid, RegularCoordinateIterator,
+        },
+        projections::geostationary::Geostationary,
+        read_u32_from_bytes,
+    },
+};
+
+use super::{
+    earth_shape::EarthShapeDefinition,
+    tables::{
+        EarthShape, ResolutionAndComponent, ResolutionAndComponentFlags, ScanningMode,
+        ScanningModeFlags,
+    },
+    GridDefinitionTemplate,
+};
+
+/// Template 3.90. Imagery as seen by a geostationary satellite, the grid is
+/// regular in the satellite's scanning angles and is projected with `geos`.
+///
+/// Grid lengths are derived from the apparent diameter of the earth the same
+/// way ecCodes does, and the sub-satellite point is assumed to lie on the
+/// equator. Grid points beyond the earth's limb have no latitude or longitude.
+pub struct SpaceViewTemplate<'a> {
+    data: Cow<'a, [u8]>,
+}
+
+impl Template for SpaceViewTemplate<'_> {
+    fn template_type(&self) -> TemplateType {
+        TemplateType::Grid
+    }
+
+    fn template_number(&self) -> u16 {
+        90
+    }
+
+    fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn template_name(&self) -> &str {
+        "Space View Perspective or Orthographic"
+    }
+}
+
+impl<'a> SpaceViewTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>) -> Self {
+        SpaceViewTemplate { data: data.into() }
+    }
+
+    fn earth(&self) -> EarthShapeDefinition<'_> {
+        EarthShapeDefinition::new(&self.data)
+    }
+
+    pub fn earth_shape(&self) -> EarthShape {
+        self.earth().shape()
+    }
+
+    pub fn number_of_points_on_x_axis(&self) -> u32 {
+        read_u32_from_bytes(&self.data, 30).unwrap_or(0)
+    }
+
+    pub fn number_of_points_on_y_axis(&self) -> u32 {
+        read_u32_from_bytes(&self.data, 34).unwrap_or(0)
+    }
+
+    /// `Lap`: latitude of the sub-satellite point
+    pub fn sub_satellite_latitude(&self) -> f64 {
+        let raw = read_u32_from_bytes(&self.data, 38).unwrap_or(0);
+        as_signed!(raw, 32, i32) as f64 * 1e-6
+    }
+
+    /// `Lop`: longitude of the sub-satellite point
+    pub fn sub_satellite_longitude(&self) -> f64 {
+        let raw = read_u32_from_bytes(&self.data, 42).unwrap_or(0);
+        wrap_longitude(as_signed!(raw, 32, i32) as f64 * 1e-6)
+    }
+
+    /// `dx`: apparent diameter of the earth in grid lengths along the x axis
+    pub fn x_apparent_diameter(&self) -> f64 {
+        read_u32_from_bytes(&self.data, 47).unwrap_or(0) as f64
+    }
+
+    /// `dy`: apparent diameter of the earth in grid lengths along the y axis
+    pub fn y_apparent_diameter(&self) -> f64 {
+        read_u32_from_bytes(&self.data, 51).unwrap_or(0) as f64
+    }
+
+    /// `Xp`: x coordinate of the sub-satellite point, in grid lengths
+    pub fn sub_satellite_x(&self) -> f64 {
+        read_u32_from_bytes(&self.data, 55).unwrap_or(0) as f64 * 1e-3
+    }
+
+    /// `Yp`: y coordinate of the sub-satellite point, in grid lengths
+    pub fn sub_satellite_y(&self) -> f64 {
+        read_u32_from_bytes(&self.data, 59).unwrap_or(0) as f64 * 1e-3
+    }
+
+    pub fn resolution_component_flags(&self) -> ResolutionAndComponentFlags {
+        ResolutionAndComponent::read_flags(self.data[46])
+    }
+
+    pub fn scanning_mode_flags(&self) -> ScanningModeFlags {
+        ScanningMode::read_flags(self.data[63])
+    }
+
+    /// Angle between the increasing y axis and the meridian of the
+    /// sub-satellite point, in the direction of increasing latitude
+    pub fn orientation_of_the_grid(&self) -> f64 {
+        let raw = read_u32_from_bytes(&self.data, 64).unwrap_or(0);
+        as_signed!(raw, 32, i32) as f64 * 1e-6
+    }
+
+    /// `Nr`: altitude of the camera from the earth's centre, in equatorial radii
+    pub fn camera_altitude(&self) -> f64 {
+        read_u32_from_bytes(&self.data, 68).unwrap_or(0) as f64 * 1e-6
+    }
+
+    /// `Xo`: x coordinate of the origin of the sector image
+    pub fn sector_origin_x(&self) -> f64 {
+        read_u32_from_bytes(&self.data, 72).unwrap_or(0) as f64
+    }
+
+    /// `Yo`: y coordinate of the origin of the sector image
+    pub fn sector_origin_y(&self) -> f64 {
+        read_u32_from_bytes(&self.data, 76).unwrap_or(0) as f64
+    }
+
+    /// Height of the satellite above the equator, in metres
+    pub fn satellite_height(&self) -> Result<f64, GribberishError> {
+        let ellipsoid = self.earth().ellipsoid()?;
+        Ok((self.camera_altitude() - 1.0) * ellipsoid.A)
+    }
+
+    /// Scanning angles, in radians, spanned by one grid length along x and y
+    pub fn scanning_angle_steps(&self) -> Result<(f64, f64), GribberishError> {
+        if self.camera_altitude() <= 1.0 {
+            return Err(GribberishError::GridTemplateError(
+                "unimplemented: orthographic space view".into(),
+            ));
+        }
+
+        let ellipsoid = self.earth().ellipsoid()?;
+        let angular_size = 2.0 * (1.0 / self.camera_altitude()).asin();
+        let rx = angular_size / self.x_apparent_diameter();
+        let ry = ellipsoid.B / ellipsoid.A * angular_size / self.y_apparent_diameter();
+        Ok((rx, ry))
+    }
+
+    pub fn projection(&self) -> Result<Geostationary, GribberishError> {
+        Ok(Geostationary::new(
+            self.sub_satellite_longitude(),
+            self.satellite_height()?,
+            self.earth().ellipsoid()?,
+        ))
+    }
+
+    /// The projected x and y axes, in metres
+    fn axes(
+        &self,
+    ) -> Result<(RegularCoordinateIterator, RegularCoordinateIterator), GribberishError> {
+        let height = self.satellite_height()?;
+        let (rx, ry) = self.scanning_angle_steps()?;
+        let flags = self.scanning_mode_flags();
+
+        // Grid lengths from the sub-satellite point to the first grid point
+        let xp = self.sub_satellite_x() - self.sector_origin_x();
+        let yp = self.sub_satellite_y() - self.sector_origin_y();
+
+        let (mut x_start, mut x_step) = if flags[0] == ScanningMode::PlusI {
+            (-xp * rx * height, rx * height)
+        } else {
+            (xp * rx * height, -rx * height)
+        };
+        let (mut y_start, mut y_step) = if flags[1] == ScanningMode::PlusJ {
+            (-yp * ry * height, ry * height)
+        } else {
+            (yp * ry * height, -ry * height)
+        };
+
+        // An upside down image, as some Meteosat products are written
+        if (self.orientation_of_the_grid().abs() - 180.0).abs() < 1e-6 {
+            (x_start, x_step) = (-x_start, -x_step);
+            (y_start, y_step) = (-y_start, -y_step);
+        }
+
+        Ok((
+            RegularCoordinateIterator::new(x_start, x_step, self.x_count()),
+            RegularCoordinateIterator::new(y_start, y_step, self.y_count()),
+        ))
+    }
+}
+
+impl GridDefinitionTemplate for SpaceViewTemplate<'_> {
+    fn proj_name(&self) -> String {
+        "geos".to_string()
+    }
+
+    fn proj_params(&self) -> HashMap<String, f64> {
+        let mut params = HashMap::new();
+        params.insert("h".to_string(), self.satellite_height().unwrap_or(0.0));
+        params.insert("lon_0".to_string(), self.sub_satellite_longitude());
+
+        let earth_params = self.earth().proj_params().unwrap_or_default();
+        for (k, v) in earth_params {
+            params.insert(k, v);
+        }
+        params
+    }
+
+    fn proj_string(&self) -> String {
+        let earth_shape = self.earth().proj_string().unwrap_or("".to_string());
+        format!(
+            "+proj=geos +h={} +lon_0={} +sweep=y{earth_shape}",
+            self.satellite_height().unwrap_or(0.0),
+            self.sub_satellite_longitude()
+        )
+    }
+
+    fn crs(&self) -> String {
+        "unknown".to_string()
+    }
+
+    fn grid_point_count(&self) -> usize {
+        (self.number_of_points_on_x_axis() * self.number_of_points_on_y_axis()) as usize
+    }
+
+    fn is_regular_grid(&self) -> bool {
+        false
+    }
+
+    fn y_count(&self) -> usize {
+        self.number_of_points_on_y_axis() as usize
+    }
+
+    fn x_count(&self) -> usize {
+        self.number_of_points_on_x_axis() as usize
+    }
+
+    fn projector(&self) -> LatLngProjection {
+        let projection = self.projection().expect("Invalid projection");
+        let (x, y) = self.axes().expect("Invalid space view grid");
+
+        LatLngProjection::Projected(ProjectedGrid {
+            x,
+            y,
+            projection: GridProjection::Geostationary(projection),
+            projection_name: self.proj_name(),
+            projection_params: self.proj_params(),
+        })
+    }
+
+    fn resolution_and_component_flags(&self) -> Option<ResolutionAndComponentFlags> {
+        Some(self.resolution_component_flags())
+    }
+}
+
+fn wrap_longitude(lng: f64) -> f64 {
+    if lng > 180.0 {
+        lng - 360.0
+    } else {
+        lng
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 9 x 9 image over 0E on the 6371229 m sphere, with the earth 10 pixels
+    /// across so the corners fall beyond the limb
+    fn full_disk_section() -> Vec<u8> {
+        let mut section = vec![0u8; 80];
+        section[0..4].copy_from_slice(&80u32.to_be_bytes());
+        section[4] = 3;
+        section[6..10].copy_from_slice(&81u32.to_be_bytes());
+        section[12..14].copy_from_slice(&90u16.to_be_bytes());
+        section[14] = 6;
+        section[30..34].copy_from_slice(&9u32.to_be_bytes());
+        section[34..38].copy_from_slice(&9u32.to_be_bytes());
+        section[47..51].copy_from_slice(&10u32.to_be_bytes());
+        section[51..55].copy_from_slice(&10u32.to_be_bytes());
+        section[55..59].copy_from_slice(&4000u32.to_be_bytes());
+        section[59..63].copy_from_slice(&4000u32.to_be_bytes());
+        // 42164 km from the centre of the earth
+        section[68..72].copy_from_slice(&6_617_845u32.to_be_bytes());
+        section
+    }
+
+    #[test]
+    fn test_space_view_grid() {
+        let template = SpaceViewTemplate::new(full_disk_section());
+        assert_eq!(template.template_number(), 90);
+        assert_eq!((template.y_count(), template.x_count()), (9, 9));
+        assert!(template.proj_string().starts_with("+proj=geos +h="));
+        let height = template.satellite_height().unwrap();
+        assert!((height - (6.617845 - 1.0) * 6_371_229.0).abs() < 1e-3);
+
+        let projector = template.projector();
+        let (lats, lngs) = projector.lat_lng();
+        assert_eq!(lats.len(), 81);
+
+        // The centre pixel looks straight down on the sub-satellite point
+        assert!(lats[40].abs() < 1e-9 && lngs[40].abs() < 1e-9);
+        // The first row is the northern edge, the first column the western one
+        assert!(lats[4] > 40.0, "{}", lats[4]);
+        assert!(lngs[36] < -40.0, "{}", lngs[36]);
+        assert!((lats[4 + 9] + lats[4 + 9 * 7]).abs() < 1e-9);
+        // The corners of the image are off the disk
+        assert!(lats[0].is_nan() && lngs[80].is_nan());
+
+        let (min_lng, min_lat, max_lng, max_lat) = projector.bbox();
+        assert!(min_lng < -40.0 && max_lng > 40.0);
+        assert!((min_lat + max_lat).abs() < 1e-9);
+
+        // An image looking entirely past the limb has no bounds
+        let mut section = full_disk_section();
+        section[55..59].copy_from_slice(&100_000u32.to_be_bytes());
+        section[59..63].copy_from_slice(&100_000u32.to_be_bytes());
+        let projector = SpaceViewTemplate::new(section).projector();
+        assert!(projector.lat_lng().0.iter().all(|v| v.is_nan()));
+        let (min_lng, min_lat, max_lng, max_lat) = projector.bbox();
+        assert!(min_lng.is_nan() && min_lat.is_nan() && max_lng.is_nan() && max_lat.is_nan());
+    }
+}
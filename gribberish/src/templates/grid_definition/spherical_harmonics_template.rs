@@ -0,0 +1,162 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use super::grid_definition_template::GridDefinitionTemplate;
+use super::tables::{
+    ResolutionAndComponentFlags, SpectralDataRepresentationMode, SpectralDataRepresentationType,
+};
+use crate::spectral::coefficient_value_count;
+use crate::templates::template::{Template, TemplateType};
+use crate::utils::iter::projection::{
+    LatLngProjection, PlateCareeProjection, RegularCoordinateIterator,
+};
+use crate::utils::read_u32_from_bytes;
+
+/// Template 3.50. The field is a set of spherical harmonic coefficients
+/// truncated by the pentagonal resolution parameters `J`, `K` and `M` rather
+/// than values on a grid, see [`crate::spectral`] to synthesise it onto one.
+pub struct SphericalHarmonicsTemplate<'a> {
+    data: Cow<'a, [u8]>,
+}
+
+impl Template for SphericalHarmonicsTemplate<'_> {
+    fn template_type(&self) -> TemplateType {
+        TemplateType::Grid
+    }
+
+    fn template_number(&self) -> u16 {
+        50
+    }
+
+    fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn template_name(&self) -> &str {
+        "Spherical Harmonic Coefficients"
+    }
+}
+
+impl<'a> SphericalHarmonicsTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>) -> Self {
+        SphericalHarmonicsTemplate { data: data.into() }
+    }
+
+    /// J, the pentagonal resolution parameter
+    pub fn j(&self) -> u32 {
+        read_u32_from_bytes(&self.data, 14).unwrap_or(0)
+    }
+
+    /// K, the pentagonal resolution parameter
+    pub fn k(&self) -> u32 {
+        read_u32_from_bytes(&self.data, 18).unwrap_or(0)
+    }
+
+    /// M, the pentagonal resolution parameter
+    pub fn m(&self) -> u32 {
+        read_u32_from_bytes(&self.data, 22).unwrap_or(0)
+    }
+
+    pub fn representation_type(&self) -> SpectralDataRepresentationType {
+        self.data[26].into()
+    }
+
+    pub fn representation_mode(&self) -> SpectralDataRepresentationMode {
+        self.data[27].into()
+    }
+
+    /// The truncation when `J`, `K` and `M` describe a triangular truncation
+    pub fn triangular_truncation(&self) -> Option<usize> {
+        (self.j() == self.k() && self.k() == self.m()).then_some(self.j() as usize)
+    }
+}
+
+impl GridDefinitionTemplate for SphericalHarmonicsTemplate<'_> {
+    fn proj_name(&self) -> String {
+        "spectral".to_string()
+    }
+
+    fn proj_params(&self) -> HashMap<String, f64> {
+        let mut params = HashMap::new();
+        params.insert("J".to_string(), self.j() as f64);
+        params.insert("K".to_string(), self.k() as f64);
+        params.insert("M".to_string(), self.m() as f64);
+        params
+    }
+
+    fn proj_string(&self) -> String {
+        // Coefficients have no location to project
+        "".to_string()
+    }
+
+    fn crs(&self) -> String {
+        "unknown".to_string()
+    }
+
+    fn grid_point_count(&self) -> usize {
+        coefficient_value_count(self.j() as usize, self.k() as usize, self.m() as usize)
+    }
+
+    fn is_regular_grid(&self) -> bool {
+        false
+    }
+
+    /// Coefficients are laid out along a single row
+    fn y_count(&self) -> usize {
+        1
+    }
+
+    fn x_count(&self) -> usize {
+        self.grid_point_count()
+    }
+
+    fn projector(&self) -> LatLngProjection {
+        LatLngProjection::PlateCaree(PlateCareeProjection {
+            latitudes: RegularCoordinateIterator::new(0.0, 1.0, 0),
+            longitudes: RegularCoordinateIterator::new(0.0, 1.0, 0),
+            projection_name: self.proj_name(),
+            projection_params: self.proj_params(),
+        })
+    }
+
+    fn resolution_and_component_flags(&self) -> Option<ResolutionAndComponentFlags> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A T159 section as written by ECMWF
+    fn t159_section() -> Vec<u8> {
+        let mut section = vec![0u8, 0, 0, 28, 3, 0];
+        section.extend_from_slice(&(160u32 * 161).to_be_bytes());
+        section.extend_from_slice(&[0, 0, 0, 50]);
+        for _ in 0..3 {
+            section.extend_from_slice(&159u32.to_be_bytes());
+        }
+        section.extend_from_slice(&[1, 1]);
+        section
+    }
+
+    #[test]
+    fn test_spherical_harmonics_template() {
+        let template = SphericalHarmonicsTemplate::new(t159_section());
+        assert_eq!(template.template_number(), 50);
+        assert_eq!((template.j(), template.k(), template.m()), (159, 159, 159));
+        assert_eq!(template.triangular_truncation(), Some(159));
+        assert_eq!(
+            template.representation_type(),
+            SpectralDataRepresentationType::AssociatedLegendre
+        );
+        assert_eq!(
+            template.representation_mode(),
+            SpectralDataRepresentationMode::ComplexPairs
+        );
+        assert_eq!(template.grid_point_count(), 160 * 161);
+        assert_eq!((template.y_count(), template.x_count()), (1, 160 * 161));
+        assert!(!template.is_regular_grid());
+        assert!(template.projector().lat_lng().0.is_empty());
+    }
+}
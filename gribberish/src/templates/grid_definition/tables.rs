@@ -2,6 +2,7 @@ use gribberish_macros::{DisplayDescription, FromValue};
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum EarthShape {
     #[description = "Earth assumed spherical with radius = 6,367,470.0 m"]
     Spherical = 0,
@@ -133,11 +134,118 @@ impl ScanningMode {
     }
 }
 
+/// Flags of the resolution and component flag table (3.3). Serialized as the
+/// flag octet they are read from, see [`resolution_and_component_octet`].
+#[derive(Clone, Copy, Eq, PartialEq, Debug, DisplayDescription, FromValue)]
+pub enum ResolutionAndComponent {
+    #[description = "i direction increments not given"]
+    IIncrementsNotGiven,
+    #[description = "i direction increments given"]
+    IIncrementsGiven,
+    #[description = "j direction increments not given"]
+    JIncrementsNotGiven,
+    #[description = "j direction increments given"]
+    JIncrementsGiven,
+    #[description = "Resolved u and v components of vector quantities relative to easterly and northerly directions"]
+    EarthRelative,
+    #[description = "Resolved u and v components of vector quantities relative to the defined grid in the direction of increasing x and y (or i and j) coordinates, respectively"]
+    GridRelative,
+}
+
+pub type ResolutionAndComponentFlags = [ResolutionAndComponent; 3];
+
+impl ResolutionAndComponent {
+    pub fn read_flags(data: u8) -> ResolutionAndComponentFlags {
+        let i_increments = match data & 32 == 0 {
+            true => ResolutionAndComponent::IIncrementsNotGiven,
+            false => ResolutionAndComponent::IIncrementsGiven,
+        };
+        let j_increments = match data & 16 == 0 {
+            true => ResolutionAndComponent::JIncrementsNotGiven,
+            false => ResolutionAndComponent::JIncrementsGiven,
+        };
+        let components = match data & 8 == 0 {
+            true => ResolutionAndComponent::EarthRelative,
+            false => ResolutionAndComponent::GridRelative,
+        };
+
+        [i_increments, j_increments, components]
+    }
+
+    /// Read the GRIB1 resolution and component flags (code table 7), which
+    /// give both direction increments in a single bit
+    pub fn read_grib1_flags(data: u8) -> ResolutionAndComponentFlags {
+        let increments = match data & 128 == 0 {
+            true => 0,
+            false => 32 | 16,
+        };
+        Self::read_flags(increments | (data & 8))
+    }
+
+    /// The GRIB2 flag octet `read_flags` reads `flags` from
+    pub fn flags_octet(flags: &ResolutionAndComponentFlags) -> u8 {
+        flags.iter().fold(0, |octet, flag| {
+            octet
+                | match flag {
+                    ResolutionAndComponent::IIncrementsGiven => 32,
+                    ResolutionAndComponent::JIncrementsGiven => 16,
+                    ResolutionAndComponent::GridRelative => 8,
+                    _ => 0,
+                }
+        })
+    }
+}
+
+/// Serde adapter writing resolution and component flags as their GRIB2 flag
+/// octet, for use with `#[serde(with = "...")]`
+#[cfg(feature = "serde")]
+pub mod resolution_and_component_octet {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{ResolutionAndComponent, ResolutionAndComponentFlags};
+
+    pub fn serialize<S: Serializer>(
+        flags: &Option<ResolutionAndComponentFlags>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        flags
+            .as_ref()
+            .map(ResolutionAndComponent::flags_octet)
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<ResolutionAndComponentFlags>, D::Error> {
+        Ok(Option::<u8>::deserialize(deserializer)?.map(ResolutionAndComponent::read_flags))
+    }
+}
+
+#[repr(u8)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, DisplayDescription, FromValue)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
+pub enum SpectralDataRepresentationType {
+    #[description = "associated Legendre functions of the first kind"]
+    AssociatedLegendre = 1,
+    #[description = "bi-Fourier representation"]
+    BiFourier = 2,
+    Missing = 255,
+}
+
+#[repr(u8)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug, DisplayDescription, FromValue)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
+pub enum SpectralDataRepresentationMode {
+    #[description = "complex coefficients stored as real and imaginary pairs, n increasing from m for each m in turn"]
+    ComplexPairs = 1,
+    Missing = 255,
+}
+
 #[cfg(test)]
 mod tests {
     use crate::templates::grid_definition::tables::ScanningMode;
 
-    use super::ProjectionCenter;
+    use super::{ProjectionCenter, ResolutionAndComponent};
 
     #[test]
     fn parse_projection_center() {
@@ -175,4 +283,32 @@ mod tests {
         assert_eq!(flags[0], ScanningMode::MinusI);
         assert_eq!(flags[1], ScanningMode::MinusJ);
     }
+
+    #[test]
+    fn parse_resolution_and_component() {
+        // HRRR: both increments given, winds relative to the grid
+        let flags = ResolutionAndComponent::read_flags(0b00111000);
+        assert_eq!(flags[0], ResolutionAndComponent::IIncrementsGiven);
+        assert_eq!(flags[1], ResolutionAndComponent::JIncrementsGiven);
+        assert_eq!(flags[2], ResolutionAndComponent::GridRelative);
+
+        let flags = ResolutionAndComponent::read_flags(0b00100000);
+        assert_eq!(flags[0], ResolutionAndComponent::IIncrementsGiven);
+        assert_eq!(flags[1], ResolutionAndComponent::JIncrementsNotGiven);
+        assert_eq!(flags[2], ResolutionAndComponent::EarthRelative);
+
+        let flags = ResolutionAndComponent::read_grib1_flags(0b10001000);
+        assert_eq!(flags[0], ResolutionAndComponent::IIncrementsGiven);
+        assert_eq!(flags[1], ResolutionAndComponent::JIncrementsGiven);
+        assert_eq!(flags[2], ResolutionAndComponent::GridRelative);
+
+        let flags = ResolutionAndComponent::read_grib1_flags(0);
+        assert_eq!(flags[0], ResolutionAndComponent::IIncrementsNotGiven);
+        assert_eq!(flags[2], ResolutionAndComponent::EarthRelative);
+
+        assert_eq!(
+            ResolutionAndComponent::flags_octet(&ResolutionAndComponent::read_flags(0b00101000)),
+            0b00101000
+        );
+    }
 }
@@ -0,0 +1,268 @@
+use crate::templates::template::{Template, TemplateType};
+use crate::utils::{read_i32_from_bytes, read_u16_from_bytes, read_u32_from_bytes};
+use chrono::{DateTime, Utc};
+use std::borrow::Cow;
+
+use super::product_template::{AerosolInterval, ProductTemplate};
+use super::tables::{FixedSurfaceType, GeneratingProcess, TimeUnit};
+use super::HorizontalAnalysisForecastTemplate;
+
+/// Length of the template as first published, when the forecast time was two
+/// octets wide. Some producers still write it.
+const LEGACY_SECTION_LENGTH: usize = 45;
+
+/// GRIB2 Product Definition Template 4.44
+///
+/// "Analysis or forecast at a horizontal level or in a horizontal layer at a
+/// point in time for aerosol."
+///
+/// Template 4.0 ([`super::HorizontalAnalysisForecastTemplate`]) with the aerosol
+/// type and an 11 octet particle size interval inserted after the parameter
+/// number, shifting everything from the generating process onwards by 13
+/// octets. The original 45 octet revision of the template had a two octet
+/// forecast time, which moves the fixed surfaces back by another 2 octets.
+pub struct AerosolHorizontalForecastTemplate<'a> {
+    data: Cow<'a, [u8]>,
+    discipline: u8,
+}
+
+impl Template for AerosolHorizontalForecastTemplate<'_> {
+    fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn template_number(&self) -> u16 {
+        44
+    }
+
+    fn template_type(&self) -> TemplateType {
+        TemplateType::Product
+    }
+
+    fn template_name(&self) -> &str {
+        "Analysis or forecast at a horizontal level or in a horizontal layer at a point in time for aerosol"
+    }
+}
+
+impl<'a> AerosolHorizontalForecastTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>, discipline: u8) -> Self {
+        AerosolHorizontalForecastTemplate {
+            data: data.into(),
+            discipline,
+        }
+    }
+
+    /// Whether the section uses the original layout with a two octet forecast time
+    pub fn is_legacy_layout(&self) -> bool {
+        read_u32_from_bytes(&self.data, 0).unwrap_or(0) as usize == LEGACY_SECTION_LENGTH
+    }
+
+    /// Index of the type of first fixed surface, which depends on the layout
+    fn surface_offset(&self) -> usize {
+        if self.is_legacy_layout() {
+            33
+        } else {
+            35
+        }
+    }
+
+    /// Aerosol type (code table 4.233)
+    pub fn aerosol_type(&self) -> u16 {
+        read_u16_from_bytes(&self.data, 11).unwrap_or(u16::MAX)
+    }
+
+    /// Type of interval for the first and second size (code table 4.91)
+    pub fn size_interval_type(&self) -> u8 {
+        self.data[13]
+    }
+
+    /// First size limit, in metres
+    pub fn first_size(&self) -> Option<f64> {
+        HorizontalAnalysisForecastTemplate::scale_value(
+            as_signed!(self.data[14], 8, i8),
+            as_signed!(read_u32_from_bytes(&self.data, 15).unwrap_or(0), 32, i32),
+        )
+    }
+
+    /// Second size limit, in metres
+    pub fn second_size(&self) -> Option<f64> {
+        HorizontalAnalysisForecastTemplate::scale_value(
+            as_signed!(self.data[19], 8, i8),
+            as_signed!(read_u32_from_bytes(&self.data, 20).unwrap_or(0), 32, i32),
+        )
+    }
+
+    pub fn observation_cutoff_hours_after_reference_time(&self) -> u16 {
+        read_u16_from_bytes(&self.data, 27).unwrap_or(0)
+    }
+
+    pub fn observation_cutoff_minutes_after_cutoff_time(&self) -> u8 {
+        self.data[29]
+    }
+
+    pub fn first_fixed_surface_scale_factor(&self) -> i8 {
+        as_signed!(self.data[self.surface_offset() + 1], 8, i8)
+    }
+
+    pub fn first_fixed_surface_scaled_value(&self) -> i32 {
+        let raw_value = read_u32_from_bytes(&self.data, self.surface_offset() + 2).unwrap_or(0);
+        as_signed!(raw_value, 32, i32)
+    }
+
+    pub fn second_fixed_surface_scale_factor(&self) -> i8 {
+        as_signed!(self.data[self.surface_offset() + 7], 8, i8)
+    }
+
+    pub fn second_fixed_surface_scaled_value(&self) -> i32 {
+        let raw_value = read_u32_from_bytes(&self.data, self.surface_offset() + 8).unwrap_or(0);
+        as_signed!(raw_value, 32, i32)
+    }
+}
+
+impl ProductTemplate for AerosolHorizontalForecastTemplate<'_> {
+    fn discipline(&self) -> u8 {
+        self.discipline
+    }
+
+    fn category_value(&self) -> u8 {
+        self.data[9]
+    }
+
+    fn parameter_value(&self) -> u8 {
+        self.data[10]
+    }
+
+    fn generating_process(&self) -> GeneratingProcess {
+        self.data[24].into()
+    }
+
+    fn time_unit(&self) -> TimeUnit {
+        self.data[30].into()
+    }
+
+    fn time_increment_unit(&self) -> Option<TimeUnit> {
+        None
+    }
+
+    fn time_interval(&self) -> i32 {
+        if self.is_legacy_layout() {
+            read_u16_from_bytes(&self.data, 31).unwrap_or(0) as i32
+        } else {
+            read_i32_from_bytes(&self.data, 31).unwrap_or(0)
+        }
+    }
+
+    fn time_increment_interval(&self) -> Option<u32> {
+        None
+    }
+
+    fn forecast_end_datetime(&self, _reference_date: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        None
+    }
+
+    fn first_fixed_surface_type(&self) -> FixedSurfaceType {
+        self.data[self.surface_offset()].into()
+    }
+
+    fn first_fixed_surface_value(&self) -> Option<f64> {
+        HorizontalAnalysisForecastTemplate::scale_value(
+            self.first_fixed_surface_scale_factor(),
+            self.first_fixed_surface_scaled_value(),
+        )
+    }
+
+    fn second_fixed_surface_type(&self) -> FixedSurfaceType {
+        self.data[self.surface_offset() + 6].into()
+    }
+
+    fn second_fixed_surface_value(&self) -> Option<f64> {
+        HorizontalAnalysisForecastTemplate::scale_value(
+            self.second_fixed_surface_scale_factor(),
+            self.second_fixed_surface_scaled_value(),
+        )
+    }
+
+    fn derived_forecast_type(&self) -> Option<super::tables::DerivedForecastType> {
+        None
+    }
+
+    fn statistical_process_type(&self) -> Option<super::tables::TypeOfStatisticalProcessing> {
+        None
+    }
+
+    fn chemical_constituent_type(&self) -> Option<u16> {
+        Some(self.aerosol_type())
+    }
+
+    fn aerosol_size_range(&self) -> Option<AerosolInterval> {
+        Some((self.first_size(), self.second_size()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sections::product_definition::ProductDefinitionSection;
+
+    /// Build a template 4.44 section for PM2.5 (aerosol type 62000, particles
+    /// smaller than 2.5e-6 m) at the surface, in either layout.
+    fn pdt44_section(legacy: bool) -> Vec<u8> {
+        let length = if legacy { 45 } else { 47 };
+        let mut data = vec![0u8; length];
+        data[0..4].copy_from_slice(&(length as u32).to_be_bytes());
+        data[4] = 4;
+        data[7..9].copy_from_slice(&44u16.to_be_bytes());
+        // octet 10-11: category 20, parameter 0 (mass density)
+        data[9] = 20;
+        data[10] = 0;
+        // octets 12-13: aerosol type
+        data[11..13].copy_from_slice(&62000u16.to_be_bytes());
+        // octet 14: smaller than second limit
+        data[13] = 4;
+        // octets 15-19: first size missing
+        data[14] = 0xff;
+        data[15..19].copy_from_slice(&[0xff; 4]);
+        // octets 20-24: second size 25e-7 m
+        data[19] = 7;
+        data[20..24].copy_from_slice(&25u32.to_be_bytes());
+        // octet 25: generating process (forecast)
+        data[24] = 2;
+        // octet 31: unit of time range (hour)
+        data[30] = 1;
+        let surface = if legacy {
+            data[31..33].copy_from_slice(&6u16.to_be_bytes());
+            33
+        } else {
+            data[31..35].copy_from_slice(&6u32.to_be_bytes());
+            35
+        };
+        // first fixed surface: 8 m above ground
+        data[surface] = 103;
+        data[surface + 2..surface + 6].copy_from_slice(&8u32.to_be_bytes());
+        data[surface + 6] = 255;
+        data
+    }
+
+    #[test]
+    fn parses_aerosol_size_in_both_layouts() {
+        for legacy in [false, true] {
+            let data = pdt44_section(legacy);
+            let section = ProductDefinitionSection::from_data(&data);
+            let template = section
+                .product_definition_template(0)
+                .expect("template 4.44 should be supported");
+
+            assert_eq!(template.parameter().unwrap().abbrev, "MASSDEN");
+            assert_eq!(template.chemical_constituent_type(), Some(62000));
+            let (first, second) = template.aerosol_size_range().unwrap();
+            assert_eq!(first, None);
+            assert!((second.unwrap() - 2.5e-6).abs() < 1e-15);
+            assert_eq!(template.aerosol_wavelength_range(), None);
+            assert_eq!(template.time_interval(), 6, "legacy: {legacy}");
+            assert_eq!(template.first_fixed_surface_value(), Some(8.0));
+            assert_eq!(
+                template.second_fixed_surface_type(),
+                crate::templates::product::tables::FixedSurfaceType::Missing
+            );
+        }
+    }
+}
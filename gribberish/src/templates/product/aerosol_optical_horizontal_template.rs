@@ -0,0 +1,260 @@
+use crate::templates::template::{Template, TemplateType};
+use crate::utils::{read_i32_from_bytes, read_u16_from_bytes, read_u32_from_bytes};
+use chrono::{DateTime, Utc};
+use std::borrow::Cow;
+
+use super::product_template::{AerosolInterval, ProductTemplate};
+use super::tables::{FixedSurfaceType, GeneratingProcess, TimeUnit};
+use super::HorizontalAnalysisForecastTemplate;
+
+/// GRIB2 Product Definition Template 4.48
+///
+/// "Analysis or forecast at a horizontal level or in a horizontal layer at a
+/// point in time for optical properties of aerosol."
+///
+/// Template 4.44 ([`super::AerosolHorizontalForecastTemplate`]) followed by an 11
+/// octet wavelength interval, so everything from the generating process onwards
+/// is shifted by 24 octets relative to template 4.0.
+pub struct AerosolOpticalPropertiesHorizontalForecastTemplate<'a> {
+    data: Cow<'a, [u8]>,
+    discipline: u8,
+}
+
+impl Template for AerosolOpticalPropertiesHorizontalForecastTemplate<'_> {
+    fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn template_number(&self) -> u16 {
+        48
+    }
+
+    fn template_type(&self) -> TemplateType {
+        TemplateType::Product
+    }
+
+    fn template_name(&self) -> &str {
+        "Analysis or forecast at a horizontal level or in a horizontal layer at a point in time for optical properties of aerosol"
+    }
+}
+
+impl<'a> AerosolOpticalPropertiesHorizontalForecastTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>, discipline: u8) -> Self {
+        AerosolOpticalPropertiesHorizontalForecastTemplate {
+            data: data.into(),
+            discipline,
+        }
+    }
+
+    /// Aerosol type (code table 4.233)
+    pub fn aerosol_type(&self) -> u16 {
+        read_u16_from_bytes(&self.data, 11).unwrap_or(u16::MAX)
+    }
+
+    /// Type of interval for the first and second size (code table 4.91)
+    pub fn size_interval_type(&self) -> u8 {
+        self.data[13]
+    }
+
+    /// First size limit, in metres
+    pub fn first_size(&self) -> Option<f64> {
+        HorizontalAnalysisForecastTemplate::scale_value(
+            as_signed!(self.data[14], 8, i8),
+            as_signed!(read_u32_from_bytes(&self.data, 15).unwrap_or(0), 32, i32),
+        )
+    }
+
+    /// Second size limit, in metres
+    pub fn second_size(&self) -> Option<f64> {
+        HorizontalAnalysisForecastTemplate::scale_value(
+            as_signed!(self.data[19], 8, i8),
+            as_signed!(read_u32_from_bytes(&self.data, 20).unwrap_or(0), 32, i32),
+        )
+    }
+
+    /// Type of interval for the first and second wavelength (code table 4.91)
+    pub fn wavelength_interval_type(&self) -> u8 {
+        self.data[24]
+    }
+
+    /// First wavelength limit, in metres
+    pub fn first_wavelength(&self) -> Option<f64> {
+        HorizontalAnalysisForecastTemplate::scale_value(
+            as_signed!(self.data[25], 8, i8),
+            as_signed!(read_u32_from_bytes(&self.data, 26).unwrap_or(0), 32, i32),
+        )
+    }
+
+    /// Second wavelength limit, in metres
+    pub fn second_wavelength(&self) -> Option<f64> {
+        HorizontalAnalysisForecastTemplate::scale_value(
+            as_signed!(self.data[30], 8, i8),
+            as_signed!(read_u32_from_bytes(&self.data, 31).unwrap_or(0), 32, i32),
+        )
+    }
+
+    pub fn observation_cutoff_hours_after_reference_time(&self) -> u16 {
+        read_u16_from_bytes(&self.data, 38).unwrap_or(0)
+    }
+
+    pub fn observation_cutoff_minutes_after_cutoff_time(&self) -> u8 {
+        self.data[40]
+    }
+
+    pub fn first_fixed_surface_scale_factor(&self) -> i8 {
+        as_signed!(self.data[47], 8, i8)
+    }
+
+    pub fn first_fixed_surface_scaled_value(&self) -> i32 {
+        as_signed!(read_u32_from_bytes(&self.data, 48).unwrap_or(0), 32, i32)
+    }
+
+    pub fn second_fixed_surface_scale_factor(&self) -> i8 {
+        as_signed!(self.data[53], 8, i8)
+    }
+
+    pub fn second_fixed_surface_scaled_value(&self) -> i32 {
+        as_signed!(read_u32_from_bytes(&self.data, 54).unwrap_or(0), 32, i32)
+    }
+}
+
+impl ProductTemplate for AerosolOpticalPropertiesHorizontalForecastTemplate<'_> {
+    fn discipline(&self) -> u8 {
+        self.discipline
+    }
+
+    fn category_value(&self) -> u8 {
+        self.data[9]
+    }
+
+    fn parameter_value(&self) -> u8 {
+        self.data[10]
+    }
+
+    fn generating_process(&self) -> GeneratingProcess {
+        self.data[35].into()
+    }
+
+    fn time_unit(&self) -> TimeUnit {
+        self.data[41].into()
+    }
+
+    fn time_increment_unit(&self) -> Option<TimeUnit> {
+        None
+    }
+
+    fn time_interval(&self) -> i32 {
+        read_i32_from_bytes(&self.data, 42).unwrap_or(0)
+    }
+
+    fn time_increment_interval(&self) -> Option<u32> {
+        None
+    }
+
+    fn forecast_end_datetime(&self, _reference_date: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        None
+    }
+
+    fn first_fixed_surface_type(&self) -> FixedSurfaceType {
+        self.data[46].into()
+    }
+
+    fn first_fixed_surface_value(&self) -> Option<f64> {
+        HorizontalAnalysisForecastTemplate::scale_value(
+            self.first_fixed_surface_scale_factor(),
+            self.first_fixed_surface_scaled_value(),
+        )
+    }
+
+    fn second_fixed_surface_type(&self) -> FixedSurfaceType {
+        self.data[52].into()
+    }
+
+    fn second_fixed_surface_value(&self) -> Option<f64> {
+        HorizontalAnalysisForecastTemplate::scale_value(
+            self.second_fixed_surface_scale_factor(),
+            self.second_fixed_surface_scaled_value(),
+        )
+    }
+
+    fn derived_forecast_type(&self) -> Option<super::tables::DerivedForecastType> {
+        None
+    }
+
+    fn statistical_process_type(&self) -> Option<super::tables::TypeOfStatisticalProcessing> {
+        None
+    }
+
+    fn chemical_constituent_type(&self) -> Option<u16> {
+        Some(self.aerosol_type())
+    }
+
+    fn aerosol_size_range(&self) -> Option<AerosolInterval> {
+        Some((self.first_size(), self.second_size()))
+    }
+
+    fn aerosol_wavelength_range(&self) -> Option<AerosolInterval> {
+        Some((self.first_wavelength(), self.second_wavelength()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sections::product_definition::ProductDefinitionSection;
+
+    /// Build a template 4.48 section for the optical thickness of dust
+    /// (aerosol type 62001) at 550 nm, for particles between 0.1 and 10 um.
+    fn pdt48_section() -> Vec<u8> {
+        let mut data = vec![0u8; 58];
+        data[0..4].copy_from_slice(&58u32.to_be_bytes());
+        data[4] = 4;
+        data[7..9].copy_from_slice(&48u16.to_be_bytes());
+        // octet 10-11: category 20, parameter 102 (optical thickness)
+        data[9] = 20;
+        data[10] = 102;
+        // octets 12-13: aerosol type
+        data[11..13].copy_from_slice(&62001u16.to_be_bytes());
+        // octets 14-24: between first and second size, 1e-7 to 1e-5 m
+        data[13] = 2;
+        data[14] = 7;
+        data[15..19].copy_from_slice(&1u32.to_be_bytes());
+        data[19] = 5;
+        data[20..24].copy_from_slice(&1u32.to_be_bytes());
+        // octets 25-35: equal to first wavelength, 550e-9 m
+        data[24] = 11;
+        data[25] = 9;
+        data[26..30].copy_from_slice(&550u32.to_be_bytes());
+        data[30] = 0xff;
+        data[31..35].copy_from_slice(&[0xff; 4]);
+        // octet 36: generating process (forecast)
+        data[35] = 2;
+        // octets 42-46: 12 hour forecast
+        data[41] = 1;
+        data[42..46].copy_from_slice(&12u32.to_be_bytes());
+        // octet 47: entire atmosphere
+        data[46] = 10;
+        data[52] = 255;
+        data
+    }
+
+    #[test]
+    fn parses_aerosol_size_and_wavelength() {
+        let data = pdt48_section();
+        let section = ProductDefinitionSection::from_data(&data);
+        let template = section
+            .product_definition_template(0)
+            .expect("template 4.48 should be supported");
+
+        assert_eq!(template.parameter().unwrap().abbrev, "APTS");
+        assert_eq!(template.chemical_constituent_type(), Some(62001));
+        assert_eq!(template.time_interval(), 12);
+
+        let (first, second) = template.aerosol_size_range().unwrap();
+        assert!((first.unwrap() - 1e-7).abs() < 1e-18);
+        assert!((second.unwrap() - 1e-5).abs() < 1e-18);
+
+        let (first, second) = template.aerosol_wavelength_range().unwrap();
+        assert!((first.unwrap() - 550e-9).abs() < 1e-18);
+        assert_eq!(second, None);
+    }
+}
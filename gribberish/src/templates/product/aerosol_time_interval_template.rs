@@ -0,0 +1,215 @@
+use crate::templates::template::{Template, TemplateType};
+use crate::utils::{read_i32_from_bytes, read_u16_from_bytes, read_u32_from_bytes};
+use chrono::prelude::*;
+use std::borrow::Cow;
+
+use super::product_template::{AerosolInterval, ProductTemplate};
+use super::tables::{
+    FixedSurfaceType, GeneratingProcess, TimeUnit, TypeOfStatisticalProcessing, TypeOfTimeInterval,
+};
+use super::HorizontalAnalysisForecastTemplate;
+
+/// GRIB2 Product Definition Template 4.46
+///
+/// "Average, accumulation and/or extreme values or other statistically
+/// processed values at a horizontal level or in a horizontal layer in a
+/// continuous or non-continuous time interval for aerosol."
+///
+/// Template 4.8 ([`super::AverageAccumulationExtremeHorizontalAnalysisForecastTemplate`])
+/// with the aerosol type and size interval of template 4.44 inserted after the
+/// parameter number, so every octet from the generating process onwards is
+/// shifted by 13.
+pub struct AerosolTimeIntervalTemplate<'a> {
+    data: Cow<'a, [u8]>,
+    discipline: u8,
+}
+
+impl Template for AerosolTimeIntervalTemplate<'_> {
+    fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn template_number(&self) -> u16 {
+        46
+    }
+
+    fn template_type(&self) -> TemplateType {
+        TemplateType::Product
+    }
+
+    fn template_name(&self) -> &str {
+        "Average, accumulation and/or extreme values or other statistically processed values at a horizontal level or in a horizontal layer in a continuous or non-continuous time interval for aerosol"
+    }
+}
+
+impl<'a> AerosolTimeIntervalTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>, discipline: u8) -> Self {
+        Self {
+            data: data.into(),
+            discipline,
+        }
+    }
+
+    /// Aerosol type (code table 4.233)
+    pub fn aerosol_type(&self) -> u16 {
+        read_u16_from_bytes(&self.data, 11).unwrap_or(u16::MAX)
+    }
+
+    /// Type of interval for the first and second size (code table 4.91)
+    pub fn size_interval_type(&self) -> u8 {
+        self.data[13]
+    }
+
+    /// First size limit, in metres
+    pub fn first_size(&self) -> Option<f64> {
+        HorizontalAnalysisForecastTemplate::scale_value(
+            as_signed!(self.data[14], 8, i8),
+            as_signed!(read_u32_from_bytes(&self.data, 15).unwrap_or(0), 32, i32),
+        )
+    }
+
+    /// Second size limit, in metres
+    pub fn second_size(&self) -> Option<f64> {
+        HorizontalAnalysisForecastTemplate::scale_value(
+            as_signed!(self.data[19], 8, i8),
+            as_signed!(read_u32_from_bytes(&self.data, 20).unwrap_or(0), 32, i32),
+        )
+    }
+
+    pub fn observation_cutoff_hours_after_reference_time(&self) -> u16 {
+        read_u16_from_bytes(&self.data, 27).unwrap_or(0)
+    }
+
+    pub fn observation_cutoff_minutes_after_cutoff_time(&self) -> u8 {
+        self.data[29]
+    }
+
+    pub fn forecast_time(&self) -> i32 {
+        read_i32_from_bytes(&self.data, 31).unwrap_or(0)
+    }
+
+    pub fn first_fixed_surface_scale_factor(&self) -> i8 {
+        as_signed!(self.data[36], 8, i8)
+    }
+
+    pub fn first_fixed_surface_scaled_value(&self) -> i32 {
+        as_signed!(read_u32_from_bytes(&self.data, 37).unwrap_or(0), 32, i32)
+    }
+
+    pub fn second_fixed_surface_scale_factor(&self) -> i8 {
+        as_signed!(self.data[42], 8, i8)
+    }
+
+    pub fn second_fixed_surface_scaled_value(&self) -> i32 {
+        as_signed!(read_u32_from_bytes(&self.data, 43).unwrap_or(0), 32, i32)
+    }
+
+    pub fn valid_end_date(&self) -> Option<DateTime<Utc>> {
+        let data = self.data();
+        let year = read_u16_from_bytes(data, 47).unwrap_or(0) as i32;
+        let month = data[49] as u32;
+        let day = data[50] as u32;
+        let hour = data[51] as u32;
+        let minute = data[52] as u32;
+        let second = data[53] as u32;
+
+        Utc.with_ymd_and_hms(year, month, day, hour, minute, second)
+            .single()
+    }
+
+    pub fn number_of_time_ranges(&self) -> u8 {
+        self.data()[54]
+    }
+
+    pub fn number_of_values_missing_from_stats(&self) -> u32 {
+        read_u32_from_bytes(self.data(), 55).unwrap_or(0)
+    }
+
+    pub fn type_of_time_interval(&self) -> TypeOfTimeInterval {
+        self.data()[60].into()
+    }
+
+    pub fn statistical_process_time_unit(&self) -> TimeUnit {
+        self.data()[61].into()
+    }
+
+    pub fn statistical_process_time_interval(&self) -> u32 {
+        read_u32_from_bytes(self.data(), 62).unwrap_or(0)
+    }
+}
+
+impl ProductTemplate for AerosolTimeIntervalTemplate<'_> {
+    fn discipline(&self) -> u8 {
+        self.discipline
+    }
+
+    fn category_value(&self) -> u8 {
+        self.data[9]
+    }
+
+    fn parameter_value(&self) -> u8 {
+        self.data[10]
+    }
+
+    fn generating_process(&self) -> GeneratingProcess {
+        self.data[24].into()
+    }
+
+    fn time_unit(&self) -> TimeUnit {
+        self.data[30].into()
+    }
+
+    fn time_increment_unit(&self) -> Option<TimeUnit> {
+        Some(self.data()[66].into())
+    }
+
+    fn time_interval(&self) -> i32 {
+        self.forecast_time()
+    }
+
+    fn time_increment_interval(&self) -> Option<u32> {
+        Some(read_u32_from_bytes(self.data(), 67).unwrap_or(0))
+    }
+
+    fn forecast_end_datetime(&self, _reference_date: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.valid_end_date()
+    }
+
+    fn first_fixed_surface_type(&self) -> FixedSurfaceType {
+        self.data[35].into()
+    }
+
+    fn first_fixed_surface_value(&self) -> Option<f64> {
+        HorizontalAnalysisForecastTemplate::scale_value(
+            self.first_fixed_surface_scale_factor(),
+            self.first_fixed_surface_scaled_value(),
+        )
+    }
+
+    fn second_fixed_surface_type(&self) -> FixedSurfaceType {
+        self.data[41].into()
+    }
+
+    fn second_fixed_surface_value(&self) -> Option<f64> {
+        HorizontalAnalysisForecastTemplate::scale_value(
+            self.second_fixed_surface_scale_factor(),
+            self.second_fixed_surface_scaled_value(),
+        )
+    }
+
+    fn derived_forecast_type(&self) -> Option<super::tables::DerivedForecastType> {
+        None
+    }
+
+    fn statistical_process_type(&self) -> Option<TypeOfStatisticalProcessing> {
+        Some(self.data()[59].into())
+    }
+
+    fn chemical_constituent_type(&self) -> Option<u16> {
+        Some(self.aerosol_type())
+    }
+
+    fn aerosol_size_range(&self) -> Option<AerosolInterval> {
+        Some((self.first_size(), self.second_size()))
+    }
+}
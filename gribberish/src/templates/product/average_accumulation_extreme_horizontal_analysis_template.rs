@@ -1,6 +1,7 @@
 use crate::templates::template::{Template, TemplateType};
 use crate::utils::{read_i32_from_bytes, read_u16_from_bytes, read_u32_from_bytes};
 use chrono::prelude::*;
+use std::borrow::Cow;
 
 use super::product_template::ProductTemplate;
 use super::tables::{
@@ -8,12 +9,12 @@ use super::tables::{
 };
 use super::HorizontalAnalysisForecastTemplate;
 
-pub struct AverageAccumulationExtremeHorizontalAnalysisForecastTemplate {
-    data: Vec<u8>,
+pub struct AverageAccumulationExtremeHorizontalAnalysisForecastTemplate<'a> {
+    data: Cow<'a, [u8]>,
     discipline: u8,
 }
 
-impl Template for AverageAccumulationExtremeHorizontalAnalysisForecastTemplate {
+impl Template for AverageAccumulationExtremeHorizontalAnalysisForecastTemplate<'_> {
     fn data(&self) -> &[u8] {
         &self.data
     }
@@ -33,9 +34,12 @@ impl Template for AverageAccumulationExtremeHorizontalAnalysisForecastTemplate {
     }
 }
 
-impl AverageAccumulationExtremeHorizontalAnalysisForecastTemplate {
-    pub fn new(data: Vec<u8>, discipline: u8) -> Self {
-        Self { data, discipline }
+impl<'a> AverageAccumulationExtremeHorizontalAnalysisForecastTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>, discipline: u8) -> Self {
+        Self {
+            data: data.into(),
+            discipline,
+        }
     }
 
     pub fn category_value(&self) -> u8 {
@@ -87,7 +91,7 @@ impl AverageAccumulationExtremeHorizontalAnalysisForecastTemplate {
         let minute = data[39] as u32;
         let second = data[40] as u32;
 
-        Utc.with_ymd_and_hms(year as i32, month, day, hour, minute, second)
+        Utc.with_ymd_and_hms(year, month, day, hour, minute, second)
             .unwrap()
     }
 
@@ -112,7 +116,7 @@ impl AverageAccumulationExtremeHorizontalAnalysisForecastTemplate {
     }
 }
 
-impl ProductTemplate for AverageAccumulationExtremeHorizontalAnalysisForecastTemplate {
+impl ProductTemplate for AverageAccumulationExtremeHorizontalAnalysisForecastTemplate<'_> {
     fn discipline(&self) -> u8 {
         self.discipline
     }
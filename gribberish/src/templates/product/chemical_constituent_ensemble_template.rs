@@ -0,0 +1,173 @@
+use crate::templates::template::{Template, TemplateType};
+use crate::utils::{read_i32_from_bytes, read_u16_from_bytes, read_u32_from_bytes};
+use chrono::{DateTime, Utc};
+use std::borrow::Cow;
+
+use super::product_template::ProductTemplate;
+use super::tables::{EnsembleForecastType, FixedSurfaceType, GeneratingProcess, TimeUnit};
+use super::HorizontalAnalysisForecastTemplate;
+
+/// GRIB2 Product Definition Template 4.41
+///
+/// "Individual ensemble forecast, control and perturbed, at a horizontal level
+/// or in a horizontal layer at a point in time for atmospheric chemical
+/// constituents."
+///
+/// Template 4.1 ([`super::HorizontalEnsembleForecastTemplate`]) with the same two
+/// octet constituent type as template 4.40 inserted after the parameter number.
+pub struct ChemicalConstituentEnsembleForecastTemplate<'a> {
+    data: Cow<'a, [u8]>,
+    discipline: u8,
+}
+
+impl Template for ChemicalConstituentEnsembleForecastTemplate<'_> {
+    fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn template_number(&self) -> u16 {
+        41
+    }
+
+    fn template_type(&self) -> TemplateType {
+        TemplateType::Product
+    }
+
+    fn template_name(&self) -> &str {
+        "Individual ensemble forecast, control and perturbed, at a horizontal level or in a horizontal layer at a point in time for atmospheric chemical constituents"
+    }
+}
+
+impl<'a> ChemicalConstituentEnsembleForecastTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>, discipline: u8) -> Self {
+        ChemicalConstituentEnsembleForecastTemplate {
+            data: data.into(),
+            discipline,
+        }
+    }
+
+    /// Atmospheric chemical constituent type (code table 4.230)
+    pub fn atmospheric_chemical_constituent_type(&self) -> u16 {
+        read_u16_from_bytes(&self.data, 11).unwrap_or(u16::MAX)
+    }
+
+    pub fn observation_cutoff_hours_after_reference_time(&self) -> u16 {
+        read_u16_from_bytes(&self.data, 16).unwrap_or(0)
+    }
+
+    pub fn observation_cutoff_minutes_after_cutoff_time(&self) -> u8 {
+        self.data[18]
+    }
+
+    pub fn first_fixed_surface_scale_factor(&self) -> i8 {
+        as_signed!(self.data[25], 8, i8)
+    }
+
+    pub fn first_fixed_surface_scaled_value(&self) -> i32 {
+        as_signed!(read_u32_from_bytes(&self.data, 26).unwrap_or(0), 32, i32)
+    }
+
+    pub fn second_fixed_surface_scale_factor(&self) -> i8 {
+        as_signed!(self.data[31], 8, i8)
+    }
+
+    pub fn second_fixed_surface_scaled_value(&self) -> i32 {
+        as_signed!(read_u32_from_bytes(&self.data, 32).unwrap_or(0), 32, i32)
+    }
+
+    pub fn type_of_ensemble_forecast(&self) -> EnsembleForecastType {
+        self.data[36].into()
+    }
+
+    pub fn perturbation_number(&self) -> u8 {
+        self.data[37]
+    }
+
+    pub fn number_of_forecasts_in_ensemble(&self) -> u8 {
+        self.data[38]
+    }
+}
+
+impl ProductTemplate for ChemicalConstituentEnsembleForecastTemplate<'_> {
+    fn discipline(&self) -> u8 {
+        self.discipline
+    }
+
+    fn category_value(&self) -> u8 {
+        self.data[9]
+    }
+
+    fn parameter_value(&self) -> u8 {
+        self.data[10]
+    }
+
+    fn generating_process(&self) -> GeneratingProcess {
+        self.data[13].into()
+    }
+
+    fn time_unit(&self) -> TimeUnit {
+        self.data[19].into()
+    }
+
+    fn time_increment_unit(&self) -> Option<TimeUnit> {
+        None
+    }
+
+    fn time_interval(&self) -> i32 {
+        read_i32_from_bytes(&self.data, 20).unwrap_or(0)
+    }
+
+    fn time_increment_interval(&self) -> Option<u32> {
+        None
+    }
+
+    fn forecast_end_datetime(&self, _reference_date: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        None
+    }
+
+    fn first_fixed_surface_type(&self) -> FixedSurfaceType {
+        self.data[24].into()
+    }
+
+    fn first_fixed_surface_value(&self) -> Option<f64> {
+        HorizontalAnalysisForecastTemplate::scale_value(
+            self.first_fixed_surface_scale_factor(),
+            self.first_fixed_surface_scaled_value(),
+        )
+    }
+
+    fn second_fixed_surface_type(&self) -> FixedSurfaceType {
+        self.data[30].into()
+    }
+
+    fn second_fixed_surface_value(&self) -> Option<f64> {
+        HorizontalAnalysisForecastTemplate::scale_value(
+            self.second_fixed_surface_scale_factor(),
+            self.second_fixed_surface_scaled_value(),
+        )
+    }
+
+    fn derived_forecast_type(&self) -> Option<super::tables::DerivedForecastType> {
+        None
+    }
+
+    fn statistical_process_type(&self) -> Option<super::tables::TypeOfStatisticalProcessing> {
+        None
+    }
+
+    fn ensemble_forecast_type(&self) -> Option<EnsembleForecastType> {
+        Some(self.type_of_ensemble_forecast())
+    }
+
+    fn perturbation_number(&self) -> Option<u8> {
+        Some(self.perturbation_number())
+    }
+
+    fn number_of_ensemble_members(&self) -> Option<u8> {
+        Some(self.number_of_forecasts_in_ensemble())
+    }
+
+    fn chemical_constituent_type(&self) -> Option<u16> {
+        Some(self.atmospheric_chemical_constituent_type())
+    }
+}
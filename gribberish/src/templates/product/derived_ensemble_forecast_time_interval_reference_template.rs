@@ -1,6 +1,7 @@
 use crate::templates::template::{Template, TemplateType};
 use crate::utils::{read_i32_from_bytes, read_u16_from_bytes, read_u32_from_bytes};
 use chrono::prelude::*;
+use std::borrow::Cow;
 
 use super::product_template::ProductTemplate;
 use super::tables::{
@@ -9,12 +10,12 @@ use super::tables::{
 };
 use super::HorizontalAnalysisForecastTemplate;
 
-pub struct DerivedEnsembleForecastTimeIntervalReferenceTemplate {
-    data: Vec<u8>,
+pub struct DerivedEnsembleForecastTimeIntervalReferenceTemplate<'a> {
+    data: Cow<'a, [u8]>,
     discipline: u8,
 }
 
-impl Template for DerivedEnsembleForecastTimeIntervalReferenceTemplate {
+impl Template for DerivedEnsembleForecastTimeIntervalReferenceTemplate<'_> {
     fn data(&self) -> &[u8] {
         &self.data
     }
@@ -34,9 +35,12 @@ impl Template for DerivedEnsembleForecastTimeIntervalReferenceTemplate {
     }
 }
 
-impl DerivedEnsembleForecastTimeIntervalReferenceTemplate {
-    pub fn new(data: Vec<u8>, discipline: u8) -> Self {
-        Self { data, discipline }
+impl<'a> DerivedEnsembleForecastTimeIntervalReferenceTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>, discipline: u8) -> Self {
+        Self {
+            data: data.into(),
+            discipline,
+        }
     }
 
     pub fn first_fixed_surface_scale_factor(&self) -> i8 {
@@ -64,7 +68,7 @@ impl DerivedEnsembleForecastTimeIntervalReferenceTemplate {
         let minute = data[39] as u32;
         let second = data[40] as u32;
 
-        Utc.with_ymd_and_hms(year as i32, month, day, hour, minute, second)
+        Utc.with_ymd_and_hms(year, month, day, hour, minute, second)
             .unwrap()
     }
 
@@ -93,7 +97,7 @@ impl DerivedEnsembleForecastTimeIntervalReferenceTemplate {
     }
 }
 
-impl ProductTemplate for DerivedEnsembleForecastTimeIntervalReferenceTemplate {
+impl ProductTemplate for DerivedEnsembleForecastTimeIntervalReferenceTemplate<'_> {
     fn discipline(&self) -> u8 {
         self.discipline
     }
@@ -1,16 +1,17 @@
 use crate::templates::template::{Template, TemplateType};
 use crate::utils::{read_i32_from_bytes, read_u32_from_bytes};
 use chrono::{DateTime, Utc};
+use std::borrow::Cow;
 
 use super::product_template::ProductTemplate;
 use super::tables::{DerivedForecastType, FixedSurfaceType, GeneratingProcess, TimeUnit};
 
-pub struct DerivedEnsembleHorizontalAnalysisForecastTemplate {
-    data: Vec<u8>,
+pub struct DerivedEnsembleHorizontalAnalysisForecastTemplate<'a> {
+    data: Cow<'a, [u8]>,
     discipline: u8,
 }
 
-impl Template for DerivedEnsembleHorizontalAnalysisForecastTemplate {
+impl Template for DerivedEnsembleHorizontalAnalysisForecastTemplate<'_> {
     fn data(&self) -> &[u8] {
         &self.data
     }
@@ -29,9 +30,12 @@ impl Template for DerivedEnsembleHorizontalAnalysisForecastTemplate {
     }
 }
 
-impl DerivedEnsembleHorizontalAnalysisForecastTemplate {
-    pub fn new(data: Vec<u8>, discipline: u8) -> Self {
-        DerivedEnsembleHorizontalAnalysisForecastTemplate { data, discipline }
+impl<'a> DerivedEnsembleHorizontalAnalysisForecastTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>, discipline: u8) -> Self {
+        DerivedEnsembleHorizontalAnalysisForecastTemplate {
+            data: data.into(),
+            discipline,
+        }
     }
 
     pub fn category_value(&self) -> u8 {
@@ -91,7 +95,7 @@ impl DerivedEnsembleHorizontalAnalysisForecastTemplate {
     }
 }
 
-impl ProductTemplate for DerivedEnsembleHorizontalAnalysisForecastTemplate {
+impl ProductTemplate for DerivedEnsembleHorizontalAnalysisForecastTemplate<'_> {
     fn discipline(&self) -> u8 {
         self.discipline
     }
@@ -1,6 +1,7 @@
 use crate::templates::template::{Template, TemplateType};
 use crate::utils::{read_i32_from_bytes, read_u16_from_bytes, read_u32_from_bytes};
 use chrono::prelude::*;
+use std::borrow::Cow;
 
 use super::product_template::ProductTemplate;
 use super::tables::{
@@ -11,12 +12,12 @@ use super::tables::{
 /// Product Definition Template 4.11
 /// Individual ensemble forecast, control and perturbed, at a horizontal level
 /// or in a horizontal layer in a continuous or non-continuous time interval
-pub struct EnsembleForecastTimeIntervalTemplate {
-    data: Vec<u8>,
+pub struct EnsembleForecastTimeIntervalTemplate<'a> {
+    data: Cow<'a, [u8]>,
     discipline: u8,
 }
 
-impl Template for EnsembleForecastTimeIntervalTemplate {
+impl Template for EnsembleForecastTimeIntervalTemplate<'_> {
     fn data(&self) -> &[u8] {
         &self.data
     }
@@ -34,9 +35,12 @@ impl Template for EnsembleForecastTimeIntervalTemplate {
     }
 }
 
-impl EnsembleForecastTimeIntervalTemplate {
-    pub fn new(data: Vec<u8>, discipline: u8) -> Self {
-        Self { data, discipline }
+impl<'a> EnsembleForecastTimeIntervalTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>, discipline: u8) -> Self {
+        Self {
+            data: data.into(),
+            discipline,
+        }
     }
 
     pub fn category_value(&self) -> u8 {
@@ -189,7 +193,7 @@ impl EnsembleForecastTimeIntervalTemplate {
     }
 }
 
-impl ProductTemplate for EnsembleForecastTimeIntervalTemplate {
+impl ProductTemplate for EnsembleForecastTimeIntervalTemplate<'_> {
     fn discipline(&self) -> u8 {
         self.discipline
     }
@@ -285,6 +289,10 @@ impl ProductTemplate for EnsembleForecastTimeIntervalTemplate {
         }
     }
 
+    fn ensemble_forecast_type(&self) -> Option<EnsembleForecastType> {
+        Some(self.type_of_ensemble_forecast())
+    }
+
     fn perturbation_number(&self) -> Option<u8> {
         Some(self.perturbation_number())
     }
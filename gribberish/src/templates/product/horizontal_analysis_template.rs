@@ -1,16 +1,17 @@
 use crate::templates::template::{Template, TemplateType};
 use crate::utils::{read_i32_from_bytes, read_u16_from_bytes, read_u32_from_bytes};
 use chrono::{DateTime, Utc};
+use std::borrow::Cow;
 
 use super::product_template::ProductTemplate;
 use super::tables::{FixedSurfaceType, GeneratingProcess, TimeUnit};
 
-pub struct HorizontalAnalysisForecastTemplate {
-    data: Vec<u8>,
+pub struct HorizontalAnalysisForecastTemplate<'a> {
+    data: Cow<'a, [u8]>,
     discipline: u8,
 }
 
-impl Template for HorizontalAnalysisForecastTemplate {
+impl Template for HorizontalAnalysisForecastTemplate<'_> {
     fn data(&self) -> &[u8] {
         &self.data
     }
@@ -28,9 +29,12 @@ impl Template for HorizontalAnalysisForecastTemplate {
     }
 }
 
-impl HorizontalAnalysisForecastTemplate {
-    pub fn new(data: Vec<u8>, discipline: u8) -> Self {
-        HorizontalAnalysisForecastTemplate { data, discipline }
+impl<'a> HorizontalAnalysisForecastTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>, discipline: u8) -> Self {
+        HorizontalAnalysisForecastTemplate {
+            data: data.into(),
+            discipline,
+        }
     }
 
     pub fn category_value(&self) -> u8 {
@@ -94,7 +98,7 @@ impl HorizontalAnalysisForecastTemplate {
     }
 }
 
-impl ProductTemplate for HorizontalAnalysisForecastTemplate {
+impl ProductTemplate for HorizontalAnalysisForecastTemplate<'_> {
     fn discipline(&self) -> u8 {
         self.discipline
     }
@@ -1,16 +1,17 @@
 use crate::templates::template::{Template, TemplateType};
 use crate::utils::{read_i32_from_bytes, read_u16_from_bytes, read_u32_from_bytes};
 use chrono::{DateTime, Utc};
+use std::borrow::Cow;
 
 use super::product_template::ProductTemplate;
 use super::tables::{EnsembleForecastType, FixedSurfaceType, GeneratingProcess, TimeUnit};
 
-pub struct HorizontalEnsembleForecastTemplate {
-    data: Vec<u8>,
+pub struct HorizontalEnsembleForecastTemplate<'a> {
+    data: Cow<'a, [u8]>,
     discipline: u8,
 }
 
-impl Template for HorizontalEnsembleForecastTemplate {
+impl Template for HorizontalEnsembleForecastTemplate<'_> {
     fn data(&self) -> &[u8] {
         &self.data
     }
@@ -28,9 +29,12 @@ impl Template for HorizontalEnsembleForecastTemplate {
     }
 }
 
-impl HorizontalEnsembleForecastTemplate {
-    pub fn new(data: Vec<u8>, discipline: u8) -> Self {
-        HorizontalEnsembleForecastTemplate { data, discipline }
+impl<'a> HorizontalEnsembleForecastTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>, discipline: u8) -> Self {
+        HorizontalEnsembleForecastTemplate {
+            data: data.into(),
+            discipline,
+        }
     }
 
     pub fn category_value(&self) -> u8 {
@@ -106,7 +110,7 @@ impl HorizontalEnsembleForecastTemplate {
     }
 }
 
-impl ProductTemplate for HorizontalEnsembleForecastTemplate {
+impl ProductTemplate for HorizontalEnsembleForecastTemplate<'_> {
     fn discipline(&self) -> u8 {
         self.discipline
     }
@@ -173,6 +177,10 @@ impl ProductTemplate for HorizontalEnsembleForecastTemplate {
         None
     }
 
+    fn ensemble_forecast_type(&self) -> Option<EnsembleForecastType> {
+        Some(self.type_of_ensemble_forecast())
+    }
+
     fn perturbation_number(&self) -> Option<u8> {
         Some(self.perturbation_number())
     }
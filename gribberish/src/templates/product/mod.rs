@@ -1,4 +1,9 @@
+pub mod aerosol_horizontal_template;
+pub mod aerosol_optical_horizontal_template;
+pub mod aerosol_time_interval_template;
 pub mod average_accumulation_extreme_horizontal_analysis_template;
+pub mod chemical_constituent_ensemble_template;
+pub mod chemical_constituent_horizontal_template;
 pub mod derived_ensemble_forecast_time_interval_reference_template;
 pub mod derived_ensemble_horizontal_analysis_template;
 pub mod derived_ensemble_horizontal_forecast_time_interval_template;
@@ -11,10 +16,17 @@ pub mod percentile_horizontal_time_interval_template;
 pub mod probability_horizontal_template;
 pub mod probability_horizontal_time_interval_template;
 pub mod product_template;
+pub mod satellite_product_template;
+pub mod simulated_satellite_template;
 pub mod tables;
 pub mod wave_period_range_horizontal_template;
 
+pub use aerosol_horizontal_template::AerosolHorizontalForecastTemplate;
+pub use aerosol_optical_horizontal_template::AerosolOpticalPropertiesHorizontalForecastTemplate;
+pub use aerosol_time_interval_template::AerosolTimeIntervalTemplate;
 pub use average_accumulation_extreme_horizontal_analysis_template::AverageAccumulationExtremeHorizontalAnalysisForecastTemplate;
+pub use chemical_constituent_ensemble_template::ChemicalConstituentEnsembleForecastTemplate;
+pub use chemical_constituent_horizontal_template::ChemicalConstituentHorizontalForecastTemplate;
 pub use derived_ensemble_forecast_time_interval_reference_template::DerivedEnsembleForecastTimeIntervalReferenceTemplate;
 pub use derived_ensemble_horizontal_analysis_template::DerivedEnsembleHorizontalAnalysisForecastTemplate;
 pub use ensemble_forecast_time_interval_template::EnsembleForecastTimeIntervalTemplate;
@@ -24,4 +36,6 @@ pub use percentile_horizontal_template::PercentileHorizontalTemplate;
 pub use percentile_horizontal_time_interval_template::PercentileHorizontalTimeIntervalTemplate;
 pub use probability_horizontal_template::ProbabilityHorizontalForecastTemplate;
 pub use probability_horizontal_time_interval_template::ProbabilityHorizontalTimeIntervalTemplate;
+pub use satellite_product_template::SatelliteProductTemplate;
+pub use simulated_satellite_template::SimulatedSatelliteTemplate;
 pub use wave_period_range_horizontal_template::WavePeriodRangeHorizontalForecastTemplate;
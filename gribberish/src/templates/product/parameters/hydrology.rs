@@ -3,6 +3,7 @@ use gribberish_types::Parameter;
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum HydrologyBasicProduct {
     #[description = "flash flood guidance"]
     #[abbrev = "FFLDG"]
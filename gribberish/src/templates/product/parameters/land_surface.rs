@@ -3,6 +3,7 @@ use gribberish_types::Parameter;
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum VegetationProduct {
     #[description = "land cover"]
     #[abbrev = "LAND"]
@@ -81,6 +82,7 @@ pub enum VegetationProduct {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum SoilProduct {
     #[description = "soil type"]
     #[abbrev = "SOTYP"]
@@ -131,6 +133,7 @@ pub enum SoilProduct {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum FireWeatherProduct {
     #[description = "fire outlook"]
     #[abbrev = "FIREOLK"]
@@ -3,6 +3,7 @@ use gribberish_types::Parameter;
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum TemperatureProduct {
     #[abbrev = "TMP"]
     #[unit = "K"]
@@ -76,6 +77,7 @@ pub enum TemperatureProduct {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum MoistureProduct {
     #[description = "specific humidity"]
     #[abbrev = "SPFH"]
@@ -300,6 +302,7 @@ pub enum MoistureProduct {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum MomentumProduct {
     #[description = "wind direction"]
     #[abbrev = "WDIR"]
@@ -461,6 +464,7 @@ pub enum MomentumProduct {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum CloudProduct {
     #[description = "total cloud cover"]
     #[abbrev = "TCDC"]
@@ -523,6 +527,7 @@ pub enum CloudProduct {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum ShortWaveRadiationProduct {
     #[description = "net shortwave radiation flux surface"]
     #[abbrev = "nswrs"]
@@ -701,6 +706,7 @@ pub enum ShortWaveRadiationProduct {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum MassProduct {
     #[abbrev = "PRES"]
     #[unit = "pa"]
@@ -786,6 +792,7 @@ pub enum MassProduct {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum RadarProduct {
     #[description = "base spectrum width"]
     #[abbrev = "BSWID"]
@@ -812,6 +819,7 @@ pub enum RadarProduct {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum ForecastRadarImagery {
     #[description = "echo top"]
     #[abbrev = "RETOP"]
@@ -834,6 +842,7 @@ pub enum ForecastRadarImagery {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum Electromagnetics {
     #[abbrev = "LTNGSD"]
     #[unit = "m-2 s-1"]
@@ -862,6 +871,7 @@ pub enum Electromagnetics {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum ThermodynamicStabilityProduct {
     #[description = "parcel lifted index"]
     #[abbrev = "PLI"]
@@ -956,6 +966,7 @@ pub enum ThermodynamicStabilityProduct {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum PhysicalAtmosphericProperties {
     #[description = "visibility"]
     #[abbrev = "VIS"]
@@ -1022,6 +1033,7 @@ pub enum PhysicalAtmosphericProperties {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum LongWaveRadiationProduct {
     #[description = "net long-wave radiation flux"]
     #[abbrev = "NLWRS"]
@@ -1044,6 +1056,7 @@ pub enum LongWaveRadiationProduct {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum TraceGasesProduct {
     #[description = "total ozone"]
     #[abbrev = "TOZNE"]
@@ -1114,6 +1127,7 @@ pub enum TraceGasesProduct {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum AerosolProduct {
     #[description = "aerosol type"]
     #[abbrev = "AEROT"]
@@ -1140,6 +1154,7 @@ pub enum AerosolProduct {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum AtmosphericChemicalConstituents {
     #[description = "mass density"]
     #[abbrev = "MASSDEN"]
@@ -1162,6 +1177,7 @@ pub enum AtmosphericChemicalConstituents {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum NcepMiscellaneous {
     #[description = "latitude"]
     #[abbrev = "NLAT"]
@@ -3,6 +3,7 @@ use gribberish_types::Parameter;
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum MRMSLightningProduct {
     #[description = "CG Average Lightning Density 1-min"]
     #[abbrev = "NLDN_CG_001min_AvgDensity"]
@@ -41,6 +42,7 @@ pub enum MRMSLightningProduct {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum MRMSConvectionProduct {
     #[description = "Rotation Track 0-2km AGL 30-min"]
     #[abbrev = "RotationTrack30min"]
@@ -91,6 +93,7 @@ pub enum MRMSConvectionProduct {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum MRMSPrecipitationProduct {
     #[description = "Surface Precipitation Type (Convective, Stratiform, Tropical, Hail, Snow)"]
     #[abbrev = "PrecipFlag"]
@@ -197,6 +200,7 @@ pub enum MRMSPrecipitationProduct {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum MRMSCompositeReflectivityProduct {
     #[description = "Composite Reflectivity Mosaic (optimal method)"]
     #[abbrev = "MergedReflectivityQCComposite"]
@@ -239,6 +243,7 @@ pub enum MRMSCompositeReflectivityProduct {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum MRMSMergedReflectivityProduct {
     #[description = "Base Reflectivity Mosaic (optimal method)"]
     #[abbrev = "MergedBaseReflectivityQC"]
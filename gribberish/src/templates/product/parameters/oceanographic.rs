@@ -3,6 +3,7 @@ use gribberish_types::Parameter;
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum WavesProduct {
     #[description = "primary wave spectra"]
     #[abbrev = "WVSP1"]
@@ -253,6 +254,7 @@ pub enum WavesProduct {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum CurrentsProduct {
     #[description = "current direction"]
     #[abbrev = "DIRC"]
@@ -278,6 +280,7 @@ pub enum CurrentsProduct {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum SurfacePropertiesProduct {
     #[description = "water temperature"]
     #[abbrev = "WTMP"]
@@ -323,6 +326,7 @@ pub enum SurfacePropertiesProduct {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum SubSurfacePropertiesProduct {
     #[description = "main thermocline depth"]
     #[abbrev = "MTHD"]
@@ -381,6 +385,7 @@ pub enum SubSurfacePropertiesProduct {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum IceProduct {
     #[description = "ice cover"]
     #[abbrev = "ICEC"]
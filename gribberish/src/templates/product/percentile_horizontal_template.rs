@@ -1,6 +1,7 @@
 use crate::templates::template::{Template, TemplateType};
 use crate::utils::{read_i32_from_bytes, read_u16_from_bytes, read_u32_from_bytes};
 use chrono::{DateTime, Utc};
+use std::borrow::Cow;
 
 use super::product_template::ProductTemplate;
 use super::tables::{FixedSurfaceType, GeneratingProcess, TimeUnit};
@@ -13,12 +14,12 @@ use super::HorizontalAnalysisForecastTemplate;
 /// Identical to template 4.0 (analysis or forecast at a point in time) up to
 /// and including the second fixed surface, with a single extra octet carrying
 /// the percentile value (1-99) appended at octet 35.
-pub struct PercentileHorizontalTemplate {
-    data: Vec<u8>,
+pub struct PercentileHorizontalTemplate<'a> {
+    data: Cow<'a, [u8]>,
     discipline: u8,
 }
 
-impl Template for PercentileHorizontalTemplate {
+impl Template for PercentileHorizontalTemplate<'_> {
     fn data(&self) -> &[u8] {
         &self.data
     }
@@ -36,9 +37,12 @@ impl Template for PercentileHorizontalTemplate {
     }
 }
 
-impl PercentileHorizontalTemplate {
-    pub fn new(data: Vec<u8>, discipline: u8) -> Self {
-        Self { data, discipline }
+impl<'a> PercentileHorizontalTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>, discipline: u8) -> Self {
+        Self {
+            data: data.into(),
+            discipline,
+        }
     }
 
     pub fn first_fixed_surface_scale_factor(&self) -> i8 {
@@ -67,7 +71,7 @@ impl PercentileHorizontalTemplate {
     }
 }
 
-impl ProductTemplate for PercentileHorizontalTemplate {
+impl ProductTemplate for PercentileHorizontalTemplate<'_> {
     fn discipline(&self) -> u8 {
         self.discipline
     }
@@ -1,6 +1,7 @@
 use crate::templates::template::{Template, TemplateType};
 use crate::utils::{read_i32_from_bytes, read_u16_from_bytes, read_u32_from_bytes};
 use chrono::prelude::*;
+use std::borrow::Cow;
 
 use super::product_template::ProductTemplate;
 use super::tables::{FixedSurfaceType, GeneratingProcess, TimeUnit, TypeOfStatisticalProcessing};
@@ -9,12 +10,12 @@ use super::HorizontalAnalysisForecastTemplate;
 /// Product Definition Template 4.10
 /// Percentile forecasts at a horizontal level or in a horizontal layer
 /// in a continuous or non-continuous time interval
-pub struct PercentileHorizontalTimeIntervalTemplate {
-    data: Vec<u8>,
+pub struct PercentileHorizontalTimeIntervalTemplate<'a> {
+    data: Cow<'a, [u8]>,
     discipline: u8,
 }
 
-impl Template for PercentileHorizontalTimeIntervalTemplate {
+impl Template for PercentileHorizontalTimeIntervalTemplate<'_> {
     fn data(&self) -> &[u8] {
         &self.data
     }
@@ -32,9 +33,12 @@ impl Template for PercentileHorizontalTimeIntervalTemplate {
     }
 }
 
-impl PercentileHorizontalTimeIntervalTemplate {
-    pub fn new(data: Vec<u8>, discipline: u8) -> Self {
-        Self { data, discipline }
+impl<'a> PercentileHorizontalTimeIntervalTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>, discipline: u8) -> Self {
+        Self {
+            data: data.into(),
+            discipline,
+        }
     }
 
     pub fn first_fixed_surface_scale_factor(&self) -> i8 {
@@ -85,7 +89,7 @@ impl PercentileHorizontalTimeIntervalTemplate {
     }
 }
 
-impl ProductTemplate for PercentileHorizontalTimeIntervalTemplate {
+impl ProductTemplate for PercentileHorizontalTimeIntervalTemplate<'_> {
     fn discipline(&self) -> u8 {
         self.discipline
     }
@@ -1,18 +1,19 @@
 use crate::templates::template::{Template, TemplateType};
 use crate::utils::{read_i32_from_bytes, read_u16_from_bytes, read_u32_from_bytes};
 use chrono::{DateTime, Utc};
+use std::borrow::Cow;
 
 use super::product_template::ProductTemplate;
 use super::tables::{FixedSurfaceType, GeneratingProcess, ProbabilityType, TimeUnit};
 
 /// Product Definition Template 4.5
 /// Probability forecast at a horizontal level or in a horizontal layer at a point in time
-pub struct ProbabilityHorizontalForecastTemplate {
-    data: Vec<u8>,
+pub struct ProbabilityHorizontalForecastTemplate<'a> {
+    data: Cow<'a, [u8]>,
     discipline: u8,
 }
 
-impl Template for ProbabilityHorizontalForecastTemplate {
+impl Template for ProbabilityHorizontalForecastTemplate<'_> {
     fn data(&self) -> &[u8] {
         &self.data
     }
@@ -30,9 +31,12 @@ impl Template for ProbabilityHorizontalForecastTemplate {
     }
 }
 
-impl ProbabilityHorizontalForecastTemplate {
-    pub fn new(data: Vec<u8>, discipline: u8) -> Self {
-        ProbabilityHorizontalForecastTemplate { data, discipline }
+impl<'a> ProbabilityHorizontalForecastTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>, discipline: u8) -> Self {
+        ProbabilityHorizontalForecastTemplate {
+            data: data.into(),
+            discipline,
+        }
     }
 
     pub fn category_value(&self) -> u8 {
@@ -139,7 +143,7 @@ impl ProbabilityHorizontalForecastTemplate {
     }
 }
 
-impl ProductTemplate for ProbabilityHorizontalForecastTemplate {
+impl ProductTemplate for ProbabilityHorizontalForecastTemplate<'_> {
     fn discipline(&self) -> u8 {
         self.discipline
     }
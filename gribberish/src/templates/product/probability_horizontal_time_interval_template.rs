@@ -1,6 +1,7 @@
 use crate::templates::template::{Template, TemplateType};
 use crate::utils::{read_i32_from_bytes, read_u16_from_bytes, read_u32_from_bytes};
 use chrono::prelude::*;
+use std::borrow::Cow;
 
 use super::product_template::ProductTemplate;
 use super::tables::{
@@ -11,12 +12,12 @@ use super::HorizontalAnalysisForecastTemplate;
 /// Product Definition Template 4.9
 /// Probability forecasts at a horizontal level or in a horizontal layer
 /// in a continuous or non-continuous time interval
-pub struct ProbabilityHorizontalTimeIntervalTemplate {
-    data: Vec<u8>,
+pub struct ProbabilityHorizontalTimeIntervalTemplate<'a> {
+    data: Cow<'a, [u8]>,
     discipline: u8,
 }
 
-impl Template for ProbabilityHorizontalTimeIntervalTemplate {
+impl Template for ProbabilityHorizontalTimeIntervalTemplate<'_> {
     fn data(&self) -> &[u8] {
         &self.data
     }
@@ -34,9 +35,12 @@ impl Template for ProbabilityHorizontalTimeIntervalTemplate {
     }
 }
 
-impl ProbabilityHorizontalTimeIntervalTemplate {
-    pub fn new(data: Vec<u8>, discipline: u8) -> Self {
-        Self { data, discipline }
+impl<'a> ProbabilityHorizontalTimeIntervalTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>, discipline: u8) -> Self {
+        Self {
+            data: data.into(),
+            discipline,
+        }
     }
 
     pub fn first_fixed_surface_scale_factor(&self) -> i8 {
@@ -127,7 +131,7 @@ impl ProbabilityHorizontalTimeIntervalTemplate {
     }
 }
 
-impl ProductTemplate for ProbabilityHorizontalTimeIntervalTemplate {
+impl ProductTemplate for ProbabilityHorizontalTimeIntervalTemplate<'_> {
     fn discipline(&self) -> u8 {
         self.discipline
     }
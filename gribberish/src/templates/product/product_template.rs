@@ -4,8 +4,8 @@ use gribberish_types::Parameter;
 use super::{
     parameters::{category, parameter},
     tables::{
-        DerivedForecastType, FixedSurfaceType, GeneratingProcess, ProbabilityType, TimeUnit,
-        TypeOfStatisticalProcessing,
+        DerivedForecastType, EnsembleForecastType, FixedSurfaceType, GeneratingProcess,
+        ProbabilityType, TimeUnit, TypeOfStatisticalProcessing,
     },
 };
 
@@ -13,6 +13,24 @@ use super::{
 /// `None` when the corresponding limit is open ended (encoded as missing).
 pub type WavePeriodRange = (Option<f64>, Option<f64>);
 
+/// Aerosol size or wavelength interval `(first, second)` in metres. Either
+/// limit is `None` when it is encoded as missing, how the limits bound the
+/// interval is given by the template's type of interval (code table 4.91).
+pub type AerosolInterval = (Option<f64>, Option<f64>);
+
+/// A spectral band contributing to a satellite product (templates 4.31 and
+/// 4.32). WMO leaves the satellite series, number and instrument type code
+/// tables undefined, so they follow the producer's own.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SatelliteBand {
+    pub satellite_series: u16,
+    pub satellite_number: u16,
+    pub instrument_type: u16,
+    /// Central wave number of the band in m-1, `None` when missing
+    pub central_wave_number: Option<f64>,
+}
+
 pub trait ProductTemplate {
     fn discipline(&self) -> u8;
     fn category_value(&self) -> u8;
@@ -36,6 +54,12 @@ pub trait ProductTemplate {
     fn derived_forecast_type(&self) -> Option<DerivedForecastType>;
     fn statistical_process_type(&self) -> Option<TypeOfStatisticalProcessing>;
 
+    /// Returns the type of ensemble forecast (code table 4.6) for ensemble
+    /// forecast templates. Returns None for non-ensemble templates.
+    fn ensemble_forecast_type(&self) -> Option<EnsembleForecastType> {
+        None
+    }
+
     /// Returns the ensemble perturbation number for ensemble forecast templates.
     /// Returns None for non-ensemble templates.
     fn perturbation_number(&self) -> Option<u8> {
@@ -80,6 +104,34 @@ pub trait ProductTemplate {
         None
     }
 
+    /// Returns the atmospheric chemical constituent type (code table 4.230) for
+    /// the chemistry templates 4.40 and 4.41, or the aerosol type (code table
+    /// 4.233, which shares its values) for the aerosol templates 4.44 to 4.48.
+    /// Species that share a parameter number, such as the mass density of
+    /// ozone and of smoke, only differ by this value.
+    fn chemical_constituent_type(&self) -> Option<u16> {
+        None
+    }
+
+    /// Returns the aerosol particle size interval for the aerosol templates
+    /// (4.44, 4.46 and 4.48). Returns `None` for any other template.
+    fn aerosol_size_range(&self) -> Option<AerosolInterval> {
+        None
+    }
+
+    /// Returns the wavelength interval of aerosol optical properties
+    /// (template 4.48). Returns `None` for any other template.
+    fn aerosol_wavelength_range(&self) -> Option<AerosolInterval> {
+        None
+    }
+
+    /// Returns the spectral bands of satellite products (templates 4.31 and
+    /// 4.32), which usually share a parameter number across channels. Empty
+    /// for any other template.
+    fn satellite_bands(&self) -> Vec<SatelliteBand> {
+        Vec::new()
+    }
+
     fn category(&self) -> &'static str {
         category(self.discipline(), self.category_value())
     }
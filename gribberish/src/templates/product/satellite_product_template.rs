@@ -0,0 +1,145 @@
+use crate::templates::template::{Template, TemplateType};
+use crate::utils::{read_u16_from_bytes, read_u32_from_bytes};
+use chrono::{DateTime, Utc};
+use std::borrow::Cow;
+
+use super::product_template::{ProductTemplate, SatelliteBand};
+use super::tables::{FixedSurfaceType, GeneratingProcess, TimeUnit};
+use super::HorizontalAnalysisForecastTemplate;
+
+/// Octets describing each contributing spectral band
+const BAND_LENGTH: usize = 11;
+
+/// Read the `count` spectral bands that start at `offset`, shared by templates
+/// 4.31 and 4.32 which lay the bands out identically.
+pub(crate) fn read_satellite_bands(data: &[u8], offset: usize, count: usize) -> Vec<SatelliteBand> {
+    (0..count)
+        .map(|band| offset + band * BAND_LENGTH)
+        .take_while(|start| start + BAND_LENGTH <= data.len())
+        .map(|start| SatelliteBand {
+            satellite_series: read_u16_from_bytes(data, start).unwrap_or(0),
+            satellite_number: read_u16_from_bytes(data, start + 2).unwrap_or(0),
+            instrument_type: read_u16_from_bytes(data, start + 4).unwrap_or(0),
+            central_wave_number: HorizontalAnalysisForecastTemplate::scale_value(
+                as_signed!(data[start + 6], 8, i8),
+                as_signed!(read_u32_from_bytes(data, start + 7).unwrap_or(0), 32, i32),
+            ),
+        })
+        .collect()
+}
+
+/// GRIB2 Product Definition Template 4.31
+///
+/// "Satellite product." Observed imagery valid at the reference time, with no
+/// forecast time or fixed surfaces, followed by the spectral bands that
+/// contributed to it.
+pub struct SatelliteProductTemplate<'a> {
+    data: Cow<'a, [u8]>,
+    discipline: u8,
+}
+
+impl Template for SatelliteProductTemplate<'_> {
+    fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn template_number(&self) -> u16 {
+        31
+    }
+
+    fn template_type(&self) -> TemplateType {
+        TemplateType::Product
+    }
+
+    fn template_name(&self) -> &str {
+        "Satellite product"
+    }
+}
+
+impl<'a> SatelliteProductTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>, discipline: u8) -> Self {
+        SatelliteProductTemplate {
+            data: data.into(),
+            discipline,
+        }
+    }
+
+    pub fn observation_generating_process_identifier(&self) -> u8 {
+        self.data[12]
+    }
+
+    pub fn number_of_contributing_spectral_bands(&self) -> u8 {
+        self.data[13]
+    }
+}
+
+impl ProductTemplate for SatelliteProductTemplate<'_> {
+    fn discipline(&self) -> u8 {
+        self.discipline
+    }
+
+    fn category_value(&self) -> u8 {
+        self.data[9]
+    }
+
+    fn parameter_value(&self) -> u8 {
+        self.data[10]
+    }
+
+    fn generating_process(&self) -> GeneratingProcess {
+        self.data[11].into()
+    }
+
+    /// Observations have no forecast time, they are valid at the reference time
+    fn time_unit(&self) -> TimeUnit {
+        TimeUnit::Hour
+    }
+
+    fn time_increment_unit(&self) -> Option<TimeUnit> {
+        None
+    }
+
+    fn time_interval(&self) -> i32 {
+        0
+    }
+
+    fn time_increment_interval(&self) -> Option<u32> {
+        None
+    }
+
+    fn forecast_end_datetime(&self, _reference_date: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        None
+    }
+
+    fn first_fixed_surface_type(&self) -> FixedSurfaceType {
+        FixedSurfaceType::Missing
+    }
+
+    fn first_fixed_surface_value(&self) -> Option<f64> {
+        None
+    }
+
+    fn second_fixed_surface_type(&self) -> FixedSurfaceType {
+        FixedSurfaceType::Missing
+    }
+
+    fn second_fixed_surface_value(&self) -> Option<f64> {
+        None
+    }
+
+    fn derived_forecast_type(&self) -> Option<super::tables::DerivedForecastType> {
+        None
+    }
+
+    fn statistical_process_type(&self) -> Option<super::tables::TypeOfStatisticalProcessing> {
+        None
+    }
+
+    fn satellite_bands(&self) -> Vec<SatelliteBand> {
+        read_satellite_bands(
+            &self.data,
+            14,
+            self.number_of_contributing_spectral_bands() as usize,
+        )
+    }
+}
@@ -0,0 +1,186 @@
+use crate::templates::template::{Template, TemplateType};
+use crate::utils::{read_i32_from_bytes, read_u16_from_bytes};
+use chrono::{DateTime, Utc};
+use std::borrow::Cow;
+
+use super::product_template::{ProductTemplate, SatelliteBand};
+use super::satellite_product_template::read_satellite_bands;
+use super::tables::{FixedSurfaceType, GeneratingProcess, TimeUnit};
+
+/// GRIB2 Product Definition Template 4.32
+///
+/// "Analysis or forecast at a horizontal level or in a horizontal layer at a
+/// point in time for simulated (synthetic) satellite data."
+///
+/// The generating process and forecast time of template 4.0 followed by the
+/// spectral bands of template 4.31. Despite the name there are no fixed
+/// surfaces, the simulated radiances are seen from the top of the atmosphere.
+pub struct SimulatedSatelliteTemplate<'a> {
+    data: Cow<'a, [u8]>,
+    discipline: u8,
+}
+
+impl Template for SimulatedSatelliteTemplate<'_> {
+    fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn template_number(&self) -> u16 {
+        32
+    }
+
+    fn template_type(&self) -> TemplateType {
+        TemplateType::Product
+    }
+
+    fn template_name(&self) -> &str {
+        "Analysis or forecast at a horizontal level or in a horizontal layer at a point in time for simulated (synthetic) satellite data"
+    }
+}
+
+impl<'a> SimulatedSatelliteTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>, discipline: u8) -> Self {
+        SimulatedSatelliteTemplate {
+            data: data.into(),
+            discipline,
+        }
+    }
+
+    pub fn observation_cutoff_hours_after_reference_time(&self) -> u16 {
+        read_u16_from_bytes(&self.data, 14).unwrap_or(0)
+    }
+
+    pub fn observation_cutoff_minutes_after_cutoff_time(&self) -> u8 {
+        self.data[16]
+    }
+
+    pub fn number_of_contributing_spectral_bands(&self) -> u8 {
+        self.data[22]
+    }
+}
+
+impl ProductTemplate for SimulatedSatelliteTemplate<'_> {
+    fn discipline(&self) -> u8 {
+        self.discipline
+    }
+
+    fn category_value(&self) -> u8 {
+        self.data[9]
+    }
+
+    fn parameter_value(&self) -> u8 {
+        self.data[10]
+    }
+
+    fn generating_process(&self) -> GeneratingProcess {
+        self.data[11].into()
+    }
+
+    fn time_unit(&self) -> TimeUnit {
+        self.data[17].into()
+    }
+
+    fn time_increment_unit(&self) -> Option<TimeUnit> {
+        None
+    }
+
+    fn time_interval(&self) -> i32 {
+        read_i32_from_bytes(&self.data, 18).unwrap_or(0)
+    }
+
+    fn time_increment_interval(&self) -> Option<u32> {
+        None
+    }
+
+    fn forecast_end_datetime(&self, _reference_date: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        None
+    }
+
+    fn first_fixed_surface_type(&self) -> FixedSurfaceType {
+        FixedSurfaceType::Missing
+    }
+
+    fn first_fixed_surface_value(&self) -> Option<f64> {
+        None
+    }
+
+    fn second_fixed_surface_type(&self) -> FixedSurfaceType {
+        FixedSurfaceType::Missing
+    }
+
+    fn second_fixed_surface_value(&self) -> Option<f64> {
+        None
+    }
+
+    fn derived_forecast_type(&self) -> Option<super::tables::DerivedForecastType> {
+        None
+    }
+
+    fn statistical_process_type(&self) -> Option<super::tables::TypeOfStatisticalProcessing> {
+        None
+    }
+
+    fn satellite_bands(&self) -> Vec<SatelliteBand> {
+        read_satellite_bands(
+            &self.data,
+            23,
+            self.number_of_contributing_spectral_bands() as usize,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sections::product_definition::ProductDefinitionSection;
+    use crate::templates::product::product_template::SatelliteBand;
+
+    /// Build a template 4.32 section for simulated GOES-16 ABI brightness
+    /// temperature in band 13 (10.3 um, 970.87 cm-1) at a 3 hour forecast
+    fn pdt32_section() -> Vec<u8> {
+        let mut data = vec![0u8; 34];
+        data[0..4].copy_from_slice(&34u32.to_be_bytes());
+        data[4] = 4;
+        data[7..9].copy_from_slice(&32u16.to_be_bytes());
+        // octets 10-11: category 192, parameter 7 (simulated brightness temperature)
+        data[9] = 192;
+        data[10] = 7;
+        // octet 12: generating process (forecast)
+        data[11] = 2;
+        // octets 18-22: 3 hour forecast
+        data[17] = 1;
+        data[18..22].copy_from_slice(&3u32.to_be_bytes());
+        // octet 23: one band
+        data[22] = 1;
+        data[23..25].copy_from_slice(&333u16.to_be_bytes());
+        data[25..27].copy_from_slice(&16u16.to_be_bytes());
+        data[27..29].copy_from_slice(&189u16.to_be_bytes());
+        data[29] = 0;
+        data[30..34].copy_from_slice(&97087u32.to_be_bytes());
+        data
+    }
+
+    #[test]
+    fn parses_spectral_bands() {
+        let data = pdt32_section();
+        let section = ProductDefinitionSection::from_data(&data);
+        let template = section
+            .product_definition_template(3)
+            .expect("template 4.32 should be supported");
+
+        assert_eq!(template.time_interval(), 3);
+        assert_eq!(
+            template.satellite_bands(),
+            vec![SatelliteBand {
+                satellite_series: 333,
+                satellite_number: 16,
+                instrument_type: 189,
+                central_wave_number: Some(97087.0),
+            }]
+        );
+
+        // A truncated section yields the bands that fit rather than panicking
+        let section = ProductDefinitionSection::from_data(&data[..30]);
+        let template = section.product_definition_template(3).unwrap();
+        assert!(template.satellite_bands().is_empty());
+    }
+}
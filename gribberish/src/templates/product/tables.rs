@@ -6,6 +6,7 @@ use crate::error::GribberishError;
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum ClusteringMethod {
     #[description = "anomoly correlation"]
     AnomolyCorrelation = 0,
@@ -15,6 +16,7 @@ pub enum ClusteringMethod {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, Clone, DisplayDescription, FromValue, ToParameter)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum FixedSurfaceType {
     #[name = "surface"]
     #[description = "ground or water surface"]
@@ -223,6 +225,7 @@ impl FixedSurfaceType {
 
 #[repr(u8)]
 #[derive(Clone, Eq, PartialEq, Debug, DisplayDescription, FromValue)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum GeneratingProcess {
     Analysis = 0,
     Initialization = 1,
@@ -300,21 +303,26 @@ impl GeneratingProcess {
 
 #[repr(u8)]
 #[derive(Clone, Eq, PartialEq, Debug, DisplayDescription, FromValue)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum EnsembleForecastType {
     UnperturbedHighResolutionControlForecast = 0,
-    NegativelyPerturbedForecast = 1,
-    PositivelyPerturbedForecast = 2,
-    MultiModelForecast = 3,
-    UnperturbedForecast = 4,
-    PerturbedForecast = 5,
-    InitialConditionsPerturbations = 6,
-    ModelPhysicsPerturbations = 7,
-    InitialConditionsAndModelPhysicsPerturbations = 8,
+    UnperturbedLowResolutionControlForecast = 1,
+    NegativelyPerturbedForecast = 2,
+    PositivelyPerturbedForecast = 3,
+    MultiModelForecast = 4,
+    UnperturbedForecast = 5,
+    PerturbedForecast = 6,
+    InitialConditionsPerturbations = 7,
+    ModelPhysicsPerturbations = 8,
+    InitialConditionsAndModelPhysicsPerturbations = 9,
+    #[description = "perturbed ensemble member"]
+    PerturbedEnsembleMember = 192,
     Missing = 255,
 }
 
 #[repr(u8)]
 #[derive(Clone, Eq, PartialEq, Debug, DisplayDescription, FromValue)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum TimeUnit {
     Minute = 0,
     Hour = 1,
@@ -393,6 +401,7 @@ impl TryFrom<&str> for TimeUnit {
 
 #[repr(u8)]
 #[derive(Clone, Eq, PartialEq, Debug, DisplayDescription, FromValue)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum TypeOfStatisticalProcessing {
     Average = 0,
     Accumulation = 1,
@@ -436,6 +445,7 @@ impl TypeOfStatisticalProcessing {
 
 #[repr(u8)]
 #[derive(Eq, PartialEq, Debug, DisplayDescription, FromValue)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum TypeOfTimeInterval {
     Reserved = 0,
     #[description = "successive times processed have same forecast time, start time of forecast is incremented."]
@@ -452,6 +462,7 @@ pub enum TypeOfTimeInterval {
 
 #[repr(u8)]
 #[derive(Clone, Eq, PartialEq, Debug, DisplayDescription, FromValue)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum DerivedForecastType {
     UnweightedMean = 0,
     WeightedMean = 1,
@@ -512,6 +523,7 @@ impl DerivedForecastType {
 
 #[repr(u8)]
 #[derive(Clone, Eq, PartialEq, Debug, DisplayDescription, FromValue)]
+#[cfg_attr(feature = "serde", derive(gribberish_macros::SerdeCodeDescription))]
 pub enum ProbabilityType {
     #[description = "Probability of event below lower limit"]
     BelowLowerLimit = 0,
@@ -1,6 +1,7 @@
 use crate::templates::template::{Template, TemplateType};
 use crate::utils::{read_i32_from_bytes, read_u32_from_bytes};
 use chrono::{DateTime, Utc};
+use std::borrow::Cow;
 
 use super::product_template::{ProductTemplate, WavePeriodRange};
 use super::tables::{FixedSurfaceType, GeneratingProcess, TimeUnit};
@@ -18,12 +19,12 @@ use super::tables::{FixedSurfaceType, GeneratingProcess, TimeUnit};
 /// Used by the ECMWF AIFS wave model for the period-banded significant wave height
 /// parameters (e.g. `h1012`, the significant wave height of all waves with periods
 /// in the inclusive range from 10 to 12 seconds).
-pub struct WavePeriodRangeHorizontalForecastTemplate {
-    data: Vec<u8>,
+pub struct WavePeriodRangeHorizontalForecastTemplate<'a> {
+    data: Cow<'a, [u8]>,
     discipline: u8,
 }
 
-impl Template for WavePeriodRangeHorizontalForecastTemplate {
+impl Template for WavePeriodRangeHorizontalForecastTemplate<'_> {
     fn data(&self) -> &[u8] {
         &self.data
     }
@@ -41,9 +42,12 @@ impl Template for WavePeriodRangeHorizontalForecastTemplate {
     }
 }
 
-impl WavePeriodRangeHorizontalForecastTemplate {
-    pub fn new(data: Vec<u8>, discipline: u8) -> Self {
-        WavePeriodRangeHorizontalForecastTemplate { data, discipline }
+impl<'a> WavePeriodRangeHorizontalForecastTemplate<'a> {
+    pub fn new(data: impl Into<Cow<'a, [u8]>>, discipline: u8) -> Self {
+        WavePeriodRangeHorizontalForecastTemplate {
+            data: data.into(),
+            discipline,
+        }
     }
 
     /// Type of wave period interval (GRIB2 code table 4.91). A value of `7`
@@ -116,7 +120,7 @@ impl WavePeriodRangeHorizontalForecastTemplate {
     }
 }
 
-impl ProductTemplate for WavePeriodRangeHorizontalForecastTemplate {
+impl ProductTemplate for WavePeriodRangeHorizontalForecastTemplate<'_> {
     fn discipline(&self) -> u8 {
         self.discipline
     }
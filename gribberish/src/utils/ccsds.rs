@@ -3,11 +3,11 @@ use std::collections::VecDeque;
 
 use crate::error::GribberishError;
 
-const ROS: u32 = 5;
+pub(crate) const ROS: u32 = 5;
 /**
 Size of the second extension table.
 */
-const SE_TABLE_SIZE: usize = 90;
+pub(crate) const SE_TABLE_SIZE: usize = 90;
 
 bitflags! {
     /// Represents a set of flags.
@@ -166,9 +166,7 @@ impl InternalState {
                 5,
             ),
             9..=16 => (2, 4),
-            5..=8 => (1, 3),
-            4 => (1, 2),
-            2..=3 => (1, 1),
+            1..=8 => (1, small_sample_id_len(bits_per_sample, flags)?),
             _ => return Err("Invalid bits_per_sample".into()),
         };
         let out_blklen = block_size * bytes_per_sample as u32;
@@ -262,17 +260,17 @@ impl InternalState {
         if self.pp {
             // Handle preprocessed data
             if self.flush_start == 0 && self.rsip > 0 {
-                self.last_out = self.rsi_buffer[0].try_into().unwrap();
+                // Reinterpret the bits, 32 bit samples do not fit in a positive i32
+                self.last_out = self.rsi_buffer[0] as i32;
 
                 // Handle signed data
                 if self.flags.intersects(Flags::AEC_DATA_SIGNED) {
-                    let m = 1u32 << (self.bits_per_sample - 1);
-                    let m2: i32 = m.try_into().unwrap();
-                    self.last_out = (self.last_out ^ m2) - m2;
+                    let m = (1u32 << (self.bits_per_sample - 1)) as i32;
+                    self.last_out = (self.last_out ^ m).wrapping_sub(m);
                 }
 
                 // byte_order.put_bytes(self.last_out.try_into().unwrap(), &mut self.next_out);
-                self.put_bytes(self.last_out.try_into().unwrap());
+                self.put_bytes(self.last_out as u32);
                 self.flush_start += 1;
             }
 
@@ -307,17 +305,17 @@ impl InternalState {
                     let d = self.rsi_buffer[i];
                     let half_d = (d >> 1) + (d & 1);
                     if (data as i32) < 0 {
-                        if half_d <= xmax + data + 1 {
+                        if half_d <= xmax.wrapping_add(data).wrapping_add(1) {
                             // Use wrapping operations for intentional bit manipulation
                             data = data.wrapping_add((d >> 1) ^ (!(d & 1).wrapping_sub(1)))
                         } else {
-                            data = d - xmax - 1;
+                            data = d.wrapping_sub(xmax).wrapping_sub(1);
                         }
-                    } else if half_d <= xmax - data {
+                    } else if half_d <= xmax.wrapping_sub(data) {
                         // Use wrapping operations for intentional bit manipulation
                         data = data.wrapping_add((d >> 1) ^ (!(d & 1).wrapping_sub(1)))
                     } else {
-                        data = xmax - d;
+                        data = xmax.wrapping_sub(d);
                     };
 
                     // byte_order.put_bytes(data, &mut self.next_out);
@@ -831,6 +829,20 @@ fn read_u32_from_bytes(bytes: &[u8], bytes_per_sample: usize) -> Vec<f32> {
     }
 }
 
+/// Length of the option identifier for samples of 8 bits or fewer. The restricted set
+/// of code options uses shorter identifiers, but only for samples of up to 4 bits.
+pub(crate) fn small_sample_id_len(bits_per_sample: usize, flags: Flags) -> Result<usize, String> {
+    if !flags.intersects(Flags::AEC_RESTRICTED) {
+        Ok(3)
+    } else if bits_per_sample <= 2 {
+        Ok(1)
+    } else if bits_per_sample <= 4 {
+        Ok(2)
+    } else {
+        Err("Restricted code options require 4 or fewer bits_per_sample".into())
+    }
+}
+
 fn create_se_table() -> [i32; 2 * (SE_TABLE_SIZE + 1)] {
     let mut table = [0; 2 * (SE_TABLE_SIZE + 1)];
     let mut k: i32 = 0;
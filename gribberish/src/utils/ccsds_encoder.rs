@@ -0,0 +1,473 @@
+//! Pure Rust CCSDS 121.0-B adaptive entropy encoder, the compressing counterpart of the
+//! decoder in [`super::ccsds`]. Like that decoder it follows libaec: samples are optionally
+//! preprocessed with a unit delay predictor, then every block is coded with whichever of
+//! the zero block, second extension, split sample or uncompressed options is shortest.
+
+use super::ccsds::{small_sample_id_len, Flags, ROS, SE_TABLE_SIZE};
+use crate::error::GribberishError;
+
+/// Zero block runs never span a segment of this many blocks
+const SEGMENT_BLOCKS: usize = 64;
+
+const MAX_RSI: u16 = 4096;
+
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u64,
+    bits: usize,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        BitWriter {
+            bytes: Vec::new(),
+            acc: 0,
+            bits: 0,
+        }
+    }
+
+    fn emit(&mut self, value: u32, n: usize) {
+        if n == 0 {
+            return;
+        }
+        self.acc = (self.acc << n) | (value as u64 & (u64::MAX >> (64 - n)));
+        self.bits += n;
+        while self.bits >= 8 {
+            self.bits -= 8;
+            self.bytes.push((self.acc >> self.bits) as u8);
+        }
+    }
+
+    /// Fundamental sequence code: `fs` zeros terminated by a one
+    fn emit_fs(&mut self, mut fs: u64) {
+        while fs >= 32 {
+            self.emit(0, 32);
+            fs -= 32;
+        }
+        self.emit(1, fs as usize + 1);
+    }
+
+    fn pad_to_byte(&mut self) {
+        if self.bits > 0 {
+            self.emit(0, 8 - self.bits);
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        self.pad_to_byte();
+        self.bytes
+    }
+}
+
+struct Encoder {
+    bits_per_sample: usize,
+    block_size: usize,
+    rsi: usize,
+    flags: Flags,
+    id_len: usize,
+    /// Largest split sample parameter, negative when only the low entropy and
+    /// uncompressed options are available
+    kmax: i32,
+    xmin: i64,
+    xmax: i64,
+    writer: BitWriter,
+}
+
+impl Encoder {
+    fn preprocess(&self, x: &[u32]) -> Vec<u32> {
+        let signed = self.flags.intersects(Flags::AEC_DATA_SIGNED);
+        let m = 1i64 << (self.bits_per_sample - 1);
+        let value = |sample: u32| {
+            if signed {
+                (sample as i64 ^ m) - m
+            } else {
+                sample as i64
+            }
+        };
+
+        let mut d = vec![0u32; x.len()];
+        for i in 0..x.len().saturating_sub(1) {
+            let (previous, current) = (value(x[i]), value(x[i + 1]));
+            d[i + 1] = if current < previous {
+                let delta = previous - current;
+                if delta <= self.xmax - previous {
+                    2 * delta - 1
+                } else {
+                    self.xmax - current
+                }
+            } else {
+                let delta = current - previous;
+                if delta <= previous - self.xmin {
+                    2 * delta
+                } else {
+                    current - self.xmin
+                }
+            } as u32;
+        }
+        d
+    }
+
+    fn encode_rsi(&mut self, samples: &[u32]) {
+        let preprocess = self.flags.intersects(Flags::AEC_DATA_PREPROCESS);
+        let rsi_len = self.rsi * self.block_size;
+
+        // A short final interval is padded with its last sample, only the blocks holding
+        // real samples are coded
+        let mut x = samples.to_vec();
+        x.resize(rsi_len, *samples.last().unwrap_or(&0));
+        let reference_sample = x[0];
+        let d = if preprocess { self.preprocess(&x) } else { x };
+        let block_count = samples.len().div_ceil(self.block_size);
+
+        let mut zero_blocks = 0;
+        let mut zero_reference = false;
+        for b in 0..block_count {
+            let block = &d[b * self.block_size..(b + 1) * self.block_size];
+            let reference = preprocess && b == 0;
+
+            if block.iter().all(|v| *v == 0) {
+                if zero_blocks == 0 {
+                    zero_reference = reference;
+                }
+                zero_blocks += 1;
+
+                if b + 1 == block_count || (b + 1) % SEGMENT_BLOCKS == 0 {
+                    let remainder_of_segment = zero_blocks > 4;
+                    self.encode_zero(
+                        zero_blocks,
+                        remainder_of_segment,
+                        zero_reference,
+                        reference_sample,
+                    );
+                    zero_blocks = 0;
+                }
+                continue;
+            }
+
+            if zero_blocks > 0 {
+                self.encode_zero(zero_blocks, false, zero_reference, reference_sample);
+                zero_blocks = 0;
+            }
+
+            let reference = reference.then_some(reference_sample);
+            self.encode_block(block, reference);
+        }
+
+        if self.flags.intersects(Flags::AEC_PAD_RSI) {
+            self.writer.pad_to_byte();
+        }
+    }
+
+    fn encode_zero(
+        &mut self,
+        zero_blocks: usize,
+        remainder_of_segment: bool,
+        reference: bool,
+        reference_sample: u32,
+    ) {
+        self.writer.emit(0, self.id_len + 1);
+        if reference {
+            self.writer.emit(reference_sample, self.bits_per_sample);
+        }
+
+        let zero_blocks = zero_blocks as u64;
+        if remainder_of_segment {
+            self.writer.emit_fs(ROS as u64 - 1);
+        } else if zero_blocks >= ROS as u64 {
+            self.writer.emit_fs(zero_blocks);
+        } else {
+            self.writer.emit_fs(zero_blocks - 1);
+        }
+    }
+
+    fn encode_block(&mut self, block: &[u32], reference: Option<u32>) {
+        let start = reference.is_some() as usize;
+        let samples = &block[start..];
+        let uncompressed_len = (samples.len() * self.bits_per_sample) as u64;
+
+        let (k, split_len) = (0..=self.kmax.max(-1))
+            .map(|k| {
+                let len = samples
+                    .iter()
+                    .map(|d| (*d >> k) as u64 + 1 + k as u64)
+                    .sum::<u64>();
+                (k, len)
+            })
+            .min_by_key(|(_, len)| *len)
+            .unwrap_or((-1, u64::MAX));
+
+        let se_len = second_extension_len(block);
+
+        if split_len < uncompressed_len {
+            if split_len < se_len {
+                self.encode_split(samples, k as u32, reference);
+            } else {
+                self.encode_second_extension(block, reference);
+            }
+        } else if uncompressed_len <= se_len {
+            self.encode_uncompressed(samples, reference);
+        } else {
+            self.encode_second_extension(block, reference);
+        }
+    }
+
+    fn encode_split(&mut self, samples: &[u32], k: u32, reference: Option<u32>) {
+        self.writer.emit(k + 1, self.id_len);
+        if let Some(reference) = reference {
+            self.writer.emit(reference, self.bits_per_sample);
+        }
+        for d in samples {
+            self.writer.emit_fs((*d >> k) as u64);
+        }
+        if k > 0 {
+            for d in samples {
+                self.writer.emit(*d, k as usize);
+            }
+        }
+    }
+
+    fn encode_second_extension(&mut self, block: &[u32], reference: Option<u32>) {
+        self.writer.emit(1, self.id_len + 1);
+        if let Some(reference) = reference {
+            self.writer.emit(reference, self.bits_per_sample);
+        }
+        for pair in block.chunks(2) {
+            self.writer
+                .emit_fs(second_extension_symbol(pair[0], pair[1]));
+        }
+    }
+
+    fn encode_uncompressed(&mut self, samples: &[u32], reference: Option<u32>) {
+        self.writer.emit((1u32 << self.id_len) - 1, self.id_len);
+        if let Some(reference) = reference {
+            self.writer.emit(reference, self.bits_per_sample);
+        }
+        for d in samples {
+            self.writer.emit(*d, self.bits_per_sample);
+        }
+    }
+}
+
+fn second_extension_symbol(a: u32, b: u32) -> u64 {
+    let sum = a as u64 + b as u64;
+    sum * (sum + 1) / 2 + b as u64
+}
+
+/// Length of a block coded with the second extension option, or `u64::MAX` when a symbol
+/// falls outside the decoder's table. For the first block of an interval the reference
+/// slot is zero so the first pair codes the first difference alone.
+fn second_extension_len(block: &[u32]) -> u64 {
+    let mut len = 1;
+    for pair in block.chunks(2) {
+        let symbol = second_extension_symbol(pair[0], pair[1]);
+        if symbol > SE_TABLE_SIZE as u64 {
+            return u64::MAX;
+        }
+        len += symbol + 1;
+    }
+    len
+}
+
+/// Compress `samples`, each holding `bits_per_sample` bits, into a CCSDS stream that
+/// [`super::ccsds::extract_ccsds_data`] (or libaec) decodes with the same block size,
+/// compression options mask and reference sample interval.
+pub fn encode_ccsds_data(
+    samples: &[u32],
+    bits_per_sample: usize,
+    block_len: u8,
+    compression_options_mask: u8,
+    reference_sample_interval: u16,
+) -> Result<Vec<u8>, GribberishError> {
+    let flags = Flags::from_bits_truncate(compression_options_mask);
+
+    if bits_per_sample == 0 || bits_per_sample > 32 {
+        return Err(GribberishError::EncodingError(format!(
+            "invalid CCSDS bits per sample {bits_per_sample}"
+        )));
+    }
+
+    let block_size = block_len as usize;
+    let valid_block_size = if flags.intersects(Flags::AEC_NOT_ENFORCE) {
+        block_size > 0 && block_size.is_multiple_of(2)
+    } else {
+        matches!(block_size, 8 | 16 | 32 | 64)
+    };
+    if !valid_block_size {
+        return Err(GribberishError::EncodingError(format!(
+            "invalid CCSDS block size {block_size}"
+        )));
+    }
+
+    if reference_sample_interval == 0 || reference_sample_interval > MAX_RSI {
+        return Err(GribberishError::EncodingError(format!(
+            "invalid CCSDS reference sample interval {reference_sample_interval}"
+        )));
+    }
+
+    let id_len = match bits_per_sample {
+        17..=32 => 5,
+        9..=16 => 4,
+        _ => small_sample_id_len(bits_per_sample, flags).map_err(GribberishError::EncodingError)?,
+    };
+
+    let (xmin, xmax) = if flags.intersects(Flags::AEC_DATA_SIGNED) {
+        (
+            -(1i64 << (bits_per_sample - 1)),
+            (1i64 << (bits_per_sample - 1)) - 1,
+        )
+    } else {
+        (0, (1i64 << bits_per_sample) - 1)
+    };
+    let sample_mask = ((1u64 << bits_per_sample) - 1) as u32;
+    if samples.iter().any(|s| *s & !sample_mask != 0) {
+        return Err(GribberishError::EncodingError(format!(
+            "CCSDS samples exceed {bits_per_sample} bits"
+        )));
+    }
+
+    let mut encoder = Encoder {
+        bits_per_sample,
+        block_size,
+        rsi: reference_sample_interval as usize,
+        flags,
+        id_len,
+        kmax: (1i32 << id_len) - 3,
+        xmin,
+        xmax,
+        writer: BitWriter::new(),
+    };
+
+    for interval in samples.chunks(encoder.rsi * block_size) {
+        encoder.encode_rsi(interval);
+    }
+
+    Ok(encoder.writer.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::ccsds::extract_ccsds_data;
+
+    fn assert_roundtrip(samples: &[u32], bits_per_sample: usize, block: u8, mask: u8, rsi: u16) {
+        let encoded = encode_ccsds_data(samples, bits_per_sample, block, mask, rsi).unwrap();
+        let size = samples.len() * bits_per_sample.div_ceil(8);
+        let decoded = extract_ccsds_data(encoded, block, mask, size, rsi, bits_per_sample).unwrap();
+        assert_eq!(
+            decoded,
+            samples.iter().map(|s| *s as f32).collect::<Vec<_>>(),
+            "bits {bits_per_sample} block {block} mask {mask} rsi {rsi}"
+        );
+    }
+
+    fn smooth_field(count: usize, bits_per_sample: usize) -> Vec<u32> {
+        let max = ((1u64 << bits_per_sample) - 1) as f64;
+        (0..count)
+            .map(|i| {
+                let v = (i as f64 * 0.013).sin() * 0.45 + 0.5 + (i as f64 * 0.7).cos() * 0.01;
+                (v * max).round() as u32
+            })
+            .collect()
+    }
+
+    /// The same streams must decode with libaec itself when it is available
+    #[cfg(feature = "libaec")]
+    #[test]
+    fn roundtrip_with_libaec() {
+        use crate::utils::ccsds_libaec;
+
+        for (bits, block, mask, rsi) in [(16, 32, 14, 128), (12, 16, 15, 64), (24, 8, 4, 3)] {
+            let samples = smooth_field(4000, bits);
+            let encoded = encode_ccsds_data(&samples, bits, block, mask, rsi).unwrap();
+            let size = samples.len() * bits.div_ceil(8);
+            let decoded =
+                ccsds_libaec::extract_ccsds_data(encoded, block, mask, size, rsi, bits).unwrap();
+            assert_eq!(
+                decoded,
+                samples.iter().map(|s| *s as f32).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn roundtrip_preprocessed_field() {
+        for bits in [8, 12, 16, 24, 32] {
+            for block in [8, 16, 32, 64] {
+                assert_roundtrip(&smooth_field(5000, bits), bits, block, 14, 128);
+            }
+        }
+    }
+
+    #[test]
+    fn roundtrip_without_preprocessing() {
+        for bits in [3, 8, 16, 20] {
+            assert_roundtrip(&smooth_field(3000, bits), bits, 16, 0, 32);
+            assert_roundtrip(&smooth_field(3000, bits), bits, 32, 4, 7);
+        }
+    }
+
+    #[test]
+    fn roundtrip_zero_runs_and_constants() {
+        let mut samples = vec![7u32; 10000];
+        samples[5000..5100].iter_mut().for_each(|s| *s = 200);
+        samples[9000] = 0;
+        assert_roundtrip(&samples, 8, 32, 14, 128);
+        assert_roundtrip(&samples, 8, 32, 6, 128);
+        assert_roundtrip(&vec![0u32; 4321], 16, 16, 0, 64);
+        assert_roundtrip(&vec![65535u32; 4321], 16, 16, 8, 64);
+    }
+
+    #[test]
+    fn roundtrip_noise_and_extremes() {
+        let mut state = 12345u64;
+        let samples = (0..4000)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                (state >> 40) as u32 & 0xFFFF
+            })
+            .collect::<Vec<_>>();
+        assert_roundtrip(&samples, 16, 32, 8, 128);
+
+        let extremes = (0..2000)
+            .map(|i| if i % 3 == 0 { 0 } else { 4095 })
+            .collect::<Vec<_>>();
+        assert_roundtrip(&extremes, 12, 16, 8, 16);
+    }
+
+    #[test]
+    fn roundtrip_signed_and_padded_rsi() {
+        // Signed samples come back sign extended to the 2 byte storage size
+        let samples = smooth_field(3000, 12)
+            .iter()
+            .map(|s| (*s as i32 - 2048) as u32 & 0xFFF)
+            .collect::<Vec<_>>();
+        let encoded = encode_ccsds_data(&samples, 12, 32, 9, 16).unwrap();
+        let decoded = extract_ccsds_data(encoded, 32, 9, samples.len() * 2, 16, 12).unwrap();
+        assert_eq!(
+            decoded
+                .iter()
+                .map(|d| *d as u32 & 0xFFF)
+                .collect::<Vec<_>>(),
+            samples
+        );
+
+        assert_roundtrip(&smooth_field(3000, 12), 12, 32, 8 | 32, 16);
+    }
+
+    #[test]
+    fn roundtrip_restricted_small_samples() {
+        assert_roundtrip(&smooth_field(1000, 2), 2, 16, 8 | 16, 32);
+        assert_roundtrip(&smooth_field(1000, 4), 4, 16, 8 | 16, 32);
+    }
+
+    #[test]
+    fn rejects_invalid_options() {
+        assert!(encode_ccsds_data(&[1, 2, 3], 8, 12, 0, 32).is_err());
+        assert!(encode_ccsds_data(&[1, 2, 3], 8, 12, 64, 32).is_ok());
+        assert!(encode_ccsds_data(&[1, 2, 3], 8, 32, 0, 0).is_err());
+        assert!(encode_ccsds_data(&[256], 8, 32, 0, 32).is_err());
+        assert!(encode_ccsds_data(&[1], 8, 32, 16, 32).is_err());
+    }
+}
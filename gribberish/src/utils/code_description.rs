@@ -0,0 +1,13 @@
+//! Serialized form of the GRIB code table enums, shared by the impls that
+//! `gribberish_macros::SerdeCodeDescription` generates.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+pub struct CodeDescription {
+    pub code: u8,
+    /// Written for readers of the serialized output. Ignored when
+    /// deserializing, where the code alone picks the variant.
+    #[serde(default)]
+    pub description: String,
+}
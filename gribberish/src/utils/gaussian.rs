@@ -0,0 +1,163 @@
+//! Gaussian latitudes, shared by GRIB1 Gaussian grids and GRIB2 template 3.40.
+//!
+//! A Gaussian grid with `N` parallels between a pole and the equator has its
+//! `2N` rows on the roots of the Legendre polynomial `P_2N(sin(lat))`, the
+//! quadrature points the spectral transforms are exact on.
+
+use std::f64::consts::PI;
+
+use super::iter::projection::RegularCoordinateIterator;
+
+const MAX_ITERATIONS: usize = 100;
+const CONVERGENCE: f64 = 1e-15;
+
+/// The `2n` Gaussian latitudes in degrees, ordered north to south
+pub fn gaussian_latitudes(n: usize) -> Vec<f64> {
+    let count = 2 * n;
+    let mut latitudes = vec![0.0; count];
+
+    // The roots are symmetric about the equator, so only solve the northern half
+    for i in 0..n {
+        // Tricomi's approximation of the root as the first guess for Newton
+        let mut x = (PI * (i as f64 + 0.75) / (count as f64 + 0.5)).cos();
+        for _ in 0..MAX_ITERATIONS {
+            let (p, dp) = legendre(count, x);
+            let dx = p / dp;
+            x -= dx;
+            if dx.abs() < CONVERGENCE {
+                break;
+            }
+        }
+
+        let latitude = x.asin().to_degrees();
+        latitudes[i] = latitude;
+        latitudes[count - 1 - i] = -latitude;
+    }
+
+    latitudes
+}
+
+/// The Gaussian latitudes of a grid with `n` parallels between a pole and the
+/// equator that fall between its first and last rows, in scanning order. A
+/// global grid gets all `2n` of them, a sub-area only the rows it covers.
+pub fn gaussian_rows(n: usize, first_latitude: f64, last_latitude: f64) -> Vec<f64> {
+    // Rows are stored to at best a millidegree, well inside a quarter row spacing
+    let tolerance = 45.0 / (2 * n.max(1)) as f64;
+    let north = first_latitude.max(last_latitude) + tolerance;
+    let south = first_latitude.min(last_latitude) - tolerance;
+
+    let mut rows = gaussian_latitudes(n)
+        .into_iter()
+        .filter(|lat| *lat <= north && *lat >= south)
+        .collect::<Vec<_>>();
+    if first_latitude < last_latitude {
+        rows.reverse();
+    }
+    rows
+}
+
+/// The longitude axis of one row of a reduced Gaussian grid with `points`
+/// points. Global rows divide the full circle evenly, sub-area rows divide the
+/// span between the first and last longitudes.
+pub fn reduced_row_longitudes(
+    points: usize,
+    first_longitude: f64,
+    last_longitude: f64,
+    is_global: bool,
+) -> RegularCoordinateIterator {
+    let step = if is_global {
+        360.0 / points.max(1) as f64
+    } else if points > 1 {
+        let mut span = last_longitude - first_longitude;
+        if span < 0.0 {
+            span += 360.0;
+        }
+        span / (points - 1) as f64
+    } else {
+        0.0
+    };
+    RegularCoordinateIterator::new(first_longitude, step, points)
+}
+
+/// `P_n(x)` and its derivative from the three term recurrence
+fn legendre(n: usize, x: f64) -> (f64, f64) {
+    let mut p0 = 1.0;
+    let mut p1 = x;
+    for k in 2..=n {
+        let k = k as f64;
+        let p2 = ((2.0 * k - 1.0) * x * p1 - (k - 1.0) * p0) / k;
+        p0 = p1;
+        p1 = p2;
+    }
+    let dp = n as f64 * (x * p1 - p0) / (x * x - 1.0);
+    (p1, dp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{gaussian_latitudes, gaussian_rows, reduced_row_longitudes};
+
+    #[test]
+    fn test_n1_roots() {
+        // P_2(x) = (3x^2 - 1) / 2
+        let latitudes = gaussian_latitudes(1);
+        let expected = (1.0f64 / 3.0).sqrt().asin().to_degrees();
+        assert_eq!(latitudes.len(), 2);
+        assert!((latitudes[0] - expected).abs() < 1e-12);
+        assert!((latitudes[1] + expected).abs() < 1e-12);
+    }
+
+    /// The first latitudes of the N320 and N640 grids from ECMWF's tables
+    #[test]
+    fn test_matches_ecmwf_grids() {
+        let n320 = gaussian_latitudes(320);
+        assert_eq!(n320.len(), 640);
+        assert!((n320[0] - 89.784_876_907_219).abs() < 1e-9, "{}", n320[0]);
+        // The rows nearest the equator sit about half a row spacing off it
+        assert!(
+            (n320[319] - 0.140_515_179_667).abs() < 1e-9,
+            "{}",
+            n320[319]
+        );
+
+        let n640 = gaussian_latitudes(640);
+        assert!((n640[0] - 89.892_396_445_59).abs() < 1e-9, "{}", n640[0]);
+    }
+
+    #[test]
+    fn test_sub_area_rows() {
+        let global = gaussian_latitudes(48);
+        assert_eq!(gaussian_rows(48, global[0], global[95]), global);
+
+        // Rows are matched within the precision GRIB1 stores them to
+        let rows = gaussian_rows(48, (global[10] * 1e3).round() / 1e3, global[20]);
+        assert_eq!(rows, global[10..=20]);
+
+        // South to north scanning reverses the rows
+        let rows = gaussian_rows(48, global[95], global[90]);
+        assert_eq!(rows.len(), 6);
+        assert_eq!(rows[0], global[95]);
+    }
+
+    #[test]
+    fn test_reduced_row_longitudes() {
+        let row = reduced_row_longitudes(20, 0.0, 342.0, true).collect::<Vec<_>>();
+        assert_eq!(row.len(), 20);
+        assert!((row[1] - 18.0).abs() < 1e-12);
+
+        let row = reduced_row_longitudes(5, 350.0, 10.0, false).collect::<Vec<_>>();
+        assert_eq!(row, vec![350.0, 355.0, 360.0, 365.0, 370.0]);
+    }
+
+    #[test]
+    fn test_symmetric_and_descending() {
+        let latitudes = gaussian_latitudes(48);
+        assert_eq!(latitudes.len(), 96);
+        for w in latitudes.windows(2) {
+            assert!(w[1] < w[0]);
+        }
+        for i in 0..48 {
+            assert!((latitudes[i] + latitudes[95 - i]).abs() < 1e-12);
+        }
+    }
+}
@@ -1,11 +1,16 @@
 use std::collections::HashMap;
 
 use itertools::Itertools;
-use mappers::{projections::LambertConformalConic, Projection, ProjectionError};
+use mappers::{Projection, ProjectionError};
 
-use crate::utils::projections::{mercator::Mercator, polar_stereographic::PolarStereographic};
+use crate::interpolation::Interpolation;
+use crate::utils::projections::{
+    geostationary::Geostationary, lambert_conformal::LambertConformal, mercator::Mercator,
+    polar_stereographic::PolarStereographic, rotated_pole::RotatedPole, wrap_longitude_degrees,
+};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PlateCareeProjection {
     pub latitudes: RegularCoordinateIterator,
     pub longitudes: RegularCoordinateIterator,
@@ -13,14 +18,94 @@ pub struct PlateCareeProjection {
     pub projection_params: HashMap<String, f64>,
 }
 
+impl PlateCareeProjection {
+    /// A regular lat/lon grid with the given axes, e.g. to regrid onto
+    pub fn new(
+        latitudes: RegularCoordinateIterator,
+        longitudes: RegularCoordinateIterator,
+    ) -> Self {
+        PlateCareeProjection {
+            latitudes,
+            longitudes,
+            projection_name: "latlon".to_string(),
+            projection_params: HashMap::new(),
+        }
+    }
+
+    /// Row-major index of the grid cell holding a location. Cells are centred
+    /// on the grid points, so those on the edges reach half a step beyond them.
+    pub(crate) fn cell_index(&self, lat: f64, lng: f64) -> Option<usize> {
+        if lng.is_nan() {
+            return None;
+        }
+        let row = cell_position(&self.latitudes, lat)?;
+        let columns = &self.longitudes;
+        let column = if columns.step == 0.0 {
+            cell_position(columns, lng)?
+        } else {
+            let step = columns.step.abs();
+            let cells = 360.0 / step;
+            let mut position =
+                ((lng - columns.start) * columns.step.signum()).rem_euclid(360.0) / step;
+            // West of the first column
+            if position > cells - 0.5 {
+                position -= cells;
+            }
+            let column = position.round();
+            if column < 0.0 || column >= columns.count as f64 {
+                if (step * columns.count as f64 - 360.0).abs() < step / 4.0 {
+                    0
+                } else {
+                    return None;
+                }
+            } else {
+                column as usize
+            }
+        };
+        Some(row * self.longitudes.count + column)
+    }
+}
+
+/// The longitudes of a Gaussian grid: one axis shared by every row of a
+/// regular grid, or one axis per row of a reduced grid.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GaussianLongitudes {
+    Regular(RegularCoordinateIterator),
+    Reduced(Vec<RegularCoordinateIterator>),
+}
+
+/// A grid whose rows lie on Gaussian latitudes, see
+/// [`gaussian_rows`](crate::utils::gaussian::gaussian_rows).
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GaussianProjection {
+    pub latitudes: Vec<f64>,
+    pub longitudes: GaussianLongitudes,
+    pub projection_name: String,
+    pub projection_params: HashMap<String, f64>,
+}
+
+impl GaussianProjection {
+    fn point_count(&self) -> usize {
+        match &self.longitudes {
+            GaussianLongitudes::Regular(longitudes) => self.latitudes.len() * longitudes.count,
+            GaussianLongitudes::Reduced(rows) => rows.iter().map(|row| row.count).sum(),
+        }
+    }
+}
+
 /// The projections a GRIB2 grid definition template can put behind a projected
 /// grid. Callers go through [`GridProjection::project`] and
 /// [`GridProjection::inverse_project`] and never learn which one it holds.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GridProjection {
-    LambertConformalConic(LambertConformalConic),
+    LambertConformalConic(LambertConformal),
     PolarStereographic(PolarStereographic),
     Mercator(Mercator),
+    RotatedPole(RotatedPole),
+    Geostationary(Geostationary),
 }
 
 impl GridProjection {
@@ -29,6 +114,8 @@ impl GridProjection {
             GridProjection::LambertConformalConic(p) => p.project(lon, lat),
             GridProjection::PolarStereographic(p) => p.project(lon, lat),
             GridProjection::Mercator(p) => p.project(lon, lat),
+            GridProjection::RotatedPole(p) => p.project(lon, lat),
+            GridProjection::Geostationary(p) => p.project(lon, lat),
         }
     }
 
@@ -37,13 +124,45 @@ impl GridProjection {
             GridProjection::LambertConformalConic(p) => p.inverse_project(x, y),
             GridProjection::PolarStereographic(p) => p.inverse_project(x, y),
             GridProjection::Mercator(p) => p.inverse_project(x, y),
+            GridProjection::RotatedPole(p) => p.inverse_project(x, y),
+            GridProjection::Geostationary(p) => p.inverse_project(x, y),
         }
     }
+
+    /// The angle, in radians, from true north clockwise to the grid's +y axis
+    /// at a location. Vectors resolved along the grid axes are turned to
+    /// earth-relative by rotating them through it, see
+    /// [`earth_relative_components`](crate::wind::earth_relative_components).
+    pub fn convergence_angle(&self, lon: f64, lat: f64) -> Result<f64, ProjectionError> {
+        // Step away from the nearer pole so the step stays on the globe
+        let step = if lat > 0.0 {
+            -CONVERGENCE_STEP
+        } else {
+            CONVERGENCE_STEP
+        };
+        let (x, y) = self.project(lon, lat)?;
+        let (stepped_x, stepped_y) = self.project(lon, lat + step)?;
+
+        let (mut dx, dy) = (stepped_x - x, stepped_y - y);
+        if let GridProjection::RotatedPole(_) = self {
+            // Rotated coordinates are degrees, whose meridians converge
+            dx = wrap_longitude_degrees(dx) * y.to_radians().cos();
+        }
+
+        // Direction of true north on the grid
+        let (north_x, north_y) = (dx * step.signum(), dy * step.signum());
+        Ok((-north_x).atan2(north_y))
+    }
 }
 
+/// Latitude step, in degrees, used to find the direction of true north on a
+/// projected grid
+const CONVERGENCE_STEP: f64 = 1e-4;
+
 /// A grid whose coordinates are regular in projected metres, with latitude and
 /// longitude recovered by inverse projection.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ProjectedGrid {
     pub x: RegularCoordinateIterator,
     pub y: RegularCoordinateIterator,
@@ -52,10 +171,38 @@ pub struct ProjectedGrid {
     pub projection_params: HashMap<String, f64>,
 }
 
-#[derive(Clone, Debug)]
+/// A block of rows and columns of a grid, see [`LatLngProjection::subset_window`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct GridWindow {
+    pub row_start: usize,
+    pub row_count: usize,
+    pub column_start: usize,
+    pub column_count: usize,
+}
+
+impl GridWindow {
+    pub fn point_count(&self) -> usize {
+        self.row_count * self.column_count
+    }
+
+    /// Row-major indices of the window's points into the data of a grid with
+    /// `nx` columns. Columns past the last wrap around to the first, for
+    /// windows across the seam of a global grid.
+    pub fn indices(&self, nx: usize) -> impl Iterator<Item = usize> {
+        let window = *self;
+        (window.row_start..window.row_start + window.row_count).flat_map(move |row| {
+            (window.column_start..window.column_start + window.column_count)
+                .map(move |column| row * nx + column % nx)
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LatLngProjection {
     PlateCaree(PlateCareeProjection),
     Projected(ProjectedGrid),
+    Gaussian(GaussianProjection),
 }
 
 impl LatLngProjection {
@@ -63,6 +210,9 @@ impl LatLngProjection {
         match self {
             LatLngProjection::PlateCaree(_) => true,
             LatLngProjection::Projected(_) => false,
+            LatLngProjection::Gaussian(projection) => {
+                matches!(projection.longitudes, GaussianLongitudes::Regular(_))
+            }
         }
     }
 
@@ -70,24 +220,26 @@ impl LatLngProjection {
         match self {
             LatLngProjection::PlateCaree(projection) => {
                 let lats: Vec<f64> = projection.latitudes.clone().collect();
-                let lon_start = projection.longitudes.start;
-                let lngs: Vec<f64> = projection
-                    .longitudes
-                    .clone()
-                    .map(|lon| {
-                        // Normalize to 0..360 range for grids that wrap around the globe
-                        // (consistent with GRIB1 handling in grid_description.rs)
-                        if lon >= 360.0 {
-                            lon - 360.0
-                        } else if lon < 0.0 && lon_start >= 0.0 {
-                            lon + 360.0
-                        } else {
-                            lon
-                        }
-                    })
-                    .collect();
-                (lats, lngs)
+                (lats, normalized_longitudes(&projection.longitudes))
             }
+            LatLngProjection::Gaussian(projection) => match &projection.longitudes {
+                GaussianLongitudes::Regular(longitudes) => (
+                    projection.latitudes.clone(),
+                    normalized_longitudes(longitudes),
+                ),
+                // Reduced rows differ in length, so every point gets its own
+                // latitude and longitude
+                GaussianLongitudes::Reduced(rows) => projection
+                    .latitudes
+                    .iter()
+                    .zip(rows)
+                    .flat_map(|(lat, row)| {
+                        normalized_longitudes(row)
+                            .into_iter()
+                            .map(move |lng| (*lat, lng))
+                    })
+                    .unzip(),
+            },
             LatLngProjection::Projected(projection) => projection
                 .y
                 .clone()
@@ -96,11 +248,12 @@ impl LatLngProjection {
                     x.current_index = 0;
                     x.clone()
                         .map(|x_coord| {
-                            let projected = projection
-                                .projection
-                                .inverse_project(x_coord, y_coord)
-                                .expect("Failed to inverse project from xy to lnglat");
-                            (projected.1, projected.0)
+                            // Only space view grids have points that miss the
+                            // earth, beyond its limb, those have no location
+                            match projection.projection.inverse_project(x_coord, y_coord) {
+                                Ok(projected) => (projected.1, projected.0),
+                                Err(_) => (f64::NAN, f64::NAN),
+                            }
                         })
                         .collect::<Vec<(f64, f64)>>()
                 })
@@ -108,6 +261,30 @@ impl LatLngProjection {
         }
     }
 
+    /// The [convergence angle](GridProjection::convergence_angle) at every grid
+    /// point, in data order. Rows and columns of latitude/longitude grids run
+    /// east and north, so theirs are all zero.
+    pub fn convergence_angles(&self) -> Vec<f64> {
+        match self {
+            LatLngProjection::PlateCaree(_) | LatLngProjection::Gaussian(_) => {
+                let (ny, nx) = self.dims();
+                vec![0.0; ny * nx]
+            }
+            LatLngProjection::Projected(projection) => {
+                let (lats, lngs) = self.lat_lng();
+                lats.iter()
+                    .zip(lngs.iter())
+                    .map(|(lat, lng)| {
+                        projection
+                            .projection
+                            .convergence_angle(*lng, *lat)
+                            .unwrap_or(f64::NAN)
+                    })
+                    .collect()
+            }
+        }
+    }
+
     /// Columns to roll this projection's longitude axis (and matching data) left
     /// so longitudes run monotonically over `[-180, 180)`, or `None` for
     /// projected/ineligible grids (callers no-op). See [`wrap_roll`] for the
@@ -119,6 +296,12 @@ impl LatLngProjection {
             // eligible, so short-circuit it.
             LatLngProjection::PlateCaree(_) => wrap_roll(&self.lat_lng().1),
             LatLngProjection::Projected(_) => None,
+            LatLngProjection::Gaussian(projection) => match &projection.longitudes {
+                GaussianLongitudes::Regular(longitudes) => {
+                    wrap_roll(&normalized_longitudes(longitudes))
+                }
+                GaussianLongitudes::Reduced(_) => None,
+            },
         }
     }
 
@@ -131,15 +314,25 @@ impl LatLngProjection {
         match self {
             LatLngProjection::PlateCaree(p) => p.latitudes.step > 0.0,
             LatLngProjection::Projected(p) => p.y.step > 0.0,
+            // Reduced rows differ in length, so they can't be swapped in place
+            LatLngProjection::Gaussian(p) => {
+                matches!(p.longitudes, GaussianLongitudes::Regular(_))
+                    && p.latitudes.len() >= 2
+                    && p.latitudes[1] > p.latitudes[0]
+            }
         }
     }
 
     /// Grid dimensions as `(ny, nx)` for the row-major layout shared by the
     /// decoded data and the flattened coordinate fields.
-    fn dims(&self) -> (usize, usize) {
+    pub(crate) fn dims(&self) -> (usize, usize) {
         match self {
             LatLngProjection::PlateCaree(p) => (p.latitudes.count, p.longitudes.count),
             LatLngProjection::Projected(p) => (p.y.count, p.x.count),
+            LatLngProjection::Gaussian(p) => match &p.longitudes {
+                GaussianLongitudes::Regular(longitudes) => (p.latitudes.len(), longitudes.count),
+                GaussianLongitudes::Reduced(_) => (1, p.point_count()),
+            },
         }
     }
 
@@ -163,6 +356,11 @@ impl LatLngProjection {
             match self {
                 // Regular grid: 1-D latitude axis; longitudes are unaffected.
                 LatLngProjection::PlateCaree(_) => lats = adjust_latitude_values(lats),
+                LatLngProjection::Gaussian(_) if self.is_regular_latlng_grid() => {
+                    lats = adjust_latitude_values(lats)
+                }
+                // Reduced: rows can't be reordered in place, leave as is.
+                LatLngProjection::Gaussian(_) => {}
                 // Projected: lat/lng are flattened ny × nx fields; row-flip both.
                 LatLngProjection::Projected(_) => {
                     lats = self.adjust_data_north_up(lats, true);
@@ -183,7 +381,7 @@ impl LatLngProjection {
             return data;
         }
         match (self, self.longitude_wrap_roll()) {
-            (LatLngProjection::PlateCaree(_), Some(roll)) => {
+            (LatLngProjection::PlateCaree(_) | LatLngProjection::Gaussian(_), Some(roll)) => {
                 let (ny, nx) = self.dims();
                 rotate_rows_left(&data, ny, nx, roll)
             }
@@ -219,26 +417,20 @@ impl LatLngProjection {
         self.adjust_data_north_up(data, north_up)
     }
 
+    /// The x axis of the grid. Reduced Gaussian grids have no shared axes, so
+    /// their points are laid out along a single row and indexed by position.
     pub fn x(&self) -> Vec<f64> {
         match self {
             LatLngProjection::PlateCaree(projection) => {
-                let lon_start = projection.longitudes.start;
-                projection
-                    .longitudes
-                    .clone()
-                    .map(|lon| {
-                        // Normalize to 0..360 range for grids that wrap around the globe
-                        if lon >= 360.0 {
-                            lon - 360.0
-                        } else if lon < 0.0 && lon_start >= 0.0 {
-                            lon + 360.0
-                        } else {
-                            lon
-                        }
-                    })
-                    .collect()
+                normalized_longitudes(&projection.longitudes)
             }
             LatLngProjection::Projected(projection) => projection.x.clone().collect(),
+            LatLngProjection::Gaussian(projection) => match &projection.longitudes {
+                GaussianLongitudes::Regular(longitudes) => normalized_longitudes(longitudes),
+                GaussianLongitudes::Reduced(_) => {
+                    (0..projection.point_count()).map(|i| i as f64).collect()
+                }
+            },
         }
     }
 
@@ -246,41 +438,214 @@ impl LatLngProjection {
         match self {
             LatLngProjection::PlateCaree(projection) => projection.latitudes.clone().collect(),
             LatLngProjection::Projected(projection) => projection.y.clone().collect(),
+            LatLngProjection::Gaussian(projection) => match &projection.longitudes {
+                GaussianLongitudes::Regular(_) => projection.latitudes.clone(),
+                GaussianLongitudes::Reduced(_) => vec![0.0],
+            },
         }
     }
 
+    /// The `(lat, lng)` of a point given in grid coordinates, NaN when it has no
+    /// location on the earth
     pub fn project_xy(&self, x: f64, y: f64) -> (f64, f64) {
         match self {
-            LatLngProjection::PlateCaree(_) => (x, y),
+            LatLngProjection::PlateCaree(_) | LatLngProjection::Gaussian(_) => (y, x),
             LatLngProjection::Projected(projection) => {
-                let projected = projection.projection.project(x, y).unwrap();
-                (projected.1, projected.0)
+                match projection.projection.inverse_project(x, y) {
+                    Ok((lng, lat)) => (lat, lng),
+                    Err(_) => (f64::NAN, f64::NAN),
+                }
             }
         }
     }
 
+    /// The `(x, y)` grid coordinates of a location, NaN when the grid's
+    /// projection cannot reach it
     pub fn project_latlng(&self, lat: f64, lng: f64) -> (f64, f64) {
         match self {
-            LatLngProjection::PlateCaree(_) => (lng, lat),
+            LatLngProjection::PlateCaree(_) | LatLngProjection::Gaussian(_) => (lng, lat),
             LatLngProjection::Projected(projection) => {
-                let projected = projection.projection.inverse_project(lng, lat).unwrap();
-                (projected.1, projected.0)
+                match projection.projection.project(lng, lat) {
+                    Ok((x, y)) => (x, y),
+                    Err(_) => (f64::NAN, f64::NAN),
+                }
             }
         }
     }
 
+    /// Indices into the row-major data of the grid points around a location,
+    /// with the weights that interpolate them to it. Empty when the location is
+    /// off the grid. Longitudes wrap around grids that circle the globe, and on
+    /// Gaussian grids locations poleward of the outermost row take that row.
+    pub fn interpolation_weights(
+        &self,
+        lat: f64,
+        lng: f64,
+        method: Interpolation,
+    ) -> Vec<(usize, f64)> {
+        match self {
+            LatLngProjection::PlateCaree(projection) => {
+                let rows = regular_axis_position(&projection.latitudes, lat);
+                let columns = longitude_axis_position(&projection.longitudes, lng);
+                grid_weights(rows, columns, projection.longitudes.count, method)
+            }
+            LatLngProjection::Projected(projection) => {
+                let (x, y) = self.project_latlng(lat, lng);
+                let rows = regular_axis_position(&projection.y, y);
+                let columns = regular_axis_position(&projection.x, x);
+                grid_weights(rows, columns, projection.x.count, method)
+            }
+            LatLngProjection::Gaussian(projection) => {
+                let rows = irregular_axis_position(&projection.latitudes, lat);
+                match &projection.longitudes {
+                    GaussianLongitudes::Regular(longitudes) => {
+                        let columns = longitude_axis_position(longitudes, lng);
+                        grid_weights(rows, columns, longitudes.count, method)
+                    }
+                    GaussianLongitudes::Reduced(row_longitudes) => {
+                        let Some(rows) = rows else {
+                            return Vec::new();
+                        };
+                        let mut weights = Vec::new();
+                        for (row, row_weight) in axis_weights(rows, method) {
+                            let Some(columns) = longitude_axis_position(&row_longitudes[row], lng)
+                            else {
+                                return Vec::new();
+                            };
+                            let row_start: usize =
+                                row_longitudes[..row].iter().map(|r| r.count).sum();
+                            weights.extend(
+                                axis_weights(columns, method)
+                                    .into_iter()
+                                    .map(|(column, weight)| {
+                                        (row_start + column, row_weight * weight)
+                                    })
+                                    .filter(|(_, weight)| *weight > 0.0),
+                            );
+                        }
+                        weights
+                    }
+                }
+            }
+        }
+    }
+
+    /// The `(min_lng, min_lat, max_lng, max_lat)` bounds of the grid's points,
+    /// NaN when none of them are on the earth
     pub fn bbox(&self) -> (f64, f64, f64, f64) {
         match self {
-            LatLngProjection::PlateCaree(_) | LatLngProjection::Projected(_) => {
+            LatLngProjection::PlateCaree(_)
+            | LatLngProjection::Projected(_)
+            | LatLngProjection::Gaussian(_) => {
                 // Use lat_lng() to get normalized coordinates
                 let (lat, lng) = self.lat_lng();
-                let (min_lat, max_lat) = lat.into_iter().minmax().into_option().unwrap();
-                let (min_lng, max_lng) = lng.into_iter().minmax().into_option().unwrap();
+                // Points beyond the limb of a space view grid are NaN
+                let (min_lat, max_lat) = lat
+                    .into_iter()
+                    .filter(|v| !v.is_nan())
+                    .minmax()
+                    .into_option()
+                    .unwrap_or((f64::NAN, f64::NAN));
+                let (min_lng, max_lng) = lng
+                    .into_iter()
+                    .filter(|v| !v.is_nan())
+                    .minmax()
+                    .into_option()
+                    .unwrap_or((f64::NAN, f64::NAN));
                 (min_lng, min_lat, max_lng, max_lat)
             }
         }
     }
 
+    /// The smallest window of the grid, in its native projection, holding
+    /// every grid point inside a `(min_lng, min_lat, max_lng, max_lat)`
+    /// bounding box, the order [`bbox`](Self::bbox) returns. A `min_lng` east
+    /// of `max_lng` crosses the antimeridian. `None` when no grid point lies
+    /// inside, and for reduced Gaussian grids whose rows share no columns.
+    pub fn subset_window(&self, bbox: (f64, f64, f64, f64)) -> Option<GridWindow> {
+        let (min_lng, min_lat, max_lng, max_lat) = bbox;
+        let in_lat = |lat: f64| lat >= min_lat - EDGE_TOLERANCE && lat <= max_lat + EDGE_TOLERANCE;
+        let in_lng = |lng: f64| longitude_in_range(lng, min_lng, max_lng);
+
+        let (latitudes, longitudes) = match self {
+            LatLngProjection::PlateCaree(projection) => (
+                projection.latitudes.clone().collect::<Vec<_>>(),
+                &projection.longitudes,
+            ),
+            LatLngProjection::Gaussian(projection) => match &projection.longitudes {
+                GaussianLongitudes::Regular(longitudes) => {
+                    (projection.latitudes.clone(), longitudes)
+                }
+                GaussianLongitudes::Reduced(_) => return None,
+            },
+            LatLngProjection::Projected(projection) => {
+                let nx = projection.x.count;
+                let (lats, lngs) = self.lat_lng();
+                let (mut rows, mut columns) = ((usize::MAX, 0), (usize::MAX, 0));
+                for (index, _) in lats
+                    .iter()
+                    .zip(lngs.iter())
+                    .enumerate()
+                    .filter(|(_, (lat, lng))| in_lat(**lat) && in_lng(**lng))
+                {
+                    let (row, column) = (index / nx, index % nx);
+                    rows = (rows.0.min(row), rows.1.max(row));
+                    columns = (columns.0.min(column), columns.1.max(column));
+                }
+                if rows.0 == usize::MAX {
+                    return None;
+                }
+
+                return Some(GridWindow {
+                    row_start: rows.0,
+                    row_count: rows.1 - rows.0 + 1,
+                    column_start: columns.0,
+                    column_count: columns.1 - columns.0 + 1,
+                });
+            }
+        };
+
+        let (row_start, row_count) = selected_range(latitudes.iter().map(|lat| in_lat(*lat)))?;
+        let columns = longitudes.clone().map(in_lng).collect::<Vec<_>>();
+        let step = longitudes.step.abs();
+        let is_global = (step * longitudes.count as f64 - 360.0).abs() < step / 4.0;
+        let (column_start, column_count) = if is_global {
+            circular_selected_range(&columns)?
+        } else {
+            selected_range(columns.into_iter())?
+        };
+
+        Some(GridWindow {
+            row_start,
+            row_count,
+            column_start,
+            column_count,
+        })
+    }
+
+    /// The `(x, y)` grid coordinates of the point at a row and column, without
+    /// normalising longitudes. Columns past the last continue along the axis.
+    pub(crate) fn grid_point(&self, row: usize, column: usize) -> (f64, f64) {
+        let along =
+            |axis: &RegularCoordinateIterator, index: usize| axis.start + axis.step * index as f64;
+        match self {
+            LatLngProjection::PlateCaree(projection) => (
+                along(&projection.longitudes, column),
+                along(&projection.latitudes, row),
+            ),
+            LatLngProjection::Projected(projection) => {
+                (along(&projection.x, column), along(&projection.y, row))
+            }
+            LatLngProjection::Gaussian(projection) => match &projection.longitudes {
+                GaussianLongitudes::Regular(longitudes) => (
+                    along(longitudes, column),
+                    projection.latitudes.get(row).copied().unwrap_or(f64::NAN),
+                ),
+                GaussianLongitudes::Reduced(_) => (f64::NAN, f64::NAN),
+            },
+        }
+    }
+
     pub fn latlng_start(&self) -> (f64, f64) {
         match self {
             LatLngProjection::PlateCaree(projection) => {
@@ -289,6 +654,13 @@ impl LatLngProjection {
             LatLngProjection::Projected(projection) => {
                 self.project_xy(projection.x.start, projection.y.start)
             }
+            LatLngProjection::Gaussian(projection) => {
+                let start = match &projection.longitudes {
+                    GaussianLongitudes::Regular(longitudes) => longitudes.start,
+                    GaussianLongitudes::Reduced(rows) => rows.first().map_or(0.0, |r| r.start),
+                };
+                (projection.latitudes.first().copied().unwrap_or(0.0), start)
+            }
         }
     }
 
@@ -300,6 +672,13 @@ impl LatLngProjection {
             LatLngProjection::Projected(projection) => {
                 self.project_xy(projection.x.end, projection.y.end)
             }
+            LatLngProjection::Gaussian(projection) => {
+                let end = match &projection.longitudes {
+                    GaussianLongitudes::Regular(longitudes) => longitudes.end,
+                    GaussianLongitudes::Reduced(rows) => rows.last().map_or(0.0, |r| r.end),
+                };
+                (projection.latitudes.last().copied().unwrap_or(0.0), end)
+            }
         }
     }
 
@@ -307,6 +686,7 @@ impl LatLngProjection {
         match self {
             LatLngProjection::PlateCaree(projection) => projection.projection_name.clone(),
             LatLngProjection::Projected(projection) => projection.projection_name.clone(),
+            LatLngProjection::Gaussian(projection) => projection.projection_name.clone(),
         }
     }
 
@@ -314,11 +694,13 @@ impl LatLngProjection {
         match self {
             LatLngProjection::PlateCaree(projection) => projection.projection_params.clone(),
             LatLngProjection::Projected(projection) => projection.projection_params.clone(),
+            LatLngProjection::Gaussian(projection) => projection.projection_params.clone(),
         }
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RegularCoordinateIterator {
     start: f64,
     step: f64,
@@ -332,7 +714,7 @@ impl RegularCoordinateIterator {
         Self {
             start,
             step,
-            end: start + (step * (count - 1) as f64),
+            end: start + (step * count.saturating_sub(1) as f64),
             current_index: 0,
             count,
         }
@@ -356,6 +738,196 @@ impl Iterator for RegularCoordinateIterator {
     }
 }
 
+/// Collect a longitude axis, normalized to the 0..360 range for grids that
+/// wrap around the globe (consistent with GRIB1 handling in grid_description.rs)
+fn normalized_longitudes(longitudes: &RegularCoordinateIterator) -> Vec<f64> {
+    let lon_start = longitudes.start;
+    longitudes
+        .clone()
+        .map(|lon| {
+            if lon >= 360.0 {
+                lon - 360.0
+            } else if lon < 0.0 && lon_start >= 0.0 {
+                lon + 360.0
+            } else {
+                lon
+            }
+        })
+        .collect()
+}
+
+/// Where a value falls along a grid axis: the indices of the points on either
+/// side of it and how far it is from the first towards the second
+type AxisPosition = (usize, usize, f64);
+
+/// Tolerance, in grid cells, for locations just beyond the edge of a grid
+const EDGE_TOLERANCE: f64 = 1e-6;
+
+fn regular_axis_position(axis: &RegularCoordinateIterator, value: f64) -> Option<AxisPosition> {
+    if axis.count == 0 || value.is_nan() {
+        return None;
+    }
+    let last = (axis.count - 1) as f64;
+    let position = if axis.step == 0.0 {
+        0.0
+    } else {
+        (value - axis.start) / axis.step
+    };
+    if position < -EDGE_TOLERANCE || position > last + EDGE_TOLERANCE {
+        return None;
+    }
+
+    let position = position.clamp(0.0, last);
+    let lower = position.floor() as usize;
+    let upper = (lower + 1).min(axis.count - 1);
+    Some((lower, upper, position - lower as f64))
+}
+
+/// Like [`regular_axis_position`], but for a longitude axis, which a location
+/// can reach from either direction around the globe. The last and first points
+/// of an axis that circles the globe are neighbours.
+fn longitude_axis_position(axis: &RegularCoordinateIterator, lng: f64) -> Option<AxisPosition> {
+    if axis.count == 0 || axis.step == 0.0 || lng.is_nan() {
+        return regular_axis_position(axis, lng);
+    }
+    let step = axis.step.abs();
+    let cells = 360.0 / step;
+    let mut position = ((lng - axis.start) * axis.step.signum()).rem_euclid(360.0) / step;
+
+    // Same test for a grid circling the globe as `wrap_roll`
+    if (step * axis.count as f64 - 360.0).abs() < step / 4.0 {
+        let lower = position.floor() as usize % axis.count;
+        return Some((lower, (lower + 1) % axis.count, position - position.floor()));
+    }
+
+    // Just west of the first point of the axis
+    if cells - position <= EDGE_TOLERANCE {
+        position = 0.0;
+    }
+    regular_axis_position(
+        &RegularCoordinateIterator::new(0.0, 1.0, axis.count),
+        position,
+    )
+}
+
+/// The position of a value along an axis of ascending or descending
+/// coordinates, clamped to its ends
+fn irregular_axis_position(axis: &[f64], value: f64) -> Option<AxisPosition> {
+    if axis.is_empty() || value.is_nan() {
+        return None;
+    }
+    let last = axis.len() - 1;
+    let descending = axis.len() > 1 && axis[1] < axis[0];
+    let before = |coordinate: f64| {
+        if descending {
+            coordinate >= value
+        } else {
+            coordinate <= value
+        }
+    };
+
+    let upper = axis.partition_point(|coordinate| before(*coordinate));
+    if upper == 0 {
+        return Some((0, 0, 0.0));
+    }
+    if upper > last {
+        return Some((last, last, 0.0));
+    }
+    let lower = upper - 1;
+    let fraction = (value - axis[lower]) / (axis[upper] - axis[lower]);
+    Some((lower, upper, fraction))
+}
+
+/// The index of the cell centred on an axis point that holds a value
+fn cell_position(axis: &RegularCoordinateIterator, value: f64) -> Option<usize> {
+    let position = if axis.step == 0.0 {
+        0.0
+    } else {
+        ((value - axis.start) / axis.step).round()
+    };
+    if position.is_nan() || position < 0.0 || position >= axis.count as f64 {
+        None
+    } else {
+        Some(position as usize)
+    }
+}
+
+/// Whether a longitude lies between `min_lng` and `max_lng` going east
+fn longitude_in_range(lng: f64, min_lng: f64, max_lng: f64) -> bool {
+    let span = max_lng - min_lng;
+    if span >= 360.0 {
+        return !lng.is_nan();
+    }
+    (lng - min_lng).rem_euclid(360.0) <= span.rem_euclid(360.0) + EDGE_TOLERANCE
+}
+
+/// Start and length of the span from the first to the last selected position
+fn selected_range(selected: impl Iterator<Item = bool>) -> Option<(usize, usize)> {
+    let (first, last) = selected
+        .enumerate()
+        .filter(|(_, selected)| *selected)
+        .map(|(index, _)| index)
+        .minmax()
+        .into_option()?;
+    Some((first, last - first + 1))
+}
+
+/// Like [`selected_range`] on an axis that circles the globe, where the span
+/// may wrap past the last position to the first. The span leaves out the
+/// longest run of positions that are not selected.
+fn circular_selected_range(selected: &[bool]) -> Option<(usize, usize)> {
+    let count = selected.len();
+    if !selected.iter().any(|s| *s) {
+        return None;
+    }
+
+    let (mut gap_start, mut gap_length, mut run) = (0, 0, 0);
+    for index in 0..2 * count {
+        if selected[index % count] {
+            run = 0;
+            continue;
+        }
+        run += 1;
+        if run > gap_length {
+            gap_start = (index + 1 - run) % count;
+            gap_length = run;
+        }
+    }
+
+    Some(((gap_start + gap_length) % count, count - gap_length))
+}
+
+/// The points along an axis used to interpolate at a position, with their weights
+fn axis_weights(position: AxisPosition, method: Interpolation) -> Vec<(usize, f64)> {
+    let (lower, upper, fraction) = position;
+    match method {
+        Interpolation::Nearest if fraction < 0.5 => vec![(lower, 1.0)],
+        Interpolation::Nearest => vec![(upper, 1.0)],
+        Interpolation::Bilinear => vec![(lower, 1.0 - fraction), (upper, fraction)],
+    }
+}
+
+fn grid_weights(
+    rows: Option<AxisPosition>,
+    columns: Option<AxisPosition>,
+    nx: usize,
+    method: Interpolation,
+) -> Vec<(usize, f64)> {
+    let (Some(rows), Some(columns)) = (rows, columns) else {
+        return Vec::new();
+    };
+    let columns = axis_weights(columns, method);
+    axis_weights(rows, method)
+        .into_iter()
+        .flat_map(|(row, row_weight)| {
+            columns
+                .iter()
+                .map(move |(column, weight)| (row * nx + column, row_weight * weight))
+        })
+        .filter(|(_, weight)| *weight > 0.0)
+        .collect()
+}
+
 /// Wrap a longitude given in `[0, 360)` into `[-180, 180)`. The antimeridian
 /// (exactly 180°) maps to -180.
 fn wrap_longitude(lon: f64) -> f64 {
@@ -862,4 +1434,173 @@ mod tests {
         }
         assert_eq!(both, expected);
     }
+
+    fn sorted(mut weights: Vec<(usize, f64)>) -> Vec<(usize, f64)> {
+        weights.sort_by_key(|(index, _)| *index);
+        weights
+    }
+
+    #[test]
+    fn test_interpolation_weights_regular_grid() {
+        use crate::interpolation::Interpolation::{Bilinear, Nearest};
+
+        // Rows at 90..86, a regional 10 column grid at 0..18
+        let projection = platecaree_grid(90.0, -1.0, 5, 0.0, 2.0, 10);
+        assert_eq!(
+            projection.interpolation_weights(88.0, 4.0, Nearest),
+            vec![(22, 1.0)]
+        );
+        assert_eq!(
+            projection.interpolation_weights(87.6, 4.9, Nearest),
+            vec![(22, 1.0)]
+        );
+        assert_eq!(
+            sorted(projection.interpolation_weights(88.5, 5.0, Bilinear)),
+            vec![(12, 0.25), (13, 0.25), (22, 0.25), (23, 0.25)]
+        );
+        // Exactly on the last column, no neighbour beyond it is used
+        assert_eq!(
+            projection.interpolation_weights(86.0, 18.0, Bilinear),
+            vec![(49, 1.0)]
+        );
+        // Off the grid, including on the far side of the globe
+        assert!(projection
+            .interpolation_weights(85.0, 4.0, Nearest)
+            .is_empty());
+        assert!(projection
+            .interpolation_weights(88.0, 19.0, Nearest)
+            .is_empty());
+        assert!(projection
+            .interpolation_weights(88.0, -1.0, Bilinear)
+            .is_empty());
+        // Just west of the first column is still on it
+        assert_eq!(
+            projection.interpolation_weights(88.0, 360.0 - 1e-9, Nearest),
+            vec![(20, 1.0)]
+        );
+    }
+
+    #[test]
+    fn test_interpolation_weights_global_grid() {
+        use crate::interpolation::Interpolation::{Bilinear, Nearest};
+
+        // A 1° global grid from 0 to 359
+        let projection = platecaree_grid(90.0, -1.0, 5, 0.0, 1.0, 360);
+        // Negative longitudes wrap into the grid
+        assert_eq!(
+            projection.interpolation_weights(90.0, -71.0, Nearest),
+            vec![(289, 1.0)]
+        );
+        // Between the last and first columns
+        assert_eq!(
+            sorted(projection.interpolation_weights(90.0, 359.5, Bilinear)),
+            vec![(0, 0.5), (359, 0.5)]
+        );
+        assert_eq!(
+            projection.interpolation_weights(90.0, -0.4, Nearest),
+            vec![(0, 1.0)]
+        );
+    }
+
+    #[test]
+    fn test_interpolation_weights_reduced_gaussian_grid() {
+        use crate::interpolation::Interpolation::{Bilinear, Nearest};
+
+        let projection = super::LatLngProjection::Gaussian(super::GaussianProjection {
+            latitudes: vec![60.0, 20.0, -20.0, -60.0],
+            longitudes: super::GaussianLongitudes::Reduced(vec![
+                super::RegularCoordinateIterator::new(0.0, 90.0, 4),
+                super::RegularCoordinateIterator::new(0.0, 45.0, 8),
+                super::RegularCoordinateIterator::new(0.0, 45.0, 8),
+                super::RegularCoordinateIterator::new(0.0, 90.0, 4),
+            ]),
+            projection_name: "gaussian".into(),
+            projection_params: HashMap::new(),
+        });
+
+        assert_eq!(
+            projection.interpolation_weights(25.0, 50.0, Nearest),
+            vec![(5, 1.0)]
+        );
+        // Poleward of the outermost row takes that row
+        assert_eq!(
+            projection.interpolation_weights(-80.0, 180.0, Nearest),
+            vec![(22, 1.0)]
+        );
+        assert_eq!(
+            sorted(projection.interpolation_weights(40.0, 45.0, Bilinear)),
+            vec![(0, 0.25), (1, 0.25), (5, 0.5)]
+        );
+    }
+
+    #[test]
+    fn test_convergence_angle() {
+        use super::GridProjection;
+        use crate::utils::projections::{
+            lambert_conformal::LambertConformal, mercator::Mercator,
+            polar_stereographic::PolarStereographic,
+        };
+        use mappers::Ellipsoid;
+
+        let sphere = Ellipsoid {
+            A: 6_371_229.0,
+            B: 6_371_229.0,
+            E: 0.0,
+            F: 0.0,
+        };
+
+        // Tangent Lambert conformal meridians converge by the cone constant
+        let hrrr = GridProjection::LambertConformalConic(
+            LambertConformal::new(-97.5, 38.5, 38.5, 38.5, sphere).unwrap(),
+        );
+        let cone = 38.5_f64.to_radians().sin();
+        for (lon, lat) in [(-97.5, 38.5), (-87.5, 30.0), (-122.0, 47.0)] {
+            let angle = hrrr.convergence_angle(lon, lat).unwrap().to_degrees();
+            let expected = cone * (lon + 97.5);
+            assert!((angle - expected).abs() < 1e-3, "{angle} != {expected}");
+        }
+
+        let polar = GridProjection::PolarStereographic(PolarStereographic::new(
+            -105.0, 60.0, false, sphere,
+        ));
+        let angle = polar.convergence_angle(-15.0, 70.0).unwrap().to_degrees();
+        assert!((angle - 90.0).abs() < 1e-3, "{angle} != 90");
+        assert!(polar.convergence_angle(-105.0, 89.99).unwrap().abs() < 1e-3);
+
+        let mercator = GridProjection::Mercator(Mercator::new(0.0, 20.0, sphere));
+        assert!(mercator.convergence_angle(45.0, -30.0).unwrap().abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_subset_window() {
+        use super::GridWindow;
+
+        // 10° global grid from 90N and 0E
+        let projection = platecaree_grid(90.0, -10.0, 19, 0.0, 10.0, 36);
+
+        let window = projection.subset_window((5.0, -15.0, 25.0, 15.0)).unwrap();
+        assert_eq!(
+            window,
+            GridWindow {
+                row_start: 8,
+                row_count: 3,
+                column_start: 1,
+                column_count: 2,
+            }
+        );
+
+        // Across the seam, the window wraps from the last columns to the first
+        let window = projection.subset_window((-20.0, 80.0, 10.0, 90.0)).unwrap();
+        assert_eq!((window.column_start, window.column_count), (34, 4));
+        assert_eq!(
+            window.indices(36).collect::<Vec<_>>(),
+            vec![34, 35, 0, 1, 70, 71, 36, 37]
+        );
+
+        // Crossing the antimeridian
+        let window = projection.subset_window((170.0, 0.0, -170.0, 0.0)).unwrap();
+        assert_eq!((window.column_start, window.column_count), (17, 3));
+
+        assert!(projection.subset_window((1.0, 1.0, 2.0, 2.0)).is_none());
+    }
 }
@@ -2,12 +2,18 @@ pub mod iter;
 #[macro_use]
 pub mod macros;
 pub mod ccsds;
+pub mod ccsds_encoder;
 #[cfg(feature = "libaec")]
 pub mod ccsds_libaec;
+#[cfg(feature = "serde")]
+pub mod code_description;
 pub mod convert;
+pub mod gaussian;
+pub mod pack;
 pub mod projections;
 
 pub use convert::*;
+pub use pack::*;
 
 #[cfg(feature = "jpeg")]
 pub mod jpeg;
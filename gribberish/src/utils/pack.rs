@@ -0,0 +1,72 @@
+use bitvec::prelude::*;
+
+/// Write a signed value in the sign-magnitude form GRIB uses for scale factors
+/// and other signed octets. This is the inverse of the `as_signed` macro.
+pub fn sign_magnitude_u16(value: i16) -> u16 {
+    let magnitude = value.unsigned_abs() & 0x7FFF;
+    if value < 0 {
+        magnitude | 0x8000
+    } else {
+        magnitude
+    }
+}
+
+/// Write a signed value in the sign-magnitude form GRIB uses for latitudes and
+/// other signed 32 bit fields
+pub fn sign_magnitude_u32(value: i32) -> u32 {
+    let magnitude = value.unsigned_abs() & 0x7FFF_FFFF;
+    if value < 0 {
+        magnitude | 0x8000_0000
+    } else {
+        magnitude
+    }
+}
+
+/// Pack unsigned integers into a big endian bit stream using `bits` bits per value,
+/// padding the final octet with zeros
+pub fn pack_bits<I>(values: I, bits: usize) -> Vec<u8>
+where
+    I: IntoIterator<Item = u32>,
+{
+    let mut packed: BitVec<u8, Msb0> = BitVec::new();
+    if bits == 0 {
+        return vec![];
+    }
+
+    for value in values {
+        let start = packed.len();
+        packed.resize(start + bits, false);
+        packed[start..].store_be(value);
+    }
+
+    packed.into_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_magnitude_roundtrip() {
+        for value in [-32767i16, -4, -1, 0, 1, 6, 32767] {
+            let raw = sign_magnitude_u16(value);
+            assert_eq!(as_signed!(raw, 16, i16), value);
+        }
+        for value in [-90_000_000i32, -1, 0, 38_500_000] {
+            let raw = sign_magnitude_u32(value);
+            assert_eq!(as_signed!(raw, 32, i32), value);
+        }
+    }
+
+    #[test]
+    fn test_pack_bits() {
+        let packed = pack_bits([1u32, 2, 3, 4, 5], 3);
+        assert_eq!(packed, vec![0b0010_1001, 0b1100_1010]);
+
+        let bits = packed.view_bits::<Msb0>();
+        let unpacked = (0..5)
+            .map(|i| bits[i * 3..(i + 1) * 3].load_be::<u32>())
+            .collect::<Vec<_>>();
+        assert_eq!(unpacked, vec![1, 2, 3, 4, 5]);
+    }
+}
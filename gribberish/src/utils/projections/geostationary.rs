@@ -0,0 +1,171 @@
+//! Geostationary satellite view, the projection of GRIB2 grid definition
+//! template 3.90 (space view perspective).
+//!
+//! Reference: CGMS *LRIT/HRIT Global Specification*, section 4.4.3.2, and
+//! PROJ's `geos`. Projected coordinates are the satellite's scanning angles
+//! multiplied by its height above the ellipsoid, so they are in metres like
+//! PROJ's. The scan sweeps around the y axis first (PROJ's default
+//! `+sweep=y`), which is the CGMS convention GRIB follows. Points on the far
+//! side of the earth, and grid points off the edge of the disk, project to NaN.
+
+use mappers::{Ellipsoid, Projection};
+
+use super::wrap_longitude_degrees;
+
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Geostationary {
+    /// Longitude of the sub-satellite point, in radians
+    lon_origin: f64,
+    /// Height of the satellite above the ellipsoid, in metres
+    height: f64,
+    /// Equatorial radius, in metres
+    radius: f64,
+    /// Ratio of the polar to the equatorial radius
+    flattening_ratio: f64,
+}
+
+impl Geostationary {
+    pub fn new(lon_origin_deg: f64, height: f64, ellipsoid: Ellipsoid) -> Self {
+        Self {
+            lon_origin: lon_origin_deg.to_radians(),
+            height,
+            radius: ellipsoid.A,
+            flattening_ratio: ellipsoid.B / ellipsoid.A,
+        }
+    }
+
+    /// Distance of the satellite from the centre of the earth, in equatorial radii
+    fn satellite_distance(&self) -> f64 {
+        1.0 + self.height / self.radius
+    }
+
+    /// Height of the satellite above the ellipsoid, in equatorial radii
+    fn scaled_height(&self) -> f64 {
+        self.height / self.radius
+    }
+}
+
+impl Projection for Geostationary {
+    fn project_unchecked(&self, lon: f64, lat: f64) -> (f64, f64) {
+        let lambda = lon.to_radians() - self.lon_origin;
+        let ratio2 = self.flattening_ratio * self.flattening_ratio;
+
+        // Geocentric latitude and the vector from the earth's centre to the point
+        let phi = (ratio2 * lat.to_radians().tan()).atan();
+        let r = self.flattening_ratio / (self.flattening_ratio * phi.cos()).hypot(phi.sin());
+        let vx = r * lambda.cos() * phi.cos();
+        let vy = r * lambda.sin() * phi.cos();
+        let vz = r * phi.sin();
+
+        let distance = self.satellite_distance();
+        if (distance - vx) * vx - vy * vy - vz * vz / ratio2 < 0.0 {
+            return (f64::NAN, f64::NAN);
+        }
+
+        let tmp = distance - vx;
+        let x = self.scaled_height() * (vy / tmp).atan();
+        let y = self.scaled_height() * (vz / vy.hypot(tmp)).atan();
+        (x * self.radius, y * self.radius)
+    }
+
+    fn inverse_project_unchecked(&self, x: f64, y: f64) -> (f64, f64) {
+        let distance = self.satellite_distance();
+        let scaled_height = self.scaled_height();
+
+        // Direction from the satellite to the point
+        let vx = -1.0;
+        let vy = (x / self.radius / scaled_height).tan();
+        let vz = (y / self.radius / scaled_height).tan() * 1.0f64.hypot(vy);
+
+        // Intersect the line of sight with the ellipsoid
+        let a = vz / self.flattening_ratio;
+        let a = vy * vy + a * a + vx * vx;
+        let b = 2.0 * distance * vx;
+        let c = distance * distance - 1.0;
+        let det = b * b - 4.0 * a * c;
+        if det < 0.0 {
+            return (f64::NAN, f64::NAN);
+        }
+
+        let k = (-b - det.sqrt()) / (2.0 * a);
+        let vx = distance + k * vx;
+        let vy = vy * k;
+        let vz = vz * k;
+
+        let lambda = vy.atan2(vx);
+        let phi = (vz * lambda.cos() / vx).atan();
+        let phi = (phi.tan() / (self.flattening_ratio * self.flattening_ratio)).atan();
+
+        (
+            wrap_longitude_degrees((lambda + self.lon_origin).to_degrees()),
+            phi.to_degrees(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Geostationary;
+    use mappers::{Ellipsoid, Projection};
+
+    /// GOES-16 ABI fixed grid: 75W, 35786023 m above GRS80
+    fn goes_east() -> Geostationary {
+        Geostationary::new(
+            -75.0,
+            35_786_023.0,
+            Ellipsoid {
+                A: 6_378_137.0,
+                B: 6_356_752.314_14,
+                E: 0.081_819_191_042_8,
+                F: 1.0 / 298.257_222_101,
+            },
+        )
+    }
+
+    #[test]
+    fn sub_satellite_point_is_origin() {
+        let projection = goes_east();
+        let (x, y) = projection.project(-75.0, 0.0).unwrap();
+        assert!(x.abs() < 1e-6 && y.abs() < 1e-6);
+    }
+
+    /// Along the equator and the sub-satellite meridian the scan angle is
+    /// plain trigonometry on the plane through the satellite and the point
+    #[test]
+    fn matches_scan_angle_geometry() {
+        let projection = goes_east();
+        let (a, b) = (6_378_137.0f64, 6_356_752.314_14f64);
+        let distance = a + 35_786_023.0;
+
+        let lambda = 25.0f64.to_radians();
+        let expected = (a * lambda.sin() / (distance - a * lambda.cos())).atan();
+        let (x, y) = projection.project(-100.0, 0.0).unwrap();
+        assert!((x / 35_786_023.0 - -expected).abs() < 1e-12, "{x}");
+        assert!(y.abs() < 1e-6);
+
+        // Geodetic to geocentric latitude on the ellipse
+        let phi = ((b * b) / (a * a) * 40.0f64.to_radians().tan()).atan();
+        let r = a * b / (b * phi.cos()).hypot(a * phi.sin());
+        let expected = (r * phi.sin() / (distance - r * phi.cos())).atan();
+        let (x, y) = projection.project(-75.0, 40.0).unwrap();
+        assert!(x.abs() < 1e-6);
+        assert!((y / 35_786_023.0 - expected).abs() < 1e-12, "{y}");
+    }
+
+    #[test]
+    fn roundtrips_and_rejects_the_far_side() {
+        let projection = goes_east();
+        for &(lon, lat) in &[(-75.0, 0.0), (-120.0, 45.0), (-30.0, -60.0), (-75.0, 80.0)] {
+            let (x, y) = projection.project(lon, lat).unwrap();
+            let (got_lon, got_lat) = projection.inverse_project(x, y).unwrap();
+            assert!((got_lon - lon).abs() < 1e-9, "{lon} {got_lon}");
+            assert!((got_lat - lat).abs() < 1e-9, "{lat} {got_lat}");
+        }
+
+        assert!(projection.project(105.0, 0.0).is_err());
+        // Beyond the limb, the apparent radius of the earth is about 8.7 degrees
+        let off_disk = 35_786_023.0 * 9.0f64.to_radians();
+        assert!(projection.inverse_project(off_disk, 0.0).is_err());
+    }
+}
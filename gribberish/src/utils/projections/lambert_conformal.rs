@@ -0,0 +1,117 @@
+//! Lambert conformal conic, the projection of GRIB2 grid definition template
+//! 3.30.
+//!
+//! The projection itself comes from `mappers`, whose fields are private. This
+//! keeps the parameters it was built from alongside it so grids using it can be
+//! serialised and rebuilt.
+
+use mappers::{projections::LambertConformalConic, Ellipsoid, Projection, ProjectionError};
+
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        try_from = "LambertConformalParameters",
+        into = "LambertConformalParameters"
+    )
+)]
+pub struct LambertConformal {
+    /// Central meridian in degrees
+    lon_origin: f64,
+    /// Latitude of origin in degrees
+    lat_origin: f64,
+    /// First standard parallel in degrees
+    lat_1: f64,
+    /// Second standard parallel in degrees
+    lat_2: f64,
+    ellipsoid: Ellipsoid,
+    projection: LambertConformalConic,
+}
+
+impl LambertConformal {
+    pub fn new(
+        lon_origin_deg: f64,
+        lat_origin_deg: f64,
+        lat_1_deg: f64,
+        lat_2_deg: f64,
+        ellipsoid: Ellipsoid,
+    ) -> Result<Self, ProjectionError> {
+        let projection = LambertConformalConic::new(
+            lon_origin_deg,
+            lat_origin_deg,
+            lat_1_deg,
+            lat_2_deg,
+            ellipsoid,
+        )?;
+
+        Ok(Self {
+            lon_origin: lon_origin_deg,
+            lat_origin: lat_origin_deg,
+            lat_1: lat_1_deg,
+            lat_2: lat_2_deg,
+            ellipsoid,
+            projection,
+        })
+    }
+}
+
+impl Projection for LambertConformal {
+    fn project_unchecked(&self, lon: f64, lat: f64) -> (f64, f64) {
+        self.projection.project_unchecked(lon, lat)
+    }
+
+    fn inverse_project_unchecked(&self, x: f64, y: f64) -> (f64, f64) {
+        self.projection.inverse_project_unchecked(x, y)
+    }
+}
+
+/// The parameters a [`LambertConformal`] is serialised as
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct LambertConformalParameters {
+    lon_origin: f64,
+    lat_origin: f64,
+    lat_1: f64,
+    lat_2: f64,
+    semi_major_axis: f64,
+    semi_minor_axis: f64,
+    eccentricity: f64,
+    flattening: f64,
+}
+
+#[cfg(feature = "serde")]
+impl From<LambertConformal> for LambertConformalParameters {
+    fn from(value: LambertConformal) -> Self {
+        LambertConformalParameters {
+            lon_origin: value.lon_origin,
+            lat_origin: value.lat_origin,
+            lat_1: value.lat_1,
+            lat_2: value.lat_2,
+            semi_major_axis: value.ellipsoid.A,
+            semi_minor_axis: value.ellipsoid.B,
+            eccentricity: value.ellipsoid.E,
+            flattening: value.ellipsoid.F,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<LambertConformalParameters> for LambertConformal {
+    type Error = ProjectionError;
+
+    fn try_from(value: LambertConformalParameters) -> Result<Self, Self::Error> {
+        LambertConformal::new(
+            value.lon_origin,
+            value.lat_origin,
+            value.lat_1,
+            value.lat_2,
+            Ellipsoid {
+                A: value.semi_major_axis,
+                B: value.semi_minor_axis,
+                E: value.eccentricity,
+                F: value.flattening,
+            },
+        )
+    }
+}
@@ -10,6 +10,7 @@ use mappers::{Ellipsoid, Projection};
 use super::conformal::{conformal_t, inverse_conformal_t};
 
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Mercator {
     /// Central meridian in radians. GRIB2 template 3.10 has no field for this,
     /// so grids built from it always pass zero.
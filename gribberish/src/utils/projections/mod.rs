@@ -1,9 +1,12 @@
 //! Map projections needed by GRIB2 grid definition templates that the
-//! `mappers` crate does not provide.
+//! `mappers` crate does not provide, or does not let us serialise.
 
 pub mod conformal;
+pub mod geostationary;
+pub mod lambert_conformal;
 pub mod mercator;
 pub mod polar_stereographic;
+pub mod rotated_pole;
 
 /// Normalize a longitude in degrees to `[-180, 180)`, the convention the rest
 /// of gribberish uses for projected grids.
@@ -16,6 +16,7 @@ use super::conformal::{conformal_t, inverse_conformal_t};
 use super::wrap_longitude_degrees;
 
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PolarStereographic {
     /// Orientation of the grid (`LoV`) in radians: the meridian that runs
     /// straight down the map from the pole.
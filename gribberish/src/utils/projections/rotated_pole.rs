@@ -0,0 +1,157 @@
+//! Rotated pole latitude/longitude, the projection of GRIB2 grid definition
+//! template 3.1.
+//!
+//! The grid is regular in a spherical coordinate system whose south pole sits
+//! at the template's southern pole location, then the system is turned about
+//! its new polar axis by the angle of rotation. Projected coordinates are the
+//! rotated longitude and latitude in degrees, matching PROJ's `ob_tran` with
+//! `o_proj=longlat`. The transformation follows ecCodes' `rotate`/`unrotate`.
+
+use mappers::Projection;
+
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RotatedPole {
+    /// Latitude of the southern pole of the rotated system, degrees
+    south_pole_lat: f64,
+    /// Longitude of the southern pole of the rotated system, degrees
+    south_pole_lon: f64,
+    /// Angle of rotation about the new polar axis, degrees
+    angle_of_rotation: f64,
+}
+
+impl RotatedPole {
+    pub fn new(south_pole_lat: f64, south_pole_lon: f64, angle_of_rotation: f64) -> Self {
+        Self {
+            south_pole_lat,
+            south_pole_lon,
+            angle_of_rotation,
+        }
+    }
+
+    /// The geographic location of the rotated system's north pole as
+    /// `(latitude, longitude)`, the form CF's `rotated_latitude_longitude`
+    /// grid mapping describes it in.
+    pub fn north_pole(&self) -> (f64, f64) {
+        (
+            -self.south_pole_lat,
+            super::wrap_longitude_degrees(self.south_pole_lon + 180.0),
+        )
+    }
+
+    /// Sines and cosines of the tilt and spin that carry the rotated system's
+    /// pole onto the geographic pole.
+    fn rotation(&self) -> (f64, f64, f64, f64) {
+        let tilt = -(90.0 + self.south_pole_lat).to_radians();
+        let spin = -self.south_pole_lon.to_radians();
+        (tilt.sin(), tilt.cos(), spin.sin(), spin.cos())
+    }
+}
+
+fn to_cartesian(lon: f64, lat: f64) -> (f64, f64, f64) {
+    let (lon, lat) = (lon.to_radians(), lat.to_radians());
+    (lon.cos() * lat.cos(), lon.sin() * lat.cos(), lat.sin())
+}
+
+fn from_cartesian(x: f64, y: f64, z: f64) -> (f64, f64) {
+    (
+        y.atan2(x).to_degrees(),
+        z.clamp(-1.0, 1.0).asin().to_degrees(),
+    )
+}
+
+impl Projection for RotatedPole {
+    fn project_unchecked(&self, lon: f64, lat: f64) -> (f64, f64) {
+        let (sin_t, cos_t, sin_o, cos_o) = self.rotation();
+        let (x, y, z) = to_cartesian(lon, lat);
+
+        // Transpose of the rotation in `inverse_project_unchecked`
+        let xr = cos_t * cos_o * x - cos_t * sin_o * y - sin_t * z;
+        let yr = sin_o * x + cos_o * y;
+        let zr = sin_t * cos_o * x - sin_t * sin_o * y + cos_t * z;
+
+        let (rlon, rlat) = from_cartesian(xr, yr, zr);
+        (
+            super::wrap_longitude_degrees(rlon - self.angle_of_rotation),
+            rlat,
+        )
+    }
+
+    fn inverse_project_unchecked(&self, x: f64, y: f64) -> (f64, f64) {
+        let (sin_t, cos_t, sin_o, cos_o) = self.rotation();
+        let (xr, yr, zr) = to_cartesian(x + self.angle_of_rotation, y);
+
+        let gx = cos_t * cos_o * xr + sin_o * yr + sin_t * cos_o * zr;
+        let gy = -cos_t * sin_o * xr + cos_o * yr - sin_t * sin_o * zr;
+        let gz = -sin_t * xr + cos_t * zr;
+
+        let (lon, lat) = from_cartesian(gx, gy, gz);
+        (super::wrap_longitude_degrees(lon), lat)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RotatedPole;
+    use mappers::Projection;
+
+    /// The COSMO-DE / ICON-D2 style pole: north pole at 40N 170W.
+    fn cosmo() -> RotatedPole {
+        RotatedPole::new(-40.0, 10.0, 0.0)
+    }
+
+    #[test]
+    fn rotated_poles_land_on_the_pole_location() {
+        let projection = cosmo();
+        assert_eq!(projection.north_pole(), (40.0, -170.0));
+
+        let (lon, lat) = projection.inverse_project(0.0, 90.0).unwrap();
+        assert!((lat - 40.0).abs() < 1e-9, "lat {lat}");
+        assert!((lon - -170.0).abs() < 1e-9, "lon {lon}");
+
+        let (lon, lat) = projection.inverse_project(0.0, -90.0).unwrap();
+        assert!((lat - -40.0).abs() < 1e-9, "lat {lat}");
+        assert!((lon - 10.0).abs() < 1e-9, "lon {lon}");
+    }
+
+    /// The rotated origin sits on the pole's meridian, 90 degrees from it.
+    #[test]
+    fn rotated_origin_is_on_the_pole_meridian() {
+        let (lon, lat) = cosmo().inverse_project(0.0, 0.0).unwrap();
+        assert!((lat - 50.0).abs() < 1e-9, "lat {lat}");
+        assert!((lon - 10.0).abs() < 1e-9, "lon {lon}");
+    }
+
+    /// Checked against CDO's `lamrot_to_lam`/`phirot_to_phi`, which use the
+    /// closed form trigonometry instead of a rotation matrix.
+    #[test]
+    fn matches_cdo_rotated_to_geographic() {
+        let (lon, lat) = cosmo().inverse_project(-5.0, -5.0).unwrap();
+        assert!((lat - 44.765_178_904_2).abs() < 1e-9, "lat {lat}");
+        assert!((lon - 2.975_852_266_7).abs() < 1e-9, "lon {lon}");
+    }
+
+    #[test]
+    fn round_trip() {
+        for projection in [cosmo(), RotatedPole::new(-35.0, -15.0, 12.5)] {
+            for &(lon, lat) in &[(3.0, 45.0), (-120.0, -30.0), (179.5, 10.0), (10.0, 50.0)] {
+                let (x, y) = projection.project(lon, lat).unwrap();
+                let (rlon, rlat) = projection.inverse_project(x, y).unwrap();
+                assert!((rlon - lon).abs() < 1e-9, "lon {lon} -> {rlon}");
+                assert!((rlat - lat).abs() < 1e-9, "lat {lat} -> {rlat}");
+            }
+        }
+    }
+
+    /// The angle of rotation spins the grid about the rotated pole, so it
+    /// only shifts rotated longitudes.
+    #[test]
+    fn angle_of_rotation_offsets_rotated_longitude() {
+        let plain = cosmo();
+        let spun = RotatedPole::new(-40.0, 10.0, 20.0);
+        let (x0, y0) = plain.project(5.0, 48.0).unwrap();
+        let (x1, y1) = spun.project(5.0, 48.0).unwrap();
+        assert!((x0 - x1 - 20.0).abs() < 1e-9);
+        assert!((y0 - y1).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,71 @@
+//! Earth-relative vector components.
+//!
+//! Vectors on projected grids, such as HRRR and NAM winds on their Lambert
+//! conformal grids, are often resolved along the grid's x and y axes, which the
+//! resolution and component flags of the grid definition signal. Away from the
+//! projection's central meridian those axes are turned from east and north by
+//! the projection's convergence angle, so the components must be rotated back
+//! before they are plotted or compared with observations.
+
+use crate::error::GribberishError;
+use crate::message::Message;
+
+/// Rotate grid-relative `u` and `v` components through the convergence angle,
+/// in radians, at each point, giving eastward and northward components
+pub fn rotate_to_earth(u: &[f64], v: &[f64], angles: &[f64]) -> (Vec<f64>, Vec<f64>) {
+    u.iter()
+        .zip(v.iter())
+        .zip(angles.iter())
+        .map(|((u, v), angle)| {
+            let (sin, cos) = angle.sin_cos();
+            (u * cos + v * sin, v * cos - u * sin)
+        })
+        .unzip()
+}
+
+/// Decode a pair of `u` and `v` messages on the same grid as eastward and
+/// northward components, rotating them when the grid flags them as resolved
+/// along the grid axes
+pub fn earth_relative_components(
+    u: &Message,
+    v: &Message,
+) -> Result<(Vec<f64>, Vec<f64>), GribberishError> {
+    if u.grid_dimensions()? != v.grid_dimensions()? || u.proj_string()? != v.proj_string()? {
+        return Err(GribberishError::MessageError(
+            "u and v components must be on the same grid".into(),
+        ));
+    }
+
+    let grid_relative = u.has_grid_relative_vectors()?;
+    if grid_relative != v.has_grid_relative_vectors()? {
+        return Err(GribberishError::MessageError(
+            "u and v components must both be grid or earth relative".into(),
+        ));
+    }
+
+    let (u_data, v_data) = (u.data()?, v.data()?);
+    if !grid_relative {
+        return Ok((u_data, v_data));
+    }
+
+    let angles = u.latlng_projector()?.convergence_angles();
+    Ok(rotate_to_earth(&u_data, &v_data, &angles))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotate_to_earth() {
+        let angles = [0.0, 90f64.to_radians(), -30f64.to_radians()];
+        let (u, v) = rotate_to_earth(&[1.0, 0.0, 0.0], &[2.0, 1.0, 1.0], &angles);
+
+        assert_eq!((u[0], v[0]), (1.0, 2.0));
+        // A grid whose +y points east
+        assert!((u[1] - 1.0).abs() < 1e-12 && v[1].abs() < 1e-12);
+        // Speed is unchanged
+        assert!((u[2] + 0.5).abs() < 1e-12);
+        assert!((u[2].hypot(v[2]) - 1.0).abs() < 1e-12);
+    }
+}
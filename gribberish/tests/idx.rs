@@ -1,9 +1,21 @@
 use std::fs::read_to_string;
+use std::future::Future;
+use std::ops::Range;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Context, Poll, Waker};
 use std::{fs::File, io::Read};
 
 use chrono::{TimeZone, Utc};
-use gribberish::index::parse_index;
+use gribberish::error::GribberishError;
+use gribberish::index::{
+    parse_ecmwf_index, parse_index, parse_noaa_index, write_ecmwf_index, write_noaa_index,
+};
+use gribberish::indexed_reader::{
+    coalesce_ranges, FileRangeSource, GribIndexedReader, RangeSource,
+};
+use gribberish::message::read_messages;
 use gribberish::message_metadata::scan_message_metadata;
+use gribberish::selector::Selector;
 
 extern crate gribberish;
 
@@ -26,6 +38,31 @@ pub fn read_idx(path: &str) -> Vec<String> {
         .collect()
 }
 
+/// Run a future to completion without an async runtime. The file backed range
+/// source never returns pending, so there is nothing to wake.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let mut context = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+/// Counts the requests made to a file backed range source
+struct CountingRangeSource {
+    inner: FileRangeSource,
+    requests: AtomicUsize,
+}
+
+impl RangeSource for CountingRangeSource {
+    async fn fetch(&self, range: Range<u64>) -> Result<Vec<u8>, GribberishError> {
+        self.requests.fetch_add(1, Ordering::SeqCst);
+        self.inner.fetch(range).await
+    }
+}
+
 #[test]
 fn test_gfs_wave_idx_generation() {
     let read_data = read_grib_messages("../test-data/gfswave.t18z.atlocn.0p16.f001.grib2");
@@ -34,7 +71,7 @@ fn test_gfs_wave_idx_generation() {
         .iter()
         .map(|m| (m.1 .0, m.1 .2.as_idx(m.1 .0)))
         .collect::<Vec<_>>();
-    idxs.sort_by(|a, b| a.0.cmp(&b.0));
+    idxs.sort_by_key(|a| a.0);
 
     let idx_lines = read_idx("../test-data/gfswave.t18z.atlocn.0p16.f001.grib2.idx");
 
@@ -81,3 +118,263 @@ fn test_index_parsing_locates_messages() {
     assert_eq!(entries[1].level.as_deref(), Some("500"));
     assert_eq!(entries[1].keys["levtype"], "pl");
 }
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_index_entry_serde() {
+    let idx_text = read_to_string("../test-data/gfswave.t18z.atlocn.0p16.f001.grib2.idx").unwrap();
+    let entries = parse_index(&idx_text, None).unwrap();
+    let json = serde_json::to_string(&entries).unwrap();
+    let roundtrip: Vec<gribberish::index::IndexEntry> = serde_json::from_str(&json).unwrap();
+    assert_eq!(roundtrip, entries);
+}
+
+#[test]
+fn test_coalesce_ranges() {
+    assert_eq!(coalesce_ranges(&[]), Vec::<Range<u64>>::new());
+    assert_eq!(
+        coalesce_ranges(&[20..30, 0..10, 10..15, 25..40, 50..60, 45..45]),
+        vec![0..15, 20..40, 50..60]
+    );
+}
+
+#[test]
+fn test_indexed_reader() {
+    let path = "../test-data/gfswave.t18z.atlocn.0p16.f001.grib2";
+    let data = read_grib_messages(path);
+    let idx_text = read_to_string(format!("{path}.idx")).unwrap();
+    let entries = parse_index(&idx_text, Some(data.len() as u64)).unwrap();
+
+    let reader = GribIndexedReader::new(
+        CountingRangeSource {
+            inner: FileRangeSource::new(path),
+            requests: AtomicUsize::new(0),
+        },
+        entries.clone(),
+    );
+
+    // Only the headers are fetched for metadata, which matches a full scan
+    let metadata = block_on(reader.metadata()).unwrap();
+    let header_requests = reader.source().requests.load(Ordering::SeqCst);
+    assert!(header_requests <= entries.len());
+
+    let mut scanned: Vec<_> = scan_message_metadata(&data).into_values().collect();
+    scanned.sort_by_key(|(index, _, _)| *index);
+    assert_eq!(metadata.len(), scanned.len());
+    for (meta, (_, offset, expected)) in metadata.iter().zip(scanned.iter()) {
+        assert_eq!(meta.byte_offset, *offset);
+        assert_eq!(meta, expected);
+    }
+    // Including the bitmaps of fields masked over land, though their bitmap
+    // sections do not fit in the headers fetched
+    assert!(metadata.iter().any(|meta| meta.has_bitmap));
+
+    // Messages are next to each other in the file, so they are fetched in one request
+    let all = entries.iter().collect::<Vec<_>>();
+    let fetched = block_on(reader.fetch_messages(&all)).unwrap();
+    assert_eq!(
+        reader.source().requests.load(Ordering::SeqCst),
+        header_requests + 1
+    );
+    let messages = read_messages(&data).collect::<Vec<_>>();
+    assert_eq!(fetched.len(), messages.len());
+    for (owned, message) in fetched.iter().zip(messages.iter()) {
+        assert_eq!(owned.offset(), message.byte_offset() as u64);
+        assert_eq!(owned.bytes().len(), message.len());
+    }
+
+    // Data is read from the whole message fetched on demand
+    let entry = &entries[2];
+    let values = block_on(reader.data(entry)).unwrap();
+    assert_eq!(values.len(), messages[2].data().unwrap().len());
+    for (a, b) in values.iter().zip(messages[2].data().unwrap()) {
+        assert!(a == &b || (a.is_nan() && b.is_nan()));
+    }
+}
+
+#[test]
+fn test_write_noaa_index() {
+    // Matches the inventory wgrib2 wrote for the file
+    let path = "../test-data/gfswave.t18z.atlocn.0p16.f001.grib2";
+    let data = read_grib_messages(path);
+    let index = write_noaa_index(&data);
+    assert_eq!(
+        index.lines().collect::<Vec<_>>(),
+        read_idx(&format!("{path}.idx"))
+    );
+
+    let levels = [
+        (
+            "gfs.t18z.pgrb2.0p25.f186-RH.grib2",
+            "RH:0.7 mb:186 hour fcst:",
+        ),
+        (
+            "hrrr.t06z.wrfsfcf01-missing-zeros.grib2",
+            "VVCSH:0-6000 m above ground:1 hour fcst:",
+        ),
+        (
+            "hrrr.t00z.wrfprsf00-template8.grib2",
+            "MAXUVV:100-1000 mb above ground:0-0 hour max fcst:",
+        ),
+        (
+            "nbm-asnow-prob-thresholds.grib2",
+            "ASNOW:surface:0-1 hour acc fcst:prob >0.00254:",
+        ),
+        ("era5-levels-members.grib", "z:500 mb:anl:ENS=hi-res ctl:"),
+        // An unperturbed forecast, type 5, is the ensemble's control
+        ("aifs-ens-cf-t500.grib2", "TMP:500 mb:anl:ENS=hi-res ctl:"),
+    ];
+    for (file, expected) in levels {
+        let index = write_noaa_index(&read_grib_messages(&format!("../test-data/{file}")));
+        let first = index.lines().next().unwrap();
+        assert!(first.ends_with(expected), "{first}");
+    }
+
+    // And it reads back to the messages it was written from
+    let data = read_grib_messages("../test-data/era5-levels-members.grib");
+    let entries = parse_noaa_index(&write_noaa_index(&data), Some(data.len() as u64)).unwrap();
+    let mut scanned: Vec<_> = scan_message_metadata(&data).into_values().collect();
+    scanned.sort_by_key(|(index, _, _)| *index);
+
+    assert_eq!(entries.len(), scanned.len());
+    for (entry, (_, offset, meta)) in entries.iter().zip(scanned.iter()) {
+        assert_eq!(entry.offset, *offset as u64);
+        assert_eq!(entry.submessage, None);
+        assert_eq!(entry.var.as_deref(), Some(meta.var.as_str()));
+        assert_eq!(entry.reference_date, Some(meta.reference_date));
+        assert_eq!(
+            entry.level,
+            meta.first_fixed_surface_value.map(|v| format!("{v} mb"))
+        );
+    }
+    assert_eq!(entries[1].extra, vec!["ENS=+1".to_string()]);
+}
+
+#[test]
+fn test_write_ecmwf_index() {
+    let data = read_grib_messages("../test-data/era5-levels-members.grib");
+    let index = write_ecmwf_index(&data);
+    assert_eq!(
+        index.lines().nth(1).unwrap(),
+        r#"{"param": "z", "levtype": "pl", "levelist": "500", "step": "0", "number": "1", "date": "20170101", "time": "0000", "class": "ea", "type": "an", "stream": "enda", "_offset": 14760, "_length": 14752}"#
+    );
+
+    // Reads back to the messages it was written from
+    let entries = parse_ecmwf_index(&index).unwrap();
+    let mut scanned: Vec<_> = scan_message_metadata(&data).into_values().collect();
+    scanned.sort_by_key(|(index, _, _)| *index);
+
+    assert_eq!(entries.len(), scanned.len());
+    for (entry, (_, offset, meta)) in entries.iter().zip(scanned.iter()) {
+        assert_eq!(entry.offset, *offset as u64);
+        assert_eq!(entry.length, Some(meta.message_size as u64));
+        assert_eq!(entry.var.as_deref(), Some(meta.var.as_str()));
+        assert_eq!(entry.reference_date, Some(meta.reference_date));
+        assert_eq!(
+            entry.keys["number"],
+            meta.perturbation_number.unwrap().to_string()
+        );
+    }
+    // The ERA5 EDA members are analyses, as its local extension says
+    assert!(entries
+        .iter()
+        .all(|entry| entry.keys["type"] == "an" && entry.keys["stream"] == "enda"));
+
+    let data = read_grib_messages("../test-data/aifs-ens-cf-t500.grib2");
+    let entries = parse_ecmwf_index(&write_ecmwf_index(&data)).unwrap();
+    assert_eq!(entries[0].keys["type"], "cf");
+    assert_eq!(entries[0].var.as_deref(), Some("t"));
+
+    // GRIB2 parameters are named by their MARS short names, as in the indexes
+    // ECMWF publishes alongside the same files
+    let params = [
+        (
+            "ecmwf-ifs-oper-surface.grib2",
+            vec!["ptype", "sd", "rsn", "asn", "tcc"],
+        ),
+        ("aifs-single-wave.grib2", vec!["swh", "swh", "swh", "WMB"]),
+    ];
+    for (file, expected) in params {
+        let data = read_grib_messages(&format!("../test-data/{file}"));
+        let mut params = parse_ecmwf_index(&write_ecmwf_index(&data))
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.keys["param"].clone())
+            .collect::<Vec<_>>();
+        params.sort();
+        let mut expected = expected;
+        expected.sort();
+        assert_eq!(params, expected, "{file}");
+    }
+
+    let data = read_grib_messages("../test-data/nbm-asnow-prob-thresholds.grib2");
+    let entries = parse_ecmwf_index(&write_ecmwf_index(&data)).unwrap();
+    assert_eq!(entries[0].keys["levtype"], "sfc");
+    assert_eq!(entries[0].keys["type"], "ep");
+    assert_eq!(entries[0].forecast_time.as_deref(), Some("1"));
+    assert_eq!(entries[0].level, None);
+}
+
+#[test]
+fn test_selector() {
+    // MARS keys have no levels for most surface types, so those selections
+    // cannot be made from an ECMWF index
+    let cases = [
+        (
+            "../test-data/era5-levels-members.grib",
+            "var=z,level_type=isobaric,level=500,number>=5",
+            20,
+            true,
+        ),
+        (
+            "../test-data/era5-levels-members.grib",
+            "level_type=pl,level!=500,date=20170101,time>=1200",
+            20,
+            true,
+        ),
+        (
+            "../test-data/gfswave.t18z.atlocn.0p16.f001.grib2",
+            "var=SWELL/SWPER,level=2/3,step=1",
+            4,
+            false,
+        ),
+        (
+            "../test-data/gfswave.t18z.atlocn.0p16.f001.grib2",
+            "level_type=sfc,var!=WIND/UGRD/VGRD",
+            16,
+            // ECMWF indexes name the winds by their MARS short names
+            false,
+        ),
+        (
+            "../test-data/hrrr.t06z.wrfsfcf01-UGRD.grib2",
+            "var=UGRD,level_type=heightAboveGround,level=10,step<2",
+            1,
+            false,
+        ),
+    ];
+
+    for (path, selector, count, in_ecmwf_index) in cases {
+        let selector: Selector = selector.parse().unwrap();
+        let data = read_grib_messages(path);
+        let mut scanned: Vec<_> = scan_message_metadata(&data).into_values().collect();
+        scanned.sort_by_key(|(index, _, _)| *index);
+        let noaa = parse_noaa_index(&write_noaa_index(&data), None).unwrap();
+        let ecmwf = parse_ecmwf_index(&write_ecmwf_index(&data)).unwrap();
+
+        // Metadata and the index formats select the same messages
+        let selected = scanned
+            .iter()
+            .map(|(_, _, meta)| selector.matches(meta))
+            .collect::<Vec<_>>();
+        assert_eq!(selected.iter().filter(|s| **s).count(), count, "{selector}");
+        let noaa_selected = noaa.iter().map(|e| selector.matches(e)).collect::<Vec<_>>();
+        assert_eq!(noaa_selected, selected, "{selector}");
+        if in_ecmwf_index {
+            let ecmwf_selected = ecmwf
+                .iter()
+                .map(|e| selector.matches(e))
+                .collect::<Vec<_>>();
+            assert_eq!(ecmwf_selected, selected, "{selector}");
+        }
+    }
+}
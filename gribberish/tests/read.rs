@@ -1,12 +1,19 @@
 extern crate gribberish;
 
 use chrono::{TimeZone, Utc};
-use gribberish::message::{read_messages, Message};
+use gribberish::error::GribberishError;
+use gribberish::message::{read_messages, read_submessage, scan_messages, Message};
+use gribberish::message_builder::MessageBuilder;
+use gribberish::message_metadata::MessageMetadata;
+use gribberish::reader::GribReader;
 use gribberish::templates::product::tables::{DerivedForecastType, TypeOfStatisticalProcessing};
 use std::time::Instant;
 use std::vec::Vec;
 
-use std::{fs::File, io::Read};
+use std::{
+    fs::File,
+    io::{Cursor, Read},
+};
 
 pub fn read_grib_messages(path: &str) -> Vec<u8> {
     let mut grib_file = File::open(path).expect("file not found");
@@ -132,6 +139,165 @@ fn read_simple() {
     assert!((data[1000] - -4.46501350402832).abs() < 0.0000001);
 }
 
+#[test]
+fn read_section_offsets() {
+    let read_data = read_grib_messages("../test-data/hrrr.t06z.wrfsfcf01-UGRD.grib2");
+    let message = read_messages(read_data.as_slice()).next().unwrap();
+
+    let sections = message.section_offsets();
+    let numbers = sections.iter().map(|s| s.number).collect::<Vec<_>>();
+    assert_eq!(numbers, vec![0, 1, 3, 4, 5, 6, 7, 8]);
+    assert_eq!(message.section_count(), 8);
+
+    // The sections are contiguous and cover the whole message
+    assert_eq!(sections[0].offset, message.byte_offset());
+    for pair in sections.windows(2) {
+        assert_eq!(pair[0].offset + pair[0].len, pair[1].offset);
+    }
+    let end = sections.last().unwrap();
+    assert_eq!(end.offset + end.len, message.byte_offset() + message.len());
+}
+
+#[test]
+fn scan_submessages() {
+    // The RH message with its sections 4 to 7 repeated as a TMP field
+    let data = read_grib_messages("../test-data/gfs.t18z.pgrb2.0p25.f186-RH-TMP-submessages.grib2");
+    let mapping = scan_messages(&data);
+    assert_eq!(mapping.len(), 2);
+
+    let mut fields = mapping.into_values().collect::<Vec<_>>();
+    fields.sort();
+    assert_eq!(fields, vec![(0, 0, Some(1)), (1, 0, Some(2))]);
+
+    let rh = read_submessage(&data, 0, 1).unwrap();
+    let tmp = read_submessage(&data, 0, 2).unwrap();
+    assert_eq!(rh.variable_abbrev().unwrap(), "RH");
+    assert_eq!(tmp.variable_abbrev().unwrap(), "TMP");
+    assert_ne!(rh.data().unwrap()[0], tmp.data().unwrap()[0]);
+}
+
+/// Repeat sections 4 to 7 of a single field message as a V wind field, the way
+/// NCEP packs U and V winds into one message. The V field can be given its own
+/// bitmap section.
+fn uv_wind_message(message: &Message, v_bitmap: Option<&[u8]>) -> Vec<u8> {
+    let data = message.byte_data();
+    let sections = message.section_offsets();
+    let raw = |numbers: std::ops::RangeInclusive<u8>| {
+        sections
+            .iter()
+            .filter(|s| numbers.contains(&s.number))
+            .flat_map(|s| &data[s.offset..s.offset + s.len])
+            .copied()
+            .collect::<Vec<u8>>()
+    };
+
+    let mut v_product_definition = raw(4..=4);
+    v_product_definition[10] = 3;
+
+    let mut encoded = raw(0..=7);
+    encoded.extend_from_slice(&v_product_definition);
+    encoded.extend_from_slice(&raw(5..=5));
+    encoded.extend_from_slice(&v_bitmap.map_or_else(|| raw(6..=6), |b| b.to_vec()));
+    encoded.extend_from_slice(&raw(7..=7));
+    encoded.extend_from_slice(b"7777");
+    let total_length = encoded.len() as u64;
+    encoded[8..16].copy_from_slice(&total_length.to_be_bytes());
+    encoded
+}
+
+#[test]
+fn read_submessages() {
+    let read_data = read_grib_messages("../test-data/hrrr.t06z.wrfsfcf01-UGRD.grib2");
+    let message = read_messages(read_data.as_slice()).next().unwrap();
+    let u_data = message.data().unwrap();
+
+    // The two field message followed by a plain one
+    let mut encoded = uv_wind_message(&message, None);
+    let second_offset = encoded.len();
+    encoded.extend_from_slice(&read_data);
+
+    let messages = read_messages(&encoded).collect::<Vec<Message>>();
+    assert_eq!(messages.len(), 3);
+
+    let metadata = messages
+        .iter()
+        .map(|m| MessageMetadata::try_from(m).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(metadata[0].var, "UGRD");
+    assert_eq!(metadata[1].var, "VGRD");
+    assert_eq!(metadata[2].var, "UGRD");
+    assert_eq!(metadata[0].submessage, Some(1));
+    assert_eq!(metadata[1].submessage, Some(2));
+    assert_eq!(metadata[2].submessage, None);
+    assert_eq!(metadata[0].byte_offset, 0);
+    assert_eq!(metadata[1].byte_offset, 0);
+    assert_eq!(metadata[2].byte_offset, second_offset);
+    assert_eq!(metadata[0].message_size, second_offset);
+    assert_ne!(metadata[0].key, metadata[1].key);
+
+    // The second field shares the grid of the first and carries its own data
+    assert_eq!(messages[1].submessage_count(), 2);
+    assert_eq!(messages[1].grid_dimensions().unwrap(), (1059, 1799));
+    let v_data = messages[1].data().unwrap();
+    assert_eq!(v_data.len(), u_data.len());
+    assert!((v_data[1000] - u_data[1000]).abs() < 1e-12);
+
+    let v = read_submessage(&encoded, 0, 2).unwrap();
+    assert_eq!(v.variable_abbrev().unwrap(), "VGRD");
+    assert!(read_submessage(&encoded, 0, 3).is_none());
+    assert!(read_submessage(&encoded, 0, 0).is_none());
+
+    // Without its data sections, the message reads as the one field it can describe
+    let header = message.section_offsets().iter().find(|s| s.number == 5);
+    let header_length = header.map(|s| s.offset + s.len).unwrap();
+    let fields = Message::submessages_from_data(&encoded[..header_length], 0);
+    assert_eq!(fields.len(), 1);
+    assert_eq!(fields[0].variable_abbrev().unwrap(), "UGRD");
+}
+
+#[test]
+fn read_submessage_bitmaps() {
+    let read_data = read_grib_messages("../test-data/hrrr.t06z.wrfsfcf01-UGRD.grib2");
+    let message = read_messages(read_data.as_slice()).next().unwrap();
+    let data = message
+        .data()
+        .unwrap()
+        .into_iter()
+        .enumerate()
+        .map(|(i, v)| if i % 7 == 0 { f64::NAN } else { v })
+        .collect::<Vec<_>>();
+    let masked = MessageBuilder::from_message(&message)
+        .unwrap()
+        .with_decimal_scale_factor(2)
+        .build(&data)
+        .unwrap();
+    let message = Message::from_data(&masked, 0).unwrap();
+    assert!(message.has_bitmap());
+
+    // 254 reuses the bitmap of the U field
+    let encoded = uv_wind_message(&message, Some(&[0, 0, 0, 6, 6, 254]));
+    let messages = read_messages(&encoded).collect::<Vec<Message>>();
+    assert!(messages[1].has_bitmap());
+    let u = messages[0].data().unwrap();
+    let v = messages[1].data().unwrap();
+    assert_eq!(v.len(), data.len());
+    assert!(v[0].is_nan() && v[7].is_nan() && !v[1].is_nan());
+    assert!((v[1000] - u[1000]).abs() < 1e-12);
+
+    // Without a bitmap defined earlier in the message there is nothing to reuse
+    let unmasked = read_messages(read_data.as_slice()).next().unwrap();
+    let encoded = uv_wind_message(&unmasked, Some(&[0, 0, 0, 6, 6, 254]));
+    let v = read_messages(&encoded).nth(1).unwrap();
+    assert!(matches!(v.data(), Err(GribberishError::BitmapError(_))));
+
+    // Predefined bitmaps are specific to the originating centre
+    let encoded = uv_wind_message(&message, Some(&[0, 0, 0, 6, 6, 5]));
+    let v = read_messages(&encoded).nth(1).unwrap();
+    let err = v.data().unwrap_err();
+    assert!(matches!(err, GribberishError::BitmapError(_)));
+    assert!(err.to_string().contains("predefined bitmap 5"), "{err}");
+}
+
 #[test]
 fn read_spatial_differenced_complex() {
     let read_data = read_grib_messages("../test-data/hrrr.t06z.wrfsfcf01-TMP.grib2");
@@ -485,7 +651,7 @@ fn read_grib1_era5_levels_members() {
     // (2 variables x 4 times x 2 levels x 10 ensemble members), but this test validates
     // just the first message's data integrity
     assert!(
-        messages.len() >= 1,
+        !messages.is_empty(),
         "Expected at least 1 message in ERA5 GRIB1 file"
     );
 
@@ -566,6 +732,298 @@ fn read_grib1_ecmwf_table_128_soil_tiny_fixture() {
     assert_eq!(vars, expected, "Unexpected GRIB1 soil variable set");
 }
 
+#[test]
+fn read_streaming_messages() {
+    let path = "../test-data/era5-levels-members.grib";
+    let grib_data = read_grib_messages(path);
+    let expected = read_messages(grib_data.as_slice())
+        .map(|m| (m.byte_offset() as u64, m.key().unwrap()))
+        .collect::<Vec<_>>();
+
+    let reader = GribReader::new(File::open(path).unwrap());
+    let streamed = reader
+        .map(|m| {
+            let m = m.unwrap();
+            (m.offset(), m.message().key().unwrap())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(streamed, expected);
+
+    // Junk before and between messages, then a truncated message at the end
+    let message = read_grib_messages("../test-data/hrrr.t06z.wrfsfcf01-TMP.grib2");
+    let mut padded = b"GRIB\x00\x00\x00\x02 header".to_vec();
+    let first_offset = padded.len() as u64;
+    padded.extend_from_slice(&message);
+    padded.extend_from_slice(b"padding");
+    let second_offset = padded.len() as u64;
+    padded.extend_from_slice(&message);
+    padded.extend_from_slice(&message[..1000]);
+
+    let mut reader = GribReader::new(Cursor::new(padded));
+    let first = reader.read_message().unwrap().unwrap();
+    assert_eq!(first.offset(), first_offset);
+    assert_eq!(first.bytes(), message.as_slice());
+    assert_eq!(first.message().variable_abbrev().unwrap(), "TMP");
+    let second = reader.read_message().unwrap().unwrap();
+    assert_eq!(second.offset(), second_offset);
+    assert_eq!(second.submessages().len(), 1);
+    assert!(reader.read_message().unwrap().is_none());
+    assert!(reader.next().is_none());
+}
+
+#[cfg(feature = "mmap")]
+#[test]
+fn read_mapped_messages() {
+    let path = "../test-data/era5-levels-members.grib";
+    let mapped = gribberish::reader::MappedGribFile::open(path).unwrap();
+    let messages = mapped.messages().collect::<Vec<Message>>();
+    assert_eq!(messages.len(), 160);
+
+    let grib_data = read_grib_messages(path);
+    let expected = read_messages(grib_data.as_slice()).nth(5).unwrap();
+    let message = mapped.message(expected.byte_offset()).unwrap();
+    assert_eq!(message.key().unwrap(), expected.key().unwrap());
+    assert_eq!(message.data().unwrap(), expected.data().unwrap());
+}
+
+#[cfg(feature = "cache")]
+#[test]
+fn read_metadata_cache() {
+    use gribberish::message_metadata::scan_message_metadata;
+    use gribberish::metadata_cache::{cache_path_in, load_or_scan, CacheKey, MetadataCache};
+
+    let path = "../test-data/hrrr.t06z.wrfsfcf01-TMP.grib2";
+    let cache_dir = std::env::temp_dir().join(format!("gribberish-cache-{}", std::process::id()));
+    std::fs::create_dir_all(&cache_dir).unwrap();
+    let cache_path = cache_path_in(&cache_dir, path).unwrap();
+
+    let expected = scan_message_metadata(&read_grib_messages(path));
+    let scanned = load_or_scan(path, &cache_path).unwrap();
+    assert!(cache_path.exists());
+    let cached = load_or_scan(path, &cache_path).unwrap();
+
+    for metadata in [scanned, cached] {
+        assert_eq!(metadata.len(), expected.len());
+        for (key, (index, offset, expected)) in &expected {
+            let (cached_index, cached_offset, cached) = &metadata[key];
+            assert_eq!(cached_index, index);
+            assert_eq!(cached_offset, offset);
+            assert_eq!(cached.var, expected.var);
+            assert_eq!(cached.reference_date, expected.reference_date);
+            assert_eq!(
+                cached.first_fixed_surface_type,
+                expected.first_fixed_surface_type
+            );
+            // The lambert conformal projection is rebuilt from its parameters
+            assert_eq!(cached.latlng(), expected.latlng());
+            // As is whether vectors on it are resolved along the grid
+            assert!(cached.has_grid_relative_vectors());
+        }
+    }
+
+    // A cache for a file that has changed is not used
+    let cache = MetadataCache::read(&cache_path).unwrap();
+    assert!(cache.is_valid_for(&CacheKey::from_file_stat(path).unwrap()));
+    assert!(!cache.is_valid_for(&CacheKey::from_content(b"GRIB")));
+
+    // Nor is a cache from another version of the format
+    let mut bytes = std::fs::read(&cache_path).unwrap();
+    bytes[8] = bytes[8].wrapping_add(1);
+    assert!(matches!(
+        MetadataCache::read_from(bytes.as_slice()),
+        Err(GribberishError::CacheError(_))
+    ));
+
+    // Files with many messages, and padding between them, scan the same way
+    let path = "../test-data/era5-levels-members.grib";
+    let cache_path = cache_path_in(&cache_dir, path).unwrap();
+    let expected = scan_message_metadata(&read_grib_messages(path));
+    let scanned = load_or_scan(path, &cache_path).unwrap();
+    assert_eq!(scanned.len(), expected.len());
+    for (key, (index, offset, expected)) in &expected {
+        let (scanned_index, scanned_offset, scanned) = &scanned[key];
+        assert_eq!((scanned_index, scanned_offset), (index, offset));
+        assert_eq!(scanned.byte_offset, expected.byte_offset);
+    }
+
+    // Caches written at once each go through their own partial file
+    let cache = MetadataCache::read(&cache_path).unwrap();
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| cache.write(&cache_path).unwrap());
+        }
+    });
+    assert!(MetadataCache::read(&cache_path).is_ok());
+    let partials = std::fs::read_dir(&cache_dir)
+        .unwrap()
+        .filter(|entry| {
+            entry
+                .as_ref()
+                .unwrap()
+                .file_name()
+                .to_string_lossy()
+                .ends_with(".partial")
+        })
+        .count();
+    assert_eq!(partials, 0);
+
+    std::fs::remove_dir_all(&cache_dir).unwrap();
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn read_metadata_serde() {
+    use gribberish::message_metadata::scan_message_metadata;
+    use gribberish::templates::product::tables::FixedSurfaceType;
+
+    let grib_data = read_grib_messages("../test-data/hrrr.t06z.wrfsfcf01-TMP.grib2");
+    let (_, (_, _, metadata)) = scan_message_metadata(&grib_data)
+        .into_iter()
+        .next()
+        .unwrap();
+
+    // Code table values carry both their numeric code and description
+    let json = serde_json::to_value(&metadata).unwrap();
+    assert_eq!(
+        json["first_fixed_surface_type"],
+        serde_json::json!({"code": 1, "description": "ground or water surface"})
+    );
+
+    let roundtrip: MessageMetadata = serde_json::from_value(json).unwrap();
+    assert_eq!(roundtrip.var, metadata.var);
+    assert_eq!(roundtrip.forecast_date, metadata.forecast_date);
+    assert_eq!(
+        roundtrip.first_fixed_surface_type,
+        FixedSurfaceType::GroundOrWater
+    );
+    assert_eq!(roundtrip.latlng(), metadata.latlng());
+
+    // Only the code is needed to deserialize a table value
+    let surface: FixedSurfaceType = serde_json::from_str(r#"{"code": 100}"#).unwrap();
+    assert_eq!(surface, FixedSurfaceType::IsobaricSurface);
+
+    // Resolution and component flags are kept as their flag octet, so winds
+    // resolved along the grid are still rotated after a round trip
+    let grib_data = read_grib_messages("../test-data/hrrr.t06z.wrfsfcf01-UGRD.grib2");
+    let (_, (_, _, metadata)) = scan_message_metadata(&grib_data)
+        .into_iter()
+        .next()
+        .unwrap();
+    assert!(metadata.has_grid_relative_vectors());
+    let json = serde_json::to_value(&metadata).unwrap();
+    assert_eq!(json["resolution_and_component_flags"], serde_json::json!(8));
+    let roundtrip: MessageMetadata = serde_json::from_value(json).unwrap();
+    assert_eq!(
+        roundtrip.resolution_and_component_flags,
+        metadata.resolution_and_component_flags
+    );
+    assert!(roundtrip.has_grid_relative_vectors());
+}
+
+#[test]
+fn read_values_at_locations() {
+    use gribberish::interpolation::Interpolation;
+
+    // A lambert conformal grid, located through its projection
+    let grib_data = read_grib_messages("../test-data/hrrr.t06z.wrfsfcf01-TMP.grib2");
+    let message = read_messages(grib_data.as_slice()).next().unwrap();
+    let data = message.data().unwrap();
+    let (lats, lngs) = message.latlng_projector().unwrap().lat_lng();
+    let (_, nx) = message.grid_dimensions().unwrap();
+    let index = 500 * nx + 900;
+
+    let nearest = message
+        .value_at(lats[index], lngs[index], Interpolation::Nearest)
+        .unwrap();
+    assert_eq!(nearest, data[index]);
+    let bilinear = message
+        .value_at(lats[index], lngs[index], Interpolation::Bilinear)
+        .unwrap();
+    assert!((bilinear - data[index]).abs() < 1e-3);
+    // Halfway between two rows
+    let midpoint = message
+        .value_at(
+            (lats[index] + lats[index + nx]) / 2.0,
+            (lngs[index] + lngs[index + nx]) / 2.0,
+            Interpolation::Bilinear,
+        )
+        .unwrap();
+    assert!((midpoint - (data[index] + data[index + nx]) / 2.0).abs() < 0.05);
+    assert!(message
+        .value_at(0.0, 0.0, Interpolation::Nearest)
+        .unwrap()
+        .is_nan());
+
+    // A global grid with longitudes in 0..360, located from either side of the
+    // antimeridian
+    let grib_data = read_grib_messages("../test-data/gfs.t18z.pgrb2.0p25.f186-RH.grib2");
+    let message = read_messages(grib_data.as_slice()).next().unwrap();
+    let data = message.data().unwrap();
+    let (lats, lngs) = message.latlng_projector().unwrap().lat_lng();
+    let (lat_index, lng_index) = (195, 1154);
+    assert!(lngs[lng_index] > 180.0);
+
+    let locations = [
+        (lats[lat_index], lngs[lng_index]),
+        (lats[lat_index], lngs[lng_index] - 360.0),
+        (lats[lat_index], 359.9),
+    ];
+    let values = message
+        .values_at(&locations, Interpolation::Nearest)
+        .unwrap();
+    let expected = data[lat_index * lngs.len() + lng_index];
+    assert_eq!(values[0], expected);
+    assert_eq!(values[1], expected);
+    assert_eq!(values[2], data[lat_index * lngs.len()]);
+}
+
+#[test]
+fn regrid_projected_message() {
+    use gribberish::interpolation::Interpolation;
+    use gribberish::regrid::{RegridMethod, RegridWeights};
+    use gribberish::{PlateCareeProjection, RegularCoordinateIterator};
+
+    let grib_data = read_grib_messages("../test-data/hrrr.t06z.wrfsfcf01-TMP.grib2");
+    let message = read_messages(grib_data.as_slice()).next().unwrap();
+    let source = message.latlng_projector().unwrap();
+    let data = message.data().unwrap();
+
+    // A 0.25° grid over the central US, inside the lambert conformal domain
+    let target = PlateCareeProjection::new(
+        RegularCoordinateIterator::new(45.0, -0.25, 40),
+        RegularCoordinateIterator::new(-105.0, 0.25, 60),
+    );
+    let target_lats = target.latitudes.clone().collect::<Vec<_>>();
+    let target_lngs = target.longitudes.clone().collect::<Vec<_>>();
+
+    let nearest = RegridWeights::new(&source, &target, RegridMethod::Nearest);
+    assert_eq!(nearest.target_shape(), (40, 60));
+    let regridded = nearest.apply(&data).unwrap();
+    for (row, column) in [(0, 0), (17, 33), (39, 59)] {
+        let expected = message
+            .value_at(
+                target_lats[row],
+                target_lngs[column],
+                Interpolation::Nearest,
+            )
+            .unwrap();
+        assert_eq!(regridded[row * 60 + column], expected);
+    }
+
+    let (min, max) = data.iter().fold((f64::MAX, f64::MIN), |(min, max), v| {
+        (min.min(*v), max.max(*v))
+    });
+    for method in [RegridMethod::Bilinear, RegridMethod::AreaAverage] {
+        let regridded = RegridWeights::new(&source, &target, method)
+            .apply(&data)
+            .unwrap();
+        assert!(regridded.iter().all(|v| *v >= min && *v <= max));
+    }
+
+    // Weights are for a single source grid
+    assert!(nearest.apply(&data[1..]).is_err());
+}
+
 #[test]
 fn test_iterator_scans_past_padding() {
     // This test verifies that the MessageIterator correctly scans past
@@ -985,10 +1443,58 @@ fn read_hrrr_atmospheric_chemistry() {
     assert_eq!(message.unit().unwrap(), "kg m-3");
     assert_eq!(message.grid_dimensions().unwrap(), (1059, 1799));
 
+    // Written with template 4.0, so the species is left implicit
+    assert_eq!(message.chemical_constituent_type().unwrap(), None);
+
     let data = message.data().unwrap();
     assert_eq!(data.len(), 1905141);
 }
 
+/// The HRRR smoke mass density product definition rewritten as template 4.40
+/// for the given constituent type
+fn chemical_constituent_product_definition(constituent: u16) -> Vec<u8> {
+    let mut section = vec![0u8, 0, 0, 36, 4, 0, 0, 0, 40, 20, 0];
+    section.extend_from_slice(&constituent.to_be_bytes());
+    section.extend_from_slice(&[2, 0, 83, 0, 0, 0, 1]);
+    section.extend_from_slice(&0u32.to_be_bytes());
+    section.extend_from_slice(&[103, 0]);
+    section.extend_from_slice(&8u32.to_be_bytes());
+    section.extend_from_slice(&[255, 0, 0, 0, 0, 0]);
+    section
+}
+
+#[test]
+fn read_chemical_constituent_templates() {
+    let read_data = read_grib_messages("../test-data/hrrr.t00z.wrfprsf00-atmo-chem.grib2");
+    let message = read_messages(read_data.as_slice()).next().unwrap();
+    let data = message.data().unwrap();
+    let original = MessageMetadata::try_from(&message).unwrap();
+
+    // Ozone (0) and smoke (62000) mass density share discipline, category and
+    // parameter, the constituent type is all that tells them apart
+    let species = [0u16, 62000].map(|constituent| {
+        let encoded = MessageBuilder::from_message(&message)
+            .unwrap()
+            .with_product_definition(&chemical_constituent_product_definition(constituent))
+            .with_decimal_scale_factor(12)
+            .build(&data)
+            .unwrap();
+        let message = Message::from_data(&encoded, 0).unwrap();
+        MessageMetadata::try_from(&message).unwrap()
+    });
+
+    for (metadata, constituent) in species.iter().zip([0, 62000]) {
+        assert_eq!(metadata.var, "MASSDEN");
+        assert_eq!(metadata.chemical_constituent_type, Some(constituent));
+        assert_eq!(metadata.aerosol_size_range, None);
+        assert_eq!(metadata.aerosol_wavelength_range, None);
+        assert_eq!(metadata.first_fixed_surface_value, Some(8.0));
+        assert_eq!(metadata.forecast_date, original.forecast_date);
+    }
+    assert_ne!(species[0].key, species[1].key);
+    assert!(species[1].key.contains(":chem62000"), "{}", species[1].key);
+}
+
 #[test]
 fn read_hrrr_hydrology() {
     // GRIB2 discipline 1 (hydrology), category 0 (basic), parameter 6 (storm surface runoff).
@@ -1697,3 +2203,44 @@ fn read_naqfc_alaska_polar_stereographic_grid() {
     assert_eq!(data.len(), 456225);
     assert!((data[1000] - 31.64).abs() < 0.001, "data[1000]");
 }
+
+#[test]
+fn read_earth_relative_winds() {
+    use gribberish::wind::earth_relative_components;
+
+    // HRRR winds are resolved along its lambert conformal grid
+    let grib_data = read_grib_messages("../test-data/hrrr.t06z.wrfsfcf01-UGRD.grib2");
+    let message = read_messages(grib_data.as_slice()).next().unwrap();
+    assert!(message.has_grid_relative_vectors().unwrap());
+    let metadata = MessageMetadata::try_from(&message).unwrap();
+    assert!(metadata.has_grid_relative_vectors());
+
+    let data = message.data().unwrap();
+    let (u, v) = earth_relative_components(&message, &message).unwrap();
+    let (_, lngs) = metadata.latlng();
+    let (_, nx) = metadata.grid_shape;
+
+    // Meridians converge by up to 20° at the edges of the domain, with the
+    // grid's y axis turned clockwise from north east of its central meridian
+    let angles = metadata.projector.convergence_angles();
+    let max_angle = angles.iter().fold(0f64, |max, a| max.max(a.abs()));
+    assert!((15.0..25.0).contains(&max_angle.to_degrees()));
+    let east = 500 * nx + nx - 10;
+    assert!(lngs[east] > -97.5 && angles[east] > 0.0);
+
+    for index in [0, 500 * nx + 900, east] {
+        let speed = data[index].hypot(data[index]);
+        assert!((u[index].hypot(v[index]) - speed).abs() < 1e-6);
+    }
+    let (sin, cos) = angles[east].sin_cos();
+    assert!((u[east] - data[east] * (cos + sin)).abs() < 1e-6);
+
+    // Latitude/longitude grids are always earth relative and left untouched
+    let grib_data = read_grib_messages("../test-data/gfs.t18z.pgrb2.0p25.f186-RH.grib2");
+    let rh = read_messages(grib_data.as_slice()).next().unwrap();
+    assert!(!rh.has_grid_relative_vectors().unwrap());
+    let (u, _) = earth_relative_components(&rh, &rh).unwrap();
+    assert_eq!(u.len(), rh.data().unwrap().len());
+
+    assert!(earth_relative_components(&message, &rh).is_err());
+}
@@ -0,0 +1,639 @@
+extern crate gribberish;
+
+use gribberish::message::{read_messages, Message};
+use gribberish::message_builder::{DataPacking, MessageBuilder};
+use gribberish::message_metadata::MessageMetadata;
+use gribberish::templates::data_representation::tables::{
+    FloatingPointPrecision, MissingValueManagement,
+};
+use gribberish::templates::data_representation::{
+    CCSDSPackingOptions, ComplexSpatialPackingOptions,
+};
+
+use std::{fs::File, io::Read};
+
+pub fn read_grib_messages(path: &str) -> Vec<u8> {
+    let mut grib_file = File::open(path).expect("file not found");
+
+    let mut raw_grib_data = Vec::new();
+    grib_file
+        .read_to_end(&mut raw_grib_data)
+        .expect("failed to read raw grib2 data");
+
+    raw_grib_data
+}
+
+fn assert_reencodes(
+    path: &str,
+    decimal_scale_factor: i16,
+    binary_scale_factor: i16,
+    packing: DataPacking,
+) -> (usize, usize) {
+    let read_data = read_grib_messages(path);
+    let message = read_messages(read_data.as_slice()).next().unwrap();
+    let original = message.data().unwrap();
+    let original_metadata = MessageMetadata::try_from(&message).unwrap();
+
+    let encoded = MessageBuilder::from_message(&message)
+        .unwrap()
+        .with_decimal_scale_factor(decimal_scale_factor)
+        .with_binary_scale_factor(binary_scale_factor)
+        .with_packing(packing)
+        .build(&original)
+        .unwrap();
+
+    let reencoded = Message::from_data(&encoded, 0).unwrap();
+    let template_number = match packing {
+        DataPacking::Simple => 0,
+        DataPacking::ComplexSpatial(_) => 3,
+        DataPacking::IEEE(_) => 4,
+        DataPacking::CCSDS(_) => 42,
+    };
+    assert_eq!(reencoded.data_template_number().unwrap(), template_number);
+
+    let metadata = MessageMetadata::try_from(&reencoded).unwrap();
+    assert_eq!(metadata.key, original_metadata.key);
+    assert_eq!(metadata.grid_shape, original_metadata.grid_shape);
+    assert_eq!(metadata.proj, original_metadata.proj);
+    assert_eq!(metadata.forecast_date, original_metadata.forecast_date);
+
+    let tolerance =
+        0.5 * 10f64.powi(-decimal_scale_factor as i32) * 2f64.powi(binary_scale_factor as i32)
+            + 1e-6;
+    let data = reencoded.data().unwrap();
+    assert_eq!(data.len(), original.len());
+    for (value, expected) in data.iter().zip(original.iter()) {
+        if expected.is_nan() {
+            assert!(value.is_nan());
+        } else {
+            assert!((value - expected).abs() <= tolerance);
+        }
+    }
+
+    (message.len(), encoded.len())
+}
+
+#[test]
+fn write_simple_packing_from_message() {
+    assert_reencodes(
+        "../test-data/hrrr.t06z.wrfsfcf01-TMP.grib2",
+        2,
+        0,
+        DataPacking::Simple,
+    );
+}
+
+#[test]
+fn write_simple_packing_with_bitmap_from_message() {
+    assert_reencodes(
+        "../test-data/gfs.t12z.pgrb2.0p25.f023-PV-TMP-missing.grib2",
+        2,
+        0,
+        DataPacking::Simple,
+    );
+}
+
+#[test]
+fn write_complex_spatial_packing_from_message() {
+    // Match the precision NCEP packed this field with
+    let path = "../test-data/hrrr.t06z.wrfsfcf01-TMP.grib2";
+    let (_, simple_size) = assert_reencodes(path, 0, -4, DataPacking::Simple);
+    let (original_size, complex_size) =
+        assert_reencodes(path, 0, -4, DataPacking::ComplexSpatial(Default::default()));
+
+    assert!(complex_size < simple_size);
+    assert!((complex_size as f64) < original_size as f64 * 1.1);
+}
+
+#[test]
+fn write_complex_spatial_packing_with_missing_values_from_message() {
+    let path = "../test-data/gfs.t12z.pgrb2.0p25.f023-PV-TMP-missing.grib2";
+    let (_, simple_size) = assert_reencodes(path, 2, 0, DataPacking::Simple);
+    let (_, bitmap_size) =
+        assert_reencodes(path, 2, 0, DataPacking::ComplexSpatial(Default::default()));
+    let (_, managed_size) = assert_reencodes(
+        path,
+        2,
+        0,
+        DataPacking::ComplexSpatial(ComplexSpatialPackingOptions {
+            missing_value_management: MissingValueManagement::IncludesMissingPrimary,
+            ..Default::default()
+        }),
+    );
+
+    assert!(bitmap_size < simple_size);
+    assert!(managed_size < simple_size);
+}
+
+#[test]
+fn write_ccsds_packing_from_message() {
+    // Meteo France packs this field with D = 0, E = -14 and the default CCSDS options
+    let path = "../test-data/meteofrance.mfwam.arome-SWELL.grib2";
+    let (_, simple_size) = assert_reencodes(path, 0, -14, DataPacking::Simple);
+    let (original_size, ccsds_size) =
+        assert_reencodes(path, 0, -14, DataPacking::CCSDS(Default::default()));
+
+    assert!(ccsds_size < simple_size);
+    assert!((ccsds_size as f64) < original_size as f64 * 1.1);
+}
+
+#[test]
+fn write_ccsds_packing_with_options_from_message() {
+    assert_reencodes(
+        "../test-data/hrrr.t06z.wrfsfcf01-TMP.grib2",
+        2,
+        0,
+        DataPacking::CCSDS(CCSDSPackingOptions {
+            block_size: 16,
+            reference_sample_interval: 64,
+            compression_options_mask: 8,
+        }),
+    );
+}
+
+#[test]
+fn write_ieee_float_from_message() {
+    // 64 bit floats hold every decoded value exactly, whatever the scale factors
+    let path = "../test-data/gfs.t12z.pgrb2.0p25.f023-PV-TMP-missing.grib2";
+    let read_data = read_grib_messages(path);
+    let message = read_messages(read_data.as_slice()).next().unwrap();
+    let original = message.data().unwrap();
+
+    let encoded = MessageBuilder::from_message(&message)
+        .unwrap()
+        .with_packing(DataPacking::IEEE(FloatingPointPrecision::IEEE64Bit))
+        .build(&original)
+        .unwrap();
+    let data = Message::from_data(&encoded, 0).unwrap().data().unwrap();
+    assert_eq!(data.len(), original.len());
+    for (value, expected) in data.iter().zip(original.iter()) {
+        assert!(value == expected || (value.is_nan() && expected.is_nan()));
+    }
+
+    assert_reencodes(
+        path,
+        2,
+        0,
+        DataPacking::IEEE(FloatingPointPrecision::IEEE32Bit),
+    );
+}
+
+/// Subset a message and check every point of the result against the point of
+/// the original grid at the same location
+fn assert_subsets(path: &str, bbox: (f64, f64, f64, f64)) -> MessageMetadata {
+    let read_data = read_grib_messages(path);
+    let message = read_messages(read_data.as_slice()).next().unwrap();
+    let original = MessageMetadata::try_from(&message).unwrap();
+    let original_data = message.data().unwrap();
+
+    let encoded = message.subset(bbox).unwrap();
+    let subset = Message::from_data(&encoded, 0).unwrap();
+    assert_eq!(
+        subset.data_template_number().unwrap(),
+        message.data_template_number().unwrap()
+    );
+    let metadata = MessageMetadata::try_from(&subset).unwrap();
+    assert_eq!(metadata.key, original.key);
+    assert_eq!(metadata.proj, original.proj);
+    assert!(metadata.data_point_count() < original.data_point_count());
+
+    let window = original.projector.subset_window(bbox).unwrap();
+    assert_eq!(metadata.grid_shape, (window.row_count, window.column_count));
+    let data = subset.data().unwrap();
+    let (lats, lngs) = metadata.latlng();
+    let (_, original_nx) = original.grid_shape;
+    let (_, nx) = metadata.grid_shape;
+    let (original_lats, original_lngs) = original.latlng();
+    let is_projected = lats.len() == data.len();
+    for (index, original_index) in window.indices(original_nx).enumerate() {
+        let (lat, lng, original_lat, original_lng) = if is_projected {
+            (
+                lats[index],
+                lngs[index],
+                original_lats[original_index],
+                original_lngs[original_index],
+            )
+        } else {
+            (
+                lats[index / nx],
+                lngs[index % nx],
+                original_lats[original_index / original_nx],
+                original_lngs[original_index % original_nx],
+            )
+        };
+        assert!((lat - original_lat).abs() < 1e-4, "{lat} != {original_lat}");
+        assert!(
+            ((lng - original_lng + 180.0).rem_euclid(360.0) - 180.0).abs() < 1e-4,
+            "{lng} != {original_lng}"
+        );
+
+        let (value, expected) = (data[index], original_data[original_index]);
+        if expected.is_nan() {
+            assert!(value.is_nan());
+        } else {
+            assert!((value - expected).abs() < 1e-3, "{value} != {expected}");
+        }
+    }
+
+    metadata
+}
+
+#[test]
+fn subset_projected_message() {
+    let metadata = assert_subsets(
+        "../test-data/hrrr.t06z.wrfsfcf01-TMP.grib2",
+        (-100.0, 35.0, -90.0, 42.0),
+    );
+    let (lats, lngs) = metadata.latlng();
+    assert!(lats.iter().any(|lat| *lat < 35.0) && lngs.iter().any(|lng| *lng < -100.0));
+
+    assert_subsets(
+        "../test-data/hrrr.t06z.wrfsfcf01-UGRD.grib2",
+        (-80.0, 38.0, -70.0, 45.0),
+    );
+    assert_subsets(
+        "../test-data/aqm.t12z.ave_1hr_o3-AK-polar-stereographic.grib2",
+        (-160.0, 55.0, -140.0, 65.0),
+    );
+    assert_subsets(
+        "../test-data/aqm.t12z.ave_1hr_o3-HI-mercator.grib2",
+        (-158.0, 19.0, -155.0, 22.0),
+    );
+}
+
+#[test]
+fn subset_latlng_message() {
+    let metadata = assert_subsets(
+        "../test-data/gfs.t12z.pgrb2.0p25.f023-PV-TMP-missing.grib2",
+        (-80.0, 20.0, -60.0, 30.0),
+    );
+    assert_eq!(metadata.grid_shape, (41, 81));
+
+    // Across the seam of a grid with longitudes in 0..360
+    let metadata = assert_subsets(
+        "../test-data/gfs.t18z.pgrb2.0p25.f186-RH.grib2",
+        (-10.0, 40.0, 10.0, 50.0),
+    );
+    assert_eq!(metadata.grid_shape, (41, 81));
+    let (_, lngs) = metadata.latlng();
+    assert_eq!(lngs[0], 350.0);
+
+    let read_data = read_grib_messages("../test-data/gfs.t18z.pgrb2.0p25.f186-RH.grib2");
+    let message = read_messages(read_data.as_slice()).next().unwrap();
+    assert!(message.subset((10.0, 95.0, 20.0, 100.0)).is_err());
+}
+
+/// Read a GeoTIFF written from a message back, checking its georeferencing and
+/// that every pixel holds the value of the grid point it covers
+#[cfg(feature = "geotiff")]
+fn assert_geotiff(
+    path: &str,
+    options: &gribberish::geotiff::GeoTiffOptions,
+) -> tiff::decoder::Decoder<std::io::Cursor<Vec<u8>>> {
+    use gribberish::geotiff::message_to_geotiff;
+    use tiff::decoder::{Decoder, DecodingResult};
+    use tiff::tags::Tag;
+
+    let read_data = read_grib_messages(path);
+    let message = read_messages(read_data.as_slice()).next().unwrap();
+    let metadata = MessageMetadata::try_from(&message).unwrap();
+    let data = message.data().unwrap();
+    let (ny, nx) = metadata.grid_shape;
+
+    let encoded = message_to_geotiff(&message, options).unwrap();
+    let mut decoder = Decoder::new(std::io::Cursor::new(encoded)).unwrap();
+    assert_eq!(decoder.dimensions().unwrap(), (nx as u32, ny as u32));
+
+    // North up, with the tiepoint on the top left corner of the top left pixel
+    let scale = decoder.get_tag_f64_vec(Tag::ModelPixelScaleTag).unwrap();
+    let tiepoint = decoder.get_tag_f64_vec(Tag::ModelTiepointTag).unwrap();
+    let (x, y) = (metadata.projector.x(), metadata.projector.y());
+    let (x0, y0) = (x[0], y[0]);
+    let flip_rows = y[1] > y[0];
+    let top = if flip_rows {
+        y0 + scale[1] * (ny - 1) as f64
+    } else {
+        y0
+    };
+    assert!((tiepoint[3] - (x0 - scale[0] / 2.0)).abs() < 1e-6);
+    assert!((tiepoint[4] - (top + scale[1] / 2.0)).abs() < 1e-6);
+
+    let DecodingResult::F32(pixels) = decoder.read_image().unwrap() else {
+        panic!("expected 32 bit float pixels");
+    };
+    for (index, pixel) in pixels.iter().enumerate() {
+        let (row, column) = (index / nx, index % nx);
+        let row = if flip_rows { ny - 1 - row } else { row };
+        let expected = data[row * nx + column];
+        if expected.is_nan() {
+            assert!(pixel.is_nan());
+        } else {
+            assert_eq!(*pixel, expected as f32);
+        }
+    }
+
+    decoder
+}
+
+#[cfg(feature = "geotiff")]
+#[test]
+fn write_geotiff_from_message() {
+    use gribberish::geotiff::GeoTiffOptions;
+    use tiff::tags::Tag;
+
+    // Lambert conformal, a user-defined projected CRS
+    let mut decoder = assert_geotiff(
+        "../test-data/hrrr.t06z.wrfsfcf01-TMP.grib2",
+        &GeoTiffOptions::default(),
+    );
+    let keys = decoder.get_tag_u16_vec(Tag::GeoKeyDirectoryTag).unwrap();
+    let key = |id: u16| {
+        keys[4..]
+            .chunks(4)
+            .find(|entry| entry[0] == id)
+            .map(|entry| entry[3])
+    };
+    assert_eq!(key(1024), Some(1));
+    assert_eq!(key(3072), Some(32767));
+    assert_eq!(key(3075), Some(8));
+    let params = decoder.get_tag_f64_vec(Tag::GeoDoubleParamsTag).unwrap();
+    assert!(params.contains(&38.5) && params.contains(&262.5));
+    assert!(!decoder.more_images());
+
+    assert_geotiff(
+        "../test-data/aqm.t12z.ave_1hr_o3-AK-polar-stereographic.grib2",
+        &GeoTiffOptions::default(),
+    );
+    assert_geotiff(
+        "../test-data/aqm.t12z.ave_1hr_o3-HI-mercator.grib2",
+        &GeoTiffOptions::default(),
+    );
+
+    // Lat/lon with masked points, as a Cloud-Optimized GeoTIFF
+    let options = GeoTiffOptions::cloud_optimized();
+    let mut decoder = assert_geotiff(
+        "../test-data/gfs.t12z.pgrb2.0p25.f023-PV-TMP-missing.grib2",
+        &options,
+    );
+    let keys = decoder.get_tag_u16_vec(Tag::GeoKeyDirectoryTag).unwrap();
+    assert_eq!(&keys[4..8], &[1024, 0, 1, 2]);
+    assert_eq!(decoder.get_tag_u32(Tag::TileWidth).unwrap(), 256);
+    assert_eq!(decoder.get_tag_u32(Tag::Compression).unwrap(), 8);
+    assert_eq!(
+        decoder.get_tag_ascii_string(Tag::GdalNodata).unwrap(),
+        "nan"
+    );
+
+    let mut overviews = Vec::new();
+    while decoder.more_images() {
+        decoder.next_image().unwrap();
+        assert_eq!(decoder.get_tag_u32(Tag::NewSubfileType).unwrap(), 1);
+        overviews.push(decoder.dimensions().unwrap());
+        decoder.read_image().unwrap();
+    }
+    assert_eq!(overviews, vec![(720, 361), (360, 181), (180, 91)]);
+
+    let read_data =
+        read_grib_messages("../test-data/gfs.t12z.pgrb2.0p25.f023-PV-TMP-missing.grib2");
+    let message = read_messages(read_data.as_slice()).next().unwrap();
+    let options = GeoTiffOptions {
+        tile_size: Some(100),
+        ..Default::default()
+    };
+    assert!(gribberish::geotiff::message_to_geotiff(&message, &options).is_err());
+}
+
+/// A variable's name, dimension ids, size and begin in a NetCDF header
+type HeaderVariable = (String, Vec<u32>, u32, u64);
+
+/// The dimensions and variables of a NetCDF-3 64-bit offset header, and its length
+fn read_netcdf_header(bytes: &[u8]) -> (Vec<(String, usize)>, Vec<HeaderVariable>, usize) {
+    assert_eq!(&bytes[..4], b"CDF\x02");
+    let mut offset = 4;
+    let read_word = |offset: &mut usize| {
+        let value = u32::from_be_bytes(bytes[*offset..*offset + 4].try_into().unwrap());
+        *offset += 4;
+        value
+    };
+    let read_name = |offset: &mut usize| {
+        let len = read_word(offset) as usize;
+        let name = String::from_utf8(bytes[*offset..*offset + len].to_vec()).unwrap();
+        *offset += len.div_ceil(4) * 4;
+        name
+    };
+    let skip_attributes = |offset: &mut usize| {
+        read_word(offset);
+        for _ in 0..read_word(offset) {
+            read_name(offset);
+            let size = match read_word(offset) {
+                1 | 2 => 1,
+                3 => 2,
+                4 | 5 => 4,
+                _ => 8,
+            };
+            let len = read_word(offset) as usize;
+            *offset += (len * size).div_ceil(4) * 4;
+        }
+    };
+
+    // numrecs, then the dimension list's tag
+    read_word(&mut offset);
+    read_word(&mut offset);
+    let dimensions = (0..read_word(&mut offset))
+        .map(|_| (read_name(&mut offset), read_word(&mut offset) as usize))
+        .collect();
+    skip_attributes(&mut offset);
+    read_word(&mut offset);
+    let variables = (0..read_word(&mut offset))
+        .map(|_| {
+            let name = read_name(&mut offset);
+            let ids = (0..read_word(&mut offset))
+                .map(|_| read_word(&mut offset))
+                .collect();
+            skip_attributes(&mut offset);
+            read_word(&mut offset);
+            let size = read_word(&mut offset);
+            let begin = (read_word(&mut offset) as u64) << 32 | read_word(&mut offset) as u64;
+            (name, ids, size, begin)
+        })
+        .collect();
+    (dimensions, variables, offset)
+}
+
+#[test]
+fn write_netcdf_from_messages() {
+    use gribberish::netcdf::{AttributeValue, Dataset, VariableData};
+
+    // Two parameters on two pressure levels, four times and ten members
+    let read_data = read_grib_messages("../test-data/era5-levels-members.grib");
+    let messages = read_messages(read_data.as_slice()).collect::<Vec<_>>();
+    let dataset = Dataset::from_messages(&messages).unwrap();
+
+    let t = dataset.variable("t").unwrap();
+    assert_eq!(
+        t.dimensions,
+        vec!["time", "isobar", "number", "latitude", "longitude"]
+    );
+    assert_eq!(dataset.dimension_len("time"), Some(4));
+    assert_eq!(dataset.dimension_len("isobar"), Some(2));
+    assert_eq!(dataset.dimension_len("number"), Some(10));
+    assert_eq!(dataset.variable("z").unwrap().dimensions, t.dimensions);
+    assert_eq!(
+        t.attribute("grid_mapping"),
+        Some(&AttributeValue::Text("spatial_ref".into()))
+    );
+    let mapping = dataset.variable("spatial_ref").unwrap();
+    assert_eq!(
+        mapping.attribute("grid_mapping_name"),
+        Some(&AttributeValue::Text("latitude_longitude".into()))
+    );
+
+    // Every message lands in the slot of its time, level and member
+    let VariableData::Double(levels) = &dataset.variable("isobar").unwrap().data else {
+        panic!("expected double levels");
+    };
+    let VariableData::Float(values) = &t.data else {
+        panic!("expected float values");
+    };
+    let times = messages
+        .iter()
+        .map(|m| MessageMetadata::try_from(m).unwrap())
+        .map(|m| m.forecast_end_date.unwrap_or(m.forecast_date))
+        .collect::<std::collections::BTreeSet<_>>();
+    for message in &messages {
+        let metadata = MessageMetadata::try_from(message).unwrap();
+        if metadata.var != "t" {
+            continue;
+        }
+        let time = times
+            .iter()
+            .position(|t| *t == metadata.forecast_date)
+            .unwrap();
+        let level = levels
+            .iter()
+            .position(|l| Some(*l) == metadata.first_fixed_surface_value)
+            .unwrap();
+        let member = metadata.perturbation_number.unwrap() as usize;
+        let count = metadata.data_point_count();
+        let slot = (time * 2 + level) * 10 + member;
+        let data = message.data().unwrap();
+        assert_eq!(values[slot * count], data[0] as f32);
+        assert_eq!(values[(slot + 1) * count - 1], data[count - 1] as f32);
+    }
+
+    // The header written lists the dataset's dimensions and variables, with
+    // each variable's data following the last in order
+    let bytes = dataset.to_bytes().unwrap();
+    let (dimensions, variables, header_len) = read_netcdf_header(&bytes);
+    assert_eq!(dimensions, dataset.dimensions);
+    assert_eq!(variables.len(), dataset.variables.len());
+    let mut begin = header_len as u64;
+    for (read, variable) in variables.iter().zip(&dataset.variables) {
+        let (name, dimension_ids, size, offset) = read;
+        assert_eq!(name, &variable.name);
+        let dimension_names = dimension_ids
+            .iter()
+            .map(|id| dimensions[*id as usize].0.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(dimension_names, variable.dimensions);
+        assert_eq!(*offset, begin, "{name}");
+        begin += *size as u64;
+    }
+    assert_eq!(begin, bytes.len() as u64);
+    let (_, _, _, t_begin) = variables.iter().find(|(name, ..)| name == "t").unwrap();
+    let t_begin = *t_begin as usize;
+    assert_eq!(
+        f32::from_be_bytes(bytes[t_begin..t_begin + 4].try_into().unwrap()),
+        values[0]
+    );
+
+    // Fields can only fill a slot once
+    let doubled = read_messages(read_data.as_slice())
+        .chain(read_messages(read_data.as_slice()))
+        .collect::<Vec<_>>();
+    assert!(Dataset::from_messages(&doubled).is_err());
+
+    // A member and a field of no member, such as an ensemble control and the
+    // deterministic forecast, become separate variables
+    let control = read_grib_messages("../test-data/aifs-ens-cf-t500.grib2");
+    let single = read_grib_messages("../test-data/aifs-single-t500.grib2");
+    let messages = read_messages(&control)
+        .chain(read_messages(&single))
+        .collect::<Vec<_>>();
+    let dataset = Dataset::from_messages(&messages).unwrap();
+    assert_eq!(
+        dataset.variable("tmp").unwrap().dimensions,
+        vec!["time", "latitude", "longitude"]
+    );
+    // The two files are from different runs, so each has its own time axis
+    assert_eq!(
+        dataset.variable("tmp_ens").unwrap().dimensions[1..],
+        ["number", "latitude", "longitude"]
+    );
+
+    // A parameter on two level types becomes a variable for each, on a
+    // projected grid with two dimensional latitudes and longitudes
+    let read_data = read_grib_messages("../test-data/nbm-multilevel-tcdc.grib2");
+    let messages = read_messages(read_data.as_slice()).collect::<Vec<_>>();
+    let dataset = Dataset::from_messages(&messages).unwrap();
+    let tmp = dataset.variable("tmp_hag").unwrap();
+    assert_eq!(tmp.dimensions, vec!["time", "y", "x"]);
+    assert_eq!(
+        tmp.attribute("GRIB_hag"),
+        Some(&AttributeValue::Double(vec![2.0]))
+    );
+    assert!(dataset.variable("tmp_sfc").is_some());
+    assert_eq!(
+        dataset.variable("latitude").unwrap().dimensions,
+        vec!["y", "x"]
+    );
+    assert_eq!(
+        dataset
+            .variable("spatial_ref")
+            .unwrap()
+            .attribute("grid_mapping_name"),
+        Some(&AttributeValue::Text("lambert_conformal_conic".into()))
+    );
+
+    assert!(Dataset::from_messages(&[]).is_err());
+}
+
+/// The HRRR smoke mass density product definition rewritten as template 4.40
+/// for the given constituent type
+fn chemical_constituent_product_definition(constituent: u16) -> Vec<u8> {
+    let mut section = vec![0u8, 0, 0, 36, 4, 0, 0, 0, 40, 20, 0];
+    section.extend_from_slice(&constituent.to_be_bytes());
+    section.extend_from_slice(&[2, 0, 83, 0, 0, 0, 1]);
+    section.extend_from_slice(&0u32.to_be_bytes());
+    section.extend_from_slice(&[103, 0]);
+    section.extend_from_slice(&8u32.to_be_bytes());
+    section.extend_from_slice(&[255, 0, 0, 0, 0, 0]);
+    section
+}
+
+#[test]
+fn write_netcdf_chemical_constituents() {
+    use gribberish::netcdf::Dataset;
+
+    // Ozone (0) and smoke (62000) mass density share their parameter, so
+    // each constituent becomes its own variable
+    let read_data = read_grib_messages("../test-data/hrrr.t00z.wrfprsf00-atmo-chem.grib2");
+    let message = read_messages(read_data.as_slice()).next().unwrap();
+    let data = message.data().unwrap();
+    let encoded = [0u16, 62000].map(|constituent| {
+        MessageBuilder::from_message(&message)
+            .unwrap()
+            .with_product_definition(&chemical_constituent_product_definition(constituent))
+            .with_decimal_scale_factor(12)
+            .build(&data)
+            .unwrap()
+    });
+    let messages = encoded
+        .iter()
+        .map(|encoded| Message::from_data(encoded, 0).unwrap())
+        .collect::<Vec<_>>();
+
+    let dataset = Dataset::from_messages(&messages).unwrap();
+    assert!(dataset.variable("massden_chem0").is_some());
+    assert!(dataset.variable("massden_chem62000").is_some());
+}
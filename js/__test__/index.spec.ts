@@ -98,6 +98,25 @@ test('GribMessageMetadataFactory lists and retrieves messages efficiently', (t)
   t.true(msg.data.length > 0)
 })
 
+// The RH message with its sections 4 to 7 repeated as a second, TMP, field
+const SUBMESSAGES = 'gfs.t18z.pgrb2.0p25.f186-RH-TMP-submessages.grib2'
+
+test('factories read each field of a message holding several', (t) => {
+  const data = readFileSync(join(DATA_DIR, SUBMESSAGES))
+  const factories = [GribMessageFactory.fromBuffer(data), GribMessageMetadataFactory.fromBuffer(data)]
+  for (const factory of factories) {
+    const keys = factory.availableMessages
+    t.is(keys.length, 2)
+
+    const messages = keys.map((key) => factory.getMessage(key))
+    const rh = messages.find((msg) => msg.varAbbrev === 'RH')
+    const tmp = messages.find((msg) => msg.varAbbrev === 'TMP')
+    t.truthy(rh)
+    t.truthy(tmp)
+    t.not(tmp!.data[0], rh!.data[0])
+  }
+})
+
 test('parseGribIndex locates messages for ranged reads', (t) => {
   // NOAA .idx: one entry per message, lengths inferred from the next offset.
   const idxText = readFileSync(join(DATA_DIR, 'gfswave.t18z.atlocn.0p16.f001.grib2.idx'), 'utf8')
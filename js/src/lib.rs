@@ -7,7 +7,7 @@ use gribberish::{
   adjust_longitude_values as adjust_longitude_values_core,
   data_message::DataMessage,
   index::parse_index,
-  message::{read_message, read_messages, scan_messages},
+  message::{read_message, read_messages, read_submessage, scan_messages},
   message_metadata::{MessageMetadata, scan_message_metadata},
 };
 use napi::bindgen_prelude::Uint8Array;
@@ -146,6 +146,22 @@ impl GribMessage {
       .map(|n| n as u32)
   }
 
+  /// 1-based field number within a GRIB2 message holding several fields
+  #[napi(getter)]
+  pub fn submessage(&self) -> Option<u32> {
+    self.inner.metadata.submessage.map(|s| s as u32)
+  }
+
+  /// Atmospheric chemical constituent or aerosol type of chemistry products
+  #[napi(getter)]
+  pub fn chemical_constituent_type(&self) -> Option<u32> {
+    self
+      .inner
+      .metadata
+      .chemical_constituent_type
+      .map(|c| c as u32)
+  }
+
   #[napi(getter)]
   pub fn data(&self) -> Vec<f64> {
     self.inner.data.clone()
@@ -255,7 +271,7 @@ pub fn parse_messages_from_buffer(buffer: Uint8Array) -> Vec<GribMessage> {
 #[napi]
 pub struct GribMessageFactory {
   data: Vec<u8>,
-  mapping: HashMap<String, (usize, usize)>,
+  mapping: HashMap<String, (usize, usize, Option<usize>)>,
 }
 
 #[napi]
@@ -274,11 +290,11 @@ impl GribMessageFactory {
 
   #[napi]
   pub fn get_message(&self, key: String) -> napi::Result<GribMessage> {
-    let (_, offset) = self
+    let (_, offset, submessage) = self
       .mapping
       .get(&key)
       .ok_or_else(|| napi::Error::from_reason(format!("Message '{key}' not found")))?;
-    let message = read_message(&self.data, *offset)
+    let message = read_submessage(&self.data, *offset, submessage.unwrap_or(1))
       .ok_or_else(|| napi::Error::from_reason("Failed to read GRIB message"))?;
     let message =
       DataMessage::try_from(&message).map_err(|e| napi::Error::from_reason(e.to_string()))?;
@@ -312,7 +328,7 @@ impl GribMessageMetadataFactory {
       .mapping
       .get(&key)
       .ok_or_else(|| napi::Error::from_reason(format!("Message '{key}' not found")))?;
-    let message = read_message(&self.data, *offset)
+    let message = read_submessage(&self.data, *offset, metadata.submessage.unwrap_or(1))
       .ok_or_else(|| napi::Error::from_reason("Failed to read GRIB message"))?;
     let data_message = DataMessage::try_from((&message, metadata))
       .map_err(|e| napi::Error::from_reason(e.to_string()))?;
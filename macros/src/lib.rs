@@ -204,3 +204,76 @@ fn generate_parameter_attributes(enum_data: &ItemEnum) -> TokenStream {
     })
     .into()
 }
+
+/// Serialize a `DisplayDescription` + `FromValue` enum as its table code along
+/// with its description, e.g. `{"code": 100, "description": "isobaric surface"}`.
+/// Deserialization only reads the code, so unknown codes fall back to the
+/// default variant exactly like `From<u8>`. Every variant must have a literal
+/// discriminant, its code, so bit flag enums cannot use it. The generated impls
+/// are for use inside gribberish, which provides the `CodeDescription` helper.
+#[proc_macro_derive(SerdeCodeDescription)]
+pub fn serde_code_description(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let item: Item = input.into();
+
+    if let Item::Enum(e) = item {
+        generate_serde_code_description_impl(&e)
+    } else {
+        panic!("Only Enums are supported for SerdeCodeDescription!");
+    }
+}
+
+fn generate_serde_code_description_impl(enum_data: &ItemEnum) -> TokenStream {
+    let name: &syn::Ident = &enum_data.ident;
+    let variants: &syn::punctuated::Punctuated<syn::Variant, syn::token::Comma> =
+        &enum_data.variants;
+    let variant_names = variants.into_iter().map(|v| v.ident.clone());
+    // The code is all that is deserialized, so every variant needs its own.
+    // Bit flag enums, whose variants have none, cannot use this derive.
+    let variant_values = variants
+        .into_iter()
+        .map(|v| match &v.discriminant {
+            Some((
+                _,
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Int(i),
+                    ..
+                }),
+            )) => i.base10_parse::<u8>(),
+            _ => Err(syn::Error::new_spanned(
+                v,
+                "SerdeCodeDescription requires every variant to have a u8 literal discriminant",
+            )),
+        })
+        .collect::<Result<Vec<_>, _>>();
+    let variant_values = match variant_values {
+        Ok(values) => values,
+        Err(error) => return error.to_compile_error().into(),
+    };
+
+    (quote! {
+        impl serde::Serialize for #name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let code: u8 = match self {
+                    #(
+                        #name::#variant_names => #variant_values,
+                    )*
+                };
+                let value = crate::utils::code_description::CodeDescription {
+                    code,
+                    description: self.to_string(),
+                };
+                serde::Serialize::serialize(&value, serializer)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for #name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value: crate::utils::code_description::CodeDescription =
+                    serde::Deserialize::deserialize(deserializer)?;
+                Ok(#name::from(value.code))
+            }
+        }
+    })
+    .into()
+}
@@ -301,6 +301,7 @@ class GribberishBackendArray(BackendArray):
         self.storage_options = storage_options or {}
         self.shape = array_metadata['shape']
         self.offsets = array_metadata['offsets']
+        self.submessages = array_metadata.get('submessages', [None] * len(self.offsets))
         self.dtype = np.dtype(np.float64)
         self.lock = DATA_VAR_LOCK
 
@@ -333,7 +334,10 @@ class GribberishBackendArray(BackendArray):
             )
 
         # Each chunk is the raw bytes of one message; decode the spatial slab.
-        arrs = [parse_grib_array(bytes(chunk), 0) for chunk in chunks]
+        arrs = [
+            parse_grib_array(bytes(chunk), 0, submessage=submessage)
+            for chunk, submessage in zip(chunks, self.submessages)
+        ]
 
         # Concatentate the flattened arrays, the reshape to the target shape
         data = np.concatenate(arrs)
@@ -56,16 +56,41 @@ _N_SPATIAL = 2
 
 
 def _gribberish_codecs(
-    var: str, *, adjust_longitude_range: bool = False, north_up: bool = False
+    var: str,
+    *,
+    adjust_longitude_range: bool = False,
+    north_up: bool = False,
+    submessage: int | None = None,
 ) -> list[dict[str, Any]]:
     configuration: dict[str, Any] = {"var": var}
     if adjust_longitude_range:
         configuration["adjust_longitude_range"] = True
     if north_up:
         configuration["north_up"] = True
+    if submessage is not None:
+        configuration["submessage"] = submessage
     return [{"name": _GRIBBERISH_CODEC, "configuration": configuration}]
 
 
+def _submessage(name: str, values: dict[str, Any]) -> int | None:
+    """The field number shared by every chunk of an array whose chunks are fields
+    packed into multi-field GRIB2 messages, or ``None`` when each chunk is a
+    message of its own.
+
+    The codec configuration applies to the whole array, so the chunks must all
+    select the same field of their message.
+    """
+    submessages = {s for s in values.get("submessages", []) if s is not None}
+    if not submessages:
+        return None
+    if len(submessages) > 1 or None in values["submessages"]:
+        raise ValueError(
+            f"variable {name!r}: its chunks are different fields of their GRIB2 "
+            f"messages ({sorted(submessages)}), which one codec cannot select"
+        )
+    return submessages.pop()
+
+
 def _data_manifest_array(
     url: str,
     name: str,
@@ -146,6 +171,7 @@ def _data_manifest_array(
             name,
             adjust_longitude_range=adjust_longitude_range,
             north_up=north_up,
+            submessage=_submessage(name, var["values"]),
         ),
         attributes={k: v for k, v in var["attrs"].items()},
         dimension_names=dims,
@@ -186,6 +212,7 @@ def _reference_coord_array(
             name,
             adjust_longitude_range=adjust_longitude_range,
             north_up=north_up,
+            submessage=_submessage(name, values),
         ),
         attributes=dict(coord["attrs"]),
         dimension_names=dims,
@@ -22,21 +22,29 @@ class GribberishCodec(ArrayBytesCodec):
     When ``north_up`` is set, the decoded data rows and the latitude/y coordinate
     are reordered so the 0th row is the northern-most. It is a no-op for grids
     that are already north-first.
+
+    ``submessage`` is the 1-based number of the field to decode when each chunk
+    is a GRIB2 message packing several fields; the first field is read when unset.
     """
 
     var: str | None
     adjust_longitude_range: bool = False
     north_up: bool = False
+    submessage: int | None = None
 
     def __init__(
         self,
         var: str | None,
         adjust_longitude_range: bool = False,
         north_up: bool = False,
+        submessage: int | None = None,
     ) -> Self:
         object.__setattr__(self, "var", var)
         object.__setattr__(self, "adjust_longitude_range", bool(adjust_longitude_range))
         object.__setattr__(self, "north_up", bool(north_up))
+        object.__setattr__(
+            self, "submessage", None if submessage is None else int(submessage)
+        )
 
     @classmethod
     def from_dict(cls, data: dict[str, JSON]) -> Self:
@@ -54,6 +62,8 @@ class GribberishCodec(ArrayBytesCodec):
             configuration["adjust_longitude_range"] = True
         if self.north_up:
             configuration["north_up"] = True
+        if self.submessage is not None:
+            configuration["submessage"] = self.submessage
         if not configuration:
             return {"name": "gribberish"}
         return {"name": "gribberish", "configuration": configuration}
@@ -67,12 +77,18 @@ class GribberishCodec(ArrayBytesCodec):
         chunk_bytes = chunk_data.to_bytes()
 
         if self.var == 'latitude' or self.var == 'longitude':
-            message = parse_grib_message_metadata(chunk_bytes, 0)
+            message = parse_grib_message_metadata(
+                chunk_bytes, 0, submessage=self.submessage
+            )
             lat, lng = message.latlng(self.adjust_longitude_range, self.north_up)
             data: NDArrayLike = lat if self.var == 'latitude' else lng
         else:
             data: NDArrayLike = parse_grib_array(
-                chunk_bytes, 0, self.adjust_longitude_range, self.north_up
+                chunk_bytes,
+                0,
+                self.adjust_longitude_range,
+                self.north_up,
+                submessage=self.submessage,
             )
 
         if (native_dtype := chunk_spec.dtype.to_native_dtype()) != data.dtype:
@@ -3,7 +3,10 @@ use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use gribberish::{
     message::read_message,
     message_metadata::{scan_message_metadata, MessageMetadata},
+    netcdf::{self, AttributeValue},
+    selector::{Comparison, Selector},
     templates::product::tables::{FixedSurfaceType, ProbabilityType},
+    LatLngProjection,
 };
 use numpy::{
     datetime::{units::Seconds, Datetime},
@@ -15,79 +18,6 @@ use pyo3::{
     types::{PyDict, PyList},
 };
 
-/// Compute the "kind" discriminator for a message: the statistical process,
-/// ensemble product, probability descriptor and anomaly flag combined into a
-/// single readable token. Plain instantaneous forecasts collapse to "instant".
-/// This is what (potentially) separates two hypercubes of the same variable at
-/// the same level type into different groups.
-fn message_kind(meta: &MessageMetadata) -> String {
-    let mut tokens: Vec<String> = Vec::new();
-
-    if let Some(stat) = meta.statistical_process.as_ref() {
-        let mut token = stat.abbv();
-        // Distinguish accumulation/averaging windows (e.g. 1h vs 6h precip).
-        if let Some(end_date) = meta.forecast_end_date {
-            let hours = end_date
-                .signed_duration_since(meta.forecast_date)
-                .num_hours();
-            if hours > 0 {
-                token = format!("{token}{hours}h");
-            }
-        }
-        tokens.push(token);
-    }
-
-    if let Some(derived) = meta.derived_forecast_type.as_ref() {
-        tokens.push(derived.abbv());
-    }
-
-    if let Some(prob) = meta.probability_type.as_ref() {
-        let mut token = prob.abbv();
-        if matches!(
-            prob,
-            ProbabilityType::BetweenLimits | ProbabilityType::BetweenLimitsInclusive
-        ) {
-            if let (Some(lower), Some(upper)) =
-                (meta.probability_lower_limit, meta.probability_upper_limit)
-            {
-                token = format!("{token}_{lower:.0}_{upper:.0}");
-            }
-        }
-        tokens.push(token);
-    } else if meta.percentile_value.is_some() {
-        // Percentile products share the "pctl" kind so they never collapse into
-        // the same array as raw members; the value itself stays a dimension.
-        tokens.push("pctl".to_string());
-    }
-
-    if meta.is_anomaly {
-        tokens.push("anom".to_string());
-    }
-
-    // Period-banded wave fields (template 4.103) share a parameter number with
-    // plain significant wave height, so the period band is the only thing that
-    // separates them. Fold it into the kind - like between-limit probability
-    // thresholds - so each band becomes its own hypercube instead of colliding.
-    if let Some((lower, upper)) = meta.wave_period_range {
-        let lower = lower.map_or("".to_string(), |v| format!("{v:.0}"));
-        let upper = upper.map_or("".to_string(), |v| format!("{v:.0}"));
-        tokens.push(format!("per{lower}-{upper}s"));
-    }
-
-    // Drop any empty tokens defensively: an empty token would join into a
-    // malformed kind (e.g. "acc__mean") or, on its own, an empty kind string,
-    // which becomes an unnamed group and corrupts the datatree hierarchy. The
-    // contributing abbreviations should already be non-empty, but a missing /
-    // unrecognized code can render blank.
-    tokens.retain(|t| !t.is_empty());
-
-    if tokens.is_empty() {
-        "instant".to_string()
-    } else {
-        tokens.join("_")
-    }
-}
-
 /// The threshold value that distinguishes probability messages of the same
 /// field, selected according to the probability type.
 ///
@@ -137,92 +67,22 @@ fn node_has_data_vars(node: &Bound<'_, PyDict>) -> PyResult<bool> {
 /// These attributes let geospatial tooling (rioxarray, cartopy, MetPy,
 /// cf_xarray) reconstruct the CRS via `pyproj.CRS.from_cf`, instead of callers
 /// hand-parsing the proj4 `crs` string. The existing per-variable `crs`/
-/// `proj_params` attrs are left untouched.
+/// `proj_params` attrs are left untouched. They are the same attributes the
+/// NetCDF export writes.
 fn cf_grid_mapping<'py>(
     py: Python<'py>,
-    proj_name: &str,
-    params: &HashMap<String, f64>,
+    projector: &LatLngProjection,
 ) -> Option<Bound<'py, PyDict>> {
     let attrs = PyDict::new(py);
-
-    // Earth shape: spherical -> earth_radius, otherwise the semi-axes.
-    let set_earth = |attrs: &Bound<'py, PyDict>| match (params.get("a"), params.get("b")) {
-        (Some(&a), Some(&b)) if (a - b).abs() < 1e-3 => {
-            attrs.set_item("earth_radius", a).unwrap();
-        }
-        (Some(&a), Some(&b)) => {
-            attrs.set_item("semi_major_axis", a).unwrap();
-            attrs.set_item("semi_minor_axis", b).unwrap();
+    for (name, value) in netcdf::cf_grid_mapping(projector)? {
+        match value {
+            AttributeValue::Text(value) => attrs.set_item(name, value),
+            AttributeValue::Double(values) if values.len() == 1 => attrs.set_item(name, values[0]),
+            AttributeValue::Double(values) => attrs.set_item(name, values),
+            AttributeValue::Float(values) => attrs.set_item(name, values),
+            AttributeValue::Int(values) => attrs.set_item(name, values),
         }
-        _ => {}
-    };
-
-    match proj_name {
-        "latlon" => {
-            attrs
-                .set_item("grid_mapping_name", "latitude_longitude")
-                .unwrap();
-            set_earth(&attrs);
-        }
-        "lcc" => {
-            attrs
-                .set_item("grid_mapping_name", "lambert_conformal_conic")
-                .unwrap();
-            if let (Some(&lat_1), Some(&lat_2)) = (params.get("lat_1"), params.get("lat_2")) {
-                attrs
-                    .set_item("standard_parallel", vec![lat_1, lat_2])
-                    .unwrap();
-            }
-            if let Some(&lon_0) = params.get("lon_0") {
-                attrs
-                    .set_item("longitude_of_central_meridian", lon_0)
-                    .unwrap();
-            }
-            if let Some(&lat_0) = params.get("lat_0") {
-                attrs
-                    .set_item("latitude_of_projection_origin", lat_0)
-                    .unwrap();
-            }
-            attrs.set_item("false_easting", 0.0).unwrap();
-            attrs.set_item("false_northing", 0.0).unwrap();
-            set_earth(&attrs);
-        }
-        "stere" => {
-            attrs
-                .set_item("grid_mapping_name", "polar_stereographic")
-                .unwrap();
-            if let Some(&lon_0) = params.get("lon_0") {
-                attrs
-                    .set_item("straight_vertical_longitude_from_pole", lon_0)
-                    .unwrap();
-            }
-            if let Some(&lat_ts) = params.get("lat_ts") {
-                attrs.set_item("standard_parallel", lat_ts).unwrap();
-            }
-            if let Some(&lat_0) = params.get("lat_0") {
-                attrs
-                    .set_item("latitude_of_projection_origin", lat_0)
-                    .unwrap();
-            }
-            attrs.set_item("false_easting", 0.0).unwrap();
-            attrs.set_item("false_northing", 0.0).unwrap();
-            set_earth(&attrs);
-        }
-        "merc" => {
-            attrs.set_item("grid_mapping_name", "mercator").unwrap();
-            if let Some(&lat_ts) = params.get("lat_ts") {
-                attrs.set_item("standard_parallel", lat_ts).unwrap();
-            }
-            // Template 3.10 has no central-meridian field; the projection
-            // origin is always the prime meridian.
-            attrs
-                .set_item("longitude_of_projection_origin", 0.0)
-                .unwrap();
-            attrs.set_item("false_easting", 0.0).unwrap();
-            attrs.set_item("false_northing", 0.0).unwrap();
-            set_earth(&attrs);
-        }
-        _ => return None,
+        .unwrap();
     }
 
     Some(attrs)
@@ -314,21 +174,21 @@ fn build_grib_dataset<'py>(
     collapse_groups: Option<bool>,
 ) -> PyResult<Bound<'py, PyDict>> {
     let collapse_groups = collapse_groups.unwrap_or(false);
-    let drop_variables = if let Some(drop_variables) = drop_variables {
-        drop_variables
-            .iter()
-            .map(|d| d.to_string().to_lowercase())
-            .collect::<Vec<String>>()
-    } else {
-        Vec::new()
-    };
-
-    let only_variables = only_variables.map(|only_variables| {
-        only_variables
-            .iter()
-            .map(|d| d.to_string().to_lowercase())
-            .collect::<Vec<String>>()
-    });
+    let mut selector = Selector::new();
+    if let Some(only_variables) = only_variables {
+        selector = selector.with(
+            "var",
+            Comparison::Equal,
+            only_variables.iter().map(|d| d.to_string()),
+        );
+    }
+    if let Some(drop_variables) = drop_variables {
+        selector = selector.with(
+            "var",
+            Comparison::NotEqual,
+            drop_variables.iter().map(|d| d.to_string()),
+        );
+    }
 
     let perserve_dims: Vec<String> = if let Some(perserve_dims) = perserve_dims {
         perserve_dims
@@ -376,14 +236,12 @@ fn build_grib_dataset<'py>(
     let mut msg_info: HashMap<String, (String, String, String, String)> = HashMap::new();
     for (k, v) in mapping.iter() {
         let meta = &v.2;
-        let var = meta.var.to_lowercase();
-        if (only_variables.is_some() && !only_variables.as_ref().unwrap().contains(&var))
-            || drop_variables.contains(&var)
-        {
+        if !selector.matches(meta) {
             continue;
         }
+        let var = meta.var.to_lowercase();
         let level = meta.first_fixed_surface_type.coordinate_name().to_string();
-        let kind = message_kind(meta);
+        let kind = meta.kind();
         let process = process_kind(meta);
         var_levels
             .entry(var.clone())
@@ -427,12 +285,6 @@ fn build_grib_dataset<'py>(
     // group path (0, 1 or 2 segments) -> variable short name -> message keys
     let mut groups: BTreeMap<Vec<String>, HashMap<String, Vec<String>>> = BTreeMap::new();
     for (k, (var, level, kind, process)) in msg_info.iter() {
-        if (only_variables.is_some() && !only_variables.as_ref().unwrap().contains(var))
-            || drop_variables.contains(var)
-        {
-            continue;
-        }
-
         let mut path = Vec::new();
         // A level coordinate name can be empty when the surface type is missing
         // or unrecognized. An empty path segment would produce an unnamed group,
@@ -443,7 +295,7 @@ fn build_grib_dataset<'py>(
         }
         // A kind segment must never be empty: an empty path segment produces
         // an unnamed group that collides with its parent and breaks the
-        // Zarr/datatree hierarchy. `message_kind` already guarantees a
+        // Zarr/datatree hierarchy. `MessageMetadata::kind` already guarantees a
         // non-empty string, so this guard is purely defensive.
         if partition_by_kind && !kind.is_empty() {
             let process_conflict = kind_processes
@@ -1097,16 +949,23 @@ fn build_group<'py>(
             v.push("longitude".to_string());
         });
     } else {
+        // Rotated pole grids are regular in rotated degrees rather than
+        // projected metres
+        let is_rotated = first.2.projector.proj_name() == "ob_tran";
+        let (x_name, y_name, xy_unit) = if is_rotated {
+            ("grid_longitude", "grid_latitude", "degrees")
+        } else {
+            ("projection_x_coordinate", "projection_y_coordinate", "m")
+        };
+
         let y = PyDict::new(py);
         let y_metadata = PyDict::new(py);
         y_metadata.set_item("axis", "Y").unwrap();
-        y_metadata
-            .set_item("standard_name", "projection_y_coordinate")
-            .unwrap();
+        y_metadata.set_item("standard_name", y_name).unwrap();
         y_metadata
             .set_item("long_name", "y coordinate of projection")
             .unwrap();
-        y_metadata.set_item("unit", "m").unwrap();
+        y_metadata.set_item("unit", xy_unit).unwrap();
         y.set_item("attrs", y_metadata).unwrap();
         y.set_item("dims", vec!["y"]).unwrap();
         y.set_item("values", PyArray::from_vec(py, first.2.projector.y()))
@@ -1116,13 +975,11 @@ fn build_group<'py>(
         let x = PyDict::new(py);
         let x_metadata = PyDict::new(py);
         x_metadata.set_item("axis", "X").unwrap();
-        x_metadata
-            .set_item("standard_name", "projection_x_coordinate")
-            .unwrap();
+        x_metadata.set_item("standard_name", x_name).unwrap();
         x_metadata
             .set_item("long_name", "x coordinate of projection")
             .unwrap();
-        x_metadata.set_item("unit", "m").unwrap();
+        x_metadata.set_item("unit", xy_unit).unwrap();
         x.set_item("attrs", x_metadata).unwrap();
         x.set_item("dims", vec!["x"]).unwrap();
         x.set_item("values", PyArray::from_vec(py, first.2.projector.x()))
@@ -1140,6 +997,11 @@ fn build_group<'py>(
             lats_array
                 .set_item("offsets", [(first.1, first.2.message_size)])
                 .unwrap();
+            if first.2.submessage.is_some() {
+                lats_array
+                    .set_item("submessages", [first.2.submessage])
+                    .unwrap();
+            }
             latitude.set_item("values", lats_array).unwrap();
 
             let lngs_array = PyDict::new(py);
@@ -1149,6 +1011,11 @@ fn build_group<'py>(
             lngs_array
                 .set_item("offsets", [(first.1, first.2.message_size)])
                 .unwrap();
+            if first.2.submessage.is_some() {
+                lngs_array
+                    .set_item("submessages", [first.2.submessage])
+                    .unwrap();
+            }
             longitude.set_item("values", lngs_array).unwrap();
         } else {
             let (lat, lng) = first.2.latlng();
@@ -1173,9 +1040,7 @@ fn build_group<'py>(
     // CF grid mapping: a scalar `spatial_ref` coordinate carrying the CRS in a
     // form geospatial tooling can auto-detect. Variables reference it via a
     // `grid_mapping` attribute below.
-    let proj_name = first.2.projector.proj_name();
-    let proj_params = first.2.projector.proj_params();
-    let has_grid_mapping = if let Some(gm_attrs) = cf_grid_mapping(py, &proj_name, &proj_params) {
+    let has_grid_mapping = if let Some(gm_attrs) = cf_grid_mapping(py, &first.2.projector) {
         // Keep the proj4 string around for tools that prefer it over CF attrs.
         gm_attrs.set_item("proj4", first.2.proj.clone()).unwrap();
 
@@ -1390,6 +1255,15 @@ fn build_group<'py>(
         array.set_item("shape", shape).unwrap();
         array.set_item("offsets", offsets).unwrap();
 
+        // Fields packed into a shared GRIB2 message are found by their submessage number
+        let submessages = v_sorted
+            .iter()
+            .map(|chunk| mapping.get(chunk).unwrap().2.submessage)
+            .collect::<Vec<_>>();
+        if submessages.iter().any(Option::is_some) {
+            array.set_item("submessages", submessages).unwrap();
+        }
+
         data_var.set_item("values", array).unwrap();
         data_vars.set_item(var, data_var).unwrap();
     }
@@ -1,9 +1,9 @@
 use std::collections::HashMap;
 use std::convert::TryFrom;
 
-use gribberish::message::Message;
+use gribberish::message::{read_submessage, Message};
 use gribberish::message_metadata::{scan_message_metadata, MessageMetadata};
-use gribberish::templates::product::product_template::WavePeriodRange;
+use gribberish::templates::product::product_template::{AerosolInterval, WavePeriodRange};
 use numpy::{PyArray, PyArray1};
 use pyo3::exceptions::PyTypeError;
 use pyo3::prelude::*;
@@ -22,6 +22,12 @@ impl GribMessageMetadata {
         self.inner.message_size
     }
 
+    /// 1-based field number within a GRIB2 message holding several fields
+    #[getter]
+    fn submessage(&self) -> Option<usize> {
+        self.inner.submessage
+    }
+
     #[getter]
     fn var_name(&self) -> &str {
         self.inner.name.as_str()
@@ -72,6 +78,45 @@ impl GribMessageMetadata {
         self.inner.wave_period_range
     }
 
+    /// Atmospheric chemical constituent type (code table 4.230), or aerosol
+    /// type, for chemistry and aerosol products (templates 4.40 - 4.48).
+    #[getter]
+    fn chemical_constituent_type(&self) -> Option<u16> {
+        self.inner.chemical_constituent_type
+    }
+
+    /// `(first, second)` aerosol particle size interval in metres.
+    #[getter]
+    fn aerosol_size_range(&self) -> Option<AerosolInterval> {
+        self.inner.aerosol_size_range
+    }
+
+    /// `(first, second)` wavelength interval in metres of aerosol optical
+    /// properties.
+    #[getter]
+    fn aerosol_wavelength_range(&self) -> Option<AerosolInterval> {
+        self.inner.aerosol_wavelength_range
+    }
+
+    /// `(satellite_series, satellite_number, instrument_type, central_wave_number)`
+    /// of each spectral band of satellite products (templates 4.31 and 4.32),
+    /// with the wave number in m-1.
+    #[getter]
+    fn satellite_bands(&self) -> Vec<(u16, u16, u16, Option<f64>)> {
+        self.inner
+            .satellite_bands
+            .iter()
+            .map(|b| {
+                (
+                    b.satellite_series,
+                    b.satellite_number,
+                    b.instrument_type,
+                    b.central_wave_number,
+                )
+            })
+            .collect()
+    }
+
     #[getter]
     fn reference_date<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDateTime>> {
         // Dates are UTC; a None tzinfo would convert to machine-local naive time
@@ -192,20 +237,28 @@ pub struct GribMessage {
 #[pymethods]
 impl GribMessage {
     fn data<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray1<f64>>> {
-        parse_grib_array(py, &self.raw_data, self.offset, false, false)
+        parse_grib_array(
+            py,
+            &self.raw_data,
+            self.offset,
+            false,
+            false,
+            self.metadata.inner.submessage,
+        )
     }
 }
 
 #[pyfunction]
-#[pyo3(signature = (data, offset, adjust_longitude_range=false, north_up=false))]
+#[pyo3(signature = (data, offset, adjust_longitude_range=false, north_up=false, submessage=None))]
 pub fn parse_grib_array<'py>(
     py: Python<'py>,
     data: &[u8],
     offset: usize,
     adjust_longitude_range: bool,
     north_up: bool,
+    submessage: Option<usize>,
 ) -> PyResult<Bound<'py, PyArray1<f64>>> {
-    let message = Message::from_data(data, offset)
+    let message = read_submessage(data, offset, submessage.unwrap_or(1))
         .ok_or_else(|| PyTypeError::new_err("Failed to read GRIB message"))?;
     let values = message
         .data()
@@ -241,8 +294,13 @@ pub fn adjust_latitude_values(py: Python<'_>, latitudes: Vec<f64>) -> Bound<'_,
 }
 
 #[pyfunction]
-pub fn parse_grib_message_metadata(data: &[u8], offset: usize) -> PyResult<GribMessageMetadata> {
-    let message = Message::from_data(data, offset)
+#[pyo3(signature = (data, offset, submessage=None))]
+pub fn parse_grib_message_metadata(
+    data: &[u8],
+    offset: usize,
+    submessage: Option<usize>,
+) -> PyResult<GribMessageMetadata> {
+    let message = read_submessage(data, offset, submessage.unwrap_or(1))
         .ok_or_else(|| PyTypeError::new_err("Failed to read GRIB message"))?;
     let metadata = MessageMetadata::try_from(&message)
         .map_err(|e| PyTypeError::new_err(format!("Failed to parse metadata: {e}")))?;
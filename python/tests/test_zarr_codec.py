@@ -144,6 +144,32 @@ def test_codec_config_roundtrips_north_up_flag():
     assert GribberishCodec.from_dict(plain.to_dict()) == plain
 
 
+def test_codec_config_roundtrips_submessage():
+    from gribberish.zarr.codec import GribberishCodec
+
+    codec = GribberishCodec("VGRD", submessage=2)
+    assert codec.to_dict() == {
+        "name": "gribberish",
+        "configuration": {"var": "VGRD", "submessage": 2},
+    }
+    assert GribberishCodec.from_dict(codec.to_dict()) == codec
+
+    # unset omits the key, so chunks that are whole messages round-trip unchanged
+    plain = GribberishCodec("VGRD")
+    assert plain.submessage is None
+    assert plain.to_dict() == {"name": "gribberish", "configuration": {"var": "VGRD"}}
+
+
+async def test_submessage_one_decodes_single_field_message():
+    """Selecting the first field of a single-field message is the same as the default."""
+    from gribberish.zarr.codec import GribberishCodec
+
+    raw = (TEST_DATA / HRRR).read_bytes()
+    native = await _decode(GribberishCodec("TMP"), raw, HRRR_SHAPE)
+    selected = await _decode(GribberishCodec("TMP", submessage=1), raw, HRRR_SHAPE)
+    np.testing.assert_array_equal(native, selected)
+
+
 async def test_north_up_flips_south_first_data_and_coordinate():
     """HRRR (Lambert) is south-first, so north_up row-reverses both the decoded
     data and the latitude coordinate."""